@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS "store_tally_sheet_version_trigger" ON "sequent_backend"."tally_sheet";
+
+DROP FUNCTION IF EXISTS "sequent_backend"."store_tally_sheet_version"();
+
+DROP TABLE "sequent_backend"."tally_sheet_version";
+
+alter table "sequent_backend"."tally_sheet" drop column "approved_by_user_id";
+
+alter table "sequent_backend"."tally_sheet" drop column "approved_at";
+
+alter table "sequent_backend"."tally_sheet" drop column "version";
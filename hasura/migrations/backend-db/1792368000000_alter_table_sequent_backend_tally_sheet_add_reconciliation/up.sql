@@ -0,0 +1,65 @@
+alter table "sequent_backend"."tally_sheet" add column "version" integer
+ not null default 1;
+
+alter table "sequent_backend"."tally_sheet" add column "approved_at" timestamptz
+ null;
+
+alter table "sequent_backend"."tally_sheet" add column "approved_by_user_id" text
+ null;
+
+-- Sheets published before double entry existed are taken as approved, so
+-- that they don't block the tally of existing election events.
+UPDATE "sequent_backend"."tally_sheet"
+SET "approved_at" = "published_at",
+    "approved_by_user_id" = "published_by_user_id"
+WHERE "published_at" IS NOT NULL;
+
+CREATE TABLE "sequent_backend"."tally_sheet_version" ("id" uuid NOT NULL DEFAULT gen_random_uuid(), "tenant_id" uuid NOT NULL, "election_event_id" uuid NOT NULL, "tally_sheet_id" uuid NOT NULL, "version" integer NOT NULL, "content" jsonb, "channel" text, "created_at" timestamptz NOT NULL DEFAULT now(), "superseded_at" timestamptz NOT NULL DEFAULT now(), "superseded_by_user_id" text, "approved_at" timestamptz, "approved_by_user_id" text, PRIMARY KEY ("id","tenant_id","election_event_id") , FOREIGN KEY ("tally_sheet_id", "tenant_id", "election_event_id") REFERENCES "sequent_backend"."tally_sheet"("id", "tenant_id", "election_event_id") ON UPDATE restrict ON DELETE restrict, FOREIGN KEY ("election_event_id") REFERENCES "sequent_backend"."election_event"("id") ON UPDATE restrict ON DELETE restrict, FOREIGN KEY ("tenant_id") REFERENCES "sequent_backend"."tenant"("id") ON UPDATE restrict ON DELETE restrict, UNIQUE ("tally_sheet_id", "tenant_id", "election_event_id", "version"));
+CREATE EXTENSION IF NOT EXISTS pgcrypto;
+
+-- Keeps the superseded version of a tally sheet whenever its content or
+-- channel changes. Changing the content invalidates any previous approval.
+CREATE OR REPLACE FUNCTION "sequent_backend"."store_tally_sheet_version"()
+RETURNS TRIGGER AS $$
+BEGIN
+  IF NEW.content IS NOT DISTINCT FROM OLD.content
+    AND NEW.channel IS NOT DISTINCT FROM OLD.channel THEN
+    RETURN NEW;
+  END IF;
+
+  INSERT INTO "sequent_backend"."tally_sheet_version" (
+    "tenant_id",
+    "election_event_id",
+    "tally_sheet_id",
+    "version",
+    "content",
+    "channel",
+    "created_at",
+    "superseded_by_user_id",
+    "approved_at",
+    "approved_by_user_id"
+  ) VALUES (
+    OLD.tenant_id,
+    OLD.election_event_id,
+    OLD.id,
+    OLD.version,
+    OLD.content,
+    OLD.channel,
+    OLD.last_updated_at,
+    current_setting('hasura.user', 't')::jsonb ->> 'x-hasura-user-id',
+    OLD.approved_at,
+    OLD.approved_by_user_id
+  );
+
+  NEW.version = OLD.version + 1;
+  NEW.last_updated_at = now();
+  NEW.approved_at = NULL;
+  NEW.approved_by_user_id = NULL;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER "store_tally_sheet_version_trigger"
+BEFORE UPDATE ON "sequent_backend"."tally_sheet"
+FOR EACH ROW
+EXECUTE PROCEDURE "sequent_backend"."store_tally_sheet_version"();
@@ -0,0 +1,9 @@
+alter table "sequent_backend"."scheduled_event" drop column "alerted_at";
+
+alter table "sequent_backend"."scheduled_event" drop column "last_error";
+
+alter table "sequent_backend"."scheduled_event" drop column "next_attempt_at";
+
+alter table "sequent_backend"."scheduled_event" drop column "attempts";
+
+alter table "sequent_backend"."scheduled_event" drop column "status";
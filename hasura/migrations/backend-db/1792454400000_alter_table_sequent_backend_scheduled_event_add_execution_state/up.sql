@@ -0,0 +1,14 @@
+alter table "sequent_backend"."scheduled_event" add column "status" varchar
+ null;
+
+alter table "sequent_backend"."scheduled_event" add column "attempts" integer
+ not null default 0;
+
+alter table "sequent_backend"."scheduled_event" add column "next_attempt_at" timestamptz
+ null;
+
+alter table "sequent_backend"."scheduled_event" add column "last_error" text
+ null;
+
+alter table "sequent_backend"."scheduled_event" add column "alerted_at" timestamptz
+ null;
@@ -0,0 +1,3 @@
+DROP TABLE "sequent_backend"."email_suppression";
+
+DROP TABLE "sequent_backend"."email_outbox";
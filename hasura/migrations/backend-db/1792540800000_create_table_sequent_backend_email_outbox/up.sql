@@ -0,0 +1,9 @@
+CREATE TABLE "sequent_backend"."email_outbox" ("id" uuid NOT NULL DEFAULT gen_random_uuid(), "tenant_id" uuid NOT NULL, "election_event_id" uuid, "task_execution_id" uuid, "receiver" text NOT NULL, "subject" text, "status" varchar NOT NULL DEFAULT 'PENDING', "attempts" integer NOT NULL DEFAULT 0, "last_error" text, "provider_message_id" text, "created_at" timestamptz NOT NULL DEFAULT now(), "updated_at" timestamptz NOT NULL DEFAULT now(), "sent_at" timestamptz, PRIMARY KEY ("id","tenant_id") , FOREIGN KEY ("tenant_id") REFERENCES "sequent_backend"."tenant"("id") ON UPDATE restrict ON DELETE restrict, FOREIGN KEY ("election_event_id") REFERENCES "sequent_backend"."election_event"("id") ON UPDATE restrict ON DELETE cascade);
+CREATE EXTENSION IF NOT EXISTS pgcrypto;
+
+CREATE INDEX "email_outbox_task_execution_id_idx" ON "sequent_backend"."email_outbox" ("tenant_id", "task_execution_id");
+CREATE INDEX "email_outbox_provider_message_id_idx" ON "sequent_backend"."email_outbox" ("provider_message_id");
+
+-- Addresses that permanently bounced or complained, which are not sent
+-- emails anymore.
+CREATE TABLE "sequent_backend"."email_suppression" ("tenant_id" uuid NOT NULL, "email" text NOT NULL, "reason" varchar NOT NULL, "detail" text, "created_at" timestamptz NOT NULL DEFAULT now(), PRIMARY KEY ("tenant_id","email") , FOREIGN KEY ("tenant_id") REFERENCES "sequent_backend"."tenant"("id") ON UPDATE restrict ON DELETE restrict);
@@ -0,0 +1 @@
+DROP TABLE "sequent_backend"."census_snapshot";
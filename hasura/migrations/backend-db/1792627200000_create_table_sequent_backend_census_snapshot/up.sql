@@ -0,0 +1,9 @@
+-- Frozen copies of the voter roll of an election event. The roll itself is
+-- stored as a document, and "census" has the number of eligible voters per
+-- election and area, as {election_id: {area_id: count}}. Snapshots taken when
+-- the voting period of a single election starts set "election_id" and only
+-- have the census of that election.
+CREATE TABLE "sequent_backend"."census_snapshot" ("id" uuid NOT NULL DEFAULT gen_random_uuid(), "tenant_id" uuid NOT NULL, "election_event_id" uuid NOT NULL, "election_id" uuid, "created_at" timestamptz NOT NULL DEFAULT now(), "created_by_user_id" text, "trigger" varchar NOT NULL, "document_id" uuid NOT NULL, "hash" text NOT NULL, "total_voters" bigint NOT NULL, "census" jsonb NOT NULL DEFAULT '{}', PRIMARY KEY ("id","tenant_id") , FOREIGN KEY ("tenant_id") REFERENCES "sequent_backend"."tenant"("id") ON UPDATE restrict ON DELETE restrict, FOREIGN KEY ("election_event_id") REFERENCES "sequent_backend"."election_event"("id") ON UPDATE restrict ON DELETE cascade);
+CREATE EXTENSION IF NOT EXISTS pgcrypto;
+
+CREATE INDEX "census_snapshot_election_event_id_idx" ON "sequent_backend"."census_snapshot" ("tenant_id", "election_event_id", "election_id", "created_at");
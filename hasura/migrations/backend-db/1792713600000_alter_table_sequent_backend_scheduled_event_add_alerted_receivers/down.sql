@@ -0,0 +1 @@
+alter table "sequent_backend"."scheduled_event" drop column "alerted_receivers";
@@ -0,0 +1,2 @@
+alter table "sequent_backend"."scheduled_event" add column "alerted_receivers" text[]
+ not null default '{}';
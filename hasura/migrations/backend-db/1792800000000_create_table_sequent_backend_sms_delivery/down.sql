@@ -0,0 +1 @@
+DROP TABLE "sequent_backend"."sms_delivery";
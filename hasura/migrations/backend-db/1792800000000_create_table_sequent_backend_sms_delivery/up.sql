@@ -0,0 +1,2 @@
+CREATE TABLE "sequent_backend"."sms_delivery" ("id" uuid NOT NULL DEFAULT gen_random_uuid(), "tenant_id" uuid NOT NULL, "task_execution_id" uuid NOT NULL, "message_id" text NOT NULL, "receiver" text, "status" varchar NOT NULL, "status_rank" integer NOT NULL DEFAULT 0, "error_code" text, "created_at" timestamptz NOT NULL DEFAULT now(), "updated_at" timestamptz NOT NULL DEFAULT now(), PRIMARY KEY ("id","tenant_id") , FOREIGN KEY ("tenant_id") REFERENCES "sequent_backend"."tenant"("id") ON UPDATE restrict ON DELETE restrict, UNIQUE ("tenant_id", "task_execution_id", "message_id"));
+CREATE EXTENSION IF NOT EXISTS pgcrypto;
@@ -65,6 +65,8 @@ export const electionStatusColor: (status: string) => string = (status) => {
         return theme.palette.info.main
     } else if (status === ITallyElectionStatus.DECRYPTING) {
         return theme.palette.info.main
+    } else if (status === ITallyElectionStatus.TALLYING) {
+        return theme.palette.info.main
     } else if (status === ITallyElectionStatus.SUCCESS) {
         return theme.palette.brandSuccess
     } else if (status === ITallyElectionStatus.ERROR) {
@@ -51,6 +51,11 @@ const catalanTranslation: TranslationType = {
                 DELETE_ELECTION_EVENT: "Esborrar esdeveniment electoral",
                 PREPARE_PUBLICATION_PREVIEW: "Preparar la vista prèvia de la publicació",
                 EXPORT_TALLY_RESULTS_XLSX: "Exporta els resultats del recompte en format XLSX",
+                EXPORT_TALLY_RESULTS_CDF: "Exporta els resultats del recompte en format CDF",
+                EXPORT_TALLY_RESULTS_EML: "Exporta els resultats del recompte en format EML",
+                RECOUNT_TALLY_SESSION: "Recomptar la sessió d'escrutini",
+                SYNC_CENSUS: "Sincronitzar el cens",
+                VALIDATE_IMPORT: "Validar la importació",
             },
             widget: {
                 taskTitle: "Tasca: {{title}}",
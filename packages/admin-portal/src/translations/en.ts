@@ -83,6 +83,11 @@ const englishTranslation = {
                 DELETE_ELECTION_EVENT: "Delete Election Event",
                 PREPARE_PUBLICATION_PREVIEW: "Prepare Publication Preview",
                 EXPORT_TALLY_RESULTS_XLSX: "Export Tally Results in XLSX format",
+                EXPORT_TALLY_RESULTS_CDF: "Export Tally Results in CDF format",
+                EXPORT_TALLY_RESULTS_EML: "Export Tally Results in EML format",
+                RECOUNT_TALLY_SESSION: "Recount Tally Session",
+                SYNC_CENSUS: "Sync Census",
+                VALIDATE_IMPORT: "Validate Import",
             },
             widget: {
                 taskTitle: "Task: {{title}}",
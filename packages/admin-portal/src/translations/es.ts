@@ -84,6 +84,11 @@ const spanishTranslation: TranslationType = {
                 DELETE_ELECTION_EVENT: "Eliminar evento electoral",
                 PREPARE_PUBLICATION_PREVIEW: "Preparar la vista previa de la publicación",
                 EXPORT_TALLY_RESULTS_XLSX: "Exportar los resultados del escrutinio en formato XLSX",
+                EXPORT_TALLY_RESULTS_CDF: "Exportar los resultados del escrutinio en formato CDF",
+                EXPORT_TALLY_RESULTS_EML: "Exportar los resultados del escrutinio en formato EML",
+                RECOUNT_TALLY_SESSION: "Recontar la sesión de escrutinio",
+                SYNC_CENSUS: "Sincronizar el censo",
+                VALIDATE_IMPORT: "Validar la importación",
             },
             widget: {
                 taskTitle: "Tarea: {{title}}",
@@ -84,6 +84,11 @@ const basqueTranslation: TranslationType = {
                 DELETE_ELECTION_EVENT: "Ezabatu Hauteskunde Gertaera",
                 PREPARE_PUBLICATION_PREVIEW: "Argitalpenaren aurrebista prestatu",
                 EXPORT_TALLY_RESULTS_XLSX: "Esportatu zenbaketa-emaitzak XLSX formatuan",
+                EXPORT_TALLY_RESULTS_CDF: "Esportatu zenbaketa-emaitzak CDF formatuan",
+                EXPORT_TALLY_RESULTS_EML: "Esportatu zenbaketa-emaitzak EML formatuan",
+                RECOUNT_TALLY_SESSION: "Zenbaketa-saioa berriro zenbatu",
+                SYNC_CENSUS: "Errolda sinkronizatu",
+                VALIDATE_IMPORT: "Inportazioa balidatu",
             },
             widget: {
                 taskTitle: "Ataza: {{title}}",
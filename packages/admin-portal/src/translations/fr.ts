@@ -84,6 +84,11 @@ const frenchTranslation: TranslationType = {
                 DELETE_ELECTION_EVENT: "Supprimer l'événement électoral",
                 PREPARE_PUBLICATION_PREVIEW: "Préparer l'aperçu de la publication",
                 EXPORT_TALLY_RESULTS_XLSX: "Exporter les résultats du dépouillement au format XLSX",
+                EXPORT_TALLY_RESULTS_CDF: "Exporter les résultats du dépouillement au format CDF",
+                EXPORT_TALLY_RESULTS_EML: "Exporter les résultats du dépouillement au format EML",
+                RECOUNT_TALLY_SESSION: "Recompter la session de dépouillement",
+                SYNC_CENSUS: "Synchroniser le recensement",
+                VALIDATE_IMPORT: "Valider l'importation",
             },
             widget: {
                 taskTitle: "Tâche: {{title}}",
@@ -84,6 +84,11 @@ const galegoTranslation: TranslationType = {
                 DELETE_ELECTION_EVENT: "Eliminar evento electoral",
                 PREPARE_PUBLICATION_PREVIEW: "Preparar a vista previa da publicación",
                 EXPORT_TALLY_RESULTS_XLSX: "Exportar os resultados do reconto en formato XLSX",
+                EXPORT_TALLY_RESULTS_CDF: "Exportar os resultados do reconto en formato CDF",
+                EXPORT_TALLY_RESULTS_EML: "Exportar os resultados do reconto en formato EML",
+                RECOUNT_TALLY_SESSION: "Recontar a sesión de escrutinio",
+                SYNC_CENSUS: "Sincronizar o censo",
+                VALIDATE_IMPORT: "Validar a importación",
             },
             widget: {
                 taskTitle: "Tarefa: {{title}}",
@@ -83,6 +83,11 @@ const dutchTranslation: TranslationType = {
                 DELETE_ELECTION_EVENT: "Verkiezingsevenement Verwijderen",
                 PREPARE_PUBLICATION_PREVIEW: "De publicatievoorbeeldweergave voorbereiden",
                 EXPORT_TALLY_RESULTS_XLSX: "Exporteer de telresultaten in XLSX-indeling",
+                EXPORT_TALLY_RESULTS_CDF: "Exporteer de telresultaten in CDF-indeling",
+                EXPORT_TALLY_RESULTS_EML: "Exporteer de telresultaten in EML-indeling",
+                RECOUNT_TALLY_SESSION: "Telsessie hertellen",
+                SYNC_CENSUS: "Kiezersregister synchroniseren",
+                VALIDATE_IMPORT: "Import valideren",
             },
             widget: {
                 taskTitle: "Taak: {{title}}",
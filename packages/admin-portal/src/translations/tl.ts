@@ -85,6 +85,13 @@ const tagalogTranslation: TranslationType = {
                 PREPARE_PUBLICATION_PREVIEW: "Ihanda ang paunang tingin ng publikasyon",
                 EXPORT_TALLY_RESULTS_XLSX:
                     "I-export ang mga resulta ng pagbibilang sa format na XLSX",
+                EXPORT_TALLY_RESULTS_CDF:
+                    "I-export ang mga resulta ng pagbibilang sa format na CDF",
+                EXPORT_TALLY_RESULTS_EML:
+                    "I-export ang mga resulta ng pagbibilang sa format na EML",
+                RECOUNT_TALLY_SESSION: "Muling bilangin ang sesyon ng pagbibilang",
+                SYNC_CENSUS: "I-sync ang census",
+                VALIDATE_IMPORT: "I-validate ang import",
             },
             widget: {
                 taskTitle: "Gawain: {{title}}",
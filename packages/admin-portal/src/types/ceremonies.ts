@@ -57,6 +57,7 @@ export enum ITallyElectionStatus {
     WAITING = "WAITING",
     MIXING = "MIXING",
     DECRYPTING = "DECRYPTING",
+    TALLYING = "TALLYING",
     SUCCESS = "SUCCESS",
     ERROR = "ERROR",
 }
@@ -67,11 +68,29 @@ export interface ITallyElection {
     progress: number
 }
 
+export enum ITallyCheckpointStatus {
+    PENDING = "PENDING",
+    SUCCESS = "SUCCESS",
+    ERROR = "ERROR",
+}
+
+export interface ITallyCheckpoint {
+    election_id: string
+    session_ids: Array<number>
+    status: ITallyCheckpointStatus
+    attempts: number
+    input_document_id?: string
+    output_document_id?: string
+    error?: string
+    updated_at?: string
+}
+
 export interface ITallyCeremonyStatus {
     stop_date?: string
     logs: Array<ILog>
     trustees: Array<ITallyTrustee>
     elections_status: Array<ITallyElection>
+    checkpoints?: Array<ITallyCheckpoint>
 }
 
 export enum ETallyType {
@@ -23,4 +23,9 @@ export enum ETasksExecution {
     DELETE_ELECTION_EVENT = "DELETE_ELECTION_EVENT",
     PREPARE_PUBLICATION_PREVIEW = "PREPARE_PUBLICATION_PREVIEW",
     EXPORT_TALLY_RESULTS_XLSX = "EXPORT_TALLY_RESULTS_XLSX",
+    EXPORT_TALLY_RESULTS_CDF = "EXPORT_TALLY_RESULTS_CDF",
+    EXPORT_TALLY_RESULTS_EML = "EXPORT_TALLY_RESULTS_EML",
+    RECOUNT_TALLY_SESSION = "RECOUNT_TALLY_SESSION",
+    SYNC_CENSUS = "SYNC_CENSUS",
+    VALIDATE_IMPORT = "VALIDATE_IMPORT",
 }
@@ -36,8 +36,14 @@ async fn main() -> Result<(), rocket::Error> {
                 routes::inetum::transaction_status_simple,
                 routes::inetum::transaction_results,
                 routes::user::upload_csv,
+                routes::sms::twilio_account,
+                routes::sms::twilio_messages,
+                routes::sms::http_send,
+                routes::sms::list_messages,
+                routes::sms::clear_messages,
             ],
         )
+        .manage(types::sms::SmsOutbox::default())
         .launch()
         .await?;
 
@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Mock SMS gateways: a Twilio-compatible REST API and a generic HTTP one.
+//! Received messages are listed in `/sms/messages`, and their delivery
+//! status is reported to the callback url with the status in the
+//! `MOCK_SMS_STATUS` env var (`delivered` by default).
+
+use crate::types::sms::{MockSms, SmsOutbox};
+use rocket::form::Form;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(FromForm, Debug)]
+pub struct TwilioMessageForm {
+    #[field(name = "To")]
+    to: String,
+    #[field(name = "Body")]
+    body: String,
+    #[field(name = "StatusCallback")]
+    status_callback: Option<String>,
+}
+
+fn get_mock_status() -> String {
+    std::env::var("MOCK_SMS_STATUS").unwrap_or("delivered".to_string())
+}
+
+fn store_sms(outbox: &SmsOutbox, sms: MockSms) -> Result<(), (Status, String)> {
+    outbox
+        .0
+        .lock()
+        .map_err(|err| (Status::InternalServerError, format!("Lock error: {err}")))?
+        .push(sms);
+    Ok(())
+}
+
+#[get("/2010-04-01/Accounts/<account_sid>")]
+pub async fn twilio_account(account_sid: &str) -> Json<Value> {
+    let account_sid = account_sid.trim_end_matches(".json");
+    Json(serde_json::json!({
+        "sid": account_sid,
+        "status": "active",
+    }))
+}
+
+#[post("/2010-04-01/Accounts/<account_sid>/Messages.json", data = "<form>")]
+pub async fn twilio_messages(
+    account_sid: &str,
+    form: Form<TwilioMessageForm>,
+    outbox: &State<SmsOutbox>,
+) -> Result<(Status, Json<Value>), (Status, String)> {
+    let input = form.into_inner();
+    let sid = format!("SM{}", Uuid::new_v4().simple());
+    store_sms(
+        outbox,
+        MockSms {
+            id: sid.clone(),
+            provider: "Twilio".to_string(),
+            to: input.to.clone(),
+            body: input.body.clone(),
+            callback_url: input.status_callback.clone(),
+        },
+    )?;
+
+    if let Some(callback_url) = input.status_callback {
+        let params = vec![
+            ("MessageSid", sid.clone()),
+            ("AccountSid", account_sid.to_string()),
+            ("MessageStatus", get_mock_status()),
+        ];
+        tokio::spawn(async move {
+            let result = reqwest::Client::new()
+                .post(&callback_url)
+                .form(&params)
+                .send()
+                .await;
+            if let Err(err) = result {
+                eprintln!("Error calling status callback {callback_url}: {err}");
+            }
+        });
+    }
+
+    Ok((
+        Status::Created,
+        Json(serde_json::json!({
+            "sid": sid,
+            "account_sid": account_sid,
+            "to": input.to,
+            "body": input.body,
+            "status": "queued",
+        })),
+    ))
+}
+
+/// Generic gateway, expects the default `SMS_HTTP_BODY` of windmill.
+#[post("/sms/send", format = "json", data = "<body>")]
+pub async fn http_send(
+    body: Json<Value>,
+    outbox: &State<SmsOutbox>,
+) -> Result<Json<Value>, (Status, String)> {
+    let get_field = |name: &str| body.get(name).and_then(Value::as_str).map(str::to_string);
+    let to = get_field("to").ok_or((Status::BadRequest, "Missing to".to_string()))?;
+    let message =
+        get_field("message").ok_or((Status::BadRequest, "Missing message".to_string()))?;
+    let callback_url = get_field("callback_url").filter(|url| !url.is_empty());
+    let id = Uuid::new_v4().to_string();
+    store_sms(
+        outbox,
+        MockSms {
+            id: id.clone(),
+            provider: "Http".to_string(),
+            to,
+            body: message,
+            callback_url: callback_url.clone(),
+        },
+    )?;
+
+    if let Some(callback_url) = callback_url {
+        let status = serde_json::json!({
+            "message_id": id.clone(),
+            "status": get_mock_status(),
+        });
+        tokio::spawn(async move {
+            let result = reqwest::Client::new()
+                .post(&callback_url)
+                .json(&status)
+                .send()
+                .await;
+            if let Err(err) = result {
+                eprintln!("Error calling status callback {callback_url}: {err}");
+            }
+        });
+    }
+
+    Ok(Json(serde_json::json!({ "id": id })))
+}
+
+#[get("/sms/messages")]
+pub async fn list_messages(
+    outbox: &State<SmsOutbox>,
+) -> Result<Json<Vec<MockSms>>, (Status, String)> {
+    let messages = outbox
+        .0
+        .lock()
+        .map_err(|err| (Status::InternalServerError, format!("Lock error: {err}")))?
+        .clone();
+    Ok(Json(messages))
+}
+
+#[delete("/sms/messages")]
+pub async fn clear_messages(outbox: &State<SmsOutbox>) -> Result<Status, (Status, String)> {
+    outbox
+        .0
+        .lock()
+        .map_err(|err| (Status::InternalServerError, format!("Lock error: {err}")))?
+        .clear();
+    Ok(Status::NoContent)
+}
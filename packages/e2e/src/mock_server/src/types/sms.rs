@@ -0,0 +1,20 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// A SMS message received by one of the mock gateways.
+#[derive(Serialize, Debug, Clone)]
+pub struct MockSms {
+    pub id: String,
+    pub provider: String,
+    pub to: String,
+    pub body: String,
+    pub callback_url: Option<String>,
+}
+
+/// Messages received since the server started, in order.
+#[derive(Default)]
+pub struct SmsOutbox(pub Mutex<Vec<MockSms>>);
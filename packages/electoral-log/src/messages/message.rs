@@ -281,6 +281,18 @@ impl Message {
         Self::from_body(event, body, sd, user_id, username, election.0, None, None)
     }
 
+    pub fn tally_recount_message(
+        event: EventIdString,
+        election: ElectionIdString,
+        tally_session: TallySessionIdString,
+        sd: &SigningData,
+        user_id: Option<String>,
+        username: Option<String>,
+    ) -> Result<Self> {
+        let body = StatementBody::TallyRecount(election.clone(), tally_session);
+        Self::from_body(event, body, sd, user_id, username, election.0, None, None)
+    }
+
     pub fn send_template(
         event: EventIdString,
         _election: ElectionIdString,
@@ -294,6 +306,30 @@ impl Message {
         Self::from_body(event, body, sd, user_id, username, None, area_id, None)
     }
 
+    pub fn census_change_message(
+        event: EventIdString,
+        sd: &SigningData,
+        user_id: Option<String>,
+        username: Option<String>,
+        change: CensusChangeString,
+        area_id: Option<String>,
+    ) -> Result<Self> {
+        let body = StatementBody::CensusChange(change);
+        Self::from_body(event, body, sd, user_id, username, None, area_id, None)
+    }
+
+    pub fn census_snapshot_message(
+        event: EventIdString,
+        sd: &SigningData,
+        user_id: Option<String>,
+        username: Option<String>,
+        snapshot_id: CensusSnapshotIdString,
+        hash: CensusSnapshotHashString,
+    ) -> Result<Self> {
+        let body = StatementBody::CensusSnapshot(snapshot_id, hash);
+        Self::from_body(event, body, sd, user_id, username, None, None, None)
+    }
+
     pub fn voter_public_key_message(
         tenant_id: TenantIdString,
         event: EventIdString,
@@ -45,6 +45,11 @@ pub struct TrusteeNameString(pub String);
 )]
 pub struct BallotPublicationIdString(pub String);
 
+#[derive(
+    BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug,
+)]
+pub struct TallySessionIdString(pub String);
+
 #[derive(
     BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug,
 )]
@@ -69,6 +74,21 @@ pub struct TenantIdString(pub String);
 )]
 pub struct AdminUserIdString(pub String);
 
+#[derive(
+    BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug,
+)]
+pub struct CensusChangeString(pub String);
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug,
+)]
+pub struct CensusSnapshotIdString(pub String);
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, Debug,
+)]
+pub struct CensusSnapshotHashString(pub String);
+
 impl PseudonymHash {
     // Provide methods to work with HashWrapper as needed
     pub fn new(hash: Hash) -> Self {
@@ -169,6 +169,24 @@ impl StatementHead {
                 description: "Admin has public key.".to_string(),
                 ..default_head
             },
+            StatementBody::TallyRecount(_, tally_session) => StatementHead {
+                kind: StatementType::TallyRecount,
+                description: format!("Tally session {} recounted.", tally_session.0),
+                ..default_head
+            },
+            StatementBody::CensusChange(change) => StatementHead {
+                kind: StatementType::CensusChange,
+                description: format!("Census synchronisation: {}", change.0),
+                ..default_head
+            },
+            StatementBody::CensusSnapshot(snapshot_id, hash) => StatementHead {
+                kind: StatementType::CensusSnapshot,
+                description: format!(
+                    "Census snapshot {} frozen with hash {}.",
+                    snapshot_id.0, hash.0
+                ),
+                ..default_head
+            },
         }
     }
 }
@@ -250,6 +268,15 @@ pub enum StatementBody {
     ///     the given admin user
     ///     hash has as their public key the given public key (in der_b64 format)
     AdminPublicKey(TenantIdString, Option<String>, PublicKeyDerB64),
+    /// Represents the assertion that the given tally session was recounted
+    /// from its frozen snapshot, for the given elections
+    TallyRecount(ElectionIdString, TallySessionIdString),
+    /// Represents a voter added, updated or disabled by a census
+    /// synchronisation, with the description of the change
+    CensusChange(CensusChangeString),
+    /// Represents the assertion that the voter roll of the election event
+    /// was frozen as the given census snapshot, with the given sha256 hash
+    CensusSnapshot(CensusSnapshotIdString, CensusSnapshotHashString),
 }
 
 // Note: When creating new variants, consider that the length limit STATEMENT_KIND_VARCHAR_LENGTH is 40.
@@ -275,6 +302,9 @@ pub enum StatementType {
     KeycloakUserEvent,
     VoterPublicKey,
     AdminPublicKey,
+    TallyRecount,
+    CensusChange,
+    CensusSnapshot,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Display, Deserialize, Serialize, Debug, Clone)]
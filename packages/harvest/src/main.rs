@@ -58,6 +58,7 @@ async fn rocket() -> _ {
                 routes::elections::create_election,
                 routes::areas::upsert_area,
                 routes::election_event_stats::get_election_event_stats,
+                routes::census_snapshot::create_census_snapshot_route,
                 routes::election_stats::get_election_stats,
                 routes::scheduled_event::create_scheduled_event,
                 routes::immudb_log_audit::list_pgaudit,
@@ -68,6 +69,7 @@ async fn rocket() -> _ {
                 routes::export_election_event_logs::export_election_event_logs_route,
                 routes::insert_election_event::insert_election_event_f,
                 routes::import_candidates::import_candidates_route,
+                routes::import_validation::validate_import_route,
                 routes::insert_election_event::import_election_event_f,
                 routes::delete_election_event::delete_election_event_f,
                 routes::import_tenant_config::import_tenant_config_route,
@@ -75,6 +77,7 @@ async fn rocket() -> _ {
                 routes::insert_tenant::insert_tenant,
                 routes::users::create_user,
                 routes::users::import_users_f,
+                routes::users::sync_census,
                 routes::users::export_users_f,
                 routes::users::export_tenant_users_f,
                 routes::users::delete_user,
@@ -105,7 +108,10 @@ async fn rocket() -> _ {
                 routes::voting_status::update_event_status,
                 routes::voting_status::update_election_status,
                 routes::tally_ceremony::update_tally_ceremony,
+                routes::tally_ceremony::recount_tally_session,
                 routes::tally_sheets::publish_tally_sheet,
+                routes::tally_sheets::reconcile_tally_sheet,
+                routes::tally_sheets::approve_tally_sheet,
                 routes::create_ballot_receipt::create_ballot_receipt,
                 routes::election_dates::manage_election_dates,
                 routes::custom_urls::update_custom_url,
@@ -124,14 +130,23 @@ async fn rocket() -> _ {
                 routes::reports::generate_report,
                 routes::reports::encrypt_report_route,
                 routes::templates::get_user_template,
+                routes::templates::preview_template_route,
+                routes::templates::send_template_test_route,
                 routes::applications::verify_user_application,
                 routes::applications::change_application_status,
                 routes::export_application::export_application_route,
                 routes::import_application::import_application_route,
                 routes::trustees::export_trustees_route,
                 routes::set_voter_authentication::set_voter_authentication,
+                routes::sms_delivery_status::sms_delivery_status_form,
+                routes::sms_delivery_status::sms_delivery_status_json,
+                routes::email_delivery::email_notifications,
+                routes::email_delivery::get_email_campaign_summary_route,
+                routes::sms_delivery_status::get_sms_delivery_summary_route,
                 routes::export_tally_results::export_tally_results_route,
                 routes::google_meet::generate_google_meeting,
+                routes::provisional_results::get_provisional_results,
+                routes::provisional_results::election_event_presentation_updated,
             ],
         )
         .manage(LastDatafixAccessToken::init())
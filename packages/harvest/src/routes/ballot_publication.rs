@@ -124,6 +124,8 @@ pub async fn generate_ballot_publication(
 pub struct PublishBallotInput {
     election_event_id: String,
     ballot_publication_id: String,
+    /// Publishes changes to the ballot encoding even if voting has started.
+    allow_encoding_changes: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -151,6 +153,8 @@ pub async fn publish_ballot(
         let username = claims.preferred_username.unwrap_or("-".to_string());
         let election_event_id = input.election_event_id.clone();
         let ballot_publication_id = input.ballot_publication_id.clone();
+        let allow_encoding_changes =
+            input.allow_encoding_changes.unwrap_or(false);
         Box::pin(async move {
             update_publish_ballot(
                 hasura_transaction,
@@ -159,6 +163,7 @@ pub async fn publish_ballot(
                 tenant_id,
                 election_event_id,
                 ballot_publication_id,
+                allow_encoding_changes,
             )
             .await
         })
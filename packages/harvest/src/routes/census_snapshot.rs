@@ -0,0 +1,97 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::services::authorization::authorize;
+use anyhow::Result;
+use deadpool_postgres::Client as DbClient;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use sequent_core::services::jwt::JwtClaims;
+use sequent_core::types::permissions::Permissions;
+use serde::{Deserialize, Serialize};
+use tracing::{event, instrument, Level};
+use windmill::postgres::census_snapshot::SnapshotCensus;
+use windmill::services::census_snapshot::{
+    create_census_snapshot, CensusSnapshotTrigger,
+};
+use windmill::services::database::get_hasura_pool;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateCensusSnapshotInput {
+    election_event_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateCensusSnapshotOutput {
+    id: String,
+    document_id: String,
+    hash: String,
+    total_voters: i64,
+    census: SnapshotCensus,
+}
+
+// Freezes the current voter roll of the election event. Tally results and
+// statistics use the census of the last snapshot
+#[instrument(skip(claims))]
+#[post("/create-census-snapshot", format = "json", data = "<body>")]
+pub async fn create_census_snapshot_route(
+    body: Json<CreateCensusSnapshotInput>,
+    claims: JwtClaims,
+) -> Result<Json<CreateCensusSnapshotOutput>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::ELECTION_STATE_WRITE],
+    )?;
+    let input = body.into_inner();
+    let tenant_id = claims.hasura_claims.tenant_id.clone();
+    let user_id = claims.hasura_claims.user_id.clone();
+
+    let mut hasura_db_client: DbClient =
+        get_hasura_pool().await.get().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error getting hasura db pool: {err}"),
+            )
+        })?;
+    let hasura_transaction =
+        hasura_db_client.transaction().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error starting hasura transaction: {err}"),
+            )
+        })?;
+
+    let snapshot = create_census_snapshot(
+        &hasura_transaction,
+        &tenant_id,
+        &input.election_event_id,
+        None,
+        CensusSnapshotTrigger::MANUAL,
+        Some(&user_id),
+        claims.preferred_username.as_deref(),
+    )
+    .await
+    .map_err(|err| (Status::InternalServerError, format!("{err:?}")))?;
+
+    hasura_transaction.commit().await.map_err(|err| {
+        (Status::InternalServerError, format!("Commit failed: {err}"))
+    })?;
+
+    event!(
+        Level::INFO,
+        "Created census snapshot {}, election_event_id={}",
+        snapshot.id,
+        input.election_event_id,
+    );
+
+    Ok(Json(CreateCensusSnapshotOutput {
+        id: snapshot.id,
+        document_id: snapshot.document_id,
+        hash: snapshot.hash,
+        total_voters: snapshot.total_voters,
+        census: snapshot.census,
+    }))
+}
@@ -10,7 +10,9 @@ use rocket::http::Status;
 use rocket::serde::json::Json;
 use sequent_core::services::jwt::JwtClaims;
 use sequent_core::types::permissions::Permissions;
-use sequent_core::types::scheduled_event::EventProcessors;
+use sequent_core::types::scheduled_event::{
+    EventProcessors, ScheduledEventPolicy,
+};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use windmill::services::database::get_hasura_pool;
@@ -22,6 +24,7 @@ pub struct ManageElectionDatesBody {
     election_id: Option<String>,
     scheduled_date: Option<String>,
     event_processor: EventProcessors,
+    policy: Option<ScheduledEventPolicy>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -86,6 +89,7 @@ pub async fn manage_election_dates(
                 &id,
                 input.scheduled_date.as_deref(),
                 input.event_processor.to_string().as_str(),
+                input.policy,
             )
             .await
             {
@@ -104,6 +108,7 @@ pub async fn manage_election_dates(
                 &input.election_event_id,
                 input.scheduled_date.as_deref(),
                 input.event_processor.to_string().as_str(),
+                input.policy,
             )
             .await
             .map_err(|e| {
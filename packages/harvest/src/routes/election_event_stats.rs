@@ -13,6 +13,7 @@ use sequent_core::services::keycloak::get_event_realm;
 use sequent_core::types::permissions::Permissions;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
+use windmill::postgres::census_snapshot::get_latest_census_snapshot;
 use windmill::services::cast_votes::{
     get_count_votes_per_day, get_top_count_votes_by_ip, CastVoteCountByIp,
     CastVotesPerDay, ListCastVotesByIpFilter,
@@ -38,6 +39,8 @@ pub struct ElectionEventStatsOutput {
     total_areas: i64,
     total_elections: i64,
     votes_per_day: Vec<CastVotesPerDay>,
+    /// Census snapshot `total_eligible_voters` was taken from, if any.
+    census_snapshot_id: Option<String>,
 }
 
 #[instrument(skip(claims))]
@@ -139,13 +142,36 @@ pub async fn get_election_event_stats(
         )
     })?;
 
-    let realm_name =
-        get_event_realm(tenant_id.as_str(), input.election_event_id.as_str());
+    // Once the census is frozen, turnout is relative to the snapshot instead
+    // of the current voters
+    let census_snapshot = get_latest_census_snapshot(
+        &hasura_transaction,
+        &tenant_id,
+        &input.election_event_id,
+        None,
+    )
+    .await
+    .map_err(|err| {
+        (
+            Status::InternalServerError,
+            format!("Error retrieving census snapshot: {err}"),
+        )
+    })?;
 
-    let total_eligible_voters: i64 =
-        count_keycloak_enabled_users(&keycloak_transaction, &realm_name)
-            .await
-            .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+    let total_eligible_voters: i64 = match census_snapshot {
+        Some(ref snapshot) => snapshot.total_voters,
+        None => {
+            let realm_name = get_event_realm(
+                tenant_id.as_str(),
+                input.election_event_id.as_str(),
+            );
+            count_keycloak_enabled_users(&keycloak_transaction, &realm_name)
+                .await
+                .map_err(|e| {
+                    (Status::InternalServerError, format!("{:?}", e))
+                })?
+        }
+    };
 
     Ok(Json(ElectionEventStatsOutput {
         total_distinct_voters,
@@ -153,6 +179,7 @@ pub async fn get_election_event_stats(
         total_eligible_voters: total_eligible_voters.into(),
         total_elections: total_elections.into(),
         votes_per_day,
+        census_snapshot_id: census_snapshot.map(|snapshot| snapshot.id),
     }))
 }
 
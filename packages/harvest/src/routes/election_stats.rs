@@ -11,6 +11,7 @@ use sequent_core::services::jwt::JwtClaims;
 use sequent_core::types::permissions::Permissions;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
+use windmill::postgres::census_snapshot::get_latest_census_snapshot;
 use windmill::services::cast_votes::{
     get_count_votes_per_day, CastVotesPerDay,
 };
@@ -32,6 +33,9 @@ pub struct ElectionStatsOutput {
     total_distinct_voters: i64,
     total_areas: i64,
     votes_per_day: Vec<CastVotesPerDay>,
+    /// Eligible voters of the election in the last census snapshot, if any.
+    total_eligible_voters: Option<i64>,
+    census_snapshot_id: Option<String>,
 }
 
 #[instrument(skip(claims))]
@@ -91,6 +95,23 @@ pub async fn get_election_stats(
         )
     })?;
 
+    let census_snapshot = get_latest_census_snapshot(
+        &hasura_transaction,
+        &tenant_id,
+        &input.election_event_id,
+        Some(&input.election_id),
+    )
+    .await
+    .map_err(|err| {
+        (
+            Status::InternalServerError,
+            format!("Error retrieving census snapshot: {err}"),
+        )
+    })?;
+    let total_eligible_voters = census_snapshot
+        .as_ref()
+        .and_then(|snapshot| snapshot.get_election_census(&input.election_id));
+
     let votes_per_day: Vec<CastVotesPerDay> = get_count_votes_per_day(
         &hasura_transaction,
         &tenant_id.as_str(),
@@ -112,5 +133,7 @@ pub async fn get_election_stats(
         total_distinct_voters,
         total_areas,
         votes_per_day,
+        total_eligible_voters,
+        census_snapshot_id: census_snapshot.map(|snapshot| snapshot.id),
     }))
 }
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::services::authorization::authorize;
+use crate::types::error_response::{ErrorCode, ErrorResponse, JsonError};
+use deadpool_postgres::Client as DbClient;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use sequent_core::services::jwt::JwtClaims;
+use sequent_core::types::permissions::Permissions;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use windmill::services::database::get_hasura_pool;
+use windmill::services::email_outbox::{
+    get_email_campaign_summary, process_email_notification,
+    verify_notifications_token, EmailCampaignSummary,
+};
+
+/// Bounce and complaint notifications of the email provider, delivered by a
+/// SNS subscription. SNS posts the body as text/plain, so it's read as a
+/// string. The request is authenticated by the token included in the
+/// subscription url.
+#[instrument(skip(token, body))]
+#[post("/email-notifications?<token>", data = "<body>")]
+pub async fn email_notifications(
+    token: String,
+    body: String,
+) -> Result<Status, JsonError> {
+    verify_notifications_token(&token).map_err(|e| {
+        ErrorResponse::new(
+            Status::Unauthorized,
+            &format!("{e:?}"),
+            ErrorCode::Unauthorized,
+        )
+    })?;
+
+    process_email_notification(&body).await.map_err(|e| {
+        ErrorResponse::new(
+            Status::BadRequest,
+            &format!("Error processing email notification: {e:?}"),
+            ErrorCode::InvalidEmailNotification,
+        )
+    })?;
+
+    Ok(Status::Ok)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetEmailCampaignSummaryInput {
+    task_execution_id: String,
+}
+
+/// Delivery summary of the emails sent by a send_template task execution.
+#[instrument(skip(claims))]
+#[post("/get-email-campaign-summary", format = "json", data = "<body>")]
+pub async fn get_email_campaign_summary_route(
+    body: Json<GetEmailCampaignSummaryInput>,
+    claims: JwtClaims,
+) -> Result<Json<EmailCampaignSummary>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::NOTIFICATION_READ],
+    )?;
+    let input = body.into_inner();
+    let tenant_id: String = claims.hasura_claims.tenant_id.clone();
+
+    let mut hasura_db_client: DbClient =
+        get_hasura_pool().await.get().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error loading hasura db client: {err}"),
+            )
+        })?;
+    let hasura_transaction =
+        hasura_db_client.transaction().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error creating a transaction: {err}"),
+            )
+        })?;
+
+    let summary = get_email_campaign_summary(
+        &hasura_transaction,
+        &tenant_id,
+        &input.task_execution_id,
+    )
+    .await
+    .map_err(|err| {
+        (
+            Status::InternalServerError,
+            format!("Error getting email campaign summary: {err:?}"),
+        )
+    })?;
+
+    Ok(Json(summary))
+}
@@ -14,15 +14,38 @@ use serde::{Deserialize, Serialize};
 use tracing::{event, instrument, Level};
 use uuid::Uuid;
 use windmill::services::celery_app::get_celery_app;
+use windmill::services::export::eml_results_generator::EMLResultsMessage;
 use windmill::services::tasks_execution::*;
 use windmill::tasks::export_ballot_publication::export_ballot_publication;
 use windmill::tasks::export_election_event::{self, ExportOptions};
+use windmill::tasks::export_tally_results::{
+    export_tally_results_to_cdf_task, export_tally_results_to_eml_task,
+    export_tally_results_to_xlsx_task,
+};
 use windmill::types::tasks::ETasksExecution;
 
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ETallyResultsExportFormat {
+    /// Spreadsheet with a sheet for each results table.
+    #[default]
+    Xlsx,
+    /// NIST SP 1500-100 Election Results Common Data Format, in JSON.
+    Cdf,
+    /// OASIS EML 510 count message.
+    Eml510,
+    /// OASIS EML 520 result message.
+    Eml520,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ExportTallyResultsInput {
     election_event_id: String,
     tally_session_id: String,
+    #[serde(default)]
+    format: Option<ETallyResultsExportFormat>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -56,10 +79,24 @@ pub async fn export_tally_results_route(
         .clone()
         .unwrap_or_else(|| claims.hasura_claims.user_id.clone());
 
+    let format = body.format.unwrap_or_default();
+    let task_type = match format {
+        ETallyResultsExportFormat::Xlsx => {
+            ETasksExecution::EXPORT_TALLY_RESULTS_XLSX
+        }
+        ETallyResultsExportFormat::Cdf => {
+            ETasksExecution::EXPORT_TALLY_RESULTS_CDF
+        }
+        ETallyResultsExportFormat::Eml510
+        | ETallyResultsExportFormat::Eml520 => {
+            ETasksExecution::EXPORT_TALLY_RESULTS_EML
+        }
+    };
+
     let task_execution = post(
         &tenant_id,
         Some(&election_event_id),
-        ETasksExecution::EXPORT_TALLY_RESULTS_XLSX,
+        task_type.clone(),
         &executer_name,
     )
     .await
@@ -73,21 +110,57 @@ pub async fn export_tally_results_route(
     let document_id = Uuid::new_v4().to_string();
     let celery_app = get_celery_app().await;
 
-    let _celery_task = match celery_app.send_task(windmill::tasks::export_tally_results::export_tally_results_to_xlsx_task::new(
-        tenant_id,
-        election_event_id,
-        tally_session_id,
-        document_id.clone(),
-        task_execution.clone(),
-    ))
-        .await
-    {
+    let send_result = match format {
+        ETallyResultsExportFormat::Xlsx => {
+            celery_app
+                .send_task(export_tally_results_to_xlsx_task::new(
+                    tenant_id,
+                    election_event_id,
+                    tally_session_id,
+                    document_id.clone(),
+                    task_execution.clone(),
+                ))
+                .await
+        }
+        ETallyResultsExportFormat::Cdf => {
+            celery_app
+                .send_task(export_tally_results_to_cdf_task::new(
+                    tenant_id,
+                    election_event_id,
+                    tally_session_id,
+                    document_id.clone(),
+                    task_execution.clone(),
+                ))
+                .await
+        }
+        ETallyResultsExportFormat::Eml510
+        | ETallyResultsExportFormat::Eml520 => {
+            let message = match format {
+                ETallyResultsExportFormat::Eml510 => EMLResultsMessage::Count,
+                _ => EMLResultsMessage::Result,
+            };
+            celery_app
+                .send_task(export_tally_results_to_eml_task::new(
+                    tenant_id,
+                    election_event_id,
+                    tally_session_id,
+                    message,
+                    document_id.clone(),
+                    task_execution.clone(),
+                ))
+                .await
+        }
+    };
+
+    let _celery_task = match send_result {
         Ok(celery_task) => celery_task,
         Err(err) => {
             return Ok(Json(ExportTallyResultsOutput {
                 document_id,
                 task_execution: task_execution.clone(),
-                error_msg: Some(format!("Failed to send EXPORT_TALLY_RESULTS_XLSX task: {err:?}")),
+                error_msg: Some(format!(
+                    "Failed to send {task_type} task: {err:?}"
+                )),
             }))
         }
     };
@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::services::authorization::authorize;
+use anyhow::Result;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use sequent_core::services::jwt::JwtClaims;
+use sequent_core::types::hasura::core::TasksExecution;
+use sequent_core::types::permissions::Permissions;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use uuid::Uuid;
+use windmill::services::celery_app::get_celery_app;
+use windmill::services::import::import_validation::{
+    ImportValidationInput, ImportValidationKind,
+};
+use windmill::services::tasks_execution::post;
+use windmill::tasks::validate_import::validate_import_task;
+use windmill::types::tasks::ETasksExecution;
+
+#[derive(Deserialize, Debug)]
+pub struct ValidateImportBody {
+    election_event_id: String,
+    document_id: String,
+    sha256: Option<String>,
+    kind: ImportValidationKind,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ValidateImportOutput {
+    document_id: String,
+    task_execution: TasksExecution,
+    error_msg: Option<String>,
+}
+
+// Checks a voters, areas or candidates file before importing it, without
+// importing anything. The per-row error report is uploaded as `document_id`
+#[instrument(skip(claims))]
+#[post("/validate-import", format = "json", data = "<body>")]
+pub async fn validate_import_route(
+    claims: JwtClaims,
+    body: Json<ValidateImportBody>,
+) -> Result<Json<ValidateImportOutput>, (Status, String)> {
+    let input = body.into_inner();
+    let tenant_id = claims.hasura_claims.tenant_id.clone();
+    // Same permissions as the import of each kind of file
+    let required_perm = match input.kind {
+        ImportValidationKind::USERS => Permissions::VOTER_IMPORT,
+        ImportValidationKind::AREAS => Permissions::AREA_WRITE,
+        ImportValidationKind::CANDIDATES => Permissions::ADMIN_USER,
+    };
+    authorize(&claims, true, Some(tenant_id.clone()), vec![required_perm])?;
+
+    let executer_name = claims
+        .name
+        .clone()
+        .unwrap_or_else(|| claims.hasura_claims.user_id.clone());
+    let task_execution = post(
+        &tenant_id,
+        Some(&input.election_event_id),
+        ETasksExecution::VALIDATE_IMPORT,
+        &executer_name,
+    )
+    .await
+    .map_err(|error| {
+        (
+            Status::InternalServerError,
+            format!("Failed to insert task execution record: {error:?}"),
+        )
+    })?;
+
+    let document_id = Uuid::new_v4().to_string();
+    let task_input = ImportValidationInput {
+        tenant_id,
+        election_event_id: input.election_event_id.clone(),
+        document_id: input.document_id,
+        sha256: input.sha256,
+        kind: input.kind,
+        report_document_id: document_id.clone(),
+    };
+    let celery_app = get_celery_app().await;
+
+    if let Err(err) = celery_app
+        .send_task(validate_import_task::new(
+            task_input,
+            task_execution.clone(),
+        ))
+        .await
+    {
+        return Ok(Json(ValidateImportOutput {
+            document_id,
+            task_execution,
+            error_msg: Some(format!(
+                "Failed to send VALIDATE_IMPORT task: {err:?}"
+            )),
+        }));
+    }
+
+    info!(
+        "Sent VALIDATE_IMPORT task {}, election_event_id={}",
+        task_execution.id, input.election_event_id
+    );
+
+    Ok(Json(ValidateImportOutput {
+        document_id,
+        task_execution,
+        error_msg: None,
+    }))
+}
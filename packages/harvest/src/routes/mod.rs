@@ -6,6 +6,7 @@ pub mod applications;
 pub mod areas;
 pub mod ballot_publication;
 pub mod ballot_publication_prepare_preview;
+pub mod census_snapshot;
 pub mod create_ballot_receipt;
 pub mod custom_urls;
 pub mod delete_election_event;
@@ -14,6 +15,7 @@ pub mod election_event_stats;
 pub mod election_stats;
 pub mod elections;
 pub mod electoral_log;
+pub mod email_delivery;
 pub mod error_catchers;
 pub mod export_application;
 pub mod export_ballot_publication;
@@ -31,6 +33,7 @@ pub mod import_areas;
 pub mod import_candidates;
 pub mod import_templates;
 pub mod import_tenant_config;
+pub mod import_validation;
 pub mod insert_cast_vote;
 pub mod insert_election_event;
 pub mod insert_tenant;
@@ -38,10 +41,12 @@ pub mod keys_ceremony;
 pub mod limit_access_by_countries;
 pub mod miru_plugin;
 pub mod permissions;
+pub mod provisional_results;
 pub mod reports;
 pub mod roles;
 pub mod scheduled_event;
 pub mod set_voter_authentication;
+pub mod sms_delivery_status;
 pub mod tally_ceremony;
 pub mod tally_sheets;
 pub mod templates;
@@ -0,0 +1,212 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::types::error_response::{ErrorCode, ErrorResponse, JsonError};
+use deadpool_postgres::Client as DbClient;
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::serde::json::Json;
+use serde::Deserialize;
+use tracing::{event, instrument, Level};
+use uuid::Uuid;
+use windmill::services::database::get_hasura_pool;
+use windmill::services::provisional_results::{
+    get_provisional_results_snapshot,
+    handle_election_event_presentation_update, verify_hasura_events_secret,
+    ElectionEventTriggerRow, PROVISIONAL_RESULTS_CACHE_CONTROL,
+};
+
+#[derive(Responder)]
+pub enum ProvisionalResultsResponse {
+    #[response(status = 200, content_type = "json")]
+    Feed {
+        feed: Vec<u8>,
+        etag: Header<'static>,
+        cache_control: Header<'static>,
+    },
+    #[response(status = 304)]
+    NotModified {
+        not_modified: (),
+        etag: Header<'static>,
+        cache_control: Header<'static>,
+    },
+}
+
+/// Entity tags of the `If-None-Match` request header, if any.
+#[derive(Debug)]
+pub struct IfNoneMatch(Option<String>);
+
+impl IfNoneMatch {
+    /// Weak comparison, as conditional GET requests use.
+    fn matches(&self, etag: &str) -> bool {
+        let Some(value) = &self.0 else {
+            return false;
+        };
+        value.split(',').map(str::trim).any(|tag| {
+            tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = ();
+
+    async fn from_request(
+        request: &'r Request<'_>,
+    ) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfNoneMatch(
+            request
+                .headers()
+                .get_one("If-None-Match")
+                .map(str::to_string),
+        ))
+    }
+}
+
+/// Logs the error and hides its details from the public.
+fn internal_error(message: &str, error: impl std::fmt::Debug) -> JsonError {
+    event!(Level::ERROR, "{message}: {error:?}");
+    ErrorResponse::new(
+        Status::InternalServerError,
+        "Internal server error",
+        ErrorCode::InternalServerError,
+    )
+}
+
+/// Public, read only, provisional results feed of an election event, served
+/// from its snapshot. The ETag is the version of the feed. Returns 404 if
+/// the election event doesn't exist or doesn't publish provisional results.
+#[instrument]
+#[get("/provisional-results/<tenant_id>/<election_event_id>")]
+pub async fn get_provisional_results(
+    tenant_id: String,
+    election_event_id: String,
+    if_none_match: IfNoneMatch,
+) -> Result<ProvisionalResultsResponse, JsonError> {
+    if Uuid::parse_str(&tenant_id).is_err()
+        || Uuid::parse_str(&election_event_id).is_err()
+    {
+        return Err(ErrorResponse::new(
+            Status::NotFound,
+            "Election event not found",
+            ErrorCode::ElectionEventNotFound,
+        ));
+    }
+
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .map_err(|e| internal_error("Error getting hasura client", e))?;
+    let hasura_transaction = hasura_db_client
+        .transaction()
+        .await
+        .map_err(|e| internal_error("Error starting transaction", e))?;
+
+    let snapshot = get_provisional_results_snapshot(
+        &hasura_transaction,
+        &tenant_id,
+        &election_event_id,
+    )
+    .await
+    .map_err(|e| internal_error("Error getting provisional results", e))?
+    .ok_or_else(|| {
+        ErrorResponse::new(
+            Status::NotFound,
+            "Provisional results are not published",
+            ErrorCode::ProvisionalResultsNotPublished,
+        )
+    })?;
+
+    hasura_transaction
+        .commit()
+        .await
+        .map_err(|e| internal_error("Error committing transaction", e))?;
+
+    let etag = format!("\"{}\"", snapshot.version);
+    let cache_control =
+        Header::new("Cache-Control", PROVISIONAL_RESULTS_CACHE_CONTROL);
+    if if_none_match.matches(&etag) {
+        return Ok(ProvisionalResultsResponse::NotModified {
+            not_modified: (),
+            etag: Header::new("ETag", etag),
+            cache_control,
+        });
+    }
+    Ok(ProvisionalResultsResponse::Feed {
+        feed: snapshot.data,
+        etag: Header::new("ETag", etag),
+        cache_control,
+    })
+}
+
+/// Secret header sent by the Hasura event triggers.
+#[derive(Debug)]
+pub struct HasuraEventsSecret(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HasuraEventsSecret {
+    type Error = ();
+
+    async fn from_request(
+        request: &'r Request<'_>,
+    ) -> Outcome<Self, Self::Error> {
+        Outcome::Success(HasuraEventsSecret(
+            request
+                .headers()
+                .get_one("X-Hasura-Events-Secret")
+                .map(str::to_string),
+        ))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ElectionEventTriggerData {
+    old: Option<ElectionEventTriggerRow>,
+    new: Option<ElectionEventTriggerRow>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ElectionEventTriggerEvent {
+    data: ElectionEventTriggerData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ElectionEventTriggerBody {
+    event: ElectionEventTriggerEvent,
+}
+
+/// Hasura event trigger of the updates of the election event presentation.
+/// Changes of the provisional results policy update the snapshot.
+#[instrument(skip(secret))]
+#[post(
+    "/election-event-presentation-updated",
+    format = "json",
+    data = "<body>"
+)]
+pub async fn election_event_presentation_updated(
+    secret: HasuraEventsSecret,
+    body: Json<ElectionEventTriggerBody>,
+) -> Result<Status, JsonError> {
+    verify_hasura_events_secret(&secret.0.unwrap_or_default()).map_err(
+        |e| {
+            ErrorResponse::new(
+                Status::Unauthorized,
+                &format!("{e:?}"),
+                ErrorCode::Unauthorized,
+            )
+        },
+    )?;
+
+    let data = body.into_inner().event.data;
+    let (Some(old), Some(new)) = (data.old, data.new) else {
+        return Ok(Status::Ok);
+    };
+    handle_election_event_presentation_update(&old, &new)
+        .await
+        .map_err(|e| internal_error("Error handling presentation update", e))?;
+
+    Ok(Status::Ok)
+}
@@ -0,0 +1,183 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::services::authorization::authorize;
+use crate::types::error_response::{ErrorCode, ErrorResponse, JsonError};
+use deadpool_postgres::Client as DbClient;
+use rocket::form::Form;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use sequent_core::services::jwt::JwtClaims;
+use sequent_core::types::permissions::Permissions;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::instrument;
+use windmill::services::database::get_hasura_pool;
+use windmill::services::sms_delivery::{
+    get_sms_delivery_summary, parse_json_delivery_status, record_sms_delivery,
+    verify_status_callback_token, SmsDeliveryStatus, SmsDeliverySummary,
+    SmsStatusPointers,
+};
+
+/// Status callback of the Twilio-compatible transport. Other fields sent by
+/// the provider are ignored.
+#[derive(FromForm, Debug)]
+pub struct TwilioStatusCallback {
+    #[field(name = "MessageSid")]
+    message_sid: String,
+    #[field(name = "MessageStatus")]
+    message_status: String,
+    #[field(name = "ErrorCode")]
+    error_code: Option<String>,
+}
+
+async fn record_delivery_status(
+    tenant_id: &str,
+    task_execution_id: &str,
+    token: &str,
+    delivery_status: SmsDeliveryStatus,
+) -> Result<Status, JsonError> {
+    verify_status_callback_token(tenant_id, task_execution_id, token).map_err(
+        |e| {
+            ErrorResponse::new(
+                Status::Unauthorized,
+                &format!("{e:?}"),
+                ErrorCode::Unauthorized,
+            )
+        },
+    )?;
+
+    record_sms_delivery(
+        tenant_id,
+        task_execution_id,
+        &delivery_status,
+        /* overwrite */ true,
+    )
+    .await
+    .map_err(|e| {
+        ErrorResponse::new(
+            Status::InternalServerError,
+            &format!("Error recording sms delivery status: {e:?}"),
+            ErrorCode::InternalServerError,
+        )
+    })?;
+
+    Ok(Status::Ok)
+}
+
+/// Delivery status reported by the SMS provider for a message sent by a
+/// send_template task execution. The request is authenticated by the token
+/// included in the callback url given to the provider.
+#[instrument(skip(token))]
+#[post(
+    "/sms-delivery-status?<tenant_id>&<task_execution_id>&<token>",
+    format = "form",
+    data = "<body>"
+)]
+pub async fn sms_delivery_status_form(
+    tenant_id: String,
+    task_execution_id: String,
+    token: String,
+    body: Form<TwilioStatusCallback>,
+) -> Result<Status, JsonError> {
+    let input = body.into_inner();
+    record_delivery_status(
+        &tenant_id,
+        &task_execution_id,
+        &token,
+        SmsDeliveryStatus {
+            message_id: input.message_sid,
+            status: input.message_status,
+            error_code: input.error_code.filter(|code| !code.is_empty()),
+            receiver: None,
+        },
+    )
+    .await
+}
+
+/// Same as `sms_delivery_status_form`, for the generic HTTP transport. The
+/// fields are read with the `SMS_HTTP_CALLBACK_*_POINTER` JSON pointers.
+#[instrument(skip(token))]
+#[post(
+    "/sms-delivery-status?<tenant_id>&<task_execution_id>&<token>",
+    format = "json",
+    data = "<body>",
+    rank = 2
+)]
+pub async fn sms_delivery_status_json(
+    tenant_id: String,
+    task_execution_id: String,
+    token: String,
+    body: Json<Value>,
+) -> Result<Status, JsonError> {
+    let delivery_status =
+        parse_json_delivery_status(&body, &SmsStatusPointers::from_env())
+            .map_err(|e| {
+                ErrorResponse::new(
+                    Status::BadRequest,
+                    &format!("{e:?}"),
+                    ErrorCode::InvalidDeliveryStatus,
+                )
+            })?;
+    record_delivery_status(
+        &tenant_id,
+        &task_execution_id,
+        &token,
+        delivery_status,
+    )
+    .await
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetSmsDeliverySummaryInput {
+    task_execution_id: String,
+}
+
+/// Delivery summary of the SMS messages sent by a send_template task
+/// execution.
+#[instrument(skip(claims))]
+#[post("/get-sms-delivery-summary", format = "json", data = "<body>")]
+pub async fn get_sms_delivery_summary_route(
+    body: Json<GetSmsDeliverySummaryInput>,
+    claims: JwtClaims,
+) -> Result<Json<SmsDeliverySummary>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::NOTIFICATION_READ],
+    )?;
+    let input = body.into_inner();
+    let tenant_id: String = claims.hasura_claims.tenant_id.clone();
+
+    let mut hasura_db_client: DbClient =
+        get_hasura_pool().await.get().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error loading hasura db client: {err}"),
+            )
+        })?;
+    let hasura_transaction =
+        hasura_db_client.transaction().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error creating a transaction: {err}"),
+            )
+        })?;
+
+    let summary = get_sms_delivery_summary(
+        &hasura_transaction,
+        &tenant_id,
+        &input.task_execution_id,
+    )
+    .await
+    .map_err(|err| {
+        (
+            Status::InternalServerError,
+            format!("Error getting sms delivery summary: {err:?}"),
+        )
+    })?;
+
+    Ok(Json(summary))
+}
@@ -12,20 +12,27 @@ use sequent_core::ballot::{
 };
 use sequent_core::serialization::deserialize_with_path;
 use sequent_core::services::jwt::decode_permission_labels;
+use sequent_core::types::ceremonies::RecountOverrides;
 use sequent_core::types::ceremonies::TallyExecutionStatus;
 use sequent_core::types::ceremonies::TallyType;
+use sequent_core::types::hasura::core::TasksExecution;
 use sequent_core::types::permissions::Permissions;
 use sequent_core::{
     services::jwt::JwtClaims, types::hasura::core::TallySessionConfiguration,
 };
 use serde::{Deserialize, Serialize};
 use tracing::{event, instrument, Level};
+use uuid::Uuid;
 use windmill::postgres::election::get_elections_by_ids;
 use windmill::postgres::tally_session::get_tally_session_by_id;
+use windmill::services::celery_app::get_celery_app;
 use windmill::services::providers::transactions_provider::provide_hasura_transaction;
+use windmill::services::tasks_execution::post;
 use windmill::services::{
     ceremonies::tally_ceremony, database::get_hasura_pool,
 };
+use windmill::tasks::execute_tally_recount::execute_tally_recount_task;
+use windmill::types::tasks::ETasksExecution;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateTallyCeremonyInput {
@@ -327,3 +334,92 @@ pub async fn restore_private_key(
     })?;
     Ok(Json(SetPrivateKeyOutput { is_valid }))
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecountTallySessionInput {
+    election_event_id: String,
+    tally_session_id: String,
+    overrides: Option<RecountOverrides>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecountTallySessionOutput {
+    document_id: String,
+    task_execution: TasksExecution,
+    error_msg: Option<String>,
+}
+
+// Recounts a completed tally session from its frozen snapshot, optionally
+// overriding contest policies and the tally sheets used
+#[instrument(skip(claims))]
+#[post("/recount-tally-session", format = "json", data = "<body>")]
+pub async fn recount_tally_session(
+    body: Json<RecountTallySessionInput>,
+    claims: JwtClaims,
+) -> Result<Json<RecountTallySessionOutput>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::ADMIN_CEREMONY, Permissions::TALLY_WRITE],
+    )?;
+    let input = body.into_inner();
+    let tenant_id = claims.hasura_claims.tenant_id.clone();
+
+    let executer_name = claims
+        .name
+        .clone()
+        .unwrap_or_else(|| claims.hasura_claims.user_id.clone());
+
+    let task_execution = post(
+        &tenant_id,
+        Some(&input.election_event_id),
+        ETasksExecution::RECOUNT_TALLY_SESSION,
+        &executer_name,
+    )
+    .await
+    .map_err(|error| {
+        (
+            Status::InternalServerError,
+            format!("Failed to insert task execution record: {error:?}"),
+        )
+    })?;
+
+    let document_id = Uuid::new_v4().to_string();
+    let celery_app = get_celery_app().await;
+
+    if let Err(err) = celery_app
+        .send_task(execute_tally_recount_task::new(
+            tenant_id,
+            input.election_event_id.clone(),
+            input.tally_session_id.clone(),
+            input.overrides.unwrap_or_default(),
+            document_id.clone(),
+            task_execution.clone(),
+            claims.hasura_claims.user_id.clone(),
+            claims.preferred_username.clone(),
+        ))
+        .await
+    {
+        return Ok(Json(RecountTallySessionOutput {
+            document_id,
+            task_execution,
+            error_msg: Some(format!(
+                "Failed to send RECOUNT_TALLY_SESSION task: {err:?}"
+            )),
+        }));
+    }
+
+    event!(
+        Level::INFO,
+        "Sent RECOUNT_TALLY_SESSION task, election_event_id={}, tally_session_id={}",
+        input.election_event_id,
+        input.tally_session_id,
+    );
+
+    Ok(Json(RecountTallySessionOutput {
+        document_id,
+        task_execution,
+        error_msg: None,
+    }))
+}
@@ -10,9 +10,15 @@ use rocket::serde::json::Json;
 use sequent_core::services::jwt::JwtClaims;
 use sequent_core::types::permissions::Permissions;
 use serde::{Deserialize, Serialize};
-use tracing::instrument;
+use tracing::{error, instrument};
 use windmill::postgres::tally_sheet;
 use windmill::services::database::get_hasura_pool;
+use windmill::services::provisional_results::enqueue_provisional_results_update;
+use windmill::services::tally_sheets::reconciliation::{
+    approve_tally_sheet as approve_tally_sheet_entry,
+    reconcile_tally_sheet as reconcile_tally_sheet_entries,
+    validate_tally_sheet_publication, TallySheetReconciliation,
+};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PublishTallySheetInput {
@@ -52,6 +58,17 @@ pub async fn publish_tally_sheet(
         .await
         .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
 
+    if input.publish {
+        validate_tally_sheet_publication(
+            &hasura_transaction,
+            &claims.hasura_claims.tenant_id,
+            &input.election_event_id,
+            &input.tally_sheet_id,
+        )
+        .await
+        .map_err(|e| (Status::BadRequest, format!("{:?}", e)))?;
+    }
+
     let found = tally_sheet::publish_tally_sheet(
         &hasura_transaction,
         &claims.hasura_claims.tenant_id,
@@ -75,7 +92,107 @@ pub async fn publish_tally_sheet(
         .with_context(|| "error comitting transaction")
         .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
 
+    if let Err(err) = enqueue_provisional_results_update(
+        &claims.hasura_claims.tenant_id,
+        &input.election_event_id,
+    )
+    .await
+    {
+        error!("Error enqueuing provisional results update: {err:?}");
+    }
+
     Ok(Json(PublishTallySheetOutput {
         tally_sheet_id: Some(input.tally_sheet_id.clone()),
     }))
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TallySheetReconciliationInput {
+    election_event_id: String,
+    tally_sheet_id: String,
+}
+
+// Compares the double entries of a tally sheet, returning the field level
+// differences
+#[instrument(skip(claims))]
+#[post("/reconcile-tally-sheet", format = "json", data = "<body>")]
+pub async fn reconcile_tally_sheet(
+    body: Json<TallySheetReconciliationInput>,
+    claims: JwtClaims,
+) -> Result<Json<TallySheetReconciliation>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::TALLY_SHEET_VIEW],
+    )?;
+    let input = body.into_inner();
+
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    let hasura_transaction = hasura_db_client
+        .transaction()
+        .await
+        .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    let reconciliation = reconcile_tally_sheet_entries(
+        &hasura_transaction,
+        &claims.hasura_claims.tenant_id,
+        &input.election_event_id,
+        &input.tally_sheet_id,
+    )
+    .await
+    .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    Ok(Json(reconciliation))
+}
+
+// Approves the current version of a tally sheet once its double entries
+// match
+#[instrument(skip(claims))]
+#[post("/approve-tally-sheet", format = "json", data = "<body>")]
+pub async fn approve_tally_sheet(
+    body: Json<TallySheetReconciliationInput>,
+    claims: JwtClaims,
+) -> Result<Json<TallySheetReconciliation>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::TALLY_SHEET_PUBLISH],
+    )?;
+    let input = body.into_inner();
+
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    let hasura_transaction = hasura_db_client
+        .transaction()
+        .await
+        .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    let reconciliation = approve_tally_sheet_entry(
+        &hasura_transaction,
+        &claims.hasura_claims.tenant_id,
+        &input.election_event_id,
+        &input.tally_sheet_id,
+        &claims.hasura_claims.user_id,
+    )
+    .await
+    .map_err(|e| (Status::BadRequest, format!("{:?}", e)))?;
+
+    hasura_transaction
+        .commit()
+        .await
+        .with_context(|| "error comitting transaction")
+        .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    Ok(Json(reconciliation))
+}
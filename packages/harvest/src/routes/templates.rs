@@ -4,12 +4,17 @@
 
 use crate::services::authorization::authorize;
 use anyhow::{anyhow, Result};
+use deadpool_postgres::Client as DbClient;
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use sequent_core::services::jwt::JwtClaims;
 use sequent_core::types::permissions::Permissions;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
+use windmill::services::communication_templates::{
+    preview_template, send_template_test, TemplatePreview, TemplatePreviewInput,
+};
+use windmill::services::database::get_hasura_pool;
 use windmill::services::reports::utils::get_public_asset_template;
 
 #[derive(Deserialize, Debug)]
@@ -71,3 +76,111 @@ pub async fn get_user_template(
         extra_config,
     }))
 }
+
+/// Renders a communication template for a voter, or a synthetic sample
+/// voter, reporting the missing variables as errors.
+#[instrument(skip(claims))]
+#[post("/preview-template", format = "json", data = "<body>")]
+pub async fn preview_template_route(
+    body: Json<TemplatePreviewInput>,
+    claims: JwtClaims,
+) -> Result<Json<TemplatePreview>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::TEMPLATE_READ],
+    )?;
+    let input = body.into_inner();
+    let tenant_id = claims.hasura_claims.tenant_id.clone();
+
+    let mut hasura_db_client: DbClient =
+        get_hasura_pool().await.get().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error loading hasura db client: {err}"),
+            )
+        })?;
+    let hasura_transaction =
+        hasura_db_client.transaction().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error creating a transaction: {err}"),
+            )
+        })?;
+
+    let preview = preview_template(&hasura_transaction, &tenant_id, &input)
+        .await
+        .map_err(|err| {
+            (
+                Status::BadRequest,
+                format!("Error previewing template: {err:?}"),
+            )
+        })?;
+
+    hasura_transaction.commit().await.map_err(|err| {
+        (
+            Status::InternalServerError,
+            format!("Error committing transaction: {err}"),
+        )
+    })?;
+
+    Ok(Json(preview))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SendTemplateTestBody {
+    #[serde(flatten)]
+    preview: TemplatePreviewInput,
+    /// Email address or phone number the test is sent to.
+    receiver: String,
+}
+
+/// Sends a communication template to a single receiver, to test it before
+/// launching a campaign.
+#[instrument(skip(claims))]
+#[post("/send-template-test", format = "json", data = "<body>")]
+pub async fn send_template_test_route(
+    body: Json<SendTemplateTestBody>,
+    claims: JwtClaims,
+) -> Result<Json<TemplatePreview>, (Status, String)> {
+    authorize(
+        &claims,
+        true,
+        Some(claims.hasura_claims.tenant_id.clone()),
+        vec![Permissions::TEMPLATE_READ, Permissions::NOTIFICATION_SEND],
+    )?;
+    let input = body.into_inner();
+    let tenant_id = claims.hasura_claims.tenant_id.clone();
+
+    let mut hasura_db_client: DbClient =
+        get_hasura_pool().await.get().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error loading hasura db client: {err}"),
+            )
+        })?;
+    let hasura_transaction =
+        hasura_db_client.transaction().await.map_err(|err| {
+            (
+                Status::InternalServerError,
+                format!("Error creating a transaction: {err}"),
+            )
+        })?;
+
+    let preview = send_template_test(
+        &hasura_transaction,
+        &tenant_id,
+        &input.preview,
+        &input.receiver,
+    )
+    .await
+    .map_err(|err| {
+        (
+            Status::BadRequest,
+            format!("Error sending template test: {err:?}"),
+        )
+    })?;
+
+    Ok(Json(preview))
+}
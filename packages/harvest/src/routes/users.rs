@@ -13,6 +13,7 @@ use rocket::serde::json::Json;
 use sequent_core::services::jwt;
 use sequent_core::services::keycloak::{get_event_realm, get_tenant_realm};
 use sequent_core::services::keycloak::{GroupInfo, KeycloakAdminClient};
+use sequent_core::types::hasura::core::TasksExecution;
 use sequent_core::types::keycloak::{
     User, UserProfileAttribute, PERMISSION_LABELS, TENANT_ID_ATTR_NAME,
 };
@@ -35,6 +36,7 @@ use windmill::services::datafix::utils::is_datafix_election_event;
 use windmill::services::export::export_users::{
     ExportBody, ExportTenantUsersBody, ExportUsersBody,
 };
+use windmill::services::import::census_sync::CensusSyncInput;
 use windmill::services::keycloak_events::list_keycloak_events_by_type;
 use windmill::services::tasks_execution::*;
 use windmill::services::users::list_users_has_voted;
@@ -44,6 +46,7 @@ use windmill::services::users::{
 use windmill::services::users::{FilterOption, ListUsersFilter};
 use windmill::tasks::export_users::{self, ExportUsersOutput};
 use windmill::tasks::import_users::{self, ImportUsersOutput};
+use windmill::tasks::sync_census::sync_census_task;
 use windmill::types::tasks::ETasksExecution;
 
 #[derive(Deserialize, Debug)]
@@ -843,6 +846,96 @@ pub async fn import_users_f(
     Ok(Json(output))
 }
 
+#[derive(Deserialize, Debug)]
+pub struct SyncCensusBody {
+    election_event_id: String,
+    document_id: String,
+    sha256: Option<String>,
+    key_attribute: Option<String>,
+    dry_run: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SyncCensusOutput {
+    document_id: String,
+    task_execution: TasksExecution,
+    error_msg: Option<String>,
+}
+
+// Synchronises the voters of an election event with a full electoral roll,
+// adding, updating and disabling voters. The report with the changes is
+// uploaded as `document_id`
+#[instrument(skip(claims))]
+#[post("/sync-census", format = "json", data = "<body>")]
+pub async fn sync_census(
+    claims: jwt::JwtClaims,
+    body: Json<SyncCensusBody>,
+) -> Result<Json<SyncCensusOutput>, (Status, String)> {
+    let input = body.into_inner();
+    let tenant_id = claims.hasura_claims.tenant_id.clone();
+    authorize(
+        &claims,
+        true,
+        Some(tenant_id.clone()),
+        vec![Permissions::VOTER_IMPORT, Permissions::VOTER_WRITE],
+    )?;
+
+    let user_id = claims.hasura_claims.user_id.clone();
+    let executer_name = claims.name.clone().unwrap_or_else(|| user_id.clone());
+
+    let task_execution = post(
+        &tenant_id,
+        Some(&input.election_event_id),
+        ETasksExecution::SYNC_CENSUS,
+        &executer_name,
+    )
+    .await
+    .map_err(|error| {
+        (
+            Status::InternalServerError,
+            format!("Failed to insert task execution record: {error:?}"),
+        )
+    })?;
+
+    let document_id = Uuid::new_v4().to_string();
+    let task_input = CensusSyncInput {
+        tenant_id,
+        election_event_id: input.election_event_id.clone(),
+        document_id: input.document_id,
+        sha256: input.sha256,
+        key_attribute: input.key_attribute,
+        dry_run: input.dry_run.unwrap_or(false),
+        report_document_id: document_id.clone(),
+        user_id,
+        username: claims.preferred_username.clone(),
+    };
+    let celery_app = get_celery_app().await;
+
+    if let Err(err) = celery_app
+        .send_task(sync_census_task::new(task_input, task_execution.clone()))
+        .await
+    {
+        return Ok(Json(SyncCensusOutput {
+            document_id,
+            task_execution,
+            error_msg: Some(format!(
+                "Failed to send SYNC_CENSUS task: {err:?}"
+            )),
+        }));
+    }
+
+    info!(
+        "Sent SYNC_CENSUS task {}, election_event_id={}",
+        task_execution.id, input.election_event_id
+    );
+
+    Ok(Json(SyncCensusOutput {
+        document_id,
+        task_execution,
+        error_msg: None,
+    }))
+}
+
 #[instrument(skip(claims))]
 #[post("/export-users", format = "json", data = "<input>")]
 pub async fn export_users_f(
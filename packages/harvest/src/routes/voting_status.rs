@@ -11,8 +11,12 @@ use sequent_core::services::jwt::{has_gold_permission, JwtClaims};
 use sequent_core::types::permissions::Permissions;
 use sequent_core::types::tally_sheets::VotingChannel;
 use serde::{Deserialize, Serialize};
-use tracing::instrument;
+use tracing::{error, instrument};
+use windmill::services::census_snapshot::{
+    enqueue_census_snapshot, is_voting_started,
+};
 use windmill::services::database::get_hasura_pool;
+use windmill::services::provisional_results::enqueue_provisional_results_update;
 use windmill::services::{election_event_status, voting_status};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -62,6 +66,15 @@ pub async fn update_event_status(
         .await
         .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
 
+    let was_voting_started = is_voting_started(
+        &hasura_transaction,
+        tenant_id,
+        &input.election_event_id,
+        None,
+    )
+    .await
+    .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
     election_event_status::update_event_voting_status(
         &hasura_transaction,
         tenant_id,
@@ -79,6 +92,22 @@ pub async fn update_event_status(
         .await
         .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
 
+    if let Err(err) =
+        enqueue_provisional_results_update(tenant_id, &input.election_event_id)
+            .await
+    {
+        error!("Error enqueuing provisional results update: {err:?}");
+    }
+    // Freeze the census the voting period starts with
+    if !was_voting_started && input.voting_status == VotingStatus::OPEN {
+        if let Err(err) =
+            enqueue_census_snapshot(tenant_id, &input.election_event_id, None)
+                .await
+        {
+            error!("Error enqueuing census snapshot: {err:?}");
+        }
+    }
+
     Ok(Json(UpdateEventVotingStatusOutput {
         election_event_id: input.election_event_id.clone(),
     }))
@@ -121,6 +150,16 @@ pub async fn update_election_status(
         .transaction()
         .await
         .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
+    let was_voting_started = is_voting_started(
+        &hasura_transaction,
+        &tenant_id,
+        &input.election_event_id,
+        Some(&input.election_id),
+    )
+    .await
+    .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
+
     voting_status::update_election_status(
         tenant_id,
         Some(&user_id),
@@ -139,6 +178,27 @@ pub async fn update_election_status(
         .await
         .map_err(|e| (Status::InternalServerError, format!("{:?}", e)))?;
 
+    if let Err(err) = enqueue_provisional_results_update(
+        &claims.hasura_claims.tenant_id,
+        &input.election_event_id,
+    )
+    .await
+    {
+        error!("Error enqueuing provisional results update: {err:?}");
+    }
+    // Freeze the census the voting period starts with
+    if !was_voting_started && input.voting_status == VotingStatus::OPEN {
+        if let Err(err) = enqueue_census_snapshot(
+            &claims.hasura_claims.tenant_id,
+            &input.election_event_id,
+            Some(&input.election_id),
+        )
+        .await
+        {
+            error!("Error enqueuing census snapshot: {err:?}");
+        }
+    }
+
     Ok(Json(voting_status::UpdateElectionVotingStatusOutput {
         election_id: input.election_id.clone(),
     }))
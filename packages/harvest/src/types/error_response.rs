@@ -35,6 +35,9 @@ pub enum ErrorCode {
     InvalidEventProcessor,
     ConfirmPolicyShowCastVoteLogsFailed,
     BallotIdMismatch,
+    InvalidDeliveryStatus,
+    InvalidEmailNotification,
+    ProvisionalResultsNotPublished,
     // Add any other needed error codes
 }
 
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigUint;
+use sequent_core::ballot_codec::get_decoders;
+use sequent_core::fixtures::ballot_codec::{
+    get_fixtures, get_writein_ballot_style,
+};
+
+// Tally pipelines decode ballots from decrypted BigUint values, which are
+// not bound to 29 bytes. Every registered decoder must reject them without
+// panicking.
+fuzz_target!(|data: &[u8]| {
+    let bigint = BigUint::from_bytes_le(data);
+    let ballot_style = get_writein_ballot_style();
+    for decoder in get_decoders() {
+        for fixture in get_fixtures() {
+            let _ = decoder.decode_contest_bigint(&fixture.contest, &bigint);
+        }
+        let _ = decoder.decode_multi_contest_bigint(
+            &ballot_style.contests,
+            &bigint,
+            None,
+        );
+    }
+});
@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sequent_core::ballot_codec::multi_ballot::BallotChoices;
+use sequent_core::fixtures::ballot_codec::get_writein_ballot_style;
+
+// Decoding an adversarial multi contest plaintext must return an error,
+// never panic.
+fuzz_target!(|plaintext: [u8; 30]| {
+    let ballot_style = get_writein_ballot_style();
+    let _ = BallotChoices::decode_from_30_bytes(&plaintext, &ballot_style);
+});
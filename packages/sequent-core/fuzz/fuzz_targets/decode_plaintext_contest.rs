@@ -0,0 +1,20 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sequent_core::ballot_codec::PlaintextCodec;
+use sequent_core::fixtures::ballot_codec::{
+    get_fixtures, get_writein_ballot_style,
+};
+
+// Decoding an adversarial plaintext must return an error, never panic.
+fuzz_target!(|plaintext: [u8; 30]| {
+    for fixture in get_fixtures() {
+        let _ = fixture.contest.decode_plaintext_contest(&plaintext);
+    }
+    for contest in get_writein_ballot_style().contests {
+        let _ = contest.decode_plaintext_contest(&plaintext);
+    }
+});
@@ -8,7 +8,7 @@ use crate::error::BallotError;
 use crate::serialization::base64::{Base64Deserialize, Base64Serialize};
 use crate::serialization::deserialize_with_path::deserialize_value;
 use crate::types::ceremonies::CeremoniesPolicy;
-use crate::types::hasura::core::{self, Area, ElectionEvent};
+use crate::types::hasura::core::{self as hasura_core, Area, ElectionEvent};
 use ::core::convert::TryInto;
 use borsh::{BorshDeserialize, BorshSerialize};
 use chrono::DateTime;
@@ -73,7 +73,7 @@ pub struct RawAuditableBallot<C: Ctx> {
     pub ballot_hash: String,
 }*/
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 pub struct AuditableBallot {
     pub version: u32,
     pub issue_date: String,
@@ -123,7 +123,14 @@ pub struct HashableBallotContest<C: Ctx> {
 }
 
 #[derive(
-    BorshSerialize, Serialize, Deserialize, PartialEq, Eq, Debug, Clone,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    Debug,
+    Clone,
 )]
 pub struct HashableBallot {
     pub version: u32,
@@ -133,7 +140,7 @@ pub struct HashableBallot {
     pub ballot_style_hash: String,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 pub struct SignedHashableBallot {
     pub version: u32,
     pub issue_date: String,
@@ -978,6 +985,10 @@ pub struct ElectionEventPresentation {
     pub voter_signing_policy: Option<VoterSigningPolicy>,
     pub weighted_voting_policy: Option<WeightedVotingPolicy>,
     pub ceremonies_policy: Option<CeremoniesPolicy>,
+    /// IANA time zone (like `Europe/Madrid`) used for scheduled dates, cron
+    /// evaluation and date formatting in templates.
+    pub time_zone: Option<String>,
+    pub provisional_results_policy: Option<ProvisionalResultsPolicy>,
 }
 
 impl ElectionEvent {
@@ -1030,6 +1041,7 @@ pub enum EGracePeriodPolicy {
 pub struct VotingPeriodDates {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
+    pub time_zone: Option<String>,
 }
 
 #[allow(non_camel_case_types)]
@@ -1230,7 +1242,7 @@ pub struct ElectionPresentation {
     pub security_confirmation_policy: Option<ESecurityConfirmationPolicy>,
 }
 
-impl core::Election {
+impl hasura_core::Election {
     pub fn get_presentation(&self) -> Option<ElectionPresentation> {
         let election_presentation: Option<ElectionPresentation> = self
             .presentation
@@ -1395,6 +1407,10 @@ impl Default for ContestPresentation {
     }
 }
 
+/// Counting algorithms of ranked (preferential) contests.
+pub const RANKED_COUNTING_ALGORITHMS: [&str; 2] =
+    ["instant-runoff", "single-transferable-vote"];
+
 #[derive(
     BorshSerialize,
     BorshDeserialize,
@@ -1422,7 +1438,7 @@ pub struct Contest {
     pub min_votes: i64,
     pub winning_candidates_num: i64,
     pub voting_type: Option<String>,
-    pub counting_algorithm: Option<String>, /* plurality-at-large|borda-nauru|borda|borda-mas-madrid|desborda3|desborda2|desborda|cumulative */
+    pub counting_algorithm: Option<String>, /* plurality-at-large|borda-nauru|borda|borda-mas-madrid|desborda3|desborda2|desborda|cumulative|instant-runoff|single-transferable-vote */
     pub is_encrypted: bool,
     pub candidates: Vec<Candidate>,
     pub presentation: Option<ContestPresentation>,
@@ -1445,6 +1461,13 @@ impl Contest {
             .unwrap_or("plurality-at-large".into())
     }
 
+    /// Ranked contests encode the position of each candidate in the voter's
+    /// ranking, and their ballots must rank candidates consecutively.
+    pub fn is_ranked(&self) -> bool {
+        RANKED_COUNTING_ALGORITHMS
+            .contains(&self.get_counting_algorithm().as_str())
+    }
+
     pub fn base32_writeins(&self) -> bool {
         self.presentation
             .as_ref()
@@ -1669,6 +1692,33 @@ pub enum Publish {
     AFTER_LOCKDOWN,
 }
 
+/// Whether the public provisional results feed of the election event is
+/// published. Results of each election are embargoed until its polls close.
+#[allow(non_camel_case_types)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Default,
+    Display,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    EnumString,
+    JsonSchema,
+)]
+pub enum ProvisionalResultsPolicy {
+    #[default]
+    #[strum(serialize = "disabled")]
+    #[serde(rename = "disabled")]
+    DISABLED,
+    #[strum(serialize = "after-polls-close")]
+    #[serde(rename = "after-polls-close")]
+    AFTER_POLLS_CLOSE,
+}
+
 #[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 #[serde(default)]
 pub struct ElectionEventStatus {
@@ -1883,7 +1933,7 @@ pub enum VotingStatusChannel {
 impl VotingStatusChannel {
     pub fn channel_from(
         &self,
-        channels: &core::VotingChannels,
+        channels: &hasura_core::VotingChannels,
     ) -> Option<bool> {
         match self {
             &VotingStatusChannel::ONLINE => channels.online.clone(),
@@ -2099,6 +2149,7 @@ pub struct ReportDates {
 pub struct ScheduledEventDates {
     pub scheduled_at: Option<String>,
     pub stopped_at: Option<String>,
+    pub time_zone: Option<String>,
 }
 
 impl PeriodDates {
@@ -2255,6 +2306,7 @@ impl ElectionStatus {
     BorshDeserialize,
     Serialize,
     Deserialize,
+    JsonSchema,
     PartialEq,
     Eq,
     Debug,
@@ -2277,6 +2329,20 @@ pub struct BallotStyle {
     pub election_event_annotations: Option<HashMap<String, String>>,
     pub election_annotations: Option<HashMap<String, String>>,
     pub area_annotations: Option<AreaAnnotations>,
+    /// Version of the ballot codec used to encode the plaintexts of this
+    /// ballot style. Ballot styles created before the codec was versioned
+    /// don't have it and use the legacy encoding. It is skipped by the
+    /// Borsh serialization so that the hash of legacy ballot styles doesn't
+    /// change, see `encrypt::hash_ballot_style_sha512`.
+    #[borsh(skip)]
+    pub codec_version: Option<u8>,
+}
+
+impl BallotStyle {
+    pub fn get_codec_version(&self) -> u8 {
+        self.codec_version
+            .unwrap_or(crate::ballot_codec::LEGACY_CODEC_VERSION)
+    }
 }
 
 #[derive(
@@ -2305,6 +2371,7 @@ pub struct CustomUrls {
     Copy,
     Serialize,
     Deserialize,
+    JsonSchema,
     BorshSerialize,
     BorshDeserialize,
 )]
@@ -2331,6 +2398,7 @@ impl Deref for Weight {
     Clone,
     Serialize,
     Deserialize,
+    JsonSchema,
     BorshSerialize,
     BorshDeserialize,
     Default,
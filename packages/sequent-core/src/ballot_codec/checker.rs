@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 use crate::ballot_codec::multi_ballot::DecodedContestChoices;
-use crate::plaintext::DecodedVoteContest;
+use crate::plaintext::{DecodedVoteChoice, DecodedVoteContest};
 use crate::{
     ballot::{
         ContestPresentation, EBlankVotePolicy, EOverVotePolicy,
@@ -11,7 +11,7 @@ use crate::{
     },
     plaintext::{InvalidPlaintextError, InvalidPlaintextErrorType},
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[derive(Default, PartialEq, Eq, Debug, Clone)]
 pub struct CheckerResult {
@@ -262,3 +262,156 @@ pub fn check_invalid_vote_policy(
     }
     checker_result
 }
+
+/// Checks the choices of a ranked contest, where `selected` is the zero based
+/// rank given to each candidate and -1 means it was not ranked. Rankings must
+/// be a permutation of the first ranks: each candidate ranked at most once,
+/// each rank given to a single candidate and no rank skipped.
+pub fn check_ranked_choices(choices: &Vec<DecodedVoteChoice>) -> CheckerResult {
+    let mut checker_result: CheckerResult = Default::default();
+
+    let ranked_choices: Vec<&DecodedVoteChoice> = choices
+        .iter()
+        .filter(|choice| choice.selected > -1)
+        .collect();
+
+    // duplicate rank: the same candidate appears ranked more than once
+    let mut seen_candidates: HashSet<&String> = HashSet::new();
+    let mut duplicated_candidates: HashSet<&String> = HashSet::new();
+    for choice in &ranked_choices {
+        if !seen_candidates.insert(&choice.id)
+            && duplicated_candidates.insert(&choice.id)
+        {
+            checker_result.invalid_errors.push(InvalidPlaintextError {
+                error_type: InvalidPlaintextErrorType::DuplicateRank,
+                candidate_id: Some(choice.id.clone()),
+                message: Some("errors.implicit.duplicateRank".to_string()),
+                message_map: HashMap::new(),
+            });
+        }
+    }
+
+    // rank -> number of candidates with that rank, sorted by rank
+    let mut rank_count: BTreeMap<i64, usize> = BTreeMap::new();
+    for choice in &ranked_choices {
+        *rank_count.entry(choice.selected).or_insert(0) += 1;
+    }
+
+    // overvote at rank: more than one candidate with the same rank
+    for (rank, count) in rank_count.iter().filter(|(_, count)| **count > 1) {
+        checker_result.invalid_errors.push(InvalidPlaintextError {
+            error_type: InvalidPlaintextErrorType::OvervoteAtRank,
+            candidate_id: None,
+            message: Some("errors.implicit.overvoteAtRank".to_string()),
+            message_map: HashMap::from([
+                ("rank".to_string(), (rank + 1).to_string()),
+                ("numSelected".to_string(), count.to_string()),
+            ]),
+        });
+    }
+
+    // skipped rank: the used ranks must be 0, 1, 2, ... without gaps. We only
+    // report the first skipped rank.
+    let skipped_rank = rank_count
+        .keys()
+        .enumerate()
+        .find(|(expected, rank)| **rank != *expected as i64)
+        .map(|(expected, _)| expected);
+    if let Some(skipped_rank) = skipped_rank {
+        checker_result.invalid_errors.push(InvalidPlaintextError {
+            error_type: InvalidPlaintextErrorType::SkippedRank,
+            candidate_id: None,
+            message: Some("errors.implicit.skippedRank".to_string()),
+            message_map: HashMap::from([(
+                "rank".to_string(),
+                (skipped_rank + 1).to_string(),
+            )]),
+        });
+    }
+
+    checker_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(ranks: Vec<(&str, i64)>) -> Vec<DecodedVoteChoice> {
+        ranks
+            .into_iter()
+            .map(|(id, selected)| DecodedVoteChoice {
+                id: id.to_string(),
+                selected,
+                write_in_text: None,
+            })
+            .collect()
+    }
+
+    fn error_types(result: &CheckerResult) -> Vec<InvalidPlaintextErrorType> {
+        result
+            .invalid_errors
+            .iter()
+            .map(|error| error.error_type.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_check_ranked_choices_valid() {
+        let result = check_ranked_choices(&ranked(vec![
+            ("a", 1),
+            ("b", -1),
+            ("c", 0),
+            ("d", 2),
+        ]));
+        assert_eq!(result, CheckerResult::default());
+        assert_eq!(
+            check_ranked_choices(&ranked(vec![("a", -1), ("b", -1)])),
+            CheckerResult::default()
+        );
+    }
+
+    #[test]
+    fn test_check_ranked_choices_duplicate_rank() {
+        let result =
+            check_ranked_choices(&ranked(vec![("a", 0), ("b", 1), ("a", 2)]));
+        assert_eq!(
+            error_types(&result),
+            vec![InvalidPlaintextErrorType::DuplicateRank]
+        );
+        assert_eq!(result.invalid_errors[0].candidate_id, Some("a".into()));
+    }
+
+    #[test]
+    fn test_check_ranked_choices_overvote_at_rank() {
+        let result =
+            check_ranked_choices(&ranked(vec![("a", 0), ("b", 0), ("c", 1)]));
+        assert_eq!(
+            error_types(&result),
+            vec![InvalidPlaintextErrorType::OvervoteAtRank]
+        );
+        assert_eq!(
+            result.invalid_errors[0].message_map.get("rank"),
+            Some(&"1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check_ranked_choices_skipped_rank() {
+        let result =
+            check_ranked_choices(&ranked(vec![("a", 0), ("b", 2), ("c", 3)]));
+        assert_eq!(
+            error_types(&result),
+            vec![InvalidPlaintextErrorType::SkippedRank]
+        );
+        assert_eq!(
+            result.invalid_errors[0].message_map.get("rank"),
+            Some(&"2".to_string())
+        );
+
+        let result = check_ranked_choices(&ranked(vec![("a", 1)]));
+        assert_eq!(
+            error_types(&result),
+            vec![InvalidPlaintextErrorType::SkippedRank]
+        );
+    }
+}
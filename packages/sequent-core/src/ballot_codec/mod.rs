@@ -9,7 +9,10 @@ pub mod checker;
 pub mod multi_ballot;
 pub mod plaintext_contest;
 pub mod raw_ballot;
+#[cfg(test)]
+mod roundtrip_tests;
 pub mod vec;
+pub mod version;
 
 pub use bases::*;
 pub use bigint::*;
@@ -18,5 +21,6 @@ pub use checker::*;
 pub use plaintext_contest::*;
 pub use raw_ballot::*;
 pub use vec::*;
+pub use version::*;
 
 pub trait BallotCodec: BasesCodec + PlaintextCodec + RawBallotCodec {}
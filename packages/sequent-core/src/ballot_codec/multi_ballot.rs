@@ -5,7 +5,7 @@ use std::num::TryFromIntError;
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 use super::bigint;
-use super::{vec, RawBallotContest};
+use super::{vec, version, RawBallotContest};
 use crate::ballot::{
     AreaPresentation, BallotStyle, Candidate, Contest, EUnderVotePolicy,
 };
@@ -170,6 +170,7 @@ impl BallotChoices {
     /// 2) RawBallotContest -> BigUint
     /// 3) BigUint -> Vec<u8>
     /// 4) Vec<u8> -> [u8; 30]
+    /// 5) The codec version of the ballot style is stored in the header
     ///
     /// Returns a fixed-size array of 30 bytes encoding this ballot.
     pub fn encode_to_30_bytes(
@@ -183,7 +184,12 @@ impl BallotChoices {
 
         let bytes = bigint::encode_bigint_to_bytes(&bigint)?;
 
-        vec::encode_vec_to_array(&bytes)
+        let mut code = vec::encode_vec_to_array(&bytes)?;
+        version::set_plaintext_codec_version(
+            &mut code,
+            config.get_codec_version(),
+        )?;
+        Ok(code)
     }
 
     /// Encode this multi-ballot into a mixed radix representation
@@ -420,10 +426,12 @@ impl BallotChoices {
         bytes: &[u8; 30],
         style: &BallotStyle,
     ) -> Result<DecodedBallotChoices, String> {
+        let decoder =
+            version::get_decoder(version::get_plaintext_codec_version(bytes))?;
         let bytes = vec::decode_array_to_vec(&bytes);
         let bigint = bigint::decode_bigint_from_bytes(&bytes)?;
 
-        Self::decode_from_bigint(&bigint, &style.contests, None)
+        decoder.decode_multi_contest_bigint(&style.contests, &bigint, None)
     }
 
     /// Returns a decoded ballot from a BigUint
@@ -766,6 +774,12 @@ impl BallotChoices {
         let mut index = 0usize;
 
         while accumulator > Zero::zero() {
+            if index >= bases.len() {
+                return Err(format!(
+                    "Encoded value has more than {} choices",
+                    bases.len()
+                ));
+            }
             let base: BigUint = bases[index].to_biguint().ok_or_else(|| {
                 format!(
                     "Error converting to biguint: bases[index={index:?}]={val}",
@@ -1198,6 +1212,7 @@ mod tests {
             election_event_annotations: None,
             election_annotations: None,
             area_annotations: None,
+            codec_version: None,
         }
     }
 
@@ -12,6 +12,12 @@ pub trait PlaintextCodec {
         plaintext: &DecodedVoteContest,
     ) -> Result<[u8; 30], String>;
 
+    fn encode_plaintext_contest_with_version(
+        &self,
+        plaintext: &DecodedVoteContest,
+        codec_version: u8,
+    ) -> Result<[u8; 30], String>;
+
     fn decode_plaintext_contest(
         &self,
         code: &[u8; 30],
@@ -42,13 +48,24 @@ impl PlaintextCodec for Contest {
         encode_vec_to_array(&plaintext_bytes_vec)
     }
 
+    fn encode_plaintext_contest_with_version(
+        &self,
+        plaintext: &DecodedVoteContest,
+        codec_version: u8,
+    ) -> Result<[u8; 30], String> {
+        let mut code = self.encode_plaintext_contest(plaintext)?;
+        set_plaintext_codec_version(&mut code, codec_version)?;
+        Ok(code)
+    }
+
     fn decode_plaintext_contest(
         &self,
         code: &[u8; 30],
     ) -> Result<DecodedVoteContest, String> {
-        let plaintext_bytes = decode_array_to_vec(code);
+        let decoder = get_decoder(get_plaintext_codec_version(code))?;
+        let bigint = self.decode_plaintext_contest_to_biguint(code)?;
 
-        self.decode_plaintext_contest_from_bytes(&plaintext_bytes)
+        decoder.decode_contest_bigint(self, &bigint)
     }
 
     fn decode_plaintext_contest_to_biguint(
@@ -108,6 +108,13 @@ impl RawBallotCodec for Contest {
                 // selected and 0 otherwise
                 choices.push(u64::from(choice.selected > -1));
             } else {
+                if self.is_ranked() && choice.selected >= self.max_votes {
+                    return Err(format!(
+                        "rank {} is out of range, maximum rank is {}",
+                        choice.selected + 1,
+                        self.max_votes
+                    ));
+                }
                 // we add 1 because the counting starts with 1, as zero means
                 // this candidate was not voted / ranked
                 let value =
@@ -378,6 +385,12 @@ impl RawBallotCodec for Contest {
         );
         decoded_contest.update(blank_vote_check);
 
+        // ranked contests must rank candidates consecutively, one per rank
+        if self.is_ranked() {
+            let ranked_check = check_ranked_choices(&decoded_contest.choices);
+            decoded_contest.update(ranked_check);
+        }
+
         Ok(decoded_contest)
     }
 }
@@ -390,6 +403,7 @@ mod tests {
     use crate::ballot_codec::*;
     use crate::fixtures::ballot_codec::*;
     use crate::mixed_radix::encode;
+    use crate::plaintext::*;
     use std::cmp;
 
     #[test]
@@ -553,4 +567,77 @@ mod tests {
             }
         }
     }
+
+    fn ranked_plaintext(
+        contest: &ballot::Contest,
+        ranks: Vec<i64>,
+    ) -> DecodedVoteContest {
+        DecodedVoteContest {
+            contest_id: contest.id.clone(),
+            is_explicit_invalid: false,
+            invalid_errors: vec![],
+            invalid_alerts: vec![],
+            choices: contest
+                .candidates
+                .iter()
+                .zip(ranks)
+                .map(|(candidate, selected)| DecodedVoteChoice {
+                    id: candidate.id.clone(),
+                    selected,
+                    write_in_text: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_ranked_contest_round_trip() {
+        let contest = get_configurable_contest(
+            3,
+            4,
+            "instant-runoff".to_string(),
+            false,
+            None,
+            true,
+        );
+        assert!(contest.is_ranked());
+
+        let cases = vec![
+            (vec![1, -1, 0, 2], None),
+            (vec![-1, -1, -1, -1], None),
+            (
+                vec![0, 2, -1, -1],
+                Some(InvalidPlaintextErrorType::SkippedRank),
+            ),
+            (
+                vec![0, 0, 1, -1],
+                Some(InvalidPlaintextErrorType::OvervoteAtRank),
+            ),
+        ];
+        for (ranks, expected_error) in cases {
+            let plaintext = ranked_plaintext(&contest, ranks.clone());
+            let raw_ballot = contest.encode_to_raw_ballot(&plaintext).unwrap();
+            let decoded = contest.decode_from_raw_ballot(&raw_ballot).unwrap();
+
+            let decoded_ranks: Vec<i64> =
+                decoded.choices.iter().map(|c| c.selected).collect();
+            assert_eq!(decoded_ranks, ranks);
+
+            let error_types: Vec<InvalidPlaintextErrorType> = decoded
+                .invalid_errors
+                .iter()
+                .map(|error| error.error_type.clone())
+                .collect();
+            assert_eq!(
+                error_types,
+                expected_error.into_iter().collect::<Vec<_>>(),
+                "ranks {:?}",
+                ranks
+            );
+        }
+
+        // ranks are limited by max_votes
+        let plaintext = ranked_plaintext(&contest, vec![0, 1, 2, 3]);
+        assert!(contest.encode_to_raw_ballot(&plaintext).is_err());
+    }
 }
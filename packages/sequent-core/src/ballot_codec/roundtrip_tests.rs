@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Property based tests for the ballot codec. Random contests and plaintexts
+//! are encoded, encrypted, decrypted and decoded, and the result must match
+//! the input. Decoders must also reject arbitrary plaintexts without panics.
+
+use crate::ballot::Contest;
+use crate::ballot_codec::multi_ballot::BallotChoices;
+use crate::ballot_codec::*;
+use crate::encrypt::{
+    encode_to_plaintext_decoded_multi_contest, encrypt_plaintext_candidate,
+    DEFAULT_PLAINTEXT_LABEL,
+};
+use crate::fixtures::ballot_codec::*;
+use crate::fixtures::generators::*;
+use crate::plaintext::{
+    map_decoded_ballot_choices_to_decoded_contests, DecodedVoteContest,
+};
+use crate::util::normalize_vote::{normalize_election, normalize_vote_contest};
+
+use num_bigint::BigUint;
+use proptest::prelude::*;
+use strand::backend::ristretto::RistrettoCtx;
+use strand::context::Ctx;
+use strand::elgamal::PrivateKey;
+
+fn encrypt_and_decrypt(plaintext: [u8; 30]) -> [u8; 30] {
+    let ctx = RistrettoCtx;
+    let sk = PrivateKey::gen(&ctx);
+    let (choice, _proof) = encrypt_plaintext_candidate(
+        &ctx,
+        sk.pk_element().clone(),
+        plaintext,
+        &DEFAULT_PLAINTEXT_LABEL,
+    )
+    .unwrap();
+    ctx.decode(&sk.decrypt(&choice.ciphertext))
+}
+
+fn normalize(
+    contest: &Contest,
+    input: &DecodedVoteContest,
+) -> DecodedVoteContest {
+    normalize_vote_contest(
+        input,
+        contest.get_counting_algorithm().as_str(),
+        true,
+        &contest.get_invalid_candidate_ids(),
+    )
+}
+
+fn has_error(decoded: &DecodedVoteContest, message: &str) -> bool {
+    decoded
+        .invalid_errors
+        .iter()
+        .any(|error| error.message.as_deref() == Some(message))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_contest_round_trip(
+        (contest, plaintext) in arb_contest_and_plaintext()
+    ) {
+        let encoded = contest.encode_plaintext_contest_with_version(
+            &plaintext,
+            CURRENT_CODEC_VERSION,
+        );
+        // long write-ins may not fit in a single plaintext
+        prop_assume!(
+            !matches!(&encoded, Err(err) if err.contains("too long"))
+        );
+        let encoded = encoded.unwrap();
+
+        let decrypted = encrypt_and_decrypt(encoded);
+        prop_assert_eq!(decrypted, encoded);
+
+        let decoded = contest.decode_plaintext_contest(&decrypted).unwrap();
+        prop_assert_eq!(
+            normalize(&contest, &decoded),
+            normalize(&contest, &plaintext)
+        );
+    }
+
+    #[test]
+    fn prop_checker_consistency(
+        (contest, plaintext) in arb_contest_and_plaintext()
+    ) {
+        let encoded = contest.encode_plaintext_contest(&plaintext);
+        prop_assume!(
+            !matches!(&encoded, Err(err) if err.contains("too long"))
+        );
+        let decoded =
+            contest.decode_plaintext_contest(&encoded.unwrap()).unwrap();
+
+        let num_selected = plaintext
+            .choices
+            .iter()
+            .filter(|choice| choice.selected > -1)
+            .count() as i64;
+        prop_assert_eq!(
+            has_error(&decoded, "errors.implicit.selectedMax"),
+            num_selected > contest.max_votes
+        );
+        prop_assert_eq!(
+            has_error(&decoded, "errors.implicit.selectedMin"),
+            num_selected < contest.min_votes
+        );
+        if contest.is_ranked() {
+            // generated rankings are always consecutive and distinct
+            prop_assert!(!has_error(&decoded, "errors.implicit.skippedRank"));
+            prop_assert!(
+                !has_error(&decoded, "errors.implicit.duplicateRank")
+            );
+            prop_assert!(
+                !has_error(&decoded, "errors.implicit.overvoteAtRank")
+            );
+        }
+    }
+
+    #[test]
+    fn prop_multi_contest_round_trip(
+        (ballot_style, plaintexts) in arb_multi_contest_plaintexts()
+    ) {
+        let max_size =
+            BallotChoices::maximum_size_bytes(&ballot_style.contests).unwrap();
+        prop_assume!(max_size <= 29);
+
+        let (encoded, _) =
+            encode_to_plaintext_decoded_multi_contest(&plaintexts, &ballot_style)
+                .unwrap();
+        prop_assert_eq!(
+            get_plaintext_codec_version(&encoded),
+            CURRENT_CODEC_VERSION
+        );
+
+        let decrypted = encrypt_and_decrypt(encoded);
+        let decoded_choices =
+            BallotChoices::decode_from_30_bytes(&decrypted, &ballot_style)
+                .unwrap();
+        let decoded = map_decoded_ballot_choices_to_decoded_contests(
+            decoded_choices,
+            &ballot_style.contests,
+        )
+        .unwrap();
+
+        prop_assert_eq!(
+            normalize_election(&decoded, &ballot_style, true).unwrap(),
+            normalize_election(&plaintexts, &ballot_style, true).unwrap()
+        );
+    }
+
+    #[test]
+    fn prop_decoders_never_panic(bytes in prop::array::uniform30(any::<u8>())) {
+        for fixture in get_fixtures() {
+            let _ = fixture.contest.decode_plaintext_contest(&bytes);
+        }
+        let ballot_style = get_writein_ballot_style();
+        for contest in &ballot_style.contests {
+            let _ = contest.decode_plaintext_contest(&bytes);
+        }
+        let _ = BallotChoices::decode_from_30_bytes(&bytes, &ballot_style);
+    }
+
+    #[test]
+    fn prop_bigint_decoders_never_panic(
+        bytes in prop::collection::vec(any::<u8>(), 0..64)
+    ) {
+        let bigint = BigUint::from_bytes_le(&bytes);
+        let ballot_style = get_writein_ballot_style();
+        for decoder in get_decoders() {
+            for fixture in get_fixtures() {
+                let _ =
+                    decoder.decode_contest_bigint(&fixture.contest, &bigint);
+            }
+            let _ = decoder.decode_multi_contest_bigint(
+                &ballot_style.contests,
+                &bigint,
+                None,
+            );
+        }
+    }
+}
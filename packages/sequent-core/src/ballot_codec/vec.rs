@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+use crate::ballot_codec::version::get_plaintext_length;
+
 // similar to ballot_codec::encode_vec_to_array but it doesn't add the size.
 pub fn vec_to_30_array(data: &Vec<u8>) -> Result<[u8; 30], String> {
     if data.len() > 30 {
@@ -41,16 +43,14 @@ pub fn encode_vec_to_array(data: &Vec<u8>) -> Result<[u8; 30], String> {
 /**
  * Decode an array of 30 bytes into a vector of bytes.
  * This is the inverse of encode_vec_to_array and in that way
- * the first byte indicates the size of the data.
+ * the first byte indicates the size of the data. The upper bits of the
+ * first byte hold the codec version and are ignored.
  */
 pub fn decode_array_to_vec(code: &[u8; 30]) -> Vec<u8> {
-    let plaintext_length = code[0] as usize;
+    let plaintext_length = get_plaintext_length(code);
 
-    let mut plaintext_bytes: Vec<u8> = vec![];
-    for i in 0..plaintext_length {
-        plaintext_bytes.push(code[i + 1]);
-    }
-    plaintext_bytes
+    // adversarial plaintexts may declare a length greater than 29
+    code[1..].iter().take(plaintext_length).cloned().collect()
 }
 
 #[cfg(test)]
@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::ballot::Contest;
+use crate::ballot_codec::multi_ballot::{BallotChoices, DecodedBallotChoices};
+use crate::ballot_codec::BigUIntCodec;
+use crate::plaintext::DecodedVoteContest;
+use num_bigint::BigUint;
+
+/**
+ * The first byte of an encoded plaintext stores the length of the data,
+ * which is at most 29 and fits in the lower 5 bits. The upper 3 bits store
+ * the codec version used to encode the ballot.
+ */
+pub const CODEC_VERSION_SHIFT: u8 = 5;
+pub const PLAINTEXT_LENGTH_MASK: u8 = 0x1f;
+pub const MAX_CODEC_VERSION: u8 = 7;
+
+/// Ballots encoded before the codec was versioned. Ballot styles without a
+/// codec version and plaintexts with the version bits unset use it.
+pub const LEGACY_CODEC_VERSION: u8 = 0;
+
+/// Mixed radix encoding with bases from `get_bases` and write-ins encoded
+/// with the contest character map.
+pub const MIXED_RADIX_CODEC_VERSION: u8 = 1;
+
+/// Codec version used for new ballot styles.
+pub const CURRENT_CODEC_VERSION: u8 = MIXED_RADIX_CODEC_VERSION;
+
+/// Returns the codec version stored in the header of an encoded plaintext.
+pub fn get_plaintext_codec_version(code: &[u8; 30]) -> u8 {
+    code[0] >> CODEC_VERSION_SHIFT
+}
+
+/// Returns the length of the data stored in an encoded plaintext.
+pub fn get_plaintext_length(code: &[u8; 30]) -> usize {
+    (code[0] & PLAINTEXT_LENGTH_MASK) as usize
+}
+
+/// Stores the codec version in the header of an encoded plaintext, keeping
+/// the data length.
+pub fn set_plaintext_codec_version(
+    code: &mut [u8; 30],
+    version: u8,
+) -> Result<(), String> {
+    if version > MAX_CODEC_VERSION {
+        return Err(format!(
+            "Codec version {} is greater than {}",
+            version, MAX_CODEC_VERSION
+        ));
+    }
+    code[0] =
+        (code[0] & PLAINTEXT_LENGTH_MASK) | (version << CODEC_VERSION_SHIFT);
+    Ok(())
+}
+
+/// Decodes the BigUint representation of a ballot. Each codec version
+/// has its own decoder, so that ballots cast with previous versions of the
+/// encoding can still be decoded after it changes.
+pub trait BallotDecoder: Sync {
+    fn version(&self) -> u8;
+
+    /// Name of the encoding the decoder reads. Codec versions with the same
+    /// encoding decode ballots the same way.
+    fn encoding(&self) -> &'static str;
+
+    fn decode_contest_bigint(
+        &self,
+        contest: &Contest,
+        bigint: &BigUint,
+    ) -> Result<DecodedVoteContest, String>;
+
+    fn decode_multi_contest_bigint(
+        &self,
+        contests: &Vec<Contest>,
+        bigint: &BigUint,
+        serial_number_counter: Option<&mut u32>,
+    ) -> Result<DecodedBallotChoices, String>;
+}
+
+/// Decoder for the mixed radix encoding. The legacy encoding is the same
+/// mixed radix encoding without the version in the plaintext header, so both
+/// versions share it.
+pub struct MixedRadixDecoder {
+    version: u8,
+}
+
+impl BallotDecoder for MixedRadixDecoder {
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn encoding(&self) -> &'static str {
+        "mixed-radix"
+    }
+
+    fn decode_contest_bigint(
+        &self,
+        contest: &Contest,
+        bigint: &BigUint,
+    ) -> Result<DecodedVoteContest, String> {
+        contest.decode_plaintext_contest_bigint(bigint)
+    }
+
+    fn decode_multi_contest_bigint(
+        &self,
+        contests: &Vec<Contest>,
+        bigint: &BigUint,
+        serial_number_counter: Option<&mut u32>,
+    ) -> Result<DecodedBallotChoices, String> {
+        BallotChoices::decode_from_bigint(
+            bigint,
+            contests,
+            serial_number_counter,
+        )
+    }
+}
+
+static LEGACY_DECODER: MixedRadixDecoder = MixedRadixDecoder {
+    version: LEGACY_CODEC_VERSION,
+};
+static MIXED_RADIX_DECODER: MixedRadixDecoder = MixedRadixDecoder {
+    version: MIXED_RADIX_CODEC_VERSION,
+};
+
+static DECODERS: [&dyn BallotDecoder; 2] =
+    [&LEGACY_DECODER, &MIXED_RADIX_DECODER];
+
+/// Returns the registered decoders, one per supported codec version.
+pub fn get_decoders() -> &'static [&'static dyn BallotDecoder] {
+    &DECODERS
+}
+
+/// Returns the decoder for a codec version, or an error if the version is
+/// not supported.
+pub fn get_decoder(version: u8) -> Result<&'static dyn BallotDecoder, String> {
+    get_decoders()
+        .iter()
+        .find(|decoder| decoder.version() == version)
+        .copied()
+        .ok_or_else(|| format!("Unsupported ballot codec version {}", version))
+}
+
+/// Returns whether ballots encoded with the previous codec version decode
+/// the same way with the current one. Unsupported versions are never
+/// compatible.
+pub fn are_codec_versions_compatible(previous: u8, current: u8) -> bool {
+    match (get_decoder(previous), get_decoder(current)) {
+        (Ok(previous), Ok(current)) => {
+            previous.encoding() == current.encoding()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ballot_codec::*;
+    use crate::fixtures::ballot_codec::*;
+
+    #[test]
+    fn test_plaintext_codec_version_header() {
+        let data: Vec<u8> = vec![33, 13, 155];
+        let mut encoded = encode_vec_to_array(&data).unwrap();
+        assert_eq!(get_plaintext_codec_version(&encoded), LEGACY_CODEC_VERSION);
+
+        set_plaintext_codec_version(&mut encoded, MIXED_RADIX_CODEC_VERSION)
+            .unwrap();
+        assert_eq!(
+            get_plaintext_codec_version(&encoded),
+            MIXED_RADIX_CODEC_VERSION
+        );
+        assert_eq!(get_plaintext_length(&encoded), data.len());
+        assert_eq!(decode_array_to_vec(&encoded), data);
+
+        assert!(set_plaintext_codec_version(&mut encoded, 8).is_err());
+    }
+
+    #[test]
+    fn test_get_decoder() {
+        for version in [LEGACY_CODEC_VERSION, CURRENT_CODEC_VERSION] {
+            assert_eq!(get_decoder(version).unwrap().version(), version);
+        }
+        assert!(get_decoder(MAX_CODEC_VERSION).is_err());
+    }
+
+    #[test]
+    fn test_legacy_plaintexts_still_decode() {
+        for fixture in get_fixtures() {
+            if fixture.expected_errors.is_some() {
+                continue;
+            }
+            let legacy = fixture
+                .contest
+                .encode_plaintext_contest(&fixture.plaintext)
+                .unwrap();
+            let versioned = fixture
+                .contest
+                .encode_plaintext_contest_with_version(
+                    &fixture.plaintext,
+                    CURRENT_CODEC_VERSION,
+                )
+                .unwrap();
+            assert_eq!(
+                get_plaintext_codec_version(&versioned),
+                CURRENT_CODEC_VERSION
+            );
+            assert_eq!(legacy[1..], versioned[1..]);
+            assert_eq!(
+                fixture.contest.decode_plaintext_contest(&legacy).unwrap(),
+                fixture
+                    .contest
+                    .decode_plaintext_contest(&versioned)
+                    .unwrap(),
+                "{}",
+                fixture.title
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_codec_version_fails() {
+        let fixture = &get_fixtures()[0];
+        let mut encoded = fixture
+            .contest
+            .encode_plaintext_contest(&fixture.plaintext)
+            .unwrap();
+        set_plaintext_codec_version(&mut encoded, MAX_CODEC_VERSION).unwrap();
+        assert!(fixture.contest.decode_plaintext_contest(&encoded).is_err());
+    }
+}
@@ -0,0 +1,674 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Semantic diff between the ballot styles of two ballot publications. Each
+//! change is classified by its impact on the ballots already cast, so that
+//! publications that would make them undecodable can be blocked once voting
+//! has started.
+
+use crate::ballot::{BallotStyle, Candidate, Contest};
+use crate::ballot_codec::bases::BasesCodec;
+use crate::ballot_codec::version::are_codec_versions_compatible;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use strum_macros::{Display, EnumString};
+
+#[allow(non_camel_case_types)]
+#[derive(
+    Display,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    EnumString,
+    JsonSchema,
+)]
+pub enum BallotChangeImpact {
+    /// Texts, translations, ordering and other presentation settings.
+    PRESENTATION,
+    /// Voting rules, which change how new ballots are validated or counted
+    /// but not how they are encoded.
+    RULES,
+    /// Ballots cast with the previous publication can't be decoded with the
+    /// new one.
+    ENCODING,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+pub struct BallotChange {
+    pub election_id: String,
+    pub area_id: String,
+    pub contest_id: Option<String>,
+    pub candidate_id: Option<String>,
+    /// Changed field, for example `max_votes` or
+    /// `presentation.allow_writeins`. Added and removed elements use
+    /// `ballot_style`, `contest` or `candidate`.
+    pub field: String,
+    pub impact: BallotChangeImpact,
+    pub previous: Option<Value>,
+    pub current: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+pub struct BallotStylesDiff {
+    /// Highest impact of the changes, None if there are no changes.
+    pub impact: Option<BallotChangeImpact>,
+    pub changes: Vec<BallotChange>,
+}
+
+impl BallotStylesDiff {
+    pub fn breaks_encoding(&self) -> bool {
+        self.impact == Some(BallotChangeImpact::ENCODING)
+    }
+
+    pub fn get_changes(
+        &self,
+        impact: BallotChangeImpact,
+    ) -> Vec<&BallotChange> {
+        self.changes
+            .iter()
+            .filter(|change| change.impact == impact)
+            .collect()
+    }
+}
+
+const PRESENTATION_FIELD: &str = "presentation";
+
+// Fields that are not compared as json because they are identifiers, nested
+// elements compared on their own or fields compared through the accessors
+// that the ballot codec uses, which take care of the defaults.
+const BALLOT_STYLE_SKIPPED_FIELDS: [&str; 8] = [
+    "id",
+    "tenant_id",
+    "election_event_id",
+    "election_id",
+    "area_id",
+    "contests",
+    "codec_version",
+    "public_key",
+];
+
+const CONTEST_SKIPPED_FIELDS: [&str; 11] = [
+    "id",
+    "tenant_id",
+    "election_event_id",
+    "election_id",
+    "created_at",
+    "candidates",
+    "is_encrypted",
+    "counting_algorithm",
+    "presentation.allow_writeins",
+    "presentation.base32_writeins",
+    "presentation.cumulative_number_of_checkboxes",
+];
+
+const CANDIDATE_SKIPPED_FIELDS: [&str; 7] = [
+    "id",
+    "tenant_id",
+    "election_event_id",
+    "election_id",
+    "contest_id",
+    "presentation.is_write_in",
+    "presentation.is_explicit_invalid",
+];
+
+fn classify_ballot_style_field(field: &str) -> BallotChangeImpact {
+    match field {
+        "num_allowed_revotes" | "election_dates" => BallotChangeImpact::RULES,
+        _ => BallotChangeImpact::PRESENTATION,
+    }
+}
+
+fn classify_contest_field(field: &str) -> BallotChangeImpact {
+    match field {
+        "max_votes"
+        | "min_votes"
+        | "winning_candidates_num"
+        | "voting_type"
+        | "presentation.invalid_vote_policy"
+        | "presentation.under_vote_policy"
+        | "presentation.blank_vote_policy"
+        | "presentation.over_vote_policy"
+        | "presentation.enable_checkable_lists"
+        | "presentation.candidates_selection_policy"
+        | "presentation.max_selections_per_type" => BallotChangeImpact::RULES,
+        _ => BallotChangeImpact::PRESENTATION,
+    }
+}
+
+fn classify_candidate_field(field: &str) -> BallotChangeImpact {
+    match field {
+        "presentation.is_explicit_blank"
+        | "presentation.is_disabled"
+        | "presentation.is_category_list" => BallotChangeImpact::RULES,
+        _ => BallotChangeImpact::PRESENTATION,
+    }
+}
+
+/// Top level fields of a serialized element, with the fields of its
+/// `presentation` flattened as `presentation.<field>`. Null fields are left
+/// out so that a missing field and a null one are equal.
+fn flatten_fields<T: Serialize>(
+    element: &T,
+) -> Result<BTreeMap<String, Value>> {
+    let mut fields = BTreeMap::new();
+    let Value::Object(map) = serde_json::to_value(element)? else {
+        return Ok(fields);
+    };
+    for (key, value) in map {
+        match value {
+            Value::Object(presentation) if key == PRESENTATION_FIELD => {
+                for (presentation_key, presentation_value) in presentation {
+                    if !presentation_value.is_null() {
+                        fields.insert(
+                            format!("{key}.{presentation_key}"),
+                            presentation_value,
+                        );
+                    }
+                }
+            }
+            Value::Null => {}
+            _ => {
+                fields.insert(key, value);
+            }
+        }
+    }
+    Ok(fields)
+}
+
+struct DiffBuilder<'a> {
+    election_id: &'a str,
+    area_id: &'a str,
+    changes: Vec<BallotChange>,
+}
+
+impl<'a> DiffBuilder<'a> {
+    fn push(
+        &mut self,
+        contest_id: Option<&str>,
+        candidate_id: Option<&str>,
+        field: &str,
+        impact: BallotChangeImpact,
+        previous: Option<Value>,
+        current: Option<Value>,
+    ) {
+        self.changes.push(BallotChange {
+            election_id: self.election_id.to_string(),
+            area_id: self.area_id.to_string(),
+            contest_id: contest_id.map(str::to_string),
+            candidate_id: candidate_id.map(str::to_string),
+            field: field.to_string(),
+            impact,
+            previous,
+            current,
+        });
+    }
+
+    fn push_if_changed<T: Serialize + PartialEq>(
+        &mut self,
+        contest_id: Option<&str>,
+        candidate_id: Option<&str>,
+        field: &str,
+        impact: BallotChangeImpact,
+        previous: T,
+        current: T,
+    ) -> Result<()> {
+        if previous != current {
+            self.push(
+                contest_id,
+                candidate_id,
+                field,
+                impact,
+                Some(serde_json::to_value(previous)?),
+                Some(serde_json::to_value(current)?),
+            );
+        }
+        Ok(())
+    }
+
+    fn compare_fields<T: Serialize>(
+        &mut self,
+        contest_id: Option<&str>,
+        candidate_id: Option<&str>,
+        previous: &T,
+        current: &T,
+        skipped_fields: &[&str],
+        classify: fn(&str) -> BallotChangeImpact,
+    ) -> Result<()> {
+        let previous_fields = flatten_fields(previous)?;
+        let mut current_fields = flatten_fields(current)?;
+
+        for (field, previous_value) in previous_fields {
+            let current_value = current_fields.remove(&field);
+            if skipped_fields.contains(&field.as_str())
+                || current_value.as_ref() == Some(&previous_value)
+            {
+                continue;
+            }
+            self.push(
+                contest_id,
+                candidate_id,
+                &field,
+                classify(&field),
+                Some(previous_value),
+                current_value,
+            );
+        }
+        for (field, current_value) in current_fields {
+            if skipped_fields.contains(&field.as_str()) {
+                continue;
+            }
+            self.push(
+                contest_id,
+                candidate_id,
+                &field,
+                classify(&field),
+                None,
+                Some(current_value),
+            );
+        }
+        Ok(())
+    }
+
+    fn compare_candidates(
+        &mut self,
+        contest_id: &str,
+        previous: &Candidate,
+        current: &Candidate,
+    ) -> Result<()> {
+        let candidate_id = Some(current.id.as_str());
+        self.push_if_changed(
+            Some(contest_id),
+            candidate_id,
+            "presentation.is_write_in",
+            BallotChangeImpact::ENCODING,
+            previous.is_write_in(),
+            current.is_write_in(),
+        )?;
+        self.push_if_changed(
+            Some(contest_id),
+            candidate_id,
+            "presentation.is_explicit_invalid",
+            BallotChangeImpact::ENCODING,
+            previous.is_explicit_invalid(),
+            current.is_explicit_invalid(),
+        )?;
+        self.compare_fields(
+            Some(contest_id),
+            candidate_id,
+            previous,
+            current,
+            &CANDIDATE_SKIPPED_FIELDS,
+            classify_candidate_field,
+        )
+    }
+
+    fn compare_contests(
+        &mut self,
+        previous: &Contest,
+        current: &Contest,
+    ) -> Result<()> {
+        let contest_id = Some(current.id.as_str());
+        let encoding = BallotChangeImpact::ENCODING;
+        self.push_if_changed(
+            contest_id,
+            None,
+            "is_encrypted",
+            encoding,
+            previous.is_encrypted,
+            current.is_encrypted,
+        )?;
+        self.push_if_changed(
+            contest_id,
+            None,
+            "counting_algorithm",
+            encoding,
+            previous.get_counting_algorithm(),
+            current.get_counting_algorithm(),
+        )?;
+        self.push_if_changed(
+            contest_id,
+            None,
+            "presentation.allow_writeins",
+            encoding,
+            previous.allow_writeins(),
+            current.allow_writeins(),
+        )?;
+        self.push_if_changed(
+            contest_id,
+            None,
+            "presentation.base32_writeins",
+            encoding,
+            previous.base32_writeins(),
+            current.base32_writeins(),
+        )?;
+        self.push_if_changed(
+            contest_id,
+            None,
+            "presentation.cumulative_number_of_checkboxes",
+            encoding,
+            previous.cumulative_number_of_checkboxes(),
+            current.cumulative_number_of_checkboxes(),
+        )?;
+        // Covers the changes of the encoding that are not visible in a single
+        // field, like a new `max_votes` in a preferential contest.
+        self.push_if_changed(
+            contest_id,
+            None,
+            "bases",
+            encoding,
+            previous.get_bases()?,
+            current.get_bases()?,
+        )?;
+        self.compare_fields(
+            contest_id,
+            None,
+            previous,
+            current,
+            &CONTEST_SKIPPED_FIELDS,
+            classify_contest_field,
+        )?;
+
+        let previous_candidates: BTreeMap<&str, &Candidate> = previous
+            .candidates
+            .iter()
+            .map(|candidate| (candidate.id.as_str(), candidate))
+            .collect();
+        let mut current_candidates: BTreeMap<&str, &Candidate> = current
+            .candidates
+            .iter()
+            .map(|candidate| (candidate.id.as_str(), candidate))
+            .collect();
+
+        // The candidates of a contest are encoded sorted by id, so adding or
+        // removing any of them changes the meaning of the encoded choices.
+        for (candidate_id, previous_candidate) in previous_candidates {
+            match current_candidates.remove(candidate_id) {
+                Some(current_candidate) => self.compare_candidates(
+                    &current.id,
+                    previous_candidate,
+                    current_candidate,
+                )?,
+                None => self.push(
+                    contest_id,
+                    Some(candidate_id),
+                    "candidate",
+                    encoding,
+                    Some(Value::String(candidate_id.to_string())),
+                    None,
+                ),
+            }
+        }
+        for candidate_id in current_candidates.into_keys() {
+            self.push(
+                contest_id,
+                Some(candidate_id),
+                "candidate",
+                encoding,
+                None,
+                Some(Value::String(candidate_id.to_string())),
+            );
+        }
+        Ok(())
+    }
+
+    fn compare_ballot_styles(
+        &mut self,
+        previous: &BallotStyle,
+        current: &BallotStyle,
+    ) -> Result<()> {
+        let encoding = BallotChangeImpact::ENCODING;
+        // Codec versions sharing a decoder, like the legacy and the mixed
+        // radix ones, don't change how the ballots are decoded.
+        if !are_codec_versions_compatible(
+            previous.get_codec_version(),
+            current.get_codec_version(),
+        ) {
+            self.push_if_changed(
+                None,
+                None,
+                "codec_version",
+                encoding,
+                previous.get_codec_version(),
+                current.get_codec_version(),
+            )?;
+        }
+        self.push_if_changed(
+            None,
+            None,
+            "public_key",
+            encoding,
+            previous
+                .public_key
+                .as_ref()
+                .map(|public_key| public_key.public_key.clone()),
+            current
+                .public_key
+                .as_ref()
+                .map(|public_key| public_key.public_key.clone()),
+        )?;
+        self.compare_fields(
+            None,
+            None,
+            previous,
+            current,
+            &BALLOT_STYLE_SKIPPED_FIELDS,
+            classify_ballot_style_field,
+        )?;
+
+        let previous_contests: BTreeMap<&str, &Contest> = previous
+            .contests
+            .iter()
+            .map(|contest| (contest.id.as_str(), contest))
+            .collect();
+        let mut current_contests: BTreeMap<&str, &Contest> = current
+            .contests
+            .iter()
+            .map(|contest| (contest.id.as_str(), contest))
+            .collect();
+
+        // Ballots with several contests encode them together, so the set of
+        // contests is part of the encoding too.
+        for (contest_id, previous_contest) in previous_contests {
+            match current_contests.remove(contest_id) {
+                Some(current_contest) => {
+                    self.compare_contests(previous_contest, current_contest)?
+                }
+                None => self.push(
+                    Some(contest_id),
+                    None,
+                    "contest",
+                    encoding,
+                    Some(Value::String(contest_id.to_string())),
+                    None,
+                ),
+            }
+        }
+        for contest_id in current_contests.into_keys() {
+            self.push(
+                Some(contest_id),
+                None,
+                "contest",
+                encoding,
+                None,
+                Some(Value::String(contest_id.to_string())),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn get_ballot_styles_by_area(
+    ballot_styles: &[BallotStyle],
+) -> BTreeMap<(&str, &str), &BallotStyle> {
+    ballot_styles
+        .iter()
+        .map(|ballot_style| {
+            (
+                (
+                    ballot_style.election_id.as_str(),
+                    ballot_style.area_id.as_str(),
+                ),
+                ballot_style,
+            )
+        })
+        .collect()
+}
+
+/// Compares the ballot styles of two publications. Ballot styles are matched
+/// by election and area, contests and candidates by id.
+pub fn diff_ballot_styles(
+    previous: &[BallotStyle],
+    current: &[BallotStyle],
+) -> Result<BallotStylesDiff> {
+    let previous_styles = get_ballot_styles_by_area(previous);
+    let mut current_styles = get_ballot_styles_by_area(current);
+    let mut changes: Vec<BallotChange> = vec![];
+
+    for ((election_id, area_id), previous_style) in previous_styles {
+        let mut builder = DiffBuilder {
+            election_id,
+            area_id,
+            changes: vec![],
+        };
+        match current_styles.remove(&(election_id, area_id)) {
+            Some(current_style) => {
+                builder.compare_ballot_styles(previous_style, current_style)?
+            }
+            // Voters of the area can't vote anymore and their ballots lose
+            // the ballot style used to decode them.
+            None => builder.push(
+                None,
+                None,
+                "ballot_style",
+                BallotChangeImpact::ENCODING,
+                Some(Value::String(previous_style.id.clone())),
+                None,
+            ),
+        }
+        changes.extend(builder.changes);
+    }
+    for ((election_id, area_id), current_style) in current_styles {
+        let mut builder = DiffBuilder {
+            election_id,
+            area_id,
+            changes: vec![],
+        };
+        builder.push(
+            None,
+            None,
+            "ballot_style",
+            BallotChangeImpact::RULES,
+            None,
+            Some(Value::String(current_style.id.clone())),
+        );
+        changes.extend(builder.changes);
+    }
+
+    Ok(BallotStylesDiff {
+        impact: changes.iter().map(|change| change.impact).max(),
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::ballot_codec::get_writein_ballot_style;
+
+    #[test]
+    fn test_diff_ballot_styles_impact() {
+        let previous = vec![get_writein_ballot_style()];
+
+        let diff = diff_ballot_styles(&previous, &previous).unwrap();
+        assert_eq!(diff.impact, None);
+        assert!(diff.changes.is_empty());
+
+        let mut current = previous.clone();
+        current[0].contests[0].name = Some("Renamed contest".into());
+        current[0].description = Some("New description".into());
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert_eq!(diff.impact, Some(BallotChangeImpact::PRESENTATION));
+        assert_eq!(diff.changes.len(), 2);
+
+        current[0].contests[0].min_votes = 0;
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert_eq!(diff.impact, Some(BallotChangeImpact::RULES));
+        assert!(!diff.breaks_encoding());
+
+        current[0].contests[0].candidates.pop();
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert!(diff.breaks_encoding());
+        let encoding_fields: Vec<&str> = diff
+            .get_changes(BallotChangeImpact::ENCODING)
+            .iter()
+            .map(|change| change.field.as_str())
+            .collect();
+        assert!(encoding_fields.contains(&"candidate"));
+    }
+
+    #[test]
+    fn test_diff_ballot_styles_codec_version() {
+        let previous = vec![get_writein_ballot_style()];
+        assert_eq!(previous[0].codec_version, None);
+
+        // The legacy and mixed radix versions share the decoder
+        let mut current = previous.clone();
+        current[0].codec_version = Some(1);
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert!(!diff.breaks_encoding());
+        assert!(diff.get_changes(BallotChangeImpact::ENCODING).is_empty());
+
+        current[0].codec_version = Some(7);
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert!(diff.breaks_encoding());
+    }
+
+    #[test]
+    fn test_diff_ballot_styles_encoding_fields() {
+        let previous = vec![get_writein_ballot_style()];
+
+        // max_votes doesn't change the bases of a plurality contest
+        let mut current = previous.clone();
+        current[0].contests[0].max_votes += 1;
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert_eq!(diff.impact, Some(BallotChangeImpact::RULES));
+
+        current[0].contests[0].counting_algorithm = Some("borda".into());
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        let encoding_fields: Vec<&str> = diff
+            .get_changes(BallotChangeImpact::ENCODING)
+            .iter()
+            .map(|change| change.field.as_str())
+            .collect();
+        assert_eq!(encoding_fields, vec!["counting_algorithm", "bases"]);
+
+        let mut current = previous.clone();
+        let mut presentation =
+            current[0].contests[0].presentation.clone().unwrap();
+        presentation.allow_writeins =
+            Some(!previous[0].contests[0].allow_writeins());
+        current[0].contests[0].presentation = Some(presentation);
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert!(diff.breaks_encoding());
+        assert_eq!(
+            diff.changes[0].field,
+            "presentation.allow_writeins".to_string()
+        );
+
+        let mut current = previous.clone();
+        current[0].area_id = "other-area".into();
+        let diff = diff_ballot_styles(&previous, &current).unwrap();
+        assert!(diff.breaks_encoding());
+        assert_eq!(diff.changes.len(), 2);
+    }
+}
@@ -8,6 +8,7 @@ use crate::ballot::{
     I18nContent, StringifiedPeriodDates, WeightedVotingPolicy,
 };
 
+use crate::ballot_codec::CURRENT_CODEC_VERSION;
 use crate::serialization::deserialize_with_path::deserialize_value;
 use crate::types::hasura::core::{self as hasura_types};
 use anyhow::{anyhow, Context, Result};
@@ -151,6 +152,7 @@ pub fn create_ballot_style(
         election_event_annotations: Some(election_event_annotations),
         election_annotations: Some(election_annotations),
         area_annotations,
+        codec_version: Some(CURRENT_CODEC_VERSION),
     })
 }
 
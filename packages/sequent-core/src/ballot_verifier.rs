@@ -1,5 +1,580 @@
 // SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
 //
 // SPDX-License-Identifier: AGPL-3.0-only
+use crate::ballot::*;
+use crate::encrypt::{
+    hash_ballot, hash_ballot_style, hash_multi_ballot,
+    recreate_encrypt_cyphertext, recreate_encrypt_multi_cyphertext,
+};
+use crate::error::BallotError;
+use crate::multi_ballot::{
+    verify_multi_ballot_signature, AuditableMultiBallot,
+    AuditableMultiBallotContests, HashableMultiBallot,
+    SignedHashableMultiBallot,
+};
 use crate::plaintext::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strand::backend::ristretto::RistrettoCtx;
+use strum_macros::{Display, EnumString};
 
+/// Result of a single step of the Benaloh cast-or-audit verification.
+#[derive(
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Debug,
+    Clone,
+)]
+pub enum VerificationCheckStatus {
+    #[strum(serialize = "passed")]
+    #[serde(rename = "passed")]
+    Passed,
+    #[strum(serialize = "failed")]
+    #[serde(rename = "failed")]
+    Failed,
+    #[strum(serialize = "skipped")]
+    #[serde(rename = "skipped")]
+    Skipped,
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Debug,
+    Clone,
+)]
+pub enum VerificationCheckKind {
+    #[strum(serialize = "ballot_style")]
+    #[serde(rename = "ballot_style")]
+    BallotStyle,
+    #[strum(serialize = "contests")]
+    #[serde(rename = "contests")]
+    Contests,
+    #[strum(serialize = "ciphertext")]
+    #[serde(rename = "ciphertext")]
+    Ciphertext,
+    #[strum(serialize = "ballot_hash")]
+    #[serde(rename = "ballot_hash")]
+    BallotHash,
+    #[strum(serialize = "receipt")]
+    #[serde(rename = "receipt")]
+    Receipt,
+    #[strum(serialize = "voter_signature")]
+    #[serde(rename = "voter_signature")]
+    VoterSignature,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct VerificationCheck {
+    pub kind: VerificationCheckKind,
+    pub status: VerificationCheckStatus,
+    pub message: Option<String>,
+}
+
+impl VerificationCheck {
+    fn new(
+        kind: VerificationCheckKind,
+        passed: bool,
+        message: Option<String>,
+    ) -> VerificationCheck {
+        VerificationCheck {
+            kind,
+            status: if passed {
+                VerificationCheckStatus::Passed
+            } else {
+                VerificationCheckStatus::Failed
+            },
+            message,
+        }
+    }
+
+    fn skipped(
+        kind: VerificationCheckKind,
+        message: &str,
+    ) -> VerificationCheck {
+        VerificationCheck {
+            kind,
+            status: VerificationCheckStatus::Skipped,
+            message: Some(message.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
+pub struct BallotVerificationReport {
+    pub ballot_hash: String,
+    pub election_id: String,
+    pub checks: Vec<VerificationCheck>,
+    pub decoded_contests: Vec<DecodedVoteContest>,
+}
+
+impl BallotVerificationReport {
+    /// A ballot is verified when no check failed. Skipped checks (no receipt
+    /// provided, unsigned ballot) do not make the verification fail.
+    pub fn is_verified(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != VerificationCheckStatus::Failed)
+    }
+}
+
+fn normalize_hash(hash: &str) -> String {
+    hash.trim().to_lowercase()
+}
+
+fn check_ballot_style(
+    ballot_config: &BallotStyle,
+    ballot_style: &BallotStyle,
+) -> Result<VerificationCheck, BallotError> {
+    let ballot_config_hash = hash_ballot_style(ballot_config)?;
+    let ballot_style_hash = hash_ballot_style(ballot_style)?;
+    let passed = ballot_config_hash == ballot_style_hash;
+
+    Ok(VerificationCheck::new(
+        VerificationCheckKind::BallotStyle,
+        passed,
+        if passed {
+            None
+        } else {
+            Some(format!(
+                "Ballot style hash {} doesn't match published ballot style hash {}",
+                ballot_config_hash, ballot_style_hash
+            ))
+        },
+    ))
+}
+
+/// Checks that the ballot has exactly one contest for each contest of the
+/// published ballot style, so that no contest is left out of the other
+/// checks.
+fn check_contests(
+    contest_ids: &[String],
+    ballot_style: &BallotStyle,
+) -> VerificationCheck {
+    let mut ballot_contest_ids: Vec<&str> =
+        contest_ids.iter().map(|id| id.as_str()).collect();
+    ballot_contest_ids.sort();
+    let mut ballot_style_contest_ids: Vec<&str> = ballot_style
+        .contests
+        .iter()
+        .map(|contest| contest.id.as_str())
+        .collect();
+    ballot_style_contest_ids.sort();
+    let passed = ballot_contest_ids == ballot_style_contest_ids;
+
+    VerificationCheck::new(
+        VerificationCheckKind::Contests,
+        passed,
+        if passed {
+            None
+        } else {
+            Some(format!(
+                "Ballot contests [{}] don't match the ballot style contests [{}]",
+                ballot_contest_ids.join(", "),
+                ballot_style_contest_ids.join(", ")
+            ))
+        },
+    )
+}
+
+fn check_hashes(
+    ballot_hash: &str,
+    computed_hash: &str,
+    receipt_ballot_hash: Option<&str>,
+) -> Vec<VerificationCheck> {
+    let hash_matches = normalize_hash(ballot_hash) == computed_hash;
+    let hash_check = VerificationCheck::new(
+        VerificationCheckKind::BallotHash,
+        hash_matches,
+        if hash_matches {
+            None
+        } else {
+            Some(format!(
+                "Computed ballot hash {} doesn't match ballot hash {}",
+                computed_hash, ballot_hash
+            ))
+        },
+    );
+
+    let receipt_check = match receipt_ballot_hash {
+        Some(receipt) => {
+            let receipt_matches = normalize_hash(receipt) == computed_hash;
+            VerificationCheck::new(
+                VerificationCheckKind::Receipt,
+                receipt_matches,
+                if receipt_matches {
+                    None
+                } else {
+                    Some(format!(
+                        "Computed ballot hash {} doesn't match receipt {}",
+                        computed_hash, receipt
+                    ))
+                },
+            )
+        }
+        None => VerificationCheck::skipped(
+            VerificationCheckKind::Receipt,
+            "No receipt provided",
+        ),
+    };
+
+    vec![hash_check, receipt_check]
+}
+
+fn signature_check<T>(result: Result<Option<T>, String>) -> VerificationCheck {
+    match result {
+        Ok(Some(_)) => VerificationCheck::new(
+            VerificationCheckKind::VoterSignature,
+            true,
+            None,
+        ),
+        Ok(None) => VerificationCheck::skipped(
+            VerificationCheckKind::VoterSignature,
+            "Ballot is not signed",
+        ),
+        Err(err) => VerificationCheck::new(
+            VerificationCheckKind::VoterSignature,
+            false,
+            Some(err),
+        ),
+    }
+}
+
+/// Verifies an audited (spoiled) ballot against the published ballot style:
+/// re-encrypts every contest with the revealed randomness, recomputes the
+/// ballot hash and compares it with the voter's receipt, checks the voter
+/// signature if present and decodes the choices.
+///
+/// Returns an error only when the ballot can't be parsed; failed checks are
+/// reported in the returned `BallotVerificationReport`.
+pub fn verify_auditable_ballot(
+    auditable_ballot: &AuditableBallot,
+    ballot_style: &BallotStyle,
+    receipt_ballot_hash: Option<&str>,
+) -> Result<BallotVerificationReport, BallotError> {
+    let ctx = RistrettoCtx;
+    let mut checks = vec![];
+
+    checks.push(check_ballot_style(&auditable_ballot.config, ballot_style)?);
+
+    let contests = auditable_ballot.deserialize_contests::<RistrettoCtx>()?;
+    let contest_ids: Vec<String> = contests
+        .iter()
+        .map(|contest| contest.contest_id.clone())
+        .collect();
+    let contests_check = check_contests(&contest_ids, ballot_style);
+    let contests_match = contests_check.status
+        == VerificationCheckStatus::Passed
+        && contests.len() == auditable_ballot.config.contests.len();
+    checks.push(contests_check);
+
+    if contests_match {
+        let recreated = recreate_encrypt_cyphertext(&ctx, auditable_ballot)?;
+        let mismatched_contests: Vec<String> = contests
+            .iter()
+            .zip(recreated.iter())
+            .filter(|(contest, choice)| {
+                contest.choice.ciphertext != choice.ciphertext
+            })
+            .map(|(contest, _)| contest.contest_id.clone())
+            .collect();
+        checks.push(VerificationCheck::new(
+            VerificationCheckKind::Ciphertext,
+            mismatched_contests.is_empty(),
+            if mismatched_contests.is_empty() {
+                None
+            } else {
+                Some(format!(
+                    "Ciphertext doesn't match the revealed plaintext and randomness for contests: {}",
+                    mismatched_contests.join(", ")
+                ))
+            },
+        ));
+    } else {
+        checks.push(VerificationCheck::skipped(
+            VerificationCheckKind::Ciphertext,
+            "The contests of the ballot don't match the ballot style",
+        ));
+    }
+
+    let signed_hashable_ballot =
+        SignedHashableBallot::try_from(auditable_ballot)?;
+    let hashable_ballot = HashableBallot::try_from(&signed_hashable_ballot)?;
+    let computed_hash = hash_ballot(&hashable_ballot)?;
+    checks.extend(check_hashes(
+        &auditable_ballot.ballot_hash,
+        &computed_hash,
+        receipt_ballot_hash,
+    ));
+
+    checks.push(signature_check(verify_ballot_signature(
+        &computed_hash,
+        &ballot_style.election_id,
+        &signed_hashable_ballot,
+    )));
+
+    let decoded_contests = if contests_match {
+        map_to_decoded_contest::<RistrettoCtx>(auditable_ballot)
+            .map_err(BallotError::Serialization)?
+    } else {
+        vec![]
+    };
+
+    Ok(BallotVerificationReport {
+        ballot_hash: computed_hash,
+        election_id: ballot_style.election_id.clone(),
+        checks,
+        decoded_contests,
+    })
+}
+
+/// Same as `verify_auditable_ballot` but for ballots where all contests are
+/// encoded in a single ciphertext.
+pub fn verify_auditable_multi_ballot(
+    auditable_multi_ballot: &AuditableMultiBallot,
+    ballot_style: &BallotStyle,
+    receipt_ballot_hash: Option<&str>,
+) -> Result<BallotVerificationReport, BallotError> {
+    let ctx = RistrettoCtx;
+    let mut checks = vec![];
+
+    checks.push(check_ballot_style(
+        &auditable_multi_ballot.config,
+        ballot_style,
+    )?);
+
+    let contests: AuditableMultiBallotContests<RistrettoCtx> =
+        auditable_multi_ballot.deserialize_contests::<RistrettoCtx>()?;
+    let contests_check = check_contests(&contests.contest_ids, ballot_style);
+    let contests_match = contests_check.status
+        == VerificationCheckStatus::Passed
+        && contests.contest_ids.len()
+            == auditable_multi_ballot.config.contests.len();
+    checks.push(contests_check);
+
+    if contests_match {
+        let recreated =
+            recreate_encrypt_multi_cyphertext(&ctx, auditable_multi_ballot)?;
+        let ciphertext_matches =
+            contests.choice.ciphertext == recreated.ciphertext;
+        checks.push(VerificationCheck::new(
+            VerificationCheckKind::Ciphertext,
+            ciphertext_matches,
+            if ciphertext_matches {
+                None
+            } else {
+                Some(
+                    "Ciphertext doesn't match the revealed plaintext and randomness"
+                        .to_string(),
+                )
+            },
+        ));
+    } else {
+        checks.push(VerificationCheck::skipped(
+            VerificationCheckKind::Ciphertext,
+            "The contests of the ballot don't match the ballot style",
+        ));
+    }
+
+    let signed_hashable_multi_ballot =
+        SignedHashableMultiBallot::try_from(auditable_multi_ballot)?;
+    let hashable_multi_ballot =
+        HashableMultiBallot::try_from(&signed_hashable_multi_ballot)?;
+    let computed_hash = hash_multi_ballot(&hashable_multi_ballot)?;
+    checks.extend(check_hashes(
+        &auditable_multi_ballot.ballot_hash,
+        &computed_hash,
+        receipt_ballot_hash,
+    ));
+
+    checks.push(signature_check(verify_multi_ballot_signature(
+        &computed_hash,
+        &ballot_style.election_id,
+        &signed_hashable_multi_ballot,
+    )));
+
+    let decoded_contests = if contests_match {
+        map_to_decoded_multi_contest::<RistrettoCtx>(auditable_multi_ballot)
+            .map_err(BallotError::Serialization)?
+    } else {
+        vec![]
+    };
+
+    Ok(BallotVerificationReport {
+        ballot_hash: computed_hash,
+        election_id: ballot_style.election_id.clone(),
+        checks,
+        decoded_contests,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encrypt::{
+        encrypt_decoded_contest, encrypt_decoded_multi_contest,
+    };
+    use crate::fixtures::ballot_codec::*;
+    use crate::serialization::base64::{Base64Deserialize, Base64Serialize};
+    use strand::context::Ctx;
+    use strand::rng::StrandRng;
+
+    #[test]
+    fn test_verify_auditable_ballot() {
+        let ctx = RistrettoCtx;
+        let ballot_style = get_writein_ballot_style();
+        let decoded_contest = get_writein_plaintext();
+        let auditable_ballot = encrypt_decoded_contest::<RistrettoCtx>(
+            &ctx,
+            &vec![decoded_contest],
+            &ballot_style,
+        )
+        .unwrap();
+
+        let report = verify_auditable_ballot(
+            &auditable_ballot,
+            &ballot_style,
+            Some(&auditable_ballot.ballot_hash),
+        )
+        .unwrap();
+        assert!(report.is_verified());
+        assert_eq!(report.ballot_hash, auditable_ballot.ballot_hash);
+        assert_eq!(report.decoded_contests.len(), 1);
+
+        let report =
+            verify_auditable_ballot(&auditable_ballot, &ballot_style, None)
+                .unwrap();
+        assert!(report.is_verified());
+        assert!(report
+            .checks
+            .iter()
+            .any(|check| check.kind == VerificationCheckKind::Receipt
+                && check.status == VerificationCheckStatus::Skipped));
+    }
+
+    #[test]
+    fn test_verify_auditable_ballot_wrong_receipt() {
+        let ctx = RistrettoCtx;
+        let ballot_style = get_writein_ballot_style();
+        let auditable_ballot = encrypt_decoded_contest::<RistrettoCtx>(
+            &ctx,
+            &vec![get_writein_plaintext()],
+            &ballot_style,
+        )
+        .unwrap();
+
+        let report = verify_auditable_ballot(
+            &auditable_ballot,
+            &ballot_style,
+            Some("0000"),
+        )
+        .unwrap();
+        assert!(!report.is_verified());
+    }
+
+    #[test]
+    fn test_verify_auditable_ballot_tampered_randomness() {
+        let mut csprng = StrandRng;
+        let ctx = RistrettoCtx;
+        let ballot_style = get_writein_ballot_style();
+        let mut auditable_ballot = encrypt_decoded_contest::<RistrettoCtx>(
+            &ctx,
+            &vec![get_writein_plaintext()],
+            &ballot_style,
+        )
+        .unwrap();
+
+        let mut contests = auditable_ballot
+            .deserialize_contests::<RistrettoCtx>()
+            .unwrap();
+        contests[0].choice.randomness = ctx.rnd_exp(&mut csprng);
+        auditable_ballot.contests =
+            AuditableBallot::serialize_contests::<RistrettoCtx>(&contests)
+                .unwrap();
+
+        let report =
+            verify_auditable_ballot(&auditable_ballot, &ballot_style, None)
+                .unwrap();
+        assert!(!report.is_verified());
+        assert!(report
+            .checks
+            .iter()
+            .any(|check| check.kind == VerificationCheckKind::Ciphertext
+                && check.status == VerificationCheckStatus::Failed));
+    }
+
+    #[test]
+    fn test_verify_auditable_ballot_missing_contest() {
+        let ctx = RistrettoCtx;
+        let ballot_style = get_writein_ballot_style();
+        let mut auditable_ballot = encrypt_decoded_contest::<RistrettoCtx>(
+            &ctx,
+            &vec![get_writein_plaintext()],
+            &ballot_style,
+        )
+        .unwrap();
+
+        auditable_ballot.contests = vec![];
+
+        let report =
+            verify_auditable_ballot(&auditable_ballot, &ballot_style, None)
+                .unwrap();
+        assert!(!report.is_verified());
+        assert!(report
+            .checks
+            .iter()
+            .any(|check| check.kind == VerificationCheckKind::Contests
+                && check.status == VerificationCheckStatus::Failed));
+        assert!(report
+            .checks
+            .iter()
+            .any(|check| check.kind == VerificationCheckKind::Ciphertext
+                && check.status == VerificationCheckStatus::Skipped));
+        assert!(report.decoded_contests.is_empty());
+    }
+
+    #[test]
+    fn test_verify_auditable_multi_ballot() {
+        let mut csprng = StrandRng;
+        let ctx = RistrettoCtx;
+        let ballot_style = get_writein_ballot_style();
+        let auditable_multi_ballot =
+            encrypt_decoded_multi_contest::<RistrettoCtx>(
+                &ctx,
+                &vec![get_writein_plaintext()],
+                &ballot_style,
+            )
+            .unwrap();
+
+        let report = verify_auditable_multi_ballot(
+            &auditable_multi_ballot,
+            &ballot_style,
+            Some(&auditable_multi_ballot.ballot_hash),
+        )
+        .unwrap();
+        assert!(report.is_verified());
+
+        let mut contests: AuditableMultiBallotContests<RistrettoCtx> =
+            Base64Deserialize::deserialize(
+                auditable_multi_ballot.contests.clone(),
+            )
+            .unwrap();
+        contests.choice.randomness = ctx.rnd_exp(&mut csprng);
+        let mut tampered = auditable_multi_ballot.clone();
+        tampered.contests = Base64Serialize::serialize(&contests).unwrap();
+        let report =
+            verify_auditable_multi_ballot(&tampered, &ballot_style, None)
+                .unwrap();
+        assert!(!report.is_verified());
+    }
+}
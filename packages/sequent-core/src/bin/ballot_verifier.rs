@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Native verifier for audited (spoiled) ballots. Takes the auditable ballot
+//! downloaded by the voter during cast-or-audit and the published ballot
+//! style, and verifies the ballot without the voting portal.
+//!
+//! Usage:
+//!   ballot_verifier --ballot auditable_ballot.json \
+//!       --ballot-style ballot_style.json [--receipt <ballot hash>] [--json]
+
+use clap::Parser;
+use sequent_core::ballot::{AuditableBallot, BallotStyle};
+use sequent_core::ballot_verifier::{
+    verify_auditable_ballot, verify_auditable_multi_ballot,
+    BallotVerificationReport,
+};
+use sequent_core::multi_ballot::AuditableMultiBallot;
+use sequent_core::serialization::deserialize_with_path::{
+    deserialize_str, deserialize_value,
+};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(name = "ballot_verifier")]
+struct Cli {
+    /// Auditable ballot or auditable multi ballot JSON file
+    #[arg(short, long)]
+    ballot: PathBuf,
+
+    /// Published ballot style JSON file
+    #[arg(short = 's', long)]
+    ballot_style: PathBuf,
+
+    /// Ballot hash shown to the voter on the receipt
+    #[arg(short, long)]
+    receipt: Option<String>,
+
+    /// Print the verification report as JSON
+    #[arg(long, default_value_t = false)]
+    json: bool,
+}
+
+fn read_file(path: &PathBuf) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|err| format!("Error reading {}: {err}", path.display()))
+}
+
+fn verify(cli: &Cli) -> Result<BallotVerificationReport, String> {
+    let ballot_style: BallotStyle =
+        deserialize_str(&read_file(&cli.ballot_style)?)
+            .map_err(|err| format!("Error parsing ballot style: {err}"))?;
+    let ballot_js: Value = deserialize_str(&read_file(&cli.ballot)?)
+        .map_err(|err| format!("Error parsing ballot: {err}"))?;
+
+    // Multi ballots serialize all contests into a single string, while
+    // regular ballots have one serialized string per contest.
+    let is_multi_ballot = ballot_js
+        .get("contests")
+        .map(|contests| contests.is_string())
+        .unwrap_or(false);
+
+    if is_multi_ballot {
+        let auditable_multi_ballot: AuditableMultiBallot =
+            deserialize_value(ballot_js).map_err(|err| {
+                format!("Error parsing auditable multi ballot: {err}")
+            })?;
+        verify_auditable_multi_ballot(
+            &auditable_multi_ballot,
+            &ballot_style,
+            cli.receipt.as_deref(),
+        )
+        .map_err(|err| format!("Error verifying multi ballot: {err:?}"))
+    } else {
+        let auditable_ballot: AuditableBallot = deserialize_value(ballot_js)
+            .map_err(|err| format!("Error parsing auditable ballot: {err}"))?;
+        verify_auditable_ballot(
+            &auditable_ballot,
+            &ballot_style,
+            cli.receipt.as_deref(),
+        )
+        .map_err(|err| format!("Error verifying ballot: {err:?}"))
+    }
+}
+
+fn print_report(report: &BallotVerificationReport) {
+    println!("Ballot hash: {}", report.ballot_hash);
+    println!("Election id: {}", report.election_id);
+    println!();
+    for check in &report.checks {
+        match &check.message {
+            Some(message) => {
+                println!("[{}] {}: {}", check.status, check.kind, message)
+            }
+            None => println!("[{}] {}", check.status, check.kind),
+        }
+    }
+    println!();
+    for contest in &report.decoded_contests {
+        println!("Contest {}", contest.contest_id);
+        if contest.is_explicit_invalid {
+            println!("  explicit invalid vote");
+        }
+        for choice in contest.choices.iter().filter(|c| c.is_selected()) {
+            match &choice.write_in_text {
+                Some(text) => println!(
+                    "  {} (selected: {}, write-in: {})",
+                    choice.id, choice.selected, text
+                ),
+                None => {
+                    println!("  {} (selected: {})", choice.id, choice.selected)
+                }
+            }
+        }
+        for error in &contest.invalid_errors {
+            println!(
+                "  invalid: {:?} {}",
+                error.error_type,
+                error.message.clone().unwrap_or_default()
+            );
+        }
+    }
+    println!();
+    if report.is_verified() {
+        println!("Ballot verified");
+    } else {
+        println!("Ballot verification FAILED");
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let report = match verify(&cli) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("Error serializing report: {err}");
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        print_report(&report);
+    }
+
+    if report.is_verified() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
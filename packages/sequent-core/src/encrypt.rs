@@ -123,6 +123,23 @@ pub fn recreate_encrypt_cyphertext<C: Ctx>(
         .collect()
 }
 
+pub fn recreate_encrypt_multi_cyphertext<C: Ctx>(
+    ctx: &C,
+    ballot: &AuditableMultiBallot,
+) -> Result<ReplicationChoice<C>, BallotError> {
+    let public_key = parse_public_key::<C>(&ballot.config)?;
+
+    let contests: AuditableMultiBallotContests<C> =
+        ballot.deserialize_contests::<C>()?;
+    if contests.contest_ids.len() != ballot.config.contests.len() {
+        return Err(BallotError::ConsistencyCheck(String::from(
+            "Number of election contests should match number of contests in the multi ballot",
+        )));
+    }
+
+    recreate_encrypt_candidate(ctx, &public_key, &contests.choice)
+}
+
 fn recreate_encrypt_candidate<C: Ctx>(
     ctx: &C,
     public_key_element: &C::E,
@@ -236,7 +253,10 @@ pub fn encrypt_decoded_contest<C: Ctx<P = [u8; 30]>>(
                 ))
             })?;
         let plaintext = contest
-            .encode_plaintext_contest(&decoded_contest)
+            .encode_plaintext_contest_with_version(
+                &decoded_contest,
+                config.get_codec_version(),
+            )
             .map_err(|err| {
                 BallotError::Serialization(format!(
                     "Error encrypting plaintext: {}",
@@ -278,7 +298,12 @@ pub fn encrypt_decoded_contest<C: Ctx<P = [u8; 30]>>(
 pub fn hash_ballot_style_sha512(
     ballot_style: &BallotStyle,
 ) -> Result<Hash, StrandError> {
-    let bytes = ballot_style.strand_serialize()?;
+    let mut bytes = ballot_style.strand_serialize()?;
+    // The codec version is only hashed when present, so that ballot styles
+    // published before it existed keep their original hash.
+    if let Some(codec_version) = ballot_style.codec_version {
+        bytes.extend(Some(codec_version).strand_serialize()?);
+    }
     hash::hash_to_array(&bytes)
 }
 
@@ -537,4 +562,48 @@ mod tests {
         assert_eq!(format!("{:?}", auditable_ballot.unwrap_err()), "".to_string());
         //assert!(auditable_ballot.is_ok());
     }*/
+
+    fn legacy_ballot_style() -> crate::ballot::BallotStyle {
+        crate::ballot::BallotStyle {
+            id: "legacy-ballot-style".to_string(),
+            tenant_id: "tenant".to_string(),
+            election_event_id: "election-event".to_string(),
+            election_id: "election".to_string(),
+            num_allowed_revotes: None,
+            description: None,
+            public_key: None,
+            area_id: "area".to_string(),
+            area_presentation: None,
+            contests: vec![],
+            election_event_presentation: None,
+            election_presentation: None,
+            election_dates: None,
+            election_event_annotations: None,
+            election_annotations: None,
+            area_annotations: None,
+            codec_version: None,
+        }
+    }
+
+    #[test]
+    fn test_hash_legacy_ballot_style() {
+        // Hash of the ballot style as computed before the codec version was
+        // added to it, it must not change.
+        assert_eq!(
+            encrypt::hash_ballot_style(&legacy_ballot_style()).unwrap(),
+            "3da28107a09c7b8423f8dd1d8c1843bac31d0c906497cb93f09f9427d6dc1a82"
+        );
+    }
+
+    #[test]
+    fn test_hash_ballot_style_with_codec_version() {
+        let ballot_style = crate::ballot::BallotStyle {
+            codec_version: Some(1),
+            ..legacy_ballot_style()
+        };
+        assert_eq!(
+            encrypt::hash_ballot_style(&ballot_style).unwrap(),
+            "5dd9c7fa191571b7a42015a86aae66b52d9aea3d74f7be707c60722131ef3095"
+        );
+    }
 }
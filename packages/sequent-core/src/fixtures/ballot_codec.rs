@@ -448,6 +448,7 @@ pub fn get_writein_ballot_style() -> BallotStyle {
             }),
         }],
         area_annotations: None,
+        codec_version: None,
     }
 }
 
@@ -0,0 +1,362 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Proptest strategies generating random but valid contests, ballot styles
+//! and plaintexts, used by the ballot codec round trip property tests.
+
+use crate::ballot::*;
+use crate::ballot_codec::CURRENT_CODEC_VERSION;
+use crate::plaintext::{DecodedVoteChoice, DecodedVoteContest};
+use proptest::prelude::*;
+use proptest::string::string_regex;
+
+/// Counting algorithms supported by the single contest encoding.
+pub const GENERATED_COUNTING_ALGORITHMS: [&str; 4] = [
+    "plurality-at-large",
+    "borda",
+    "instant-runoff",
+    "cumulative",
+];
+
+const MAX_CANDIDATES: usize = 6;
+const MAX_WRITE_INS: usize = 2;
+const MAX_CHECKBOXES: u64 = 3;
+
+fn s(value: &str) -> String {
+    value.to_string()
+}
+
+fn generated_candidate(
+    contest_id: &str,
+    index: usize,
+    is_write_in: bool,
+    is_explicit_invalid: bool,
+) -> Candidate {
+    let mut presentation = CandidatePresentation::new();
+    presentation.is_write_in = Some(is_write_in);
+    presentation.is_explicit_invalid = Some(is_explicit_invalid);
+    Candidate {
+        // ids sort in the same order as they are generated
+        id: format!("{}-candidate-{}", contest_id, index),
+        tenant_id: s("tenant"),
+        election_event_id: s("election-event"),
+        election_id: s("election"),
+        contest_id: s(contest_id),
+        name: Some(format!("Candidate {}", index)),
+        name_i18n: None,
+        description: None,
+        description_i18n: None,
+        alias: None,
+        alias_i18n: None,
+        candidate_type: None,
+        presentation: Some(presentation),
+        annotations: None,
+    }
+}
+
+/// Builds a contest with `num_candidates` valid candidates, the last
+/// `num_write_ins` of which are write-ins, plus an optional explicit invalid
+/// candidate.
+pub fn generated_contest(
+    contest_id: &str,
+    counting_algorithm: &str,
+    num_candidates: usize,
+    num_write_ins: usize,
+    has_explicit_invalid: bool,
+    base32_writeins: bool,
+    cumulative_number_of_checkboxes: u64,
+    min_votes: i64,
+    max_votes: i64,
+) -> Contest {
+    let mut candidates: Vec<Candidate> = (0..num_candidates)
+        .map(|index| {
+            generated_candidate(
+                contest_id,
+                index,
+                index >= num_candidates - num_write_ins,
+                false,
+            )
+        })
+        .collect();
+    if has_explicit_invalid {
+        candidates.push(generated_candidate(
+            contest_id,
+            num_candidates,
+            false,
+            true,
+        ));
+    }
+
+    let mut presentation = ContestPresentation::new();
+    presentation.allow_writeins = Some(num_write_ins > 0);
+    presentation.base32_writeins = Some(base32_writeins);
+    presentation.cumulative_number_of_checkboxes =
+        Some(cumulative_number_of_checkboxes);
+
+    Contest {
+        id: s(contest_id),
+        tenant_id: s("tenant"),
+        election_event_id: s("election-event"),
+        election_id: s("election"),
+        name: Some(s(contest_id)),
+        name_i18n: None,
+        description: None,
+        description_i18n: None,
+        alias: None,
+        alias_i18n: None,
+        max_votes,
+        min_votes,
+        winning_candidates_num: 1,
+        voting_type: None,
+        counting_algorithm: Some(s(counting_algorithm)),
+        is_encrypted: true,
+        candidates,
+        presentation: Some(presentation),
+        created_at: None,
+        annotations: None,
+    }
+}
+
+/// Random contest using any of `GENERATED_COUNTING_ALGORITHMS`.
+pub fn arb_contest() -> impl Strategy<Value = Contest> {
+    (
+        0..GENERATED_COUNTING_ALGORITHMS.len(),
+        1..=MAX_CANDIDATES,
+        0..=MAX_WRITE_INS,
+        any::<bool>(),
+        any::<bool>(),
+        1..=MAX_CHECKBOXES,
+        any::<usize>(),
+        any::<usize>(),
+    )
+        .prop_map(
+            |(
+                algorithm,
+                num_candidates,
+                num_write_ins,
+                has_explicit_invalid,
+                base32_writeins,
+                checkboxes,
+                max_seed,
+                min_seed,
+            )| {
+                let max_votes = 1 + max_seed % num_candidates;
+                let min_votes = min_seed % (max_votes + 1);
+                generated_contest(
+                    "contest",
+                    GENERATED_COUNTING_ALGORITHMS[algorithm],
+                    num_candidates,
+                    num_write_ins.min(num_candidates),
+                    has_explicit_invalid,
+                    base32_writeins,
+                    checkboxes,
+                    min_votes as i64,
+                    max_votes as i64,
+                )
+            },
+        )
+}
+
+/// Random plurality contest without write-ins, the only kind of contest
+/// supported by the multi contest encoding.
+pub fn arb_plurality_contest(
+    contest_id: String,
+) -> impl Strategy<Value = Contest> {
+    (1..=4usize, any::<usize>(), any::<usize>()).prop_map(
+        move |(num_candidates, max_seed, min_seed)| {
+            let max_votes = 1 + max_seed % num_candidates;
+            let min_votes = min_seed % (max_votes + 1);
+            generated_contest(
+                &contest_id,
+                "plurality-at-large",
+                num_candidates,
+                0,
+                false,
+                true,
+                1,
+                min_votes as i64,
+                max_votes as i64,
+            )
+        },
+    )
+}
+
+pub fn generated_ballot_style(contests: Vec<Contest>) -> BallotStyle {
+    BallotStyle {
+        id: s("ballot-style"),
+        tenant_id: s("tenant"),
+        election_event_id: s("election-event"),
+        election_id: s("election"),
+        num_allowed_revotes: None,
+        description: None,
+        public_key: None,
+        area_id: s("area"),
+        area_presentation: None,
+        contests,
+        election_event_presentation: None,
+        election_presentation: None,
+        election_dates: None,
+        election_event_annotations: None,
+        election_annotations: None,
+        area_annotations: None,
+        codec_version: Some(CURRENT_CODEC_VERSION),
+    }
+}
+
+/// Random ballot style with 1 to 3 plurality contests.
+pub fn arb_multi_contest_ballot_style() -> impl Strategy<Value = BallotStyle> {
+    (1..=3usize)
+        .prop_flat_map(|num_contests| {
+            (0..num_contests)
+                .map(|index| {
+                    arb_plurality_contest(format!("contest-{}", index))
+                })
+                .collect::<Vec<_>>()
+        })
+        .prop_map(generated_ballot_style)
+}
+
+fn write_in_regex(contest: &Contest) -> &'static str {
+    if contest.base32_writeins() {
+        "[A-Z]{1,4}"
+    } else {
+        "[a-z0-9ñ]{1,4}"
+    }
+}
+
+/// Random plaintext for a contest. Ordered contests (borda, ranked) rank at
+/// most `max_votes` candidates, while plurality contests may select any
+/// number of candidates so that overvotes and undervotes are generated too.
+pub fn arb_decoded_vote_contest(
+    contest: Contest,
+) -> impl Strategy<Value = DecodedVoteContest> {
+    arb_decoded_vote_contest_with_min(contest, 0)
+}
+
+/// Same as `arb_decoded_vote_contest`, selecting at least `min_selected`
+/// candidates.
+fn arb_decoded_vote_contest_with_min(
+    contest: Contest,
+    min_selected: usize,
+) -> impl Strategy<Value = DecodedVoteContest> {
+    let num_valid = contest
+        .candidates
+        .iter()
+        .filter(|candidate| !candidate.is_explicit_invalid())
+        .count();
+    let positions: Vec<usize> = (0..num_valid).collect();
+    let write_in_text = string_regex(write_in_regex(&contest)).unwrap();
+
+    (
+        any::<bool>(),
+        Just(positions).prop_shuffle(),
+        any::<usize>(),
+        proptest::collection::vec(any::<u64>(), num_valid),
+        proptest::collection::vec(write_in_text, num_valid),
+    )
+        .prop_map(
+            move |(is_explicit_invalid, order, count_seed, values, texts)| {
+                let algorithm = contest.get_counting_algorithm();
+                let limit = if algorithm == "plurality-at-large" {
+                    num_valid
+                } else {
+                    (contest.max_votes as usize).min(num_valid)
+                };
+                let min_selected = min_selected.min(limit);
+                let count =
+                    min_selected + count_seed % (limit - min_selected + 1);
+                let checkboxes = contest.cumulative_number_of_checkboxes();
+
+                let choices = contest
+                    .candidates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, candidate)| {
+                        let rank = order
+                            .iter()
+                            .position(|position| *position == index)
+                            .filter(|rank| {
+                                !candidate.is_explicit_invalid()
+                                    && *rank < count
+                            });
+                        let selected = match (rank, algorithm.as_str()) {
+                            (None, _) => -1,
+                            (Some(_), "plurality-at-large") => 0,
+                            (Some(_), "cumulative") => {
+                                (values[index] % checkboxes) as i64
+                            }
+                            (Some(rank), _) => rank as i64,
+                        };
+                        let write_in_text =
+                            if candidate.is_write_in() && selected > -1 {
+                                Some(texts[index].clone())
+                            } else {
+                                None
+                            };
+                        DecodedVoteChoice {
+                            id: candidate.id.clone(),
+                            selected,
+                            write_in_text,
+                        }
+                    })
+                    .collect();
+
+                DecodedVoteContest {
+                    contest_id: contest.id.clone(),
+                    is_explicit_invalid,
+                    invalid_errors: vec![],
+                    invalid_alerts: vec![],
+                    choices,
+                }
+            },
+        )
+}
+
+/// Random contest together with a random plaintext for it.
+pub fn arb_contest_and_plaintext(
+) -> impl Strategy<Value = (Contest, DecodedVoteContest)> {
+    arb_contest().prop_flat_map(|contest| {
+        (Just(contest.clone()), arb_decoded_vote_contest(contest))
+    })
+}
+
+/// Random multi contest ballot style together with a plaintext for each of
+/// its contests. All contests share the explicit invalid flag, as the multi
+/// contest encoding only has one for the whole ballot.
+pub fn arb_multi_contest_plaintexts(
+) -> impl Strategy<Value = (BallotStyle, Vec<DecodedVoteContest>)> {
+    arb_multi_contest_ballot_style().prop_flat_map(|ballot_style| {
+        let plaintexts: Vec<_> = ballot_style
+            .contests
+            .iter()
+            .map(|contest| {
+                let mut limited = contest.clone();
+                // the multi contest encoding can't hold overvotes or
+                // undervotes
+                limited.counting_algorithm = Some(s("borda"));
+                arb_decoded_vote_contest_with_min(
+                    limited,
+                    contest.min_votes as usize,
+                )
+            })
+            .collect();
+        (Just(ballot_style), plaintexts, any::<bool>()).prop_map(
+            |(ballot_style, plaintexts, is_explicit_invalid)| {
+                let plaintexts = plaintexts
+                    .into_iter()
+                    .map(|mut plaintext| {
+                        plaintext.is_explicit_invalid = is_explicit_invalid;
+                        for choice in plaintext.choices.iter_mut() {
+                            if choice.selected > -1 {
+                                choice.selected = 0;
+                            }
+                        }
+                        plaintext
+                    })
+                    .collect();
+                (ballot_style, plaintexts)
+            },
+        )
+    })
+}
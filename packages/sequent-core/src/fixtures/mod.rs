@@ -6,3 +6,5 @@ pub mod ballot_codec;
 
 #[cfg(test)]
 pub mod encrypt;
+#[cfg(test)]
+pub mod generators;
@@ -100,6 +100,16 @@ pub fn get_layout_properties(
             sorted: true,
             ordered: true,
         }),
+        "instant-runoff" => Some(ContestLayoutProperties {
+            state: ContestState::MultiContest,
+            sorted: true,
+            ordered: true,
+        }),
+        "single-transferable-vote" => Some(ContestLayoutProperties {
+            state: ContestState::MultiContest,
+            sorted: true,
+            ordered: true,
+        }),
         "cumulative" => Some(ContestLayoutProperties {
             state: ContestState::SimultaneousContestsScreen,
             sorted: false,
@@ -6,7 +6,9 @@ extern crate quick_error;
 extern crate cfg_if;
 
 pub mod ballot;
+pub mod ballot_diff;
 pub mod ballot_style;
+pub mod ballot_verifier;
 pub mod error;
 pub mod multi_ballot;
 pub mod types;
@@ -17,6 +19,7 @@ pub mod fixtures;
 pub mod interpret_plaintext;
 pub mod mixed_radix;
 pub mod plaintext;
+pub mod schemas;
 pub mod serialization;
 pub mod services;
 #[cfg(feature = "sqlite")]
@@ -1,8 +1,21 @@
 // SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
 //
 // SPDX-License-Identifier: AGPL-3.0-only
+use sequent_core::schemas::{public_schemas, write_schemas, SCHEMAS_VERSION};
+use std::path::PathBuf;
 
-fn main() {
-    //let schema = schema_for!(AuditableBallot);
-    //println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+// Generates the JSON schemas of the public ballot formats into
+// `<output_dir>/v<SCHEMAS_VERSION>`. Usage: `sequent-core [output_dir]`,
+// output_dir defaults to `schemas`.
+fn main() -> anyhow::Result<()> {
+    let output_dir: PathBuf = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("schemas"));
+
+    let paths = write_schemas(&output_dir, SCHEMAS_VERSION, &public_schemas())?;
+    for path in paths {
+        println!("{}", path.display());
+    }
+    Ok(())
 }
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 use borsh::{BorshDeserialize, BorshSerialize};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::encrypt::hash_ballot_style;
@@ -22,7 +23,7 @@ use strand::signature::StrandSignature;
 use strand::signature::StrandSignaturePk;
 use strand::signature::StrandSignatureSk;
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 pub struct AuditableMultiBallot {
     pub version: u32,
     pub issue_date: String,
@@ -45,7 +46,14 @@ pub struct AuditableMultiBallotContests<C: Ctx> {
 }
 
 #[derive(
-    BorshSerialize, Serialize, Deserialize, PartialEq, Eq, Debug, Clone,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    Debug,
+    Clone,
 )]
 pub struct HashableMultiBallot {
     pub version: u32,
@@ -59,7 +67,7 @@ pub struct HashableMultiBallot {
     pub ballot_style_hash: String,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
 pub struct SignedHashableMultiBallot {
     pub version: u32,
     pub issue_date: String,
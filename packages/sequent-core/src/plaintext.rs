@@ -18,6 +18,12 @@ pub enum InvalidPlaintextErrorType {
     Explicit,
     Implicit,
     EncodingError,
+    /// A candidate was ranked more than once in a ranked contest.
+    DuplicateRank,
+    /// A rank was left empty while a later rank was used.
+    SkippedRank,
+    /// More than one candidate was given the same rank.
+    OvervoteAtRank,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, PartialEq, Eq, Debug, Clone)]
@@ -0,0 +1,247 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::ballot::{AuditableBallot, BallotStyle, HashableBallot};
+use crate::ballot_verifier::BallotVerificationReport;
+use crate::multi_ballot::{AuditableMultiBallot, HashableMultiBallot};
+use crate::plaintext::DecodedVoteContest;
+use crate::types::hasura::core::TallySheet;
+use crate::types::tally_sheets::AreaContestResults;
+use anyhow::{Context, Result};
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the published schemas. Bump it whenever a public format changes
+/// in a way that isn't backwards compatible, so that third party verifiers
+/// can keep validating documents of previous elections.
+pub const SCHEMAS_VERSION: u32 = 1;
+
+pub const SCHEMA_FILE_EXTENSION: &str = "schema.json";
+
+/// A JSON schema of a public format, together with the name used for the
+/// generated file.
+#[derive(Debug, Clone)]
+pub struct PublicSchema {
+    pub name: &'static str,
+    pub schema: RootSchema,
+}
+
+impl PublicSchema {
+    pub fn new(name: &'static str, schema: RootSchema) -> PublicSchema {
+        PublicSchema { name, schema }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.name, SCHEMA_FILE_EXTENSION)
+    }
+}
+
+/// Schemas of the public ballot formats defined in sequent-core.
+pub fn public_schemas() -> Vec<PublicSchema> {
+    vec![
+        PublicSchema::new("auditable_ballot", schema_for!(AuditableBallot)),
+        PublicSchema::new("hashable_ballot", schema_for!(HashableBallot)),
+        PublicSchema::new(
+            "auditable_multi_ballot",
+            schema_for!(AuditableMultiBallot),
+        ),
+        PublicSchema::new(
+            "hashable_multi_ballot",
+            schema_for!(HashableMultiBallot),
+        ),
+        PublicSchema::new("ballot_style", schema_for!(BallotStyle)),
+        PublicSchema::new(
+            "decoded_vote_contests",
+            schema_for!(Vec<DecodedVoteContest>),
+        ),
+        PublicSchema::new(
+            "ballot_verification_report",
+            schema_for!(BallotVerificationReport),
+        ),
+        PublicSchema::new("tally_sheet", schema_for!(TallySheet)),
+        PublicSchema::new(
+            "tally_sheet_content",
+            schema_for!(AreaContestResults),
+        ),
+    ]
+}
+
+/// Directory where the schemas of a given version are written, for example
+/// `<output_dir>/v1`.
+pub fn versioned_schemas_dir(output_dir: &Path, version: u32) -> PathBuf {
+    output_dir.join(format!("v{}", version))
+}
+
+/// Writes the schemas as pretty printed JSON files into the versioned
+/// subfolder of `output_dir`, returning the paths of the written files.
+pub fn write_schemas(
+    output_dir: &Path,
+    version: u32,
+    schemas: &Vec<PublicSchema>,
+) -> Result<Vec<PathBuf>> {
+    let schemas_dir = versioned_schemas_dir(output_dir, version);
+    fs::create_dir_all(&schemas_dir).with_context(|| {
+        format!("Error creating directory {}", schemas_dir.display())
+    })?;
+
+    schemas
+        .iter()
+        .map(|public_schema| {
+            let path = schemas_dir.join(public_schema.file_name());
+            let contents = serde_json::to_string_pretty(&public_schema.schema)
+                .with_context(|| {
+                    format!("Error serializing schema {}", public_schema.name)
+                })?;
+            fs::write(&path, contents).with_context(|| {
+                format!("Error writing schema {}", path.display())
+            })?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ballot_verifier::verify_auditable_ballot;
+    use crate::encrypt::{
+        encrypt_decoded_contest, encrypt_decoded_multi_contest,
+    };
+    use crate::fixtures::ballot_codec::*;
+    use crate::types::tally_sheets::{CandidateResults, InvalidVotes};
+    use jsonschema::JSONSchema;
+    use serde::Serialize;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use strand::backend::ristretto::RistrettoCtx;
+
+    fn get_schema(name: &str) -> Value {
+        let public_schema = public_schemas()
+            .into_iter()
+            .find(|public_schema| public_schema.name == name)
+            .unwrap();
+        serde_json::to_value(&public_schema.schema).unwrap()
+    }
+
+    fn assert_valid<T: Serialize>(name: &str, instance: &T) {
+        let schema = get_schema(name);
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = serde_json::to_value(instance).unwrap();
+        let result = compiled.validate(&instance);
+        if let Err(errors) = result {
+            let messages: Vec<String> = errors
+                .map(|error| format!("{} at {}", error, error.instance_path))
+                .collect();
+            panic!("{} is not valid: {}", name, messages.join(", "));
+        }
+    }
+
+    #[test]
+    fn test_schemas_compile() {
+        for public_schema in public_schemas() {
+            let schema = serde_json::to_value(&public_schema.schema).unwrap();
+            assert!(
+                JSONSchema::compile(&schema).is_ok(),
+                "Invalid schema {}",
+                public_schema.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_schemas() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths =
+            write_schemas(dir.path(), SCHEMAS_VERSION, &public_schemas())
+                .unwrap();
+        assert_eq!(paths.len(), public_schemas().len());
+        assert!(dir
+            .path()
+            .join("v1")
+            .join("auditable_ballot.schema.json")
+            .exists());
+    }
+
+    #[test]
+    fn test_ballot_fixtures_match_schemas() {
+        let ctx = RistrettoCtx;
+        let ballot_style = get_writein_ballot_style();
+        assert_valid("ballot_style", &ballot_style);
+
+        for fixture in get_fixtures() {
+            let mut fixture_ballot_style = ballot_style.clone();
+            fixture_ballot_style.contests = vec![fixture.contest.clone()];
+            assert_valid("ballot_style", &fixture_ballot_style);
+            assert_valid("decoded_vote_contests", &vec![fixture.plaintext]);
+        }
+
+        let auditable_ballot = encrypt_decoded_contest::<RistrettoCtx>(
+            &ctx,
+            &vec![get_writein_plaintext()],
+            &ballot_style,
+        )
+        .unwrap();
+        assert_valid("auditable_ballot", &auditable_ballot);
+        assert_valid(
+            "hashable_ballot",
+            &HashableBallot::try_from(
+                &crate::ballot::SignedHashableBallot::try_from(
+                    &auditable_ballot,
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_valid(
+            "ballot_verification_report",
+            &verify_auditable_ballot(&auditable_ballot, &ballot_style, None)
+                .unwrap(),
+        );
+
+        let auditable_multi_ballot =
+            encrypt_decoded_multi_contest::<RistrettoCtx>(
+                &ctx,
+                &vec![get_writein_plaintext()],
+                &ballot_style,
+            )
+            .unwrap();
+        assert_valid("auditable_multi_ballot", &auditable_multi_ballot);
+        assert_valid(
+            "hashable_multi_ballot",
+            &HashableMultiBallot::try_from(&auditable_multi_ballot).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_tally_sheet_content_matches_schema() {
+        let candidate_results = HashMap::from([(
+            "candidate".to_string(),
+            CandidateResults {
+                candidate_id: "candidate".to_string(),
+                total_votes: Some(10),
+            },
+        )]);
+        let content = AreaContestResults {
+            area_id: "area".to_string(),
+            contest_id: "contest".to_string(),
+            total_votes: Some(12),
+            total_valid_votes: Some(10),
+            invalid_votes: Some(InvalidVotes {
+                total_invalid: Some(2),
+                implicit_invalid: Some(1),
+                explicit_invalid: Some(1),
+            }),
+            total_blank_votes: Some(0),
+            census: Some(20),
+            candidate_results,
+        };
+        assert_valid("tally_sheet_content", &content);
+
+        let mut invalid = serde_json::to_value(&content).unwrap();
+        invalid["total_votes"] = Value::String("twelve".to_string());
+        let schema = get_schema("tally_sheet_content");
+        assert!(!JSONSchema::compile(&schema).unwrap().is_valid(&invalid));
+    }
+}
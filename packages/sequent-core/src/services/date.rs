@@ -3,7 +3,12 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, Local, LocalResult, TimeZone, Utc};
+use chrono::{
+    DateTime, Duration, Local, LocalResult, NaiveDateTime, Offset, TimeZone,
+    Utc,
+};
+pub use chrono_tz::Tz;
+use std::str::FromStr;
 use time::OffsetDateTime;
 
 // format: 2023-08-10T22:05:22.214163+00:00
@@ -31,6 +36,28 @@ impl ISO8601 {
         Local::now()
     }
 
+    /// Parses an RFC 3339 date, or a date without offset like
+    /// `2025-03-30T02:30:00` as the wall-clock time in the given zone.
+    pub fn to_date_in_time_zone(
+        date_string: &str,
+        time_zone: &Tz,
+    ) -> Result<DateTime<Tz>> {
+        if let Ok(date) = DateTime::parse_from_rfc3339(date_string) {
+            return Ok(date.with_timezone(time_zone));
+        }
+        let naive = [
+            "%Y-%m-%dT%H:%M:%S%.f",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%d %H:%M:%S",
+        ]
+        .iter()
+        .find_map(|format| {
+            NaiveDateTime::parse_from_str(date_string, format).ok()
+        })
+        .ok_or_else(|| anyhow!("Invalid date {date_string:?}"))?;
+        local_to_date(&naive, time_zone)
+    }
+
     pub fn timestamp_ms_utc_to_date(millis: i64) -> DateTime<Local> {
         // Convert Unix timestamp in milliseconds to DateTime<Utc>
         let date_time_utc = Utc.timestamp_millis_opt(millis).unwrap();
@@ -65,3 +92,71 @@ impl ISO8601 {
 pub fn get_now_utc_unix_ms() -> i64 {
     OffsetDateTime::now_utc().unix_timestamp() * 1000
 }
+
+/// Parses an IANA time zone name, like `Europe/Madrid`.
+pub fn parse_time_zone(time_zone: &str) -> Result<Tz> {
+    Tz::from_str(time_zone)
+        .map_err(|err| anyhow!("Invalid time zone {time_zone:?}: {err}"))
+}
+
+/// Wall-clock time in the given zone as an absolute date. Times skipped by a
+/// DST transition are moved forward by the length of the gap, and repeated
+/// times resolve to their first occurrence.
+pub fn local_to_date(
+    naive: &NaiveDateTime,
+    time_zone: &Tz,
+) -> Result<DateTime<Tz>> {
+    match time_zone.from_local_datetime(naive) {
+        LocalResult::Single(date) => Ok(date),
+        LocalResult::Ambiguous(earliest, _latest) => Ok(earliest),
+        LocalResult::None => {
+            // use the offset in effect before the gap
+            let offset = time_zone
+                .offset_from_local_datetime(&(*naive - Duration::days(1)))
+                .earliest()
+                .ok_or_else(|| anyhow!("Invalid local date {naive}"))?
+                .fix();
+            let naive_utc =
+                *naive - Duration::seconds(offset.local_minus_utc().into());
+            Ok(Utc.from_utc_datetime(&naive_utc).with_timezone(time_zone))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_date_in_time_zone_dst() {
+        let madrid = parse_time_zone("Europe/Madrid").unwrap();
+
+        // winter and summer offsets
+        let winter =
+            ISO8601::to_date_in_time_zone("2025-01-15T09:00:00", &madrid)
+                .unwrap();
+        assert_eq!(winter.to_rfc3339(), "2025-01-15T09:00:00+01:00");
+        let summer =
+            ISO8601::to_date_in_time_zone("2025-06-15T09:00", &madrid).unwrap();
+        assert_eq!(summer.to_rfc3339(), "2025-06-15T09:00:00+02:00");
+
+        // skipped by the spring forward transition
+        let gap = ISO8601::to_date_in_time_zone("2025-03-30T02:30:00", &madrid)
+            .unwrap();
+        assert_eq!(gap.to_rfc3339(), "2025-03-30T03:30:00+02:00");
+
+        // repeated by the fall back transition
+        let repeated =
+            ISO8601::to_date_in_time_zone("2025-10-26T02:30:00", &madrid)
+                .unwrap();
+        assert_eq!(repeated.to_rfc3339(), "2025-10-26T02:30:00+02:00");
+
+        // dates with offset keep their instant
+        let with_offset =
+            ISO8601::to_date_in_time_zone("2025-06-15T07:00:00Z", &madrid)
+                .unwrap();
+        assert_eq!(with_offset, summer);
+
+        assert!(parse_time_zone("Europe/Atlantis").is_err());
+    }
+}
@@ -2,7 +2,8 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 use anyhow::{anyhow, Context as ContextAnyhow, Result};
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
 use handlebars::{
     handlebars_helper, BlockParamHolder, Context, Handlebars, Helper,
     HelperDef, HelperResult, JsonValue, Output, RenderContext, RenderError,
@@ -84,6 +85,84 @@ pub fn render_template_text(
     reg.render_template(template, &json!(variables_map))
 }
 
+/// Maximum number of missing variables reported by `find_missing_variables`.
+const MAX_MISSING_VARIABLES: usize = 100;
+
+/// Sets the value at a dotted path of the variables, creating the
+/// intermediate objects. Returns false if the path can't be set.
+fn set_variable_path(
+    variables_map: &mut Map<String, Value>,
+    path: &str,
+    value: Value,
+) -> bool {
+    let path = path
+        .strip_prefix("this.")
+        .or_else(|| path.strip_prefix("@root."))
+        .unwrap_or(path);
+    let keys: Vec<&str> = path.split('.').collect();
+    if keys.iter().any(|key| {
+        key.is_empty()
+            || key.starts_with('@')
+            || key.starts_with('[')
+            || *key == ".."
+            || *key == "this"
+    }) {
+        return false;
+    }
+    let Some((last, parents)) = keys.split_last() else {
+        return false;
+    };
+    let mut current = variables_map;
+    for key in parents {
+        let entry = current
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(ref mut next) = entry else {
+            return false;
+        };
+        current = next;
+    }
+    current.insert(last.to_string(), value);
+    true
+}
+
+/// Returns the variables used by the template that are missing from the
+/// variables map, by rendering it in strict mode and filling each missing
+/// variable in turn. Syntax errors of the template are returned as errors.
+#[instrument(skip_all, err)]
+pub fn find_missing_variables(
+    template: &str,
+    variables_map: Map<String, Value>,
+) -> Result<Vec<String>, RenderError> {
+    let mut reg = get_registry();
+    reg.set_strict_mode(true);
+    let mut variables_map = variables_map;
+    let mut missing: Vec<String> = vec![];
+
+    while missing.len() < MAX_MISSING_VARIABLES {
+        let error = match reg.render_template(template, &json!(variables_map)) {
+            Ok(_) => break,
+            Err(error) => error,
+        };
+        let RenderErrorReason::MissingVariable(path) = error.reason() else {
+            return Err(error);
+        };
+        let path = path.clone().unwrap_or_else(|| "-".to_string());
+        if missing.contains(&path) {
+            break;
+        }
+        missing.push(path.clone());
+        if !set_variable_path(
+            &mut variables_map,
+            &path,
+            Value::String(String::new()),
+        ) {
+            break;
+        }
+    }
+    Ok(missing)
+}
+
 #[instrument(skip_all, err)]
 pub fn render_template(
     template_name: &str,
@@ -465,10 +544,60 @@ pub fn format_percentage(
     Ok(())
 }
 
+/// Writes a date with a strftime format. Unlike `to_string()`, it fails
+/// instead of panicking when the format can't be applied, like time
+/// specifiers on a date without time.
+fn write_date(
+    date: impl std::fmt::Display,
+    format_str: &str,
+) -> Result<String> {
+    use std::fmt::Write;
+
+    let mut formatted = String::new();
+    write!(formatted, "{date}")
+        .map_err(|_| anyhow!("Invalid date format {format_str:?}"))?;
+    Ok(formatted)
+}
+
+/// Formats a date, given as RFC 3339, `YYYY-MM-DD HH:MM:SS` in UTC or
+/// `YYYY-MM-DD`, in the given IANA time zone or else in the local one.
+/// Dates without time are formatted as is, without time zone conversion.
+pub fn format_date_in_time_zone(
+    date_str: &str,
+    format_str: &str,
+    time_zone: Option<&Tz>,
+) -> Result<String> {
+    let date = if let Ok(date) = DateTime::parse_from_rfc3339(date_str) {
+        date.with_timezone(&Utc)
+    } else if date_str.contains(':') {
+        NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S")
+            .with_context(|| format!("Invalid date {date_str:?}"))?
+            .and_utc()
+    } else {
+        let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date {date_str:?}"))?;
+        return write_date(date.format(format_str), format_str);
+    };
+
+    match time_zone {
+        Some(time_zone) => write_date(
+            date.with_timezone(time_zone).format(format_str),
+            format_str,
+        ),
+        None => write_date(
+            date.with_timezone(&Local).format(format_str),
+            format_str,
+        ),
+    }
+}
+
+/// Handlebars helper: `{{format_date date format [time_zone]}}`. Without
+/// time zone parameter it uses the `time_zone` variable of the template,
+/// usually the one of the election event.
 pub fn format_date(
     helper: &Helper,
     _: &Handlebars,
-    _: &Context,
+    ctx: &Context,
     _: &mut RenderContext,
     out: &mut dyn Output,
 ) -> HelperResult {
@@ -496,33 +625,27 @@ pub fn format_date(
         RenderErrorReason::InvalidParamType("couldn't parse as &str")
     })?;
 
-    // Detect the appropriate date parsing format dynamically
-    let parsed_date = if date_str.contains(':') {
-        // If the date string contains a time, assume "YYYY-MM-DD HH:MM:SS"
-        DateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S")
+    let time_zone = helper
+        .param(2)
+        .and_then(|param| param.value().as_str())
+        .or_else(|| ctx.data().get("time_zone").and_then(Value::as_str))
+        .filter(|time_zone| !time_zone.is_empty())
+        .map(|time_zone| {
+            Tz::from_str(time_zone).map_err(|err| {
+                RenderError::new(format!(
+                    "Invalid time zone {time_zone:?}: {err}"
+                ))
+            })
+        })
+        .transpose()?;
+
+    let formatted_date =
+        format_date_in_time_zone(date_str, format_str, time_zone.as_ref())
             .map_err(|err| {
                 RenderError::new(format!(
                     "Date parsing error: {err:?}, date_json={date_json:?}"
                 ))
-            })?
-            .with_timezone(&Local) // Convert to local timezone
-    } else {
-        // Otherwise, assume it's just a date "YYYY-MM-DD" and add a time
-        // placeholder
-        DateTime::parse_from_str(
-            &format!("{} 00:00:00", date_str),
-            "%Y-%m-%d %H:%M:%S",
-        )
-        .map_err(|err| {
-            RenderError::new(format!(
-                "Date parsing error: {err:?}, date_json={date_json:?}"
-            ))
-        })?
-        .with_timezone(&Local) // Convert to local timezone
-    };
-
-    // Format the date using the provided format string
-    let formatted_date = parsed_date.format(format_str).to_string();
+            })?;
 
     // Write the formatted date to the output
     out.write(&formatted_date)?;
@@ -587,3 +710,62 @@ pub fn to_json(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_date_in_time_zone() {
+        let madrid = Tz::from_str("Europe/Madrid").unwrap();
+        let format = "%Y-%m-%d %H:%M %Z";
+
+        assert_eq!(
+            format_date_in_time_zone(
+                "2025-01-15T08:00:00+00:00",
+                format,
+                Some(&madrid)
+            )
+            .unwrap(),
+            "2025-01-15 09:00 CET"
+        );
+        assert_eq!(
+            format_date_in_time_zone(
+                "2025-07-15 08:00:00",
+                format,
+                Some(&madrid)
+            )
+            .unwrap(),
+            "2025-07-15 10:00 CEST"
+        );
+        assert_eq!(
+            format_date_in_time_zone("2025-07-15", "%d/%m/%Y", Some(&madrid))
+                .unwrap(),
+            "15/07/2025"
+        );
+        assert!(format_date_in_time_zone("15/07/2025", format, None).is_err());
+        assert!(format_date_in_time_zone("2025-07-15", format, None).is_err());
+    }
+
+    #[test]
+    fn test_find_missing_variables() {
+        let variables = json!({"user": {"first_name": "Jane"}});
+        let Value::Object(variables) = variables else {
+            panic!("variables must be an object");
+        };
+        let template =
+            "{{user.first_name}} {{user.last_name}} {{vote_url}} {{user.first_name}}";
+
+        assert_eq!(
+            find_missing_variables(template, variables.clone()).unwrap(),
+            vec!["user.last_name".to_string(), "vote_url".to_string()]
+        );
+        assert!(find_missing_variables(
+            "{{user.first_name}}",
+            variables.clone()
+        )
+        .unwrap()
+        .is_empty());
+        assert!(find_missing_variables("{{#if}}", variables).is_err());
+    }
+}
@@ -555,6 +555,17 @@ pub async fn get_file_from_s3(
     s3_bucket: String,
     path: String,
 ) -> Result<Vec<u8>> {
+    get_file_from_s3_if_exists(s3_bucket, path.clone())
+        .await?
+        .ok_or_else(|| anyhow!("File {path} not found in s3"))
+}
+
+/// Like `get_file_from_s3`, but returns `None` if the object doesn't exist.
+#[instrument(err)]
+pub async fn get_file_from_s3_if_exists(
+    s3_bucket: String,
+    path: String,
+) -> Result<Option<Vec<u8>>> {
     let config = get_s3_aws_config(true)
         .await
         .with_context(|| "Error getting s3 aws config")?;
@@ -562,12 +573,23 @@ pub async fn get_file_from_s3(
         .await
         .with_context(|| "Error getting s3 client")?;
 
-    let mut object = client
+    let response = client
         .get_object()
         .bucket(s3_bucket.clone())
         .key(path)
         .send()
-        .await?;
+        .await;
+    let mut object = match response {
+        Ok(object) => object,
+        Err(err)
+            if err.as_service_error().is_some_and(|service_error| {
+                service_error.is_no_such_key()
+            }) =>
+        {
+            return Ok(None);
+        }
+        Err(err) => return Err(err.into()),
+    };
 
     let mut result: Vec<u8> = Vec::new();
     while let Some(bytes) = object.body.try_next().await.map_err(|err| {
@@ -576,7 +598,7 @@ pub async fn get_file_from_s3(
         result.extend(&bytes);
     }
 
-    Ok(result)
+    Ok(Some(result))
 }
 
 #[instrument(err)]
@@ -92,6 +92,32 @@ fn get_name_from_i18n(
     lang_name.or(default_lang_name)
 }
 
+impl Name for ElectionEvent {
+    /// Falls back to the default language of the election event, then to
+    /// DEFAULT_LANG and finally to the base name.
+    fn get_name(&self, language: &str) -> String {
+        let base_name = self.name.clone();
+        let Some(presentation_val) = self.presentation.clone() else {
+            return base_name;
+        };
+        let Ok(presentation) =
+            deserialize_value::<ElectionEventPresentation>(presentation_val)
+        else {
+            return base_name;
+        };
+        let get_lang_name = |lang: &str| {
+            let lang_i18n = presentation.i18n.as_ref()?.get(lang)?;
+            let alias = lang_i18n.get("alias").cloned().flatten();
+            let name = lang_i18n.get("name").cloned().flatten();
+            alias.or(name)
+        };
+        get_lang_name(language)
+            .or_else(|| get_lang_name(&self.get_default_language()))
+            .or_else(|| get_lang_name(DEFAULT_LANG))
+            .unwrap_or(base_name)
+    }
+}
+
 impl Name for Election {
     fn get_name(&self, language: &str) -> String {
         let base_name = self.name.clone();
@@ -3,6 +3,9 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 #![allow(non_camel_case_types)]
 
+use crate::ballot::{
+    EBlankVotePolicy, EOverVotePolicy, EUnderVotePolicy, InvalidVotePolicy,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -121,6 +124,7 @@ pub enum TallyElectionStatus {
     WAITING,
     MIXING,
     DECRYPTING,
+    TALLYING,
     SUCCESS,
     ERROR,
 }
@@ -132,12 +136,87 @@ pub struct TallyElection {
     pub progress: f64,
 }
 
+#[derive(
+    Display,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    EnumString,
+    Default,
+)]
+pub enum TallyCheckpointStatus {
+    #[default]
+    PENDING,
+    SUCCESS,
+    ERROR,
+}
+
+/// Velvet output of a single election of a tally session, computed by its own
+/// subtask so that a failed tally can resume from the elections already
+/// tallied.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TallyCheckpoint {
+    pub election_id: String,
+    /// Batches tallied in the checkpoint. The checkpoint is only reused while
+    /// the batches of the election don't change.
+    pub session_ids: Vec<i64>,
+    pub status: TallyCheckpointStatus,
+    pub attempts: u32,
+    pub input_document_id: Option<String>,
+    pub output_document_id: Option<String>,
+    pub error: Option<String>,
+    pub updated_at: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TallyCeremonyStatus {
     pub stop_date: Option<String>,
     pub logs: Vec<Log>,
     pub trustees: Vec<TallyTrustee>,
     pub elections_status: Vec<TallyElection>,
+    #[serde(default)]
+    pub checkpoints: Vec<TallyCheckpoint>,
+    /// Set when the execution is a recount of a previous one.
+    #[serde(default)]
+    pub recount: Option<TallyRecount>,
+}
+
+/// Contest settings replaced in the frozen tally input before a recount. The
+/// counting algorithm can't be overridden, as the ballots were encoded with
+/// it, so unknown fields are rejected rather than silently ignored.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ContestRecountOverride {
+    pub contest_id: String,
+    pub invalid_vote_policy: Option<InvalidVotePolicy>,
+    pub blank_vote_policy: Option<EBlankVotePolicy>,
+    pub under_vote_policy: Option<EUnderVotePolicy>,
+    pub over_vote_policy: Option<EOverVotePolicy>,
+    pub winning_candidates_num: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecountOverrides {
+    #[serde(default)]
+    pub contests: Vec<ContestRecountOverride>,
+    /// Replace the tally sheets of the frozen input with the currently
+    /// published ones.
+    #[serde(default)]
+    pub use_current_tally_sheets: bool,
+    #[serde(default)]
+    pub excluded_tally_sheet_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TallyRecount {
+    /// Execution with the original results the recount is compared with.
+    pub original_execution_id: String,
+    pub original_results_event_id: String,
+    pub overrides: RecountOverrides,
+    pub report_document_id: String,
 }
 
 #[derive(
@@ -164,6 +243,9 @@ pub enum TallyType {
 pub struct TallySessionDocuments {
     pub sqlite: Option<String>,
     pub xlsx: Option<String>,
+    /// Frozen velvet input of the completed tally, used for recounts.
+    #[serde(default)]
+    pub snapshot: Option<String>,
 }
 
 #[derive(
@@ -4,6 +4,7 @@
 
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::value::Value;
 use std::str::FromStr;
@@ -284,7 +285,7 @@ pub struct AreaContest {
     pub contest_id: String,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TallySheet {
     pub id: String,
     pub tenant_id: String,
@@ -302,6 +303,9 @@ pub struct TallySheet {
     pub channel: Option<String>,
     pub deleted_at: Option<DateTime<Local>>,
     pub created_by_user_id: String,
+    pub version: Option<i32>,
+    pub approved_at: Option<DateTime<Local>>,
+    pub approved_by_user_id: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
@@ -394,6 +398,9 @@ pub struct TallySessionContestAnnotations {
     pub elegible_voters: u64,
     pub ballots_without_voter: u64,
     pub casted_ballots: u64,
+    /// Census snapshot `elegible_voters` was taken from, if any.
+    #[serde(default)]
+    pub census_snapshot_id: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
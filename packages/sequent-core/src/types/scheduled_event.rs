@@ -51,10 +51,117 @@ pub enum EventProcessors {
     ALLOW_TALLY,
 }
 
+#[derive(
+    Display,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    EnumString,
+    Default,
+)]
+pub enum ScheduledEventStatus {
+    #[default]
+    #[strum(serialize = "PENDING")]
+    PENDING,
+    #[strum(serialize = "RETRYING")]
+    RETRYING,
+    #[strum(serialize = "COMPLETED")]
+    COMPLETED,
+    #[strum(serialize = "FAILED")]
+    FAILED,
+}
+
+/// Conditions that must hold before a scheduled event is executed.
+#[derive(
+    Display,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    EnumString,
+    Hash,
+)]
+pub enum ScheduledEventPrerequisite {
+    /// The initialization report of the election(s) was generated.
+    #[strum(serialize = "INIT_REPORT_GENERATED")]
+    INIT_REPORT_GENERATED,
+    /// The keys ceremony of the election(s) finished successfully.
+    #[strum(serialize = "KEYS_CEREMONY_FINISHED")]
+    KEYS_CEREMONY_FINISHED,
+    /// The online voting period of the election(s) is closed.
+    #[strum(serialize = "VOTING_PERIOD_ENDED")]
+    VOTING_PERIOD_ENDED,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: i32,
+    pub backoff_secs: i64,
+    pub max_backoff_secs: Option<i64>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            backoff_secs: 30,
+            max_backoff_secs: Some(600),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential back-off in seconds before the given retry, starting at 1.
+    pub fn get_backoff_secs(&self, retry: i32) -> i64 {
+        let exponent = u32::try_from(retry.max(1) - 1).unwrap_or_default();
+        let backoff = 2_i64
+            .checked_pow(exponent)
+            .and_then(|factor| self.backoff_secs.checked_mul(factor))
+            .unwrap_or(i64::MAX);
+        match self.max_backoff_secs {
+            Some(max_backoff_secs) => backoff.min(max_backoff_secs),
+            None => backoff,
+        }
+    }
+}
+
+/// Alert sent with a communication template when the scheduled event failed
+/// or did not complete within the grace period after its scheduled date.
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ScheduledEventAlert {
+    pub template_alias: String,
+    pub receivers: Vec<String>,
+    #[serde(default)]
+    pub grace_period_secs: i64,
+}
+
+/// How a scheduled event is executed: what it waits for, how failures are
+/// retried and who is alerted when it does not happen on time.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct ScheduledEventPolicy {
+    #[serde(default)]
+    pub prerequisites: Vec<ScheduledEventPrerequisite>,
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    #[serde(default)]
+    pub alert: Option<ScheduledEventAlert>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
 pub struct CronConfig {
     pub cron: Option<String>,
     pub scheduled_date: Option<String>,
+    /// IANA time zone in which the cron expression is evaluated and
+    /// `scheduled_date` was entered. UTC if not set.
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    #[serde(flatten)]
+    pub policy: ScheduledEventPolicy,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -98,6 +205,18 @@ pub struct ScheduledEvent {
     pub cron_config: Option<CronConfig>,
     pub event_payload: Option<Value>,
     pub task_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<ScheduledEventStatus>,
+    #[serde(default)]
+    pub attempts: i32,
+    #[serde(default)]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub alerted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub alerted_receivers: Vec<String>,
 }
 
 pub fn generate_manage_date_task_name(
@@ -152,7 +271,7 @@ pub fn generate_voting_period_dates(
         election_id,
         &EventProcessors::END_VOTING_PERIOD,
     );
-    let end_date = scheduled_events.into_iter().find(|scheduled_event| {
+    let end_date = scheduled_events.iter().find(|scheduled_event| {
         scheduled_event.tenant_id == Some(tenant_id.to_string())
             && scheduled_event.election_event_id
                 == Some(election_event_id.to_string())
@@ -160,15 +279,21 @@ pub fn generate_voting_period_dates(
             && scheduled_event.event_payload == Some(payload_val.clone())
     });
 
+    let time_zone = start_date
+        .as_ref()
+        .into_iter()
+        .chain(end_date)
+        .find_map(|val| val.cron_config.as_ref()?.time_zone.clone());
+
     Ok(VotingPeriodDates {
         start_date: start_date
             .map(|val| val.cron_config.map(|val| val.scheduled_date))
             .flatten()
             .flatten(),
         end_date: end_date
-            .map(|val| val.cron_config.map(|val| val.scheduled_date))
-            .flatten()
-            .flatten(),
+            .and_then(|val| val.cron_config.as_ref())
+            .and_then(|val| val.scheduled_date.clone()),
+        time_zone,
     })
 }
 
@@ -230,6 +355,10 @@ pub fn prepare_scheduled_dates(
                         &scheduled_event.stopped_at,
                         "-",
                     )),
+                    time_zone: scheduled_event
+                        .cron_config
+                        .as_ref()
+                        .and_then(|cron| cron.time_zone.clone()),
                 },
             ));
         })
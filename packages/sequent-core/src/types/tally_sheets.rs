@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 #![allow(non_camel_case_types)]
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -12,6 +13,7 @@ use strum_macros::{Display, EnumString};
     Display,
     Serialize,
     Deserialize,
+    JsonSchema,
     Debug,
     PartialEq,
     Eq,
@@ -38,20 +40,22 @@ impl From<Option<String>> for VotingChannel {
     }
 }
 
-#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(
+    PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, Clone, Default,
+)]
 pub struct InvalidVotes {
     pub total_invalid: Option<u64>,
     pub implicit_invalid: Option<u64>,
     pub explicit_invalid: Option<u64>,
 }
 
-#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct CandidateResults {
     pub candidate_id: String,
     pub total_votes: Option<u64>,
 }
 
-#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, JsonSchema, Debug, Clone)]
 pub struct AreaContestResults {
     pub area_id: String,
     pub contest_id: String,
@@ -7,6 +7,7 @@ use crate::ballot::{
     verify_ballot_signature,
 };
 use crate::ballot_codec::bigint::BigUIntCodec;
+use crate::ballot_codec::checker::check_ranked_choices;
 use crate::ballot_codec::multi_ballot::*;
 use crate::ballot_codec::raw_ballot::RawBallotCodec;
 use crate::encrypt;
@@ -904,6 +905,26 @@ pub fn check_is_blank_js(
         .into_json()
 }
 
+#[wasm_bindgen]
+pub fn check_ranked_choices_js(
+    decoded_contest_json: JsValue,
+) -> Result<JsValue, JsValue> {
+    let decoded_contest: DecodedVoteContest =
+        serde_wasm_bindgen::from_value(decoded_contest_json)
+            .map_err(|err| format!("Error parsing decoded contest: {}", err))
+            .into_json()?;
+    let checker_result = check_ranked_choices(&decoded_contest.choices);
+
+    let serializer = Serializer::json_compatible();
+    checker_result
+        .invalid_errors
+        .serialize(&serializer)
+        .map_err(|err| {
+            format!("Error converting ranked choices errors to json {:?}", err)
+        })
+        .into_json()
+}
+
 #[wasm_bindgen]
 pub fn check_voting_not_allowed_next(
     contests: JsValue,
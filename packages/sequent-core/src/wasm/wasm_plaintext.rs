@@ -8,7 +8,10 @@ const IINVALID_PLAINTEXT_ERROR_TYPE: &'static str = r#"
 enum IInvalidPlaintextErrorType {
     Explicit = "Explicit",
     Implicit = "Implicit",
-    EncodingError = "EncodingError"
+    EncodingError = "EncodingError",
+    DuplicateRank = "DuplicateRank",
+    SkippedRank = "SkippedRank",
+    OvervoteAtRank = "OvervoteAtRank"
 }
 "#;
 
@@ -0,0 +1,78 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::{
+    types::election_event_spec::ElectionEventSpec,
+    utils::{
+        apply::{
+            hasura_applier::HasuraApplier,
+            plan::{reconcile, DryRun},
+            state::get_election_event_state,
+        },
+        read_config::read_config,
+    },
+};
+use clap::Args;
+use serde_json::Value;
+use std::fs;
+
+#[derive(Args)]
+#[command(
+    about = "Apply an election event described in a YAML file, creating or updating only what differs",
+    long_about = None
+)]
+pub struct Apply {
+    /// Path to the YAML file describing the election event
+    #[arg(short = 'f', long)]
+    file: String,
+
+    /// Only print the plan, without applying it
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+}
+
+impl Apply {
+    pub fn run(&self) {
+        match apply(&self.file, self.dry_run) {
+            Ok(Some(election_event_id)) => {
+                println!("Success! Election event applied successfully! ID: {election_event_id}");
+            }
+            Ok(None) => {
+                println!("Success! Nothing to apply");
+            }
+            Err(err) => {
+                eprintln!("Error! Failed to apply election event: {}", err)
+            }
+        }
+    }
+}
+
+/// Prints the plan and applies it unless it's a dry run. Returns the id of
+/// the election event if it was changed.
+pub fn apply(file: &str, dry_run: bool) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let config = read_config()?;
+    let client = reqwest::blocking::Client::new();
+
+    let contents = fs::read_to_string(file)?;
+    let spec: ElectionEventSpec = serde_yaml::from_str(&contents)?;
+
+    let state = get_election_event_state(&client, &config, &spec.election_event.name)?;
+    let plan = reconcile(&spec, &state, &mut DryRun)?;
+    println!("Plan for election event \"{}\":", spec.election_event.name);
+    println!("{plan}");
+
+    if dry_run || !plan.has_changes() {
+        return Ok(None);
+    }
+
+    let election_event_id = state
+        .election_event
+        .as_ref()
+        .and_then(|election_event| election_event.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let mut applier = HasuraApplier::new(config, election_event_id);
+    reconcile(&spec, &state, &mut applier)?;
+    Ok(applier.election_event_id().map(str::to_string))
+}
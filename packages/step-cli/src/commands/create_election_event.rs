@@ -54,7 +54,7 @@ impl CreateElectionEventCLI {
     }
 }
 
-fn create_election_event(
+pub fn create_election_event(
     name: &str,
     description: &str,
     encryption_protocol: &str,
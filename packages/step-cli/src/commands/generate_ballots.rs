@@ -0,0 +1,446 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::commands::cast_vote::{insert_cast_vote, InsertCastVote};
+use crate::types::config::VoteDistribution;
+use crate::utils::election_event_export::BallotPublicationExport;
+use crate::utils::read_config::{load_external_config, read_config};
+use crate::utils::synthetic_ballots::{
+    decode_as_tallied, generate_decoded_ballot, get_contest_encryption_policy, ExpectedTally,
+};
+use anyhow::anyhow;
+use b3::messages::newtypes::BatchNumber;
+use clap::Args;
+use csv::Writer;
+use graphql_client::{GraphQLQuery, Response};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rayon::prelude::*;
+use sequent_core::ballot::{
+    BallotStyle, ContestEncryptionPolicy, PublicKeyConfig, SignedHashableBallot,
+};
+use sequent_core::encrypt::{encrypt_decoded_contest, encrypt_decoded_multi_contest};
+use sequent_core::multi_ballot::SignedHashableMultiBallot;
+use sequent_core::plaintext::DecodedVoteContest;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+use strand::backend::ristretto::RistrettoCtx;
+use strand::elgamal::Ciphertext;
+use windmill::postgres::trustee::get_trustees_by_name;
+use windmill::services::protocol_manager::{
+    add_ballots_to_board, generate_trustee_set, get_b3_pgsql_client, get_board_messages,
+    get_configuration, get_protocol_manager, get_public_key_hash,
+};
+use windmill::services::providers::transactions_provider::provide_hasura_transaction;
+use windmill::services::public_keys::deserialize_public_key;
+
+#[derive(clap::ValueEnum, Clone, PartialEq)]
+pub enum BallotsTarget {
+    /// Only write the ballots to the CSV file
+    File,
+    /// Insert the ballots directly into the bulletin board
+    Board,
+    /// Cast the ballots through the harvest API
+    Harvest,
+}
+
+#[derive(Args)]
+#[command(about = "Generate encrypted ballots with a configured vote distribution", long_about = None)]
+pub struct GenerateBallots {
+    /// Working directory for input/output
+    #[arg(long)]
+    working_directory: String,
+
+    /// Number of ballots to generate per area
+    #[arg(long)]
+    num_ballots: usize,
+
+    /// Where to send the generated ballots
+    #[arg(long, value_enum, default_value = "file")]
+    target: BallotsTarget,
+
+    /// Election public key, overriding the one in the ballot styles
+    #[arg(long)]
+    public_key: Option<String>,
+
+    /// Bulletin board to insert the ballots into, with the board target
+    #[arg(long, required_if_eq("target", "board"))]
+    board_name: Option<String>,
+
+    /// Batch of the first area, the following areas (and contests, with one
+    /// contest per ballot) use the next batches
+    #[arg(long, default_value_t = 1)]
+    first_batch: usize,
+
+    /// Trustees selected for the tally, with the board target
+    #[arg(long, value_delimiter = ',')]
+    trustees: Vec<String>,
+
+    /// File with one voter auth token per line, with the harvest target. By
+    /// default all the ballots are cast with the configured auth token
+    #[arg(long)]
+    voter_tokens_file: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BallotStylesFile {
+    Publications(Vec<BallotPublicationExport>),
+    BallotStyles(Vec<BallotStyle>),
+}
+
+struct GeneratedBallot {
+    area_id: String,
+    election_id: String,
+    ballot_id: String,
+    content: String,
+    /// Ciphertexts by contest, or a single one without contest id with
+    /// multiple contests per ballot
+    ciphertexts: Vec<(Option<String>, Ciphertext<RistrettoCtx>)>,
+}
+
+impl GenerateBallots {
+    pub fn run(&self) {
+        match self.run_generate_ballots() {
+            Ok(num_ballots) => println!("Success! Generated {num_ballots} ballots"),
+            Err(err) => eprintln!("Error! Failed to generate ballots: {err}"),
+        }
+    }
+
+    fn run_generate_ballots(&self) -> Result<usize, Box<dyn Error>> {
+        let external_config = load_external_config(&self.working_directory)?;
+        let config = external_config
+            .generate_ballots
+            .ok_or("Missing generate_ballots in the external config")?;
+        validate_distribution(&config.distribution)?;
+
+        let mut ballot_styles: Vec<BallotStyle> =
+            read_ballot_styles(&self.working_directory, &config.ballot_styles_file)?
+                .into_iter()
+                .filter(|ballot_style| ballot_style.election_id == external_config.election_id)
+                .collect();
+        if ballot_styles.is_empty() {
+            return Err(Box::from(format!(
+                "No ballot styles found for election {}",
+                external_config.election_id
+            )));
+        }
+        if let Some(public_key) = &self.public_key {
+            for ballot_style in ballot_styles.iter_mut() {
+                ballot_style.public_key = Some(PublicKeyConfig {
+                    public_key: public_key.clone(),
+                    is_demo: false,
+                });
+            }
+        }
+
+        let mut rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+
+        // Choices are generated sequentially so that a seed always gives
+        // the same result, the encryption is done in parallel.
+        let mut expected_tally = ExpectedTally::default();
+        let mut decoded_ballots: Vec<(&BallotStyle, Vec<DecodedVoteContest>)> = vec![];
+        for ballot_style in &ballot_styles {
+            for _ in 0..self.num_ballots {
+                let decoded_contests =
+                    generate_decoded_ballot(&mut rng, ballot_style, &config.distribution)?;
+                let tallied_contests = decode_as_tallied(ballot_style, &decoded_contests)?;
+                expected_tally.add_ballot(&ballot_style.area_id, &tallied_contests);
+                decoded_ballots.push((ballot_style, decoded_contests));
+            }
+        }
+        let ballots = decoded_ballots
+            .par_iter()
+            .map(|(ballot_style, decoded_contests)| encrypt_ballot(ballot_style, decoded_contests))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let working_dir = PathBuf::from(&self.working_directory);
+        let csv_path =
+            working_dir.join(format!("{}_{}.csv", config.csv_file_name, self.num_ballots));
+        let mut writer = Writer::from_path(&csv_path)?;
+        writer.write_record(["area_id", "election_id", "ballot_id", "content"])?;
+        for ballot in &ballots {
+            writer.write_record([
+                &ballot.area_id,
+                &ballot.election_id,
+                &ballot.ballot_id,
+                &ballot.content,
+            ])?;
+        }
+        writer.flush()?;
+        println!("Ballots written to {}", csv_path.display());
+
+        let expected_tally_path =
+            working_dir.join(format!("{}.json", config.expected_tally_file_name));
+        fs::write(
+            &expected_tally_path,
+            serde_json::to_string_pretty(&expected_tally)?,
+        )?;
+        println!(
+            "Expected tally written to {}",
+            expected_tally_path.display()
+        );
+
+        match self.target {
+            BallotsTarget::File => {}
+            BallotsTarget::Harvest => self.cast_ballots(&ballots)?,
+            BallotsTarget::Board => {
+                let board_name = self.board_name.clone().ok_or("Missing board name")?;
+                let batches = group_by_batch(&ballots, self.first_batch);
+                for (batch, (area_id, contest_id, ciphertexts)) in &batches {
+                    println!(
+                        "Batch {batch}: area {area_id}, contest {}, {} ballots",
+                        contest_id.as_deref().unwrap_or("all"),
+                        ciphertexts.len()
+                    );
+                }
+                let runtime =
+                    tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+                runtime.block_on(insert_into_board(
+                    external_config.tenant_id,
+                    external_config.election_event_id,
+                    board_name,
+                    self.trustees.clone(),
+                    batches,
+                ))?;
+            }
+        }
+
+        Ok(ballots.len())
+    }
+
+    fn cast_ballots(&self, ballots: &[GeneratedBallot]) -> Result<(), Box<dyn Error>> {
+        let config = read_config()?;
+        let auth_tokens: Vec<String> = match &self.voter_tokens_file {
+            Some(voter_tokens_file) => fs::read_to_string(voter_tokens_file)?
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => vec![config.auth_token.clone()],
+        };
+        if auth_tokens.is_empty() {
+            return Err(Box::from("No voter auth tokens found"));
+        }
+
+        let client = reqwest::blocking::Client::new();
+        let errors: Vec<String> = ballots
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, ballot)| {
+                let variables = insert_cast_vote::Variables {
+                    election_id: ballot.election_id.clone(),
+                    ballot_id: ballot.ballot_id.clone(),
+                    content: ballot.content.clone(),
+                };
+                let request_body = InsertCastVote::build_query(variables);
+                let result = client
+                    .post(&config.endpoint_url)
+                    .bearer_auth(&auth_tokens[index % auth_tokens.len()])
+                    .json(&request_body)
+                    .send()
+                    .map_err(|err| err.to_string())
+                    .and_then(|response| {
+                        response
+                            .json::<Response<insert_cast_vote::ResponseData>>()
+                            .map_err(|err| err.to_string())
+                    });
+                let error = match result {
+                    Ok(response) => response.errors.map(|errors| {
+                        errors
+                            .into_iter()
+                            .map(|error| error.message)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    }),
+                    Err(err) => Some(err),
+                };
+                error.map(|err| format!("ballot {}: {err}", ballot.ballot_id))
+            })
+            .collect();
+
+        for err in errors.iter().take(10) {
+            eprintln!("{err}");
+        }
+        if !errors.is_empty() {
+            return Err(Box::from(format!(
+                "{} of {} ballots failed to be cast",
+                errors.len(),
+                ballots.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn validate_distribution(distribution: &VoteDistribution) -> Result<(), Box<dyn Error>> {
+    for (name, rate) in [
+        ("blank_rate", distribution.blank_rate),
+        ("invalid_rate", distribution.invalid_rate),
+        ("write_in_rate", distribution.write_in_rate),
+    ] {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(Box::from(format!("{name} must be between 0 and 1")));
+        }
+    }
+    if distribution.vote_shares.values().any(|share| *share < 0.0) {
+        return Err(Box::from("Vote shares can't be negative"));
+    }
+    Ok(())
+}
+
+/// Reads the ballot styles, either from a publications export (using the
+/// latest publication) or from a list of ballot styles.
+fn read_ballot_styles(
+    working_dir: &str,
+    ballot_styles_file: &str,
+) -> Result<Vec<BallotStyle>, Box<dyn Error>> {
+    let path = PathBuf::from(working_dir).join(ballot_styles_file);
+    let file = File::open(&path)?;
+    let ballot_styles_file: BallotStylesFile = serde_json::from_reader(BufReader::new(file))?;
+    let ballot_styles = match ballot_styles_file {
+        BallotStylesFile::Publications(publications) => publications
+            .into_iter()
+            .last()
+            .map(|publication| publication.ballot_styles)
+            .ok_or("The publications file is empty")?,
+        BallotStylesFile::BallotStyles(ballot_styles) => ballot_styles,
+    };
+    Ok(ballot_styles)
+}
+
+fn encrypt_ballot(
+    ballot_style: &BallotStyle,
+    decoded_contests: &Vec<DecodedVoteContest>,
+) -> anyhow::Result<GeneratedBallot> {
+    let ctx = RistrettoCtx;
+    let (ballot_id, content, ciphertexts) = match get_contest_encryption_policy(ballot_style) {
+        ContestEncryptionPolicy::MULTIPLE_CONTESTS => {
+            let auditable_ballot =
+                encrypt_decoded_multi_contest(&ctx, decoded_contests, ballot_style)
+                    .map_err(|err| anyhow!("Error encrypting ballot: {err:?}"))?;
+            let signed_ballot = SignedHashableMultiBallot::try_from(&auditable_ballot)
+                .map_err(|err| anyhow!("Error hashing ballot: {err:?}"))?;
+            let contests = signed_ballot
+                .deserialize_contests::<RistrettoCtx>()
+                .map_err(|err| anyhow!("{err:?}"))?;
+            (
+                auditable_ballot.ballot_hash,
+                serde_json::to_string(&signed_ballot)?,
+                vec![(None, contests.ciphertext)],
+            )
+        }
+        ContestEncryptionPolicy::SINGLE_CONTEST => {
+            let auditable_ballot = encrypt_decoded_contest(&ctx, decoded_contests, ballot_style)
+                .map_err(|err| anyhow!("Error encrypting ballot: {err:?}"))?;
+            let signed_ballot = SignedHashableBallot::try_from(&auditable_ballot)
+                .map_err(|err| anyhow!("Error hashing ballot: {err:?}"))?;
+            let contests = signed_ballot
+                .deserialize_contests::<RistrettoCtx>()
+                .map_err(|err| anyhow!("{err:?}"))?;
+            (
+                auditable_ballot.ballot_hash,
+                serde_json::to_string(&signed_ballot)?,
+                contests
+                    .into_iter()
+                    .map(|contest| (Some(contest.contest_id), contest.ciphertext))
+                    .collect(),
+            )
+        }
+    };
+    Ok(GeneratedBallot {
+        area_id: ballot_style.area_id.clone(),
+        election_id: ballot_style.election_id.clone(),
+        ballot_id,
+        content,
+        ciphertexts,
+    })
+}
+
+type Batches = BTreeMap<BatchNumber, (String, Option<String>, Vec<Ciphertext<RistrettoCtx>>)>;
+
+/// Assigns a batch to each area, or to each area and contest with one
+/// contest per ballot, in order of area and contest id.
+fn group_by_batch(ballots: &[GeneratedBallot], first_batch: usize) -> Batches {
+    let mut groups: BTreeMap<(String, Option<String>), Vec<Ciphertext<RistrettoCtx>>> =
+        BTreeMap::new();
+    for ballot in ballots {
+        for (contest_id, ciphertext) in &ballot.ciphertexts {
+            groups
+                .entry((ballot.area_id.clone(), contest_id.clone()))
+                .or_default()
+                .push(ciphertext.clone());
+        }
+    }
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, ((area_id, contest_id), ciphertexts))| {
+            (first_batch + index, (area_id, contest_id, ciphertexts))
+        })
+        .collect()
+}
+
+async fn insert_into_board(
+    tenant_id: String,
+    election_event_id: String,
+    board_name: String,
+    trustee_names: Vec<String>,
+    batches: Batches,
+) -> anyhow::Result<()> {
+    provide_hasura_transaction(|hasura_transaction| {
+        Box::pin(async move {
+            let trustees =
+                get_trustees_by_name(hasura_transaction, &tenant_id, &trustee_names).await?;
+            let trustee_pks = trustees
+                .into_iter()
+                .map(|trustee| {
+                    let public_key = trustee
+                        .public_key
+                        .ok_or(anyhow!("Missing trustee public key"))?;
+                    deserialize_public_key(public_key)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let protocol_manager = get_protocol_manager::<RistrettoCtx>(
+                hasura_transaction,
+                &tenant_id,
+                Some(&election_event_id),
+                &board_name,
+            )
+            .await?;
+            let mut board_client = get_b3_pgsql_client().await?;
+            let board_messages =
+                get_board_messages::<RistrettoCtx>(&board_name, &board_client).await?;
+            let configuration = get_configuration::<RistrettoCtx>(&board_messages)?;
+            let public_key_hash = get_public_key_hash::<RistrettoCtx>(&board_messages)?;
+            let selected_trustees = generate_trustee_set(&configuration, trustee_pks);
+
+            for (batch, (_, _, ciphertexts)) in batches {
+                add_ballots_to_board(
+                    &protocol_manager,
+                    &mut board_client,
+                    &board_name,
+                    &board_messages,
+                    &configuration,
+                    public_key_hash,
+                    selected_trustees,
+                    ciphertexts,
+                    batch,
+                )
+                .await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+pub mod apply;
 pub mod cast_vote;
 pub mod complete_key_ceremony;
 pub mod configure;
@@ -17,9 +18,11 @@ pub mod create_electoral_logs;
 pub mod create_voter;
 pub mod duplicate_votes;
 pub mod export_cast_votes;
+pub mod generate_ballots;
 pub mod generate_voters;
 pub mod hash_passwords;
 pub mod import_election_event;
+pub mod offline_tally;
 pub mod publish_changes;
 pub mod refresh_token;
 pub mod render_template;
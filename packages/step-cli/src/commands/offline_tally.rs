@@ -0,0 +1,576 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::utils::election_event_export::{read_election_event_export, ElectionEventExport};
+use anyhow::{anyhow, Context, Result};
+use b3::messages::{message::Message, statement::StatementType};
+use clap::Args;
+use csv::WriterBuilder;
+use sequent_core::ballot::BallotStyle;
+use sequent_core::services::area_tree::TreeNodeArea;
+use sequent_core::temp_path::{
+    PUBLIC_ASSETS_ELECTORAL_RESULTS_TEMPLATE_SYSTEM, PUBLIC_ASSETS_INITIALIZATION_TEMPLATE_SYSTEM,
+};
+use sequent_core::types::ceremonies::TallyType;
+use sequent_core::types::hasura::core::{Candidate, Election, TallySession, TallySheet};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use strand::serialization::StrandDeserialize;
+use tempfile::NamedTempFile;
+use windmill::services::ceremonies::velvet_tally::{
+    build_reports_pipe_config, call_velvet, create_election_configs_blocking,
+    prepare_tally_for_area_contest, write_sqlite_election_event_data, write_velvet_config,
+};
+use windmill::services::import::import_bulletin_boards::get_board_record;
+use windmill::services::tally_sheets::tally::create_tally_sheets_map;
+use windmill::tasks::execute_tally_session::{
+    build_area_contests, clean_tally_sheets, count_cast_votes_election_with_census,
+    get_plaintexts_by_batch, PlaintextsByBatch,
+};
+
+/// System template used when no public assets are given.
+const DEFAULT_SYSTEM_TEMPLATE: &str = "{{{rendered_user_template}}}";
+
+#[derive(Args)]
+#[command(
+    about = "Run a tally from an election event export, without a running backend",
+    long_about = None
+)]
+pub struct OfflineTally {
+    /// Election event export zip, as generated by the export election event action
+    #[arg(long)]
+    export_file: String,
+
+    /// Password of the export, if it's encrypted
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Tally session to reproduce, by default the latest one in the export
+    #[arg(long)]
+    tally_session_id: Option<String>,
+
+    /// JSON file with the decrypted plaintexts, as an object mapping each
+    /// batch number to a list of hex encoded plaintexts
+    #[arg(long, conflicts_with = "board_file")]
+    plaintexts_file: Option<String>,
+
+    /// Bulletin boards dump CSV, by default the one in the export
+    #[arg(long)]
+    board_file: Option<String>,
+
+    /// Ballot publication to tally with, by default the one each election had
+    /// published when the tally session was created
+    #[arg(long)]
+    ballot_publication_id: Option<String>,
+
+    /// Folder with the public assets, used for the report system template and logo
+    #[arg(long)]
+    public_assets_dir: Option<String>,
+
+    /// Output folder, where the velvet input and output are written
+    #[arg(long, default_value = "offline-tally")]
+    output_dir: String,
+}
+
+impl OfflineTally {
+    pub fn run(&self) {
+        // velvet_tally blocks in place, which needs the multi thread runtime
+        let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+        match runtime.block_on(self.run_offline_tally()) {
+            Ok(output_path) => {
+                println!(
+                    "Success! Tally results written to {}",
+                    output_path.display()
+                )
+            }
+            Err(err) => eprintln!("Error! Failed to run offline tally: {err:?}"),
+        }
+    }
+
+    async fn run_offline_tally(&self) -> Result<PathBuf> {
+        let base_tally_path = PathBuf::from(&self.output_dir);
+        if base_tally_path.exists() && fs::read_dir(&base_tally_path)?.next().is_some() {
+            return Err(anyhow!(
+                "Output folder {} is not empty",
+                base_tally_path.display()
+            ));
+        }
+        fs::create_dir_all(&base_tally_path)?;
+
+        println!("Reading export {}", self.export_file);
+        let export =
+            read_election_event_export(Path::new(&self.export_file), self.password.as_deref())
+                .await?;
+
+        let tally_session = self.get_tally_session(&export)?;
+        println!("Tallying session {}", tally_session.id);
+        let tally_session_contests: Vec<_> = export
+            .tally_session_contests
+            .iter()
+            .filter(|contest| contest.tally_session_id == tally_session.id)
+            .cloned()
+            .collect();
+        let batch_ids: HashSet<i64> = tally_session_contests
+            .iter()
+            .map(|contest| contest.session_id as i64)
+            .collect();
+
+        let plaintexts_by_batch = match &self.plaintexts_file {
+            Some(plaintexts_file) => read_plaintexts_file(Path::new(plaintexts_file))?,
+            None => self.read_board_plaintexts(&export, &tally_session, &batch_ids)?,
+        };
+        let mut missing_batches: Vec<_> = batch_ids
+            .iter()
+            .filter(|batch| !plaintexts_by_batch.contains_key(batch))
+            .collect();
+        missing_batches.sort();
+        for batch in missing_batches {
+            println!("Warning! Missing plaintexts for batch {batch}");
+        }
+
+        let ballot_styles = match &self.ballot_publication_id {
+            Some(id) => export
+                .publications
+                .iter()
+                .find(|publication| &publication.ballot_publication_id == id)
+                .ok_or(anyhow!("Ballot publication {id} not found"))?
+                .ballot_styles
+                .clone(),
+            None => get_tally_session_ballot_styles(&export, &tally_session)?,
+        };
+
+        let contest_encryption_policy = tally_session
+            .configuration
+            .clone()
+            .unwrap_or_default()
+            .get_contest_encryption_policy();
+        let schema = &export.schema;
+        let area_contests = build_area_contests(
+            &plaintexts_by_batch,
+            ballot_styles,
+            tally_session_contests.clone(),
+            &schema.areas,
+            &schema.contests,
+            contest_encryption_policy,
+        )
+        .map_err(|err| anyhow!("Error mapping plaintexts to contests: {err:?}"))?;
+        if area_contests.is_empty() {
+            return Err(anyhow!("No plaintexts to tally"));
+        }
+        let cast_votes_count = count_cast_votes_election_with_census(&tally_session_contests)
+            .await
+            .map_err(|err| anyhow!("Error counting cast votes: {err:?}"))?;
+
+        // Same velvet input as velvet_tally::prepare_velvet_tally
+        let tally_sheets = get_tally_session_tally_sheets(&export, &tally_session)?;
+        let tally_sheets = clean_tally_sheets(&tally_sheets, &area_contests)
+            .map_err(|err| anyhow!("Invalid tally sheets: {err:?}"))?;
+        let tally_sheet_map = create_tally_sheets_map(&tally_sheets);
+        for area_contest in &area_contests {
+            prepare_tally_for_area_contest(
+                base_tally_path.clone(),
+                area_contest,
+                &tally_sheet_map,
+                &tally_session,
+            )?;
+        }
+
+        let elections_single_map: HashMap<String, Election> = schema
+            .elections
+            .iter()
+            .map(|election| (election.id.clone(), election.clone()))
+            .collect();
+        let basic_areas: Vec<TreeNodeArea> = schema.areas.iter().map(|area| area.into()).collect();
+        create_election_configs_blocking(
+            base_tally_path.clone(),
+            &area_contests,
+            &cast_votes_count,
+            &export.scheduled_events,
+            elections_single_map,
+            basic_areas,
+            schema.election_event.get_default_language(),
+            schema.election_event.clone(),
+        )?;
+
+        self.write_sqlite_data(&base_tally_path, &export, &tally_session)?;
+
+        let tally_type = tally_session
+            .tally_type
+            .as_deref()
+            .map(|tally_type| TallyType::try_from(tally_type).unwrap_or_default())
+            .unwrap_or_default();
+        let (assets_base, assets_path, report_system_template) =
+            self.get_public_assets(&tally_type)?;
+        let gen_report_pipe_config = build_reports_pipe_config(
+            &tally_session,
+            assets_base,
+            assets_path,
+            None,
+            report_system_template,
+            None,
+            tally_type,
+        )
+        .await?;
+        write_velvet_config(&base_tally_path, &tally_session, gen_report_pipe_config)?;
+
+        println!("Running velvet");
+        call_velvet(base_tally_path.clone(), "decode-ballots").await?;
+
+        Ok(base_tally_path.join("output"))
+    }
+
+    fn get_tally_session(&self, export: &ElectionEventExport) -> Result<TallySession> {
+        match &self.tally_session_id {
+            Some(id) => export
+                .tally_sessions
+                .iter()
+                .find(|tally_session| &tally_session.id == id)
+                .cloned()
+                .ok_or(anyhow!("Tally session {id} not found in the export")),
+            None => export
+                .tally_sessions
+                .iter()
+                .max_by_key(|tally_session| tally_session.created_at)
+                .cloned()
+                .ok_or(anyhow!("The export has no tally sessions")),
+        }
+    }
+
+    /// Reads the plaintexts of the tally session batches from the board of
+    /// its keys ceremony.
+    fn read_board_plaintexts(
+        &self,
+        export: &ElectionEventExport,
+        tally_session: &TallySession,
+        batch_ids: &HashSet<i64>,
+    ) -> Result<PlaintextsByBatch> {
+        let data = match &self.board_file {
+            Some(board_file) => fs::read(board_file)
+                .with_context(|| format!("Error reading board dump {board_file}"))?,
+            None => export.bulletin_boards.clone().ok_or(anyhow!(
+                "The export has no bulletin boards, use --board-file or --plaintexts-file"
+            ))?,
+        };
+
+        // Rows of the event board have no election id
+        let board_election_id = export
+            .schema
+            .keys_ceremonies
+            .iter()
+            .flatten()
+            .find(|keys_ceremony| keys_ceremony.id == tally_session.keys_ceremony_id)
+            .filter(|keys_ceremony| !keys_ceremony.is_default())
+            .map(|keys_ceremony| {
+                export
+                    .schema
+                    .elections
+                    .iter()
+                    .find(|election| {
+                        election.keys_ceremony_id.as_deref() == Some(keys_ceremony.id.as_str())
+                    })
+                    .map(|election| election.id.clone())
+                    .ok_or(anyhow!(
+                        "Can't find election with keys ceremony {}",
+                        keys_ceremony.id
+                    ))
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut messages = vec![];
+        for record in csv::Reader::from_reader(data.as_slice()).records() {
+            let record = record.map_err(|err| anyhow!("Error reading board record: {err:?}"))?;
+            let (election_id, row) = get_board_record(record)?;
+            if election_id != board_election_id {
+                continue;
+            }
+            let message = Message::strand_deserialize(&row.message)
+                .map_err(|err| anyhow!("Error deserializing board message {}: {err:?}", row.id))?;
+            if message.statement.get_kind() == StatementType::Plaintexts
+                && batch_ids.contains(&(message.statement.get_batch_number() as i64))
+            {
+                messages.push(message);
+            }
+        }
+        Ok(get_plaintexts_by_batch(&messages.iter().collect()))
+    }
+
+    /// Writes the election event data of the tally session into the results
+    /// database, like velvet_tally::populate_sqlite_election_event_data does.
+    fn write_sqlite_data(
+        &self,
+        base_tally_path: &Path,
+        export: &ElectionEventExport,
+        tally_session: &TallySession,
+    ) -> Result<()> {
+        let schema = &export.schema;
+        let elections: Vec<_> = schema
+            .elections
+            .iter()
+            .filter(|election| {
+                tally_session
+                    .election_ids
+                    .as_ref()
+                    .map_or(true, |ids| ids.contains(&election.id))
+            })
+            .cloned()
+            .collect();
+        let election_ids: HashSet<&String> =
+            elections.iter().map(|election| &election.id).collect();
+        let contests: Vec<_> = schema
+            .contests
+            .iter()
+            .filter(|contest| election_ids.contains(&contest.election_id))
+            .cloned()
+            .collect();
+        let contest_ids: HashSet<&String> = contests.iter().map(|contest| &contest.id).collect();
+        let candidates: Vec<&Candidate> = schema
+            .candidates
+            .iter()
+            .filter(|candidate| {
+                candidate
+                    .contest_id
+                    .as_ref()
+                    .is_some_and(|id| contest_ids.contains(id))
+            })
+            .collect();
+        let areas: Vec<_> = schema
+            .areas
+            .iter()
+            .filter(|area| {
+                tally_session
+                    .area_ids
+                    .as_ref()
+                    .map_or(true, |ids| ids.contains(&area.id))
+            })
+            .cloned()
+            .collect();
+        let area_contests: Vec<_> = match &tally_session.area_ids {
+            Some(area_ids) => schema
+                .area_contests
+                .iter()
+                .filter(|area_contest| {
+                    area_ids.contains(&area_contest.area_id)
+                        && contest_ids.contains(&area_contest.contest_id)
+                })
+                .cloned()
+                .collect(),
+            None => schema.area_contests.clone(),
+        };
+
+        let candidates_csv = NamedTempFile::new()?;
+        write_candidates_csv(candidates_csv.path(), &candidates)?;
+
+        write_sqlite_election_event_data(
+            base_tally_path,
+            schema.election_event.clone(),
+            elections,
+            contests,
+            candidates_csv.path(),
+            areas,
+            area_contests,
+        )
+    }
+
+    /// Returns the base and path of the public assets and the report system
+    /// template.
+    fn get_public_assets(&self, tally_type: &TallyType) -> Result<(String, String, String)> {
+        let Some(public_assets_dir) = &self.public_assets_dir else {
+            return Ok((
+                ".".to_string(),
+                ".".to_string(),
+                DEFAULT_SYSTEM_TEMPLATE.to_string(),
+            ));
+        };
+        let public_assets_dir = fs::canonicalize(public_assets_dir)?;
+        let template_name = match tally_type {
+            TallyType::INITIALIZATION_REPORT => PUBLIC_ASSETS_INITIALIZATION_TEMPLATE_SYSTEM,
+            _ => PUBLIC_ASSETS_ELECTORAL_RESULTS_TEMPLATE_SYSTEM,
+        };
+        let report_system_template = fs::read_to_string(public_assets_dir.join(template_name))
+            .with_context(|| format!("Error reading {template_name}"))?;
+        let assets_path = public_assets_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let assets_base = public_assets_dir
+            .parent()
+            .map(|parent| format!("file://{}", parent.display()))
+            .unwrap_or_default();
+        Ok((assets_base, assets_path, report_system_template))
+    }
+}
+
+/// Returns whether the election is tallied in the tally session.
+fn is_tally_session_election(tally_session: &TallySession, election_id: &str) -> bool {
+    tally_session
+        .election_ids
+        .as_ref()
+        .map_or(true, |ids| ids.iter().any(|id| id == election_id))
+}
+
+/// Returns the ballot styles each election of the tally session had published
+/// when the tally session was created, which are the ones the online tally
+/// used.
+fn get_tally_session_ballot_styles(
+    export: &ElectionEventExport,
+    tally_session: &TallySession,
+) -> Result<Vec<BallotStyle>> {
+    let mut ballot_styles = vec![];
+    for election in &export.schema.elections {
+        if !is_tally_session_election(tally_session, &election.id) {
+            continue;
+        }
+        let publication = export
+            .publications
+            .iter()
+            .filter(|publication| {
+                publication.published_at.is_some_and(|published_at| {
+                    tally_session
+                        .created_at
+                        .map_or(true, |created_at| published_at <= created_at)
+                })
+            })
+            .filter(|publication| {
+                publication
+                    .ballot_styles
+                    .iter()
+                    .any(|style| style.election_id == election.id)
+            })
+            .max_by_key(|publication| publication.published_at)
+            .ok_or(anyhow!(
+                "Can't find the ballot publication of election {} for the tally session, \
+                 use --ballot-publication-id",
+                election.id
+            ))?;
+        ballot_styles.extend(
+            publication
+                .ballot_styles
+                .iter()
+                .filter(|style| style.election_id == election.id)
+                .cloned(),
+        );
+    }
+    Ok(ballot_styles)
+}
+
+/// Returns the published tally sheets of the tally session elections. Fails
+/// if the export has no tally sheets but any of the elections has a paper
+/// channel, as the results would miss them.
+fn get_tally_session_tally_sheets(
+    export: &ElectionEventExport,
+    tally_session: &TallySession,
+) -> Result<Vec<TallySheet>> {
+    let Some(tally_sheets) = &export.tally_sheets else {
+        let paper_election = export.schema.elections.iter().find(|election| {
+            is_tally_session_election(tally_session, &election.id)
+                && election
+                    .voting_channels
+                    .as_ref()
+                    .and_then(|channels| channels.get("paper"))
+                    .and_then(|paper| paper.as_bool())
+                    .unwrap_or(false)
+        });
+        if let Some(election) = paper_election {
+            return Err(anyhow!(
+                "The export has no tally sheets, but election {} has a paper channel",
+                election.id
+            ));
+        }
+        return Ok(vec![]);
+    };
+    Ok(tally_sheets
+        .iter()
+        .filter(|tally_sheet| {
+            tally_sheet.published_at.is_some()
+                && tally_sheet.deleted_at.is_none()
+                && is_tally_session_election(tally_session, &tally_sheet.election_id)
+        })
+        .cloned()
+        .collect())
+}
+
+/// Reads a JSON object mapping each batch number to its hex encoded
+/// plaintexts.
+fn read_plaintexts_file(path: &Path) -> Result<PlaintextsByBatch> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Error reading plaintexts file {}", path.display()))?;
+    let plaintexts: HashMap<String, Vec<String>> = serde_json::from_str(&contents)?;
+    plaintexts
+        .into_iter()
+        .map(|(batch, plaintexts)| {
+            let batch: i64 = batch
+                .parse()
+                .map_err(|_| anyhow!("Invalid batch number {batch}"))?;
+            let plaintexts = plaintexts
+                .iter()
+                .map(|plaintext| {
+                    hex::decode(plaintext)?
+                        .try_into()
+                        .map_err(|_| anyhow!("Invalid plaintext length in batch {batch}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok((batch, plaintexts))
+        })
+        .collect()
+}
+
+/// Writes the candidates with the columns of `export_candidate_csv`.
+fn write_candidates_csv(path: &Path, candidates: &[&Candidate]) -> Result<()> {
+    let mut writer = WriterBuilder::new().from_path(path)?;
+    writer.write_record([
+        "id",
+        "tenant_id",
+        "election_event_id",
+        "contest_id",
+        "created_at",
+        "last_updated_at",
+        "labels",
+        "annotations",
+        "name",
+        "alias",
+        "description",
+        "type",
+        "presentation",
+        "is_public",
+        "image_document_id",
+    ])?;
+    for candidate in candidates {
+        let json = |value: &Option<serde_json::Value>| {
+            value
+                .as_ref()
+                .map(|value| value.to_string())
+                .unwrap_or_default()
+        };
+        writer.write_record([
+            candidate.id.clone(),
+            candidate.tenant_id.clone(),
+            candidate.election_event_id.clone(),
+            candidate.contest_id.clone().unwrap_or_default(),
+            candidate
+                .created_at
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_default(),
+            candidate
+                .last_updated_at
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_default(),
+            json(&candidate.labels),
+            json(&candidate.annotations),
+            candidate.name.clone().unwrap_or_default(),
+            candidate.alias.clone().unwrap_or_default(),
+            candidate.description.clone().unwrap_or_default(),
+            candidate.r#type.clone().unwrap_or_default(),
+            json(&candidate.presentation),
+            candidate
+                .is_public
+                .map(|is_public| is_public.to_string())
+                .unwrap_or_default(),
+            candidate.image_document_id.clone().unwrap_or_default(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -23,6 +23,11 @@ pub struct PublishChanges {
     /// Election event id - the election event id to publish changes for
     #[arg(long)]
     election_event_id: String,
+
+    /// Publish changes to the ballot encoding even if voting has started,
+    /// which makes the ballots already cast undecodable
+    #[arg(long, default_value_t = false)]
+    allow_encoding_changes: bool,
 }
 
 #[derive(GraphQLQuery)]
@@ -35,7 +40,7 @@ pub struct PublishBallot;
 
 impl PublishChanges {
     pub fn run(&self) {
-        match publish_changes(&self.election_event_id) {
+        match publish_changes(&self.election_event_id, self.allow_encoding_changes) {
             Ok(id) => {
                 println!("Success! Published successfully! ID: {}", id);
             }
@@ -46,7 +51,10 @@ impl PublishChanges {
     }
 }
 
-pub fn publish_changes(election_event_id: &str) -> Result<String, Box<dyn std::error::Error>> {
+pub fn publish_changes(
+    election_event_id: &str,
+    allow_encoding_changes: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
     let config = read_config()?;
 
     let client = reqwest::blocking::Client::new();
@@ -84,6 +92,7 @@ pub fn publish_changes(election_event_id: &str) -> Result<String, Box<dyn std::e
     let variables = publish_ballot::Variables {
         election_event_id: election_event_id.to_string(),
         ballot_publication_id,
+        allow_encoding_changes: Some(allow_encoding_changes),
     };
 
     let request_body = PublishBallot::build_query(variables);
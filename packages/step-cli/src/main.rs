@@ -29,6 +29,7 @@ enum MainCommand {
 #[derive(Subcommand)]
 enum StepCommands {
     Config(commands::configure::Config),
+    Apply(commands::apply::Apply),
     CreateElectionEvent(commands::create_election_event::CreateElectionEventCLI),
     CreateElection(commands::create_election::CreateElection),
     CreateContest(commands::create_contest::CreateContest),
@@ -48,9 +49,11 @@ enum StepCommands {
     StartTally(commands::start_tally::StartTallyCeremony),
     UpdateTally(commands::update_tally_status::UpdateTallyStatus),
     ConfirmKeyTally(commands::confirm_tally_ceremoney_key::ConfirmKeyForTally),
+    OfflineTally(commands::offline_tally::OfflineTally),
     RenderTemplate(commands::render_template::RenderTemplate),
     GenerateVoters(commands::generate_voters::GenerateVoters),
     DuplicateVotes(commands::duplicate_votes::DuplicateVotes),
+    GenerateBallots(commands::generate_ballots::GenerateBallots),
     CreateApplications(commands::create_applications::CreateApplications),
     CreateElectoralLogs(commands::create_electoral_logs::CreateElectoralLogs),
     HashPassword(commands::hash_passwords::HashPasswords),
@@ -62,6 +65,7 @@ fn main() {
     match &cli.command {
         MainCommand::Step(step_cmd) => match step_cmd {
             StepCommands::Config(cmd) => cmd.run(),
+            StepCommands::Apply(apply) => apply.run(),
             StepCommands::CreateElectionEvent(create_event) => create_event.run(),
             StepCommands::CreateElection(create_election) => create_election.run(),
             StepCommands::CreateContest(create_contest) => create_contest.run(),
@@ -81,9 +85,11 @@ fn main() {
             StepCommands::StartTally(start) => start.run(),
             StepCommands::UpdateTally(update) => update.run(),
             StepCommands::ConfirmKeyTally(confirm) => confirm.run(),
+            StepCommands::OfflineTally(offline_tally) => offline_tally.run(),
             StepCommands::RenderTemplate(render) => render.run(),
             StepCommands::GenerateVoters(render) => render.run(),
             StepCommands::DuplicateVotes(render) => render.run(),
+            StepCommands::GenerateBallots(render) => render.run(),
             StepCommands::CreateApplications(render) => render.run(),
             StepCommands::CreateElectoralLogs(render) => render.run(),
             StepCommands::HashPassword(render) => render.run(),
@@ -119,7 +119,7 @@ fn run_e2e() -> Result<(), Box<dyn Error>> {
     update_status(&election_event_id, &status)?;
 
     // Step 3.5 : Create Publication
-    publish_changes(&election_event_id)?;
+    publish_changes(&election_event_id, false)?;
 
     // Step 4: Create + Run Loadero Test
     let voting_portal_domain = std::env::var("VOTING_PORTAL_DOMAIN")?;
@@ -30,6 +30,7 @@ pub struct ExternalConfigData {
     pub generate_voters: GenerateVoters,
     pub duplicate_votes: DuplicateVotes,
     pub generate_applications: GenerateApplications,
+    pub generate_ballots: Option<GenerateBallots>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -59,3 +60,26 @@ pub struct GenerateApplications {
     pub applicant_data: HashMap<String, Value>,
     pub annotations: HashMap<String, Value>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenerateBallots {
+    /// Ballot publication export or list of ballot styles to vote with
+    pub ballot_styles_file: String,
+    pub csv_file_name: String,
+    pub expected_tally_file_name: String,
+    /// Seed of the vote choices, random if not set
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub distribution: VoteDistribution,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoteDistribution {
+    pub blank_rate: f64,
+    pub invalid_rate: f64,
+    pub write_in_rate: f64,
+    /// Relative vote share of each candidate by id. Candidates not listed get
+    /// no votes, and if empty votes are uniformly distributed.
+    pub vote_shares: HashMap<String, f64>,
+}
@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Declarative description of an election event, as read by `step apply`.
+//! Resources are identified by name (templates by alias) and only the fields
+//! present in the file are managed: omitted fields are left untouched.
+//! Serializing a resource gives the columns managed by the file.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ElectionEventSpec {
+    pub election_event: EventSpec,
+    #[serde(default)]
+    pub elections: Vec<ElectionSpec>,
+    #[serde(default)]
+    pub areas: Vec<AreaSpec>,
+    #[serde(default)]
+    pub scheduled_events: Vec<ScheduledEventSpec>,
+    #[serde(default)]
+    pub templates: Vec<TemplateSpec>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EventSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ElectionSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Value>,
+    #[serde(default, skip_serializing)]
+    pub contests: Vec<ContestSpec>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ContestSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_votes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_votes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winning_candidates_num: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counting_algorithm: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voting_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Value>,
+    #[serde(default, skip_serializing)]
+    pub candidates: Vec<CandidateSpec>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CandidateSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub candidate_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_public: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presentation: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AreaSpec {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub area_type: Option<String>,
+    /// Contests of the area, which are linked through area-contests.
+    #[serde(default, skip_serializing)]
+    pub contests: Vec<ContestRef>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContestRef {
+    pub election: String,
+    pub contest: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ScheduledEventSpec {
+    /// One of the `EventProcessors`, for example `START_VOTING_PERIOD`.
+    pub event_processor: String,
+    /// Name of the election, for the event processors that apply to a single
+    /// election.
+    #[serde(default, skip_serializing)]
+    pub election: Option<String>,
+    /// Serialized `CronConfig`.
+    pub cron_config: Value,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TemplateSpec {
+    /// Stored in the `alias` field of the template.
+    #[serde(skip_serializing)]
+    pub alias: String,
+    #[serde(rename = "type")]
+    pub template_type: String,
+    pub communication_method: String,
+    pub template: Value,
+}
+
+/// Columns managed by the file for a resource.
+pub fn get_columns<T: Serialize>(resource: &T) -> Map<String, Value> {
+    match serde_json::to_value(resource) {
+        Ok(Value::Object(columns)) => columns,
+        _ => Map::new(),
+    }
+}
+
+impl TemplateSpec {
+    pub fn get_columns(&self) -> Map<String, Value> {
+        let mut columns = get_columns(self);
+        let mut template = self.template.clone();
+        if let Value::Object(ref mut template) = template {
+            template.insert("alias".to_string(), Value::String(self.alias.clone()));
+        }
+        columns.insert("template".to_string(), template);
+        columns
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 pub mod config;
+pub mod election_event_spec;
 pub mod environments;
 pub mod hasura_types;
 pub mod keycloak;
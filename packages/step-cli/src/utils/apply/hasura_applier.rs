@@ -0,0 +1,249 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use super::plan::{Applier, ResourceKind};
+use super::state::{get_election_event_by_name, send_graphql};
+use crate::commands::create_election_event::create_election_event;
+use crate::{types::config::ConfigData, types::hasura_types::*};
+use graphql_client::{GraphQLQuery, QueryBody};
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// The structs are only used for their generated query modules.
+macro_rules! apply_mutation {
+    ($name:ident) => {
+        #[allow(dead_code)]
+        #[derive(GraphQLQuery)]
+        #[graphql(
+            schema_path = "src/graphql/schema.json",
+            query_path = "src/graphql/apply_election_event.graphql",
+            response_derives = "Debug,Clone,Deserialize,Serialize"
+        )]
+        pub struct $name;
+    };
+}
+
+apply_mutation!(UpdateElectionEventColumns);
+apply_mutation!(InsertElectionObject);
+apply_mutation!(UpdateElectionColumns);
+apply_mutation!(InsertContestObject);
+apply_mutation!(UpdateContestColumns);
+apply_mutation!(InsertCandidateObject);
+apply_mutation!(UpdateCandidateColumns);
+apply_mutation!(InsertAreaObject);
+apply_mutation!(UpdateAreaColumns);
+apply_mutation!(InsertAreaContestObject);
+apply_mutation!(InsertScheduledEventObject);
+apply_mutation!(UpdateScheduledEventColumns);
+apply_mutation!(InsertTemplateObject);
+apply_mutation!(UpdateTemplateColumns);
+
+/// Election events are created asynchronously, so we wait for them.
+const ELECTION_EVENT_TIMEOUT: Duration = Duration::from_secs(60);
+const ELECTION_EVENT_POLLING_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Applies the changes through Hasura. The variables are sent as JSON so
+/// that only the managed columns are set.
+pub struct HasuraApplier {
+    client: reqwest::blocking::Client,
+    config: ConfigData,
+    election_event_id: Option<String>,
+}
+
+impl HasuraApplier {
+    pub fn new(config: ConfigData, election_event_id: Option<String>) -> Self {
+        HasuraApplier {
+            client: reqwest::blocking::Client::new(),
+            config,
+            election_event_id,
+        }
+    }
+
+    /// Id of the election event, once it exists.
+    pub fn election_event_id(&self) -> Option<&str> {
+        self.election_event_id.as_deref()
+    }
+
+    fn get_election_event_id(&self) -> Result<String, Box<dyn Error>> {
+        self.election_event_id
+            .clone()
+            .ok_or(Box::from("The election event has not been created"))
+    }
+
+    /// Sends the mutation and returns the id of the affected row.
+    fn send_mutation(
+        &self,
+        query: &'static str,
+        operation_name: &'static str,
+        variables: Value,
+    ) -> Result<String, Box<dyn Error>> {
+        let request_body = QueryBody {
+            variables,
+            query,
+            operation_name,
+        };
+        let data: Value = send_graphql(&self.client, &self.config, &request_body)?;
+        data.as_object()
+            .and_then(|data| data.values().next())
+            .and_then(|row| row.get("id"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or(Box::from(format!("{operation_name}: no row was affected")))
+    }
+
+    /// Creates the election event and waits for it, setting afterwards the
+    /// columns not supported by the creation.
+    fn create_election_event(
+        &mut self,
+        mut columns: Map<String, Value>,
+    ) -> Result<String, Box<dyn Error>> {
+        let name = columns
+            .remove("name")
+            .and_then(|name| name.as_str().map(str::to_string))
+            .ok_or("Missing election event name")?;
+        let description = columns
+            .remove("description")
+            .and_then(|description| description.as_str().map(str::to_string))
+            .unwrap_or_default();
+        create_election_event(&name, &description, "RSA256", false)?;
+
+        let start_time = Instant::now();
+        let election_event_id = loop {
+            let election_event = get_election_event_by_name(&self.client, &self.config, &name)?;
+            if let Some(id) = election_event
+                .as_ref()
+                .and_then(|election_event| election_event.get("id"))
+                .and_then(Value::as_str)
+            {
+                break id.to_string();
+            }
+            if Instant::now().duration_since(start_time) >= ELECTION_EVENT_TIMEOUT {
+                return Err(Box::from(
+                    "Timeout while waiting for the election event to be created",
+                ));
+            }
+            sleep(ELECTION_EVENT_POLLING_INTERVAL);
+        };
+        self.election_event_id = Some(election_event_id.clone());
+
+        if !columns.is_empty() {
+            self.update(ResourceKind::ElectionEvent, &election_event_id, columns)?;
+        }
+        Ok(election_event_id)
+    }
+}
+
+impl Applier for HasuraApplier {
+    fn create(
+        &mut self,
+        kind: ResourceKind,
+        mut columns: Map<String, Value>,
+    ) -> Result<String, Box<dyn Error>> {
+        if kind == ResourceKind::ElectionEvent {
+            return self.create_election_event(columns);
+        }
+        columns.insert("tenant_id".to_string(), json!(self.config.tenant_id));
+        let (query, operation_name) = match kind {
+            ResourceKind::Election => (
+                insert_election_object::QUERY,
+                insert_election_object::OPERATION_NAME,
+            ),
+            ResourceKind::Contest => (
+                insert_contest_object::QUERY,
+                insert_contest_object::OPERATION_NAME,
+            ),
+            ResourceKind::Candidate => (
+                insert_candidate_object::QUERY,
+                insert_candidate_object::OPERATION_NAME,
+            ),
+            ResourceKind::Area => (
+                insert_area_object::QUERY,
+                insert_area_object::OPERATION_NAME,
+            ),
+            ResourceKind::AreaContest => (
+                insert_area_contest_object::QUERY,
+                insert_area_contest_object::OPERATION_NAME,
+            ),
+            ResourceKind::ScheduledEvent => (
+                insert_scheduled_event_object::QUERY,
+                insert_scheduled_event_object::OPERATION_NAME,
+            ),
+            ResourceKind::Template => (
+                insert_template_object::QUERY,
+                insert_template_object::OPERATION_NAME,
+            ),
+            ResourceKind::ElectionEvent => unreachable!(),
+        };
+        self.send_mutation(query, operation_name, json!({ "object": columns }))
+    }
+
+    fn update(
+        &mut self,
+        kind: ResourceKind,
+        id: &str,
+        columns: Map<String, Value>,
+    ) -> Result<(), Box<dyn Error>> {
+        let tenant_id = self.config.tenant_id.clone();
+        let (query, operation_name, pk) = match kind {
+            ResourceKind::ElectionEvent => (
+                update_election_event_columns::QUERY,
+                update_election_event_columns::OPERATION_NAME,
+                json!({ "id": id }),
+            ),
+            ResourceKind::Election => (
+                update_election_columns::QUERY,
+                update_election_columns::OPERATION_NAME,
+                json!({
+                    "id": id,
+                    "tenant_id": tenant_id,
+                    "election_event_id": self.get_election_event_id()?,
+                }),
+            ),
+            ResourceKind::Contest => (
+                update_contest_columns::QUERY,
+                update_contest_columns::OPERATION_NAME,
+                json!({
+                    "id": id,
+                    "tenant_id": tenant_id,
+                    "election_event_id": self.get_election_event_id()?,
+                }),
+            ),
+            ResourceKind::Candidate => (
+                update_candidate_columns::QUERY,
+                update_candidate_columns::OPERATION_NAME,
+                json!({
+                    "id": id,
+                    "tenant_id": tenant_id,
+                    "election_event_id": self.get_election_event_id()?,
+                }),
+            ),
+            ResourceKind::Area => (
+                update_area_columns::QUERY,
+                update_area_columns::OPERATION_NAME,
+                json!({
+                    "id": id,
+                    "tenant_id": tenant_id,
+                    "election_event_id": self.get_election_event_id()?,
+                }),
+            ),
+            ResourceKind::ScheduledEvent => (
+                update_scheduled_event_columns::QUERY,
+                update_scheduled_event_columns::OPERATION_NAME,
+                json!({ "id": id }),
+            ),
+            ResourceKind::Template => (
+                update_template_columns::QUERY,
+                update_template_columns::OPERATION_NAME,
+                json!({ "id": id, "tenant_id": tenant_id }),
+            ),
+            ResourceKind::AreaContest => {
+                return Err(Box::from("Area contests have no columns to update"));
+            }
+        };
+        self.send_mutation(query, operation_name, json!({ "pk": pk, "set": columns }))?;
+        Ok(())
+    }
+}
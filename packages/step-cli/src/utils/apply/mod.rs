@@ -0,0 +1,7 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+pub mod hasura_applier;
+pub mod plan;
+pub mod state;
@@ -0,0 +1,568 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::types::election_event_spec::{
+    get_columns, ContestRef, ElectionEventSpec, ScheduledEventSpec,
+};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use strum_macros::Display;
+
+/// Id given to the resources that would be created, when computing a plan.
+pub const PLANNED_ID: &str = "<planned>";
+
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    #[strum(serialize = "election-event")]
+    ElectionEvent,
+    #[strum(serialize = "election")]
+    Election,
+    #[strum(serialize = "contest")]
+    Contest,
+    #[strum(serialize = "candidate")]
+    Candidate,
+    #[strum(serialize = "area")]
+    Area,
+    #[strum(serialize = "area-contest")]
+    AreaContest,
+    #[strum(serialize = "scheduled-event")]
+    ScheduledEvent,
+    #[strum(serialize = "template")]
+    Template,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanAction {
+    Create,
+    /// Fields that differ from the current state.
+    Update(Vec<String>),
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanStep {
+    pub kind: ResourceKind,
+    pub key: String,
+    pub action: PlanAction,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    pub steps: Vec<PlanStep>,
+}
+
+impl Plan {
+    pub fn count(&self, is_action: impl Fn(&PlanAction) -> bool) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| is_action(&step.action))
+            .count()
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.count(|action| *action != PlanAction::Unchanged) > 0
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in self.steps.iter() {
+            match &step.action {
+                PlanAction::Create => writeln!(f, "  + {} \"{}\"", step.kind, step.key)?,
+                PlanAction::Update(fields) => writeln!(
+                    f,
+                    "  ~ {} \"{}\" ({})",
+                    step.kind,
+                    step.key,
+                    fields.join(", ")
+                )?,
+                PlanAction::Unchanged => (),
+            }
+        }
+        write!(
+            f,
+            "{} to create, {} to update, {} unchanged",
+            self.count(|action| *action == PlanAction::Create),
+            self.count(|action| matches!(action, PlanAction::Update(_))),
+            self.count(|action| *action == PlanAction::Unchanged),
+        )
+    }
+}
+
+/// Current state of the election event, one object per row with at least the
+/// `id` and the columns managed by the file.
+#[derive(Debug, Clone, Default)]
+pub struct ElectionEventState {
+    pub election_event: Option<Value>,
+    pub elections: Vec<Value>,
+    pub contests: Vec<Value>,
+    pub candidates: Vec<Value>,
+    pub areas: Vec<Value>,
+    pub area_contests: Vec<Value>,
+    pub scheduled_events: Vec<Value>,
+    /// Templates of the tenant.
+    pub templates: Vec<Value>,
+}
+
+/// Applies the changes of a plan. The columns of created resources include
+/// the ids of their parents, except the tenant id.
+pub trait Applier {
+    fn create(
+        &mut self,
+        kind: ResourceKind,
+        columns: Map<String, Value>,
+    ) -> Result<String, Box<dyn Error>>;
+
+    fn update(
+        &mut self,
+        kind: ResourceKind,
+        id: &str,
+        columns: Map<String, Value>,
+    ) -> Result<(), Box<dyn Error>>;
+}
+
+/// Applier that doesn't change anything, used to compute the plan.
+pub struct DryRun;
+
+impl Applier for DryRun {
+    fn create(
+        &mut self,
+        _kind: ResourceKind,
+        _columns: Map<String, Value>,
+    ) -> Result<String, Box<dyn Error>> {
+        Ok(PLANNED_ID.to_string())
+    }
+
+    fn update(
+        &mut self,
+        _kind: ResourceKind,
+        _id: &str,
+        _columns: Map<String, Value>,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+fn get_str<'a>(row: &'a Value, field: &str) -> &'a str {
+    row.get(field).and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Managed columns whose value differs from the current one.
+fn get_changed_fields(current: &Value, columns: &Map<String, Value>) -> Vec<String> {
+    columns
+        .iter()
+        .filter(|(field, value)| current.get(field.as_str()).unwrap_or(&Value::Null) != *value)
+        .map(|(field, _)| field.clone())
+        .collect()
+}
+
+fn check_unique<'a>(
+    kind: ResourceKind,
+    keys: impl Iterator<Item = String> + 'a,
+) -> Result<(), Box<dyn Error>> {
+    let mut seen: HashSet<String> = HashSet::new();
+    for key in keys {
+        if !seen.insert(key.clone()) {
+            return Err(format!("Duplicated {kind} \"{key}\"").into());
+        }
+    }
+    Ok(())
+}
+
+fn get_scheduled_event_key(scheduled_event: &ScheduledEventSpec) -> String {
+    match &scheduled_event.election {
+        Some(election) => format!("{} ({election})", scheduled_event.event_processor),
+        None => scheduled_event.event_processor.clone(),
+    }
+}
+
+/// Checks that names are unique and references point to resources of the
+/// file, before changing anything.
+pub fn validate_spec(spec: &ElectionEventSpec) -> Result<(), Box<dyn Error>> {
+    check_unique(
+        ResourceKind::Election,
+        spec.elections.iter().map(|election| election.name.clone()),
+    )?;
+    let mut contests: HashSet<ContestRef> = HashSet::new();
+    for election in spec.elections.iter() {
+        check_unique(
+            ResourceKind::Contest,
+            election
+                .contests
+                .iter()
+                .map(|contest| format!("{}/{}", election.name, contest.name)),
+        )?;
+        for contest in election.contests.iter() {
+            check_unique(
+                ResourceKind::Candidate,
+                contest.candidates.iter().map(|candidate| {
+                    format!("{}/{}/{}", election.name, contest.name, candidate.name)
+                }),
+            )?;
+            contests.insert(ContestRef {
+                election: election.name.clone(),
+                contest: contest.name.clone(),
+            });
+        }
+    }
+
+    check_unique(
+        ResourceKind::Area,
+        spec.areas.iter().map(|area| area.name.clone()),
+    )?;
+    for area in spec.areas.iter() {
+        if let Some(contest_ref) = area
+            .contests
+            .iter()
+            .find(|contest_ref| !contests.contains(*contest_ref))
+        {
+            return Err(format!(
+                "Area \"{}\" references unknown contest \"{}/{}\"",
+                area.name, contest_ref.election, contest_ref.contest
+            )
+            .into());
+        }
+    }
+
+    check_unique(
+        ResourceKind::ScheduledEvent,
+        spec.scheduled_events.iter().map(get_scheduled_event_key),
+    )?;
+    for scheduled_event in spec.scheduled_events.iter() {
+        if let Some(election) = &scheduled_event.election {
+            if !spec.elections.iter().any(|spec| spec.name == *election) {
+                return Err(format!(
+                    "Scheduled event {} references unknown election \"{election}\"",
+                    scheduled_event.event_processor
+                )
+                .into());
+            }
+        }
+    }
+
+    check_unique(
+        ResourceKind::Template,
+        spec.templates.iter().map(|template| template.alias.clone()),
+    )
+}
+
+struct Reconciler<'a> {
+    applier: &'a mut dyn Applier,
+    plan: Plan,
+}
+
+impl Reconciler<'_> {
+    /// Creates the resource if it doesn't exist or updates the fields that
+    /// changed, returning its id. `parent_columns` are only set on creation.
+    fn reconcile(
+        &mut self,
+        kind: ResourceKind,
+        key: String,
+        current: Option<&Value>,
+        mut columns: Map<String, Value>,
+        parent_columns: Map<String, Value>,
+    ) -> Result<String, Box<dyn Error>> {
+        let (id, action) = match current {
+            Some(current) => {
+                let id = get_str(current, "id").to_string();
+                let changed_fields = get_changed_fields(current, &columns);
+                if changed_fields.is_empty() {
+                    (id, PlanAction::Unchanged)
+                } else {
+                    columns.retain(|field, _| changed_fields.contains(field));
+                    self.applier.update(kind, &id, columns)?;
+                    (id, PlanAction::Update(changed_fields))
+                }
+            }
+            None => {
+                columns.extend(parent_columns);
+                let id = self.applier.create(kind, columns)?;
+                (id, PlanAction::Create)
+            }
+        };
+        self.plan.steps.push(PlanStep { kind, key, action });
+        Ok(id)
+    }
+}
+
+/// Brings the election event to the state described by the file, in
+/// dependency order, and returns the plan of the changes. Resources not in
+/// the file are left untouched. With the `DryRun` applier it only computes
+/// the plan.
+pub fn reconcile(
+    spec: &ElectionEventSpec,
+    state: &ElectionEventState,
+    applier: &mut dyn Applier,
+) -> Result<Plan, Box<dyn Error>> {
+    validate_spec(spec)?;
+    let mut reconciler = Reconciler {
+        applier,
+        plan: Plan::default(),
+    };
+
+    let election_event_id = reconciler.reconcile(
+        ResourceKind::ElectionEvent,
+        spec.election_event.name.clone(),
+        state.election_event.as_ref(),
+        get_columns(&spec.election_event),
+        Map::new(),
+    )?;
+    let event_columns = || {
+        let mut columns = Map::new();
+        columns.insert("election_event_id".to_string(), json!(election_event_id));
+        columns
+    };
+
+    let mut election_ids: HashMap<String, String> = HashMap::new();
+    let mut contest_ids: HashMap<ContestRef, String> = HashMap::new();
+    for election in spec.elections.iter() {
+        let current = state
+            .elections
+            .iter()
+            .find(|current| get_str(current, "name") == election.name);
+        let election_id = reconciler.reconcile(
+            ResourceKind::Election,
+            election.name.clone(),
+            current,
+            get_columns(election),
+            event_columns(),
+        )?;
+        election_ids.insert(election.name.clone(), election_id.clone());
+
+        for contest in election.contests.iter() {
+            let current = state.contests.iter().find(|current| {
+                get_str(current, "election_id") == election_id
+                    && get_str(current, "name") == contest.name
+            });
+            let mut parent_columns = event_columns();
+            parent_columns.insert("election_id".to_string(), json!(election_id));
+            let contest_key = format!("{}/{}", election.name, contest.name);
+            let contest_id = reconciler.reconcile(
+                ResourceKind::Contest,
+                contest_key.clone(),
+                current,
+                get_columns(contest),
+                parent_columns,
+            )?;
+            contest_ids.insert(
+                ContestRef {
+                    election: election.name.clone(),
+                    contest: contest.name.clone(),
+                },
+                contest_id.clone(),
+            );
+
+            for candidate in contest.candidates.iter() {
+                let current = state.candidates.iter().find(|current| {
+                    get_str(current, "contest_id") == contest_id
+                        && get_str(current, "name") == candidate.name
+                });
+                let mut parent_columns = event_columns();
+                parent_columns.insert("contest_id".to_string(), json!(contest_id));
+                reconciler.reconcile(
+                    ResourceKind::Candidate,
+                    format!("{contest_key}/{}", candidate.name),
+                    current,
+                    get_columns(candidate),
+                    parent_columns,
+                )?;
+            }
+        }
+    }
+
+    for area in spec.areas.iter() {
+        let current = state
+            .areas
+            .iter()
+            .find(|current| get_str(current, "name") == area.name);
+        let area_id = reconciler.reconcile(
+            ResourceKind::Area,
+            area.name.clone(),
+            current,
+            get_columns(area),
+            event_columns(),
+        )?;
+
+        for contest_ref in area.contests.iter() {
+            let contest_id = contest_ids.get(contest_ref).cloned().unwrap_or_default();
+            let current = state.area_contests.iter().find(|current| {
+                get_str(current, "area_id") == area_id
+                    && get_str(current, "contest_id") == contest_id
+            });
+            let mut parent_columns = event_columns();
+            parent_columns.insert("area_id".to_string(), json!(area_id));
+            parent_columns.insert("contest_id".to_string(), json!(contest_id));
+            reconciler.reconcile(
+                ResourceKind::AreaContest,
+                format!(
+                    "{} -> {}/{}",
+                    area.name, contest_ref.election, contest_ref.contest
+                ),
+                current,
+                Map::new(),
+                parent_columns,
+            )?;
+        }
+    }
+
+    for scheduled_event in spec.scheduled_events.iter() {
+        let election_id = scheduled_event
+            .election
+            .as_ref()
+            .and_then(|election| election_ids.get(election))
+            .cloned();
+        let current = state.scheduled_events.iter().find(|current| {
+            get_str(current, "event_processor") == scheduled_event.event_processor
+                && current
+                    .get("event_payload")
+                    .and_then(|payload| payload.get("election_id"))
+                    .and_then(Value::as_str)
+                    == election_id.as_deref()
+        });
+        let mut parent_columns = event_columns();
+        parent_columns.insert(
+            "event_payload".to_string(),
+            json!({ "election_id": election_id }),
+        );
+        reconciler.reconcile(
+            ResourceKind::ScheduledEvent,
+            get_scheduled_event_key(scheduled_event),
+            current,
+            get_columns(scheduled_event),
+            parent_columns,
+        )?;
+    }
+
+    for template in spec.templates.iter() {
+        let current = state.templates.iter().find(|current| {
+            current
+                .get("template")
+                .and_then(|template| template.get("alias"))
+                .and_then(Value::as_str)
+                == Some(template.alias.as_str())
+        });
+        reconciler.reconcile(
+            ResourceKind::Template,
+            template.alias.clone(),
+            current,
+            template.get_columns(),
+            Map::new(),
+        )?;
+    }
+
+    Ok(reconciler.plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+election_event:
+  name: General Election
+  description: General election of the board
+elections:
+  - name: Board
+    alias: board
+    contests:
+      - name: President
+        max_votes: 1
+        candidates:
+          - name: Alice
+          - name: Bob
+areas:
+  - name: North
+    contests:
+      - election: Board
+        contest: President
+scheduled_events:
+  - event_processor: START_VOTING_PERIOD
+    election: Board
+    cron_config:
+      scheduled_date: "2030-01-01T09:00:00Z"
+"#;
+
+    fn spec() -> ElectionEventSpec {
+        serde_yaml::from_str(SPEC).unwrap()
+    }
+
+    fn actions(plan: &Plan) -> Vec<(ResourceKind, PlanAction)> {
+        plan.steps
+            .iter()
+            .map(|step| (step.kind, step.action.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_reconcile_new_election_event() {
+        let plan = reconcile(&spec(), &ElectionEventState::default(), &mut DryRun).unwrap();
+        assert_eq!(plan.count(|action| *action == PlanAction::Create), 8);
+        assert!(plan.has_changes());
+    }
+
+    #[test]
+    fn test_reconcile_existing_election_event() {
+        let state = ElectionEventState {
+            election_event: Some(json!({
+                "id": "event-1",
+                "name": "General Election",
+                "description": "General election of the board",
+                "alias": "ignored"
+            })),
+            elections: vec![json!({"id": "election-1", "name": "Board", "alias": "board"})],
+            contests: vec![json!({
+                "id": "contest-1",
+                "election_id": "election-1",
+                "name": "President",
+                "max_votes": 2
+            })],
+            candidates: vec![
+                json!({"id": "candidate-1", "contest_id": "contest-1", "name": "Alice"}),
+            ],
+            areas: vec![json!({"id": "area-1", "name": "North"})],
+            area_contests: vec![
+                json!({"id": "ac-1", "area_id": "area-1", "contest_id": "contest-1"}),
+            ],
+            scheduled_events: vec![json!({
+                "id": "se-1",
+                "event_processor": "START_VOTING_PERIOD",
+                "event_payload": {"election_id": "election-1"},
+                "cron_config": {"scheduled_date": "2030-01-01T09:00:00Z"}
+            })],
+            templates: vec![],
+        };
+        let plan = reconcile(&spec(), &state, &mut DryRun).unwrap();
+        assert_eq!(
+            actions(&plan),
+            vec![
+                (ResourceKind::ElectionEvent, PlanAction::Unchanged),
+                (ResourceKind::Election, PlanAction::Unchanged),
+                (
+                    ResourceKind::Contest,
+                    PlanAction::Update(vec!["max_votes".to_string()])
+                ),
+                (ResourceKind::Candidate, PlanAction::Unchanged),
+                (ResourceKind::Candidate, PlanAction::Create),
+                (ResourceKind::Area, PlanAction::Unchanged),
+                (ResourceKind::AreaContest, PlanAction::Unchanged),
+                (ResourceKind::ScheduledEvent, PlanAction::Unchanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_spec() {
+        let mut invalid_spec = spec();
+        invalid_spec.areas[0].contests[0].contest = "Treasurer".to_string();
+        assert!(validate_spec(&invalid_spec).is_err());
+
+        let mut invalid_spec = spec();
+        invalid_spec
+            .elections
+            .push(invalid_spec.elections[0].clone());
+        assert!(validate_spec(&invalid_spec).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use super::plan::ElectionEventState;
+use crate::{types::config::ConfigData, types::hasura_types::*};
+use graphql_client::{GraphQLQuery, QueryBody, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.json",
+    query_path = "src/graphql/get_election_event_state.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct GetElectionEventByName;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/graphql/schema.json",
+    query_path = "src/graphql/get_election_event_state.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct GetElectionEventResources;
+
+/// Sends a GraphQL request to Hasura and returns its data.
+pub fn send_graphql<V: Serialize, T: DeserializeOwned>(
+    client: &reqwest::blocking::Client,
+    config: &ConfigData,
+    request_body: &QueryBody<V>,
+) -> Result<T, Box<dyn Error>> {
+    let response = client
+        .post(&config.endpoint_url)
+        .bearer_auth(&config.auth_token)
+        .json(request_body)
+        .send()?;
+
+    if response.status().is_success() {
+        let response_body: Response<T> = response.json()?;
+        if let Some(errors) = response_body.errors.filter(|errors| !errors.is_empty()) {
+            let error_messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+            Err(Box::from(error_messages.join(", ")))
+        } else if let Some(data) = response_body.data {
+            Ok(data)
+        } else {
+            Err(Box::from("Unknown error occurred"))
+        }
+    } else {
+        let status = response.status();
+        let error_message = response.text()?;
+        let error = format!("HTTP Status: {}\nError Message: {}", status, error_message);
+        Err(Box::from(error))
+    }
+}
+
+fn to_values<T: Serialize>(rows: Vec<T>) -> Result<Vec<Value>, Box<dyn Error>> {
+    rows.into_iter()
+        .map(|row| Ok(serde_json::to_value(row)?))
+        .collect()
+}
+
+/// Election event of the tenant with the given name. Names are how the file
+/// identifies the election event, so they must be unique.
+pub fn get_election_event_by_name(
+    client: &reqwest::blocking::Client,
+    config: &ConfigData,
+    name: &str,
+) -> Result<Option<Value>, Box<dyn Error>> {
+    let variables = get_election_event_by_name::Variables {
+        tenant_id: config.tenant_id.clone(),
+        name: name.to_string(),
+    };
+    let data: get_election_event_by_name::ResponseData = send_graphql(
+        client,
+        config,
+        &GetElectionEventByName::build_query(variables),
+    )?;
+
+    let mut election_events = data.sequent_backend_election_event;
+    if election_events.len() > 1 {
+        return Err(Box::from(format!(
+            "Found {} election events named \"{name}\"",
+            election_events.len()
+        )));
+    }
+    election_events
+        .pop()
+        .map(|election_event| Ok(serde_json::to_value(election_event)?))
+        .transpose()
+}
+
+pub fn get_election_event_state(
+    client: &reqwest::blocking::Client,
+    config: &ConfigData,
+    name: &str,
+) -> Result<ElectionEventState, Box<dyn Error>> {
+    let Some(election_event) = get_election_event_by_name(client, config, name)? else {
+        return Ok(ElectionEventState::default());
+    };
+    let election_event_id = election_event
+        .get("id")
+        .and_then(Value::as_str)
+        .ok_or("Missing election event id")?
+        .to_string();
+
+    let variables = get_election_event_resources::Variables {
+        tenant_id: config.tenant_id.clone(),
+        election_event_id,
+    };
+    let data: get_election_event_resources::ResponseData = send_graphql(
+        client,
+        config,
+        &GetElectionEventResources::build_query(variables),
+    )?;
+
+    Ok(ElectionEventState {
+        election_event: Some(election_event),
+        elections: to_values(data.sequent_backend_election)?,
+        contests: to_values(data.sequent_backend_contest)?,
+        candidates: to_values(data.sequent_backend_candidate)?,
+        areas: to_values(data.sequent_backend_area)?,
+        area_contests: to_values(data.sequent_backend_area_contest)?,
+        scheduled_events: to_values(data.sequent_backend_scheduled_event)?,
+        templates: to_values(data.sequent_backend_template)?,
+    })
+}
@@ -0,0 +1,245 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local};
+use csv::StringRecord;
+use sequent_core::ballot::BallotStyle;
+use sequent_core::serialization::deserialize_with_path::deserialize_str;
+use sequent_core::types::hasura::core::{TallySession, TallySessionContest, TallySheet};
+use sequent_core::types::scheduled_event::ScheduledEvent;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tempfile::NamedTempFile;
+use windmill::services::consolidation::aes_256_cbc_encrypt::decrypt_file_aes_256_cbc;
+use windmill::services::import::import_election_event::ImportElectionEventSchema;
+use windmill::services::import::import_scheduled_events::get_scheduled_event_from_record;
+use windmill::services::import::import_tally::{
+    process_tally_session_contest_record, process_tally_session_record, process_tally_sheet_record,
+};
+use windmill::types::documents::{EDocuments, ETallyDocuments};
+use zip::ZipArchive;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BallotPublicationExport {
+    pub ballot_publication_id: String,
+    /// Missing in exports generated before it was added.
+    #[serde(default)]
+    pub published_at: Option<DateTime<Local>>,
+    pub ballot_styles: Vec<BallotStyle>,
+}
+
+/// Contents of an election event export, as generated by
+/// `export_election_event`. Ids are kept as they are in the export.
+pub struct ElectionEventExport {
+    pub schema: ImportElectionEventSchema,
+    pub publications: Vec<BallotPublicationExport>,
+    pub scheduled_events: Vec<ScheduledEvent>,
+    pub tally_sessions: Vec<TallySession>,
+    pub tally_session_contests: Vec<TallySessionContest>,
+    /// Published tally sheets, None if the export doesn't include them.
+    pub tally_sheets: Option<Vec<TallySheet>>,
+    /// Bulletin boards CSV, if the export includes it.
+    pub bulletin_boards: Option<Vec<u8>>,
+}
+
+/// Reads the export zip file, decrypting it first if a password is given.
+pub async fn read_election_event_export(
+    path: &Path,
+    password: Option<&str>,
+) -> Result<ElectionEventExport> {
+    let entries = match password {
+        Some(password) => {
+            let decrypted_file = NamedTempFile::new()?;
+            let input_path = path.to_str().ok_or(anyhow!("Invalid export path"))?;
+            let output_path = decrypted_file
+                .path()
+                .to_str()
+                .ok_or(anyhow!("Invalid temp file path"))?;
+            decrypt_file_aes_256_cbc(input_path, output_path, password)
+                .context("Error decrypting the export")?;
+            read_zip_entries(decrypted_file.path())?
+        }
+        None => read_zip_entries(path)?,
+    };
+
+    let schema_data = find_entry(&entries, EDocuments::ELECTION_EVENT.to_file_name())
+        .ok_or(anyhow!("The export has no election event"))?;
+    let schema: ImportElectionEventSchema = deserialize_str(std::str::from_utf8(schema_data)?)
+        .map_err(|err| anyhow!("Error reading the election event: {err}"))?;
+    let tenant_id = schema.tenant_id.to_string();
+    let election_event_id = schema.election_event.id.clone();
+
+    let publications: Vec<BallotPublicationExport> =
+        match find_entry(&entries, EDocuments::PUBLICATIONS.to_file_name()) {
+            Some(data) => deserialize_str(std::str::from_utf8(data)?)
+                .map_err(|err| anyhow!("Error reading the publications: {err}"))?,
+            None => vec![],
+        };
+
+    let tally_session_records = find_entry(&entries, ETallyDocuments::TALLY_SESSION.to_file_name())
+        .map(|data| read_csv_records(data))
+        .transpose()?
+        .unwrap_or_default();
+    let tally_session_contest_records = find_entry(
+        &entries,
+        ETallyDocuments::TALLY_SESSION_CONTEST.to_file_name(),
+    )
+    .map(|data| read_csv_records(data))
+    .transpose()?
+    .unwrap_or_default();
+
+    // The import functions replace the ids of the export, here they are kept.
+    let mut replacement_map: HashMap<String, String> = schema
+        .elections
+        .iter()
+        .map(|election| election.id.clone())
+        .chain(schema.contests.iter().map(|contest| contest.id.clone()))
+        .chain(schema.areas.iter().map(|area| area.id.clone()))
+        .chain(
+            schema
+                .candidates
+                .iter()
+                .map(|candidate| candidate.id.clone()),
+        )
+        .chain(
+            schema
+                .keys_ceremonies
+                .iter()
+                .flatten()
+                .map(|keys_ceremony| keys_ceremony.id.clone()),
+        )
+        .map(|id| (id.clone(), id))
+        .collect();
+    for record in &tally_session_records {
+        for index in [0, 10] {
+            if let Some(id) = record
+                .get(index)
+                .and_then(|value| deserialize_str::<String>(value).ok())
+            {
+                replacement_map.insert(id.clone(), id);
+            }
+        }
+    }
+
+    let mut tally_sessions = vec![];
+    for record in &tally_session_records {
+        tally_sessions.push(
+            process_tally_session_record(
+                &tenant_id,
+                &election_event_id,
+                record,
+                replacement_map.clone(),
+            )
+            .await
+            .context("Error reading tally session")?,
+        );
+    }
+    let mut tally_session_contests = vec![];
+    for record in &tally_session_contest_records {
+        tally_session_contests.push(
+            process_tally_session_contest_record(
+                &tenant_id,
+                &election_event_id,
+                record,
+                &replacement_map,
+            )
+            .await
+            .context("Error reading tally session contest")?,
+        );
+    }
+
+    let tally_sheets = match find_entry(&entries, ETallyDocuments::TALLY_SHEET.to_file_name()) {
+        Some(data) => {
+            let mut tally_sheets = vec![];
+            for record in read_csv_records(data)? {
+                tally_sheets.push(
+                    process_tally_sheet_record(
+                        &tenant_id,
+                        &election_event_id,
+                        &record,
+                        &replacement_map,
+                    )
+                    .await
+                    .context("Error reading tally sheet")?,
+                );
+            }
+            Some(tally_sheets)
+        }
+        None => None,
+    };
+
+    let scheduled_events = match find_entry(&entries, EDocuments::SCHEDULED_EVENTS.to_file_name()) {
+        Some(data) => read_csv_records(data)?
+            .iter()
+            .map(|record| {
+                get_scheduled_event_from_record(
+                    &tenant_id,
+                    &election_event_id,
+                    record,
+                    &replacement_map,
+                )
+            })
+            .collect::<Result<Vec<_>>>()
+            .context("Error reading scheduled events")?,
+        None => schema.scheduled_events.clone().unwrap_or_default(),
+    };
+
+    let bulletin_boards = find_entry(&entries, EDocuments::BULLETIN_BOARDS.to_file_name()).cloned();
+
+    Ok(ElectionEventExport {
+        schema,
+        publications,
+        scheduled_events,
+        tally_sessions,
+        tally_session_contests,
+        tally_sheets,
+        bulletin_boards,
+    })
+}
+
+fn read_zip_entries(path: &Path) -> Result<HashMap<String, Vec<u8>>> {
+    let file = File::open(path).with_context(|| format!("Error opening {path:?}"))?;
+    let mut zip = ZipArchive::new(file).context("Error reading the export zip")?;
+    let mut entries = HashMap::new();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let mut data = vec![];
+        entry.read_to_end(&mut data)?;
+        entries.insert(entry.name().to_string(), data);
+    }
+    Ok(entries)
+}
+
+/// Finds the entry named `<file_name>.<ext>` or `<file_name>-<id>.<ext>`,
+/// in any folder of the zip.
+fn find_entry<'a>(entries: &'a HashMap<String, Vec<u8>>, file_name: &str) -> Option<&'a Vec<u8>> {
+    entries.iter().find_map(|(name, data)| {
+        let base_name = name.rsplit('/').next().unwrap_or(name);
+        let stem = base_name
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(base_name);
+        let matches = stem == file_name
+            || stem
+                .strip_prefix(file_name)
+                .is_some_and(|rest| rest.starts_with('-'));
+        matches.then_some(data)
+    })
+}
+
+fn read_csv_records(data: &[u8]) -> Result<Vec<StringRecord>> {
+    csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(data)
+        .records()
+        .map(|record| record.map_err(|err| anyhow!("Error reading CSV record: {err:?}")))
+        .collect()
+}
@@ -2,11 +2,14 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+pub mod apply;
 pub mod areas;
+pub mod election_event_export;
 pub mod elections;
 pub mod hasura;
 pub mod keycloak;
 pub mod publication;
 pub mod read_config;
+pub mod synthetic_ballots;
 pub mod trustees;
 pub mod upload_file;
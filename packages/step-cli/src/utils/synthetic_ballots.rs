@@ -0,0 +1,312 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::types::config::VoteDistribution;
+use anyhow::{anyhow, Result};
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::Rng;
+use sequent_core::ballot::{BallotStyle, Contest, ContestEncryptionPolicy};
+use sequent_core::ballot_codec::multi_ballot::BallotChoices;
+use sequent_core::ballot_codec::PlaintextCodec;
+use sequent_core::encrypt::encode_to_plaintext_decoded_multi_contest;
+use sequent_core::plaintext::{
+    map_decoded_ballot_choices_to_decoded_contests, DecodedVoteChoice, DecodedVoteContest,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Text of the generated write-ins, encodable with any write-in character map.
+pub const WRITE_IN_TEXT: &str = "WRITEIN";
+
+pub fn get_contest_encryption_policy(ballot_style: &BallotStyle) -> ContestEncryptionPolicy {
+    ballot_style
+        .election_event_presentation
+        .as_ref()
+        .and_then(|presentation| presentation.contest_encryption_policy.clone())
+        .unwrap_or_default()
+}
+
+/// Generates the choices of a ballot. With multiple contests per ballot the
+/// invalid vote flag applies to the whole ballot and write-ins are not
+/// supported.
+pub fn generate_decoded_ballot<R: Rng>(
+    rng: &mut R,
+    ballot_style: &BallotStyle,
+    distribution: &VoteDistribution,
+) -> Result<Vec<DecodedVoteContest>> {
+    let is_multi_contest =
+        get_contest_encryption_policy(ballot_style) == ContestEncryptionPolicy::MULTIPLE_CONTESTS;
+    let is_ballot_invalid = is_multi_contest && rng.random_bool(distribution.invalid_rate);
+    ballot_style
+        .contests
+        .iter()
+        .map(|contest| {
+            let is_invalid = if is_multi_contest {
+                is_ballot_invalid
+            } else {
+                rng.random_bool(distribution.invalid_rate)
+            };
+            generate_decoded_contest(rng, contest, distribution, is_invalid, !is_multi_contest)
+        })
+        .collect()
+}
+
+/// Generates the choices of a contest. Valid votes select `min_votes`
+/// candidates (at least one), drawn according to the vote shares.
+pub fn generate_decoded_contest<R: Rng>(
+    rng: &mut R,
+    contest: &Contest,
+    distribution: &VoteDistribution,
+    is_invalid: bool,
+    allow_write_ins: bool,
+) -> Result<DecodedVoteContest> {
+    let mut choices: Vec<DecodedVoteChoice> = contest
+        .candidates
+        .iter()
+        .map(|candidate| DecodedVoteChoice {
+            id: candidate.id.clone(),
+            selected: -1,
+            write_in_text: None,
+        })
+        .collect();
+
+    if !is_invalid && !rng.random_bool(distribution.blank_rate) {
+        let write_ins: Vec<usize> = (0..contest.candidates.len())
+            .filter(|index| contest.candidates[*index].is_write_in())
+            .collect();
+        let is_write_in = allow_write_ins
+            && contest.allow_writeins()
+            && !write_ins.is_empty()
+            && rng.random_bool(distribution.write_in_rate);
+
+        let (candidates, mut weights): (Vec<usize>, Vec<f64>) = if is_write_in {
+            (write_ins.clone(), vec![1.0; write_ins.len()])
+        } else {
+            contest
+                .candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, candidate)| {
+                    !candidate.is_write_in()
+                        && !candidate.is_explicit_invalid()
+                        && !candidate.is_explicit_blank()
+                        && !candidate.is_category_list()
+                        && !candidate.is_disabled()
+                })
+                .map(|(index, candidate)| {
+                    let weight = if distribution.vote_shares.is_empty() {
+                        1.0
+                    } else {
+                        distribution
+                            .vote_shares
+                            .get(&candidate.id)
+                            .copied()
+                            .unwrap_or(0.0)
+                    };
+                    (index, weight)
+                })
+                .unzip()
+        };
+
+        let num_selections = contest.min_votes.max(1).min(contest.max_votes).max(0) as usize;
+        for rank in 0..num_selections {
+            let weighted_index = WeightedIndex::new(&weights).map_err(|_| {
+                anyhow!(
+                    "Not enough candidates with vote share in contest {}",
+                    contest.id
+                )
+            })?;
+            let index = weighted_index.sample(rng);
+            weights[index] = 0.0;
+
+            let choice = &mut choices[candidates[index]];
+            choice.selected = if contest.is_ranked() { rank as i64 } else { 0 };
+            if is_write_in {
+                choice.write_in_text = Some(WRITE_IN_TEXT.to_string());
+            }
+        }
+    }
+
+    Ok(DecodedVoteContest {
+        contest_id: contest.id.clone(),
+        is_explicit_invalid: is_invalid,
+        invalid_errors: vec![],
+        invalid_alerts: vec![],
+        choices,
+    })
+}
+
+/// Encodes and decodes the ballot, to obtain the choices as they will be
+/// tallied, including the invalid vote checks.
+pub fn decode_as_tallied(
+    ballot_style: &BallotStyle,
+    decoded_contests: &Vec<DecodedVoteContest>,
+) -> Result<Vec<DecodedVoteContest>> {
+    match get_contest_encryption_policy(ballot_style) {
+        ContestEncryptionPolicy::MULTIPLE_CONTESTS => {
+            let (plaintext, _) =
+                encode_to_plaintext_decoded_multi_contest(decoded_contests, ballot_style)
+                    .map_err(|err| anyhow!("Error encoding ballot: {err:?}"))?;
+            let ballot_choices = BallotChoices::decode_from_30_bytes(&plaintext, ballot_style)
+                .map_err(|err| anyhow!("Error decoding ballot: {err}"))?;
+            map_decoded_ballot_choices_to_decoded_contests(ballot_choices, &ballot_style.contests)
+                .map_err(|err| anyhow!("Error decoding ballot: {err}"))
+        }
+        ContestEncryptionPolicy::SINGLE_CONTEST => decoded_contests
+            .iter()
+            .map(|decoded_contest| {
+                let contest = ballot_style
+                    .contests
+                    .iter()
+                    .find(|contest| contest.id == decoded_contest.contest_id)
+                    .ok_or(anyhow!("Contest {} not found", decoded_contest.contest_id))?;
+                let plaintext = contest
+                    .encode_plaintext_contest_with_version(
+                        decoded_contest,
+                        ballot_style.get_codec_version(),
+                    )
+                    .map_err(|err| anyhow!("Error encoding contest: {err}"))?;
+                contest
+                    .decode_plaintext_contest(&plaintext)
+                    .map_err(|err| anyhow!("Error decoding contest: {err}"))
+            })
+            .collect(),
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContestExpectedTally {
+    pub total_ballots: u64,
+    pub blank_votes: u64,
+    pub invalid_votes: u64,
+    pub write_in_votes: u64,
+    /// Number of valid ballots selecting each candidate.
+    pub candidates: BTreeMap<String, u64>,
+}
+
+impl ContestExpectedTally {
+    pub fn add(&mut self, decoded_contest: &DecodedVoteContest) {
+        self.total_ballots += 1;
+        if decoded_contest.is_invalid() {
+            self.invalid_votes += 1;
+        } else if decoded_contest.is_blank() {
+            self.blank_votes += 1;
+        } else {
+            for choice in decoded_contest
+                .choices
+                .iter()
+                .filter(|choice| choice.is_selected())
+            {
+                *self.candidates.entry(choice.id.clone()).or_default() += 1;
+                if choice.write_in_text.is_some() {
+                    self.write_in_votes += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Tally the generated ballots should produce, by area and contest and by
+/// contest.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExpectedTally {
+    pub areas: BTreeMap<String, BTreeMap<String, ContestExpectedTally>>,
+    pub contests: BTreeMap<String, ContestExpectedTally>,
+}
+
+impl ExpectedTally {
+    pub fn add_ballot(&mut self, area_id: &str, decoded_contests: &Vec<DecodedVoteContest>) {
+        let area = self.areas.entry(area_id.to_string()).or_default();
+        for decoded_contest in decoded_contests {
+            area.entry(decoded_contest.contest_id.clone())
+                .or_default()
+                .add(decoded_contest);
+            self.contests
+                .entry(decoded_contest.contest_id.clone())
+                .or_default()
+                .add(decoded_contest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sequent_core::ballot::Candidate;
+    use std::collections::HashMap;
+
+    fn contest(candidate_ids: &[&str], max_votes: i64) -> Contest {
+        Contest {
+            id: "contest".to_string(),
+            max_votes,
+            min_votes: 0,
+            candidates: candidate_ids
+                .iter()
+                .map(|id| Candidate {
+                    id: id.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_votes_follow_shares() {
+        let contest = contest(&["a", "b", "c"], 1);
+        let distribution = VoteDistribution {
+            vote_shares: HashMap::from([("a".to_string(), 3.0), ("b".to_string(), 1.0)]),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut tally = ContestExpectedTally::default();
+        for _ in 0..2000 {
+            let decoded =
+                generate_decoded_contest(&mut rng, &contest, &distribution, false, true).unwrap();
+            assert_eq!(decoded.choices.len(), 3);
+            tally.add(&decoded);
+        }
+        let a = tally.candidates.get("a").copied().unwrap_or_default();
+        let b = tally.candidates.get("b").copied().unwrap_or_default();
+        assert_eq!(tally.total_ballots, 2000);
+        assert_eq!(a + b, 2000);
+        assert!(!tally.candidates.contains_key("c"));
+        assert!(a > 2 * b);
+    }
+
+    #[test]
+    fn test_blank_and_invalid_votes() {
+        let contest = contest(&["a", "b"], 1);
+        let distribution = VoteDistribution {
+            blank_rate: 1.0,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut tally = ContestExpectedTally::default();
+        for is_invalid in [true, false, false] {
+            let decoded =
+                generate_decoded_contest(&mut rng, &contest, &distribution, is_invalid, true)
+                    .unwrap();
+            tally.add(&decoded);
+        }
+        assert_eq!(tally.invalid_votes, 1);
+        assert_eq!(tally.blank_votes, 2);
+        assert!(tally.candidates.is_empty());
+    }
+
+    #[test]
+    fn test_missing_shares_error() {
+        let mut contest = contest(&["a", "b"], 2);
+        contest.min_votes = 2;
+        let distribution = VoteDistribution {
+            vote_shares: HashMap::from([("a".to_string(), 1.0)]),
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        assert!(generate_decoded_contest(&mut rng, &contest, &distribution, false, true).is_err());
+    }
+}
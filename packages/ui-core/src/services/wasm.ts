@@ -26,9 +26,11 @@ import {
     test_multi_contest_reencoding_js,
     get_write_in_available_characters_js,
     check_is_blank_js,
+    check_ranked_choices_js,
     sign_hashable_ballot_with_ephemeral_voter_signing_key_js,
     sign_hashable_multi_ballot_with_ephemeral_voter_signing_key_js,
     IDecodedVoteContest,
+    IInvalidPlaintextError,
     check_voting_not_allowed_next,
     check_voting_error_dialog,
     verify_ballot_signature_js,
@@ -311,6 +313,18 @@ export const checkIsBlank = (contest: IDecodedVoteContest): boolean | null => {
     }
 }
 
+export const checkRankedChoices = (
+    contest: IDecodedVoteContest
+): Array<IInvalidPlaintextError> | null => {
+    try {
+        let errors: Array<IInvalidPlaintextError> = check_ranked_choices_js(contest)
+        return errors
+    } catch (error) {
+        console.log(error)
+        return null
+    }
+}
+
 export const verifyBallotSignature = (
     ballot_id: string,
     election_id: string,
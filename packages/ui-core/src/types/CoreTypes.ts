@@ -187,6 +187,9 @@ export enum EInvalidPlaintextErrorType {
     Explicit = "Explicit",
     Implicit = "Implicit",
     EncodingError = "EncodingError",
+    DuplicateRank = "DuplicateRank",
+    SkippedRank = "SkippedRank",
+    OvervoteAtRank = "OvervoteAtRank",
 }
 
 export interface IVotingPortalCountdownPolicy {
@@ -18,6 +18,15 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     Run(CliRun),
+    Schemas(CliSchemas),
+}
+
+/// Writes the JSON schemas of the public ballot and result formats into
+/// `<output_dir>/v<SCHEMAS_VERSION>`.
+#[derive(Parser, Debug, Clone)]
+pub struct CliSchemas {
+    #[arg(short, long)]
+    pub output_dir: PathBuf,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -398,6 +398,7 @@ mod tests {
     use crate::pipes::pipe_inputs::{PREFIX_AREA, PREFIX_CONTEST, PREFIX_ELECTION};
     use crate::pipes::pipe_name::PipeNameOutputDir;
     use anyhow::{Error, Result};
+    use jsonschema::JSONSchema;
     use sequent_core::ballot_codec::BigUIntCodec;
     use sequent_core::plaintext::{DecodedVoteChoice, DecodedVoteContest};
     use sequent_core::serialization::deserialize_with_path::deserialize_str;
@@ -405,6 +406,7 @@ mod tests {
     use std::fs;
     use std::io::Read;
     use std::io::Write;
+    use std::path::Path;
     use std::str::FromStr;
     use uuid::Uuid;
     use walkdir::WalkDir;
@@ -689,6 +691,49 @@ mod tests {
             election_num * contest_num * area_num + election_num * contest_num
         );
 
+        assert_outputs_match_schemas(&cli.output_dir)?;
+
+        Ok(())
+    }
+
+    // Validates every contest_result.json and winners.json in the output
+    // directory against the published JSON schemas.
+    fn assert_outputs_match_schemas(output_dir: &Path) -> Result<()> {
+        let schemas = crate::schemas::public_schemas();
+        let compile = |name: &str| -> Result<JSONSchema> {
+            let public_schema = schemas
+                .iter()
+                .find(|public_schema| public_schema.name == name)
+                .ok_or_else(|| anyhow::anyhow!("Schema {name} not found"))?;
+            let schema = serde_json::to_value(&public_schema.schema)?;
+            JSONSchema::compile(&schema).map_err(|err| anyhow::anyhow!("{err}"))
+        };
+        let contest_result_schema = compile(crate::schemas::CONTEST_RESULT_SCHEMA)?;
+        let winners_schema = compile(crate::schemas::WINNERS_SCHEMA)?;
+
+        let mut validated = 0;
+        for entry in WalkDir::new(output_dir).into_iter().filter_map(Result::ok) {
+            let schema = match entry.path().file_name() {
+                Some(f) if f == OUTPUT_CONTEST_RESULT_FILE => &contest_result_schema,
+                Some(f) if f == OUTPUT_WINNERS => &winners_schema,
+                _ => continue,
+            };
+            let instance: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(entry.path())?)?;
+            if let Err(errors) = schema.validate(&instance) {
+                let messages: Vec<String> = errors
+                    .map(|error| format!("{} at {}", error, error.instance_path))
+                    .collect();
+                panic!(
+                    "{} doesn't match schema: {}",
+                    entry.path().display(),
+                    messages.join(", ")
+                );
+            }
+            validated += 1;
+        }
+        assert!(validated > 0);
+
         Ok(())
     }
 
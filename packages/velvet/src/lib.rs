@@ -6,4 +6,5 @@ pub mod cli;
 pub mod config;
 pub mod fixtures;
 pub mod pipes;
+pub mod schemas;
 pub mod utils;
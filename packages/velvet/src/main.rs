@@ -6,10 +6,12 @@ mod cli;
 mod config;
 mod fixtures;
 mod pipes;
+mod schemas;
 mod utils;
 
 use clap::Parser;
 use cli::{state::State, Cli, Commands};
+use sequent_core::schemas::{write_schemas, SCHEMAS_VERSION};
 use sequent_core::util::init_log::init_log;
 use tracing::{event, Level};
 
@@ -28,6 +30,16 @@ fn main() -> std::result::Result<(), Box<dyn std::error::Error + 'static>> {
                 state.exec_next()?;
             }
         }
+        Commands::Schemas(cli_schemas) => {
+            let paths = write_schemas(
+                &cli_schemas.output_dir,
+                SCHEMAS_VERSION,
+                &schemas::public_schemas(),
+            )?;
+            for path in paths {
+                event!(Level::INFO, "Written schema {}", path.display());
+            }
+        }
     }
 
     Ok(())
@@ -3,18 +3,16 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 use crate::pipes::error::{Error, Result};
-use crate::pipes::pipe_inputs::{PipeInputs, BALLOTS_FILE};
+use crate::pipes::pipe_inputs::{parse_ballot_line, PipeInputs, BALLOTS_FILE};
 use crate::pipes::Pipe;
-use num_bigint::BigUint;
 use sequent_core::ballot::Contest;
-use sequent_core::ballot_codec::BigUIntCodec;
+use sequent_core::ballot_codec::get_decoder;
 use sequent_core::plaintext::DecodedVoteContest;
 
 use std::fs::{self, File};
 use std::io::BufRead;
 use std::path::Path;
 
-use std::str::FromStr;
 use tracing::{instrument, warn};
 
 use rayon::prelude::*;
@@ -36,7 +34,11 @@ impl DecodeBallots {
 
 impl DecodeBallots {
     #[instrument(err, skip(contest))]
-    fn decode_ballots(path: &Path, contest: &Contest) -> Result<Vec<DecodedVoteContest>> {
+    fn decode_ballots(
+        path: &Path,
+        contest: &Contest,
+        codec_version: u8,
+    ) -> Result<Vec<DecodedVoteContest>> {
         let file = fs::File::open(path).map_err(|e| Error::FileAccess(path.to_path_buf(), e))?;
         let reader = std::io::BufReader::new(file);
         let mut decoded_ballots: Vec<DecodedVoteContest> = vec![];
@@ -44,19 +46,15 @@ impl DecodeBallots {
         for line in reader.lines() {
             let line = line?;
 
-            let plaintext = BigUint::from_str(&line);
+            let Some((codec_version, plaintext)) = parse_ballot_line(&line, codec_version)? else {
+                continue;
+            };
+            // Ballots cast before the election was republished may have been
+            // encoded with a different codec version than the ballot style's.
+            let decoder = get_decoder(codec_version).map_err(Error::UnexpectedError)?;
 
-            if let Err(error) = &plaintext {
-                if error.to_string() == "cannot parse integer from empty string" {
-                    continue;
-                }
-            }
-
-            let plaintext =
-                plaintext.map_err(|_| Error::UnexpectedError("Wrong ballot format".into()))?;
-
-            let decoded_vote = contest
-                .decode_plaintext_contest_bigint(&plaintext)
+            let decoded_vote = decoder
+                .decode_contest_bigint(contest, &plaintext)
                 .map_err(|_| Error::UnexpectedError("Wrong ballot format".into()))?;
 
             decoded_ballots.push(decoded_vote);
@@ -96,8 +94,11 @@ impl Pipe for DecodeBallots {
                 )
                 .join(BALLOTS_FILE);
 
-                let res =
-                    DecodeBallots::decode_ballots(path_ballots.as_path(), &contest_input.contest);
+                let res = DecodeBallots::decode_ballots(
+                    path_ballots.as_path(),
+                    &contest_input.contest,
+                    election_input.get_codec_version(&area_input.id),
+                );
 
                 match res {
                     Ok(decoded_ballots) => {
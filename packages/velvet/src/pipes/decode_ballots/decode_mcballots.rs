@@ -3,11 +3,11 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 use crate::pipes::error::{Error, Result};
-use crate::pipes::pipe_inputs::{InputElectionConfig, PipeInputs, BALLOTS_FILE};
+use crate::pipes::pipe_inputs::{parse_ballot_line, InputElectionConfig, PipeInputs, BALLOTS_FILE};
 use crate::pipes::Pipe;
-use num_bigint::BigUint;
 use sequent_core::ballot::Contest;
-use sequent_core::ballot_codec::multi_ballot::{BallotChoices, DecodedBallotChoices};
+use sequent_core::ballot_codec::get_decoder;
+use sequent_core::ballot_codec::multi_ballot::DecodedBallotChoices;
 use sequent_core::plaintext::{
     map_decoded_ballot_choices_to_decoded_contests, DecodedVoteChoice, DecodedVoteContest,
 };
@@ -18,7 +18,6 @@ use std::fs::{self, File};
 use std::io::BufRead;
 use std::path::Path;
 
-use std::str::FromStr;
 use tracing::instrument;
 
 use crate::pipes::pipe_name::{PipeName, PipeNameOutputDir};
@@ -42,6 +41,7 @@ impl DecodeMCBallots {
     fn decode_ballots(
         path: &Path,
         contests: &Vec<Contest>,
+        codec_version: u8,
         serial_number_counter: &mut u32,
     ) -> Result<Vec<DecodedBallotChoices>> {
         let file = fs::File::open(path).map_err(|e| Error::FileAccess(path.to_path_buf(), e))?;
@@ -51,23 +51,16 @@ impl DecodeMCBallots {
         for line in reader.lines() {
             let line = line?;
 
-            let plaintext = BigUint::from_str(&line);
+            let Some((codec_version, plaintext)) = parse_ballot_line(&line, codec_version)? else {
+                continue;
+            };
+            // Ballots cast before the election was republished may have been
+            // encoded with a different codec version than the ballot style's.
+            let decoder = get_decoder(codec_version).map_err(Error::UnexpectedError)?;
 
-            if let Err(error) = &plaintext {
-                if error.to_string() == "cannot parse integer from empty string" {
-                    continue;
-                }
-            }
-
-            let plaintext =
-                plaintext.map_err(|_| Error::UnexpectedError("Wrong ballot format".into()))?;
-
-            let decoded = BallotChoices::decode_from_bigint(
-                &plaintext,
-                contests,
-                Some(serial_number_counter),
-            )
-            .map_err(|_| Error::UnexpectedError("Wrong ballot format".into()))?;
+            let decoded = decoder
+                .decode_multi_contest_bigint(contests, &plaintext, Some(serial_number_counter))
+                .map_err(|_| Error::UnexpectedError("Wrong ballot format".into()))?;
 
             decoded_ballots.push(decoded);
         }
@@ -127,6 +120,7 @@ impl Pipe for DecodeMCBallots {
                 let res = Self::decode_ballots(
                     path_ballots.as_path(),
                     &contests,
+                    election_input.get_codec_version(&area_id),
                     &mut serial_number_counter,
                 );
 
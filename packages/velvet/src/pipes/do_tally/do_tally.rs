@@ -26,6 +26,7 @@ use sequent_core::{
     services::area_tree::TreeNode,
 };
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::cmp;
 use std::sync::Arc;
@@ -41,6 +42,32 @@ pub const OUTPUT_CONTEST_RESULT_FILE: &str = "contest_result.json";
 pub const OUTPUT_CONTEST_RESULT_AREA_CHILDREN_AGGREGATE_FOLDER: &str = "aggregate";
 pub const INPUT_TALLY_SHEET_FILE: &str = "tally-sheet.json";
 pub const OUTPUT_BREAKDOWNS_FOLDER: &str = "breakdowns";
+pub const OUTPUT_BREAKDOWN_TALLY_SHEETS_FILE: &str = "tally_sheets.json";
+
+/// Approved tally sheet entry aggregated into a breakdown, so that the
+/// breakdown can be traced back to the entered tally sheets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakdownTallySheet {
+    pub tally_sheet_id: String,
+    pub version: Option<i32>,
+    pub created_by_user_id: String,
+    pub approved_at: Option<String>,
+    pub approved_by_user_id: Option<String>,
+}
+
+impl From<&TallySheet> for BreakdownTallySheet {
+    fn from(tally_sheet: &TallySheet) -> Self {
+        BreakdownTallySheet {
+            tally_sheet_id: tally_sheet.id.clone(),
+            version: tally_sheet.version,
+            created_by_user_id: tally_sheet.created_by_user_id.clone(),
+            approved_at: tally_sheet
+                .approved_at
+                .map(|approved_at| approved_at.to_rfc3339()),
+            approved_by_user_id: tally_sheet.approved_by_user_id.clone(),
+        }
+    }
+}
 
 pub struct DoTally {
     pub pipe_inputs: PipeInputs,
@@ -76,25 +103,32 @@ impl DoTally {
         base_file_path: &PathBuf,
     ) -> Result<()> {
         let base_breakdown_path = base_file_path.join(OUTPUT_BREAKDOWNS_FOLDER);
-        let mut breakdown_map: HashMap<VotingChannel, ContestResult> = HashMap::new();
+        let mut breakdown_map: HashMap<VotingChannel, (ContestResult, Vec<BreakdownTallySheet>)> =
+            HashMap::new();
 
         for (contest_result, tally_sheet) in tally_sheet_results {
             let channel: VotingChannel = tally_sheet.channel.clone().into();
 
             breakdown_map
                 .entry(channel)
-                .and_modify(|current_result| {
+                .and_modify(|(current_result, tally_sheets)| {
                     current_result.aggregate(&contest_result, true);
+                    tally_sheets.push(tally_sheet.into());
                 })
-                .or_insert_with(|| contest_result.clone());
+                .or_insert_with(|| (contest_result.clone(), vec![tally_sheet.into()]));
         }
 
-        for (channel, contest_result) in breakdown_map {
+        for (channel, (contest_result, tally_sheets)) in breakdown_map {
             let breakdown_folder_path = base_breakdown_path.join(&channel.to_string());
             fs::create_dir_all(&breakdown_folder_path)?;
             let breakdown_file_path = breakdown_folder_path.join(OUTPUT_CONTEST_RESULT_FILE);
             let contest_result_file = fs::File::create(&breakdown_file_path)?;
             serde_json::to_writer(contest_result_file, &contest_result)?;
+
+            let tally_sheets_file_path =
+                breakdown_folder_path.join(OUTPUT_BREAKDOWN_TALLY_SHEETS_FILE);
+            let tally_sheets_file = fs::File::create(&tally_sheets_file_path)?;
+            serde_json::to_writer_pretty(tally_sheets_file, &tally_sheets)?;
         }
 
         Ok(())
@@ -455,7 +489,7 @@ impl Pipe for DoTally {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct InvalidVotes {
     pub explicit: u64,
     pub implicit: u64,
@@ -472,7 +506,7 @@ impl InvalidVotes {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ExtendedMetricsContest {
     // Voted more candidates than the allowed amount per contest
     pub over_votes: u64,
@@ -504,14 +538,14 @@ impl ExtendedMetricsContest {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ExtendedMetricsElection {
     // Number of valid ballots processed by the ACM without any
     // single mark on all contests.
     pub abstentions: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ContestResult {
     pub contest: Contest,
     pub census: u64,
@@ -632,7 +666,7 @@ impl ContestResult {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CandidateResult {
     pub candidate: Candidate,
     pub percentage_votes: f64,
@@ -5,6 +5,7 @@
 use std::path::PathBuf;
 use std::{cmp::Ordering, fs};
 
+use schemars::JsonSchema;
 use sequent_core::ballot::Candidate;
 use sequent_core::util::path::list_subfolders;
 use serde::Serialize;
@@ -226,7 +227,7 @@ impl Pipe for MarkWinners {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, serde::Deserialize, JsonSchema)]
 pub struct WinnerResult {
     pub candidate: Candidate,
     pub total_count: u64,
@@ -7,8 +7,10 @@ use crate::{
     cli::{state::Stage, CliRun},
     utils::parse_file,
 };
+use num_bigint::BigUint;
 use sequent_core::{
     ballot::{BallotStyle, Contest, ReportDates, StringifiedPeriodDates},
+    ballot_codec::LEGACY_CODEC_VERSION,
     services::area_tree::TreeNodeArea,
     util::path::get_folder_name,
 };
@@ -17,6 +19,7 @@ use std::{
     collections::HashMap,
     fs,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 use tracing::{info, instrument};
 use uuid::Uuid;
@@ -35,6 +38,9 @@ pub const ELECTION_CONFIG_FILE: &str = "election-config.json";
 pub const CONTEST_CONFIG_FILE: &str = "contest-config.json";
 pub const AREA_CONFIG_FILE: &str = "area-config.json";
 pub const BALLOTS_FILE: &str = "ballots.csv";
+/// Separates the codec version from the plaintext in a line of the ballots
+/// file, as in `1:123456`.
+pub const BALLOT_CODEC_VERSION_SEPARATOR: char = ':';
 const UUID_LEN: usize = 36;
 
 #[derive(Debug)]
@@ -329,6 +335,18 @@ impl InputElectionConfig {
 
         ret
     }
+
+    /// Codec version of the ballots cast in an area, taken from the area's
+    /// ballot style. Falls back to the legacy version when the election has
+    /// no ballot style for the area.
+    pub fn get_codec_version(&self, area_id: &Uuid) -> u8 {
+        let area_id = area_id.to_string();
+        self.ballot_styles
+            .iter()
+            .find(|ballot_style| ballot_style.area_id == area_id)
+            .map(|ballot_style| ballot_style.get_codec_version())
+            .unwrap_or(LEGACY_CODEC_VERSION)
+    }
 }
 
 #[derive(Debug)]
@@ -391,3 +409,55 @@ impl Into<TreeNodeArea> for &AreaConfig {
         }
     }
 }
+
+/// Formats a line of the ballots file, tagging the plaintext with the codec
+/// version it was encoded with.
+pub fn format_ballot_line(codec_version: u8, plaintext: &BigUint) -> String {
+    format!(
+        "{codec_version}{BALLOT_CODEC_VERSION_SEPARATOR}{}",
+        plaintext.to_str_radix(10)
+    )
+}
+
+/// Parses a line of the ballots file into its codec version and plaintext.
+/// Untagged lines were encoded with `default_codec_version`, the one of the
+/// ballot style. Returns `None` for empty lines.
+pub fn parse_ballot_line(line: &str, default_codec_version: u8) -> Result<Option<(u8, BigUint)>> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let wrong_format = || Error::UnexpectedError("Wrong ballot format".into());
+    let (codec_version, plaintext) = match line.split_once(BALLOT_CODEC_VERSION_SEPARATOR) {
+        Some((codec_version, plaintext)) => (
+            u8::from_str(codec_version).map_err(|_| wrong_format())?,
+            plaintext,
+        ),
+        None => (default_codec_version, line),
+    };
+    let plaintext = BigUint::from_str(plaintext).map_err(|_| wrong_format())?;
+
+    Ok(Some((codec_version, plaintext)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ballot_line_roundtrip() {
+        let plaintext = BigUint::from(123456u32);
+        let line = format_ballot_line(0, &plaintext);
+        assert_eq!(line, "0:123456");
+        assert_eq!(
+            parse_ballot_line(&line, 1).unwrap(),
+            Some((0, plaintext.clone()))
+        );
+        assert_eq!(
+            parse_ballot_line("123456", 1).unwrap(),
+            Some((1, plaintext))
+        );
+        assert_eq!(parse_ballot_line("", 1).unwrap(), None);
+        assert!(parse_ballot_line("a:123456", 1).is_err());
+    }
+}
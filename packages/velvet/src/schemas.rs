@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use crate::pipes::do_tally::ContestResult;
+use crate::pipes::mark_winners::WinnerResult;
+use schemars::schema_for;
+use sequent_core::schemas::PublicSchema;
+
+pub const CONTEST_RESULT_SCHEMA: &str = "contest_result";
+pub const WINNERS_SCHEMA: &str = "winners";
+
+/// Schemas of the velvet output formats (`contest_result.json` and
+/// `winners.json`) together with the sequent-core public formats velvet
+/// consumes, like ballot styles and tally sheets.
+pub fn public_schemas() -> Vec<PublicSchema> {
+    let mut schemas = sequent_core::schemas::public_schemas();
+    schemas.push(PublicSchema::new(
+        CONTEST_RESULT_SCHEMA,
+        schema_for!(ContestResult),
+    ));
+    schemas.push(PublicSchema::new(
+        WINNERS_SCHEMA,
+        schema_for!(Vec<WinnerResult>),
+    ));
+    schemas
+}
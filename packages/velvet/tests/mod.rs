@@ -3,3 +3,4 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 pub mod pipes;
+pub mod scenarios;
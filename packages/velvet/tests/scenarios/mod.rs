@@ -0,0 +1,706 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Golden result regression suite. Each subfolder of `tests/scenarios` with a
+//! `scenario.json` file is a scenario: the ballot styles, the votes and tally
+//! sheets to count, and the expected fields of the results. Every scenario is
+//! run through the full velvet pipeline and the outputs are compared with the
+//! expected values, reporting each mismatching field.
+//!
+//! Run a single scenario with `VELVET_SCENARIO=<folder name> cargo test`.
+
+use anyhow::{anyhow, Context, Result};
+use sequent_core::ballot::{BallotStyle, Contest, ContestEncryptionPolicy};
+use sequent_core::ballot_codec::multi_ballot::{BallotChoices, ContestChoices};
+use sequent_core::ballot_codec::BigUIntCodec;
+use sequent_core::plaintext::{DecodedVoteChoice, DecodedVoteContest};
+use sequent_core::services::area_tree::TreeNodeArea;
+use sequent_core::types::hasura::core::TallySheet;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use velvet::cli::state::State;
+use velvet::cli::CliRun;
+use velvet::fixtures::TestFixture;
+use velvet::pipes::do_tally::{INPUT_TALLY_SHEET_FILE, OUTPUT_CONTEST_RESULT_FILE};
+use velvet::pipes::generate_reports::OUTPUT_JSON;
+use velvet::pipes::mark_winners::OUTPUT_WINNERS;
+use velvet::pipes::pipe_inputs::{
+    AreaConfig, ElectionConfig, PipeInputs, AREA_CONFIG_FILE, BALLOTS_FILE, CONTEST_CONFIG_FILE,
+    DEFAULT_DIR_TALLY_SHEETS, ELECTION_CONFIG_FILE,
+};
+use velvet::pipes::pipe_name::PipeNameOutputDir;
+
+const SCENARIOS_DIR: &str = "tests/scenarios";
+const SCENARIO_FILE: &str = "scenario.json";
+const SCENARIO_ENV_VAR: &str = "VELVET_SCENARIO";
+
+#[derive(Deserialize, Debug)]
+struct Scenario {
+    #[serde(default)]
+    description: String,
+    ballot_styles: Vec<BallotStyle>,
+    /// Census and parent of the areas. Areas not listed have no census.
+    #[serde(default)]
+    areas: Vec<ScenarioArea>,
+    #[serde(default)]
+    votes: Vec<ScenarioVote>,
+    #[serde(default)]
+    tally_sheets: Vec<TallySheet>,
+    expected: Vec<ScenarioExpectation>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScenarioArea {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    census: u64,
+    #[serde(default)]
+    auditable_votes: u64,
+    parent_id: Option<String>,
+}
+
+/// A ballot cast `count` times in an area. With multiple contests per ballot
+/// it must include all the contests of the ballot style.
+#[derive(Deserialize, Debug)]
+struct ScenarioVote {
+    area_id: String,
+    #[serde(default = "default_vote_count")]
+    count: u64,
+    #[serde(default)]
+    is_explicit_invalid: bool,
+    contests: Vec<ScenarioContestVote>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScenarioContestVote {
+    contest_id: String,
+    #[serde(default)]
+    is_explicit_invalid: bool,
+    /// Selected candidates, the rest of the candidates are left unselected.
+    #[serde(default)]
+    choices: Vec<DecodedVoteChoice>,
+}
+
+/// Expected fields of the results of a contest, for the whole contest or
+/// for an area and optionally one of its tally sheets. Only the fields
+/// present are compared.
+#[derive(Deserialize, Debug)]
+struct ScenarioExpectation {
+    contest_id: String,
+    area_id: Option<String>,
+    tally_sheet_id: Option<String>,
+    contest_result: Option<Value>,
+    winners: Option<Value>,
+    report: Option<Value>,
+}
+
+fn default_vote_count() -> u64 {
+    1
+}
+
+#[derive(Debug, PartialEq)]
+struct Mismatch {
+    path: String,
+    expected: String,
+    actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.path, self.expected, self.actual
+        )
+    }
+}
+
+/// Compares the expected value with the actual one. Objects only compare the
+/// expected keys, arrays must have the same length and arrays of objects are
+/// compared regardless of the order of their elements.
+fn diff_json(path: &str, expected: &Value, actual: &Value) -> Vec<Mismatch> {
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => expected_map
+            .iter()
+            .flat_map(|(key, expected_value)| {
+                let key_path = format!("{path}.{key}");
+                match actual_map.get(key) {
+                    Some(actual_value) => diff_json(&key_path, expected_value, actual_value),
+                    None => vec![Mismatch {
+                        path: key_path,
+                        expected: expected_value.to_string(),
+                        actual: "nothing".to_string(),
+                    }],
+                }
+            })
+            .collect(),
+        (Value::Array(expected_list), Value::Array(actual_list)) => {
+            if expected_list.len() != actual_list.len() {
+                return vec![Mismatch {
+                    path: path.to_string(),
+                    expected: format!("{} elements", expected_list.len()),
+                    actual: format!("{} elements", actual_list.len()),
+                }];
+            }
+            if expected_list.iter().all(Value::is_object) {
+                diff_unordered(path, expected_list, actual_list)
+            } else {
+                expected_list
+                    .iter()
+                    .zip(actual_list)
+                    .enumerate()
+                    .flat_map(|(index, (expected_value, actual_value))| {
+                        diff_json(&format!("{path}[{index}]"), expected_value, actual_value)
+                    })
+                    .collect()
+            }
+        }
+        (Value::Number(expected_number), Value::Number(actual_number))
+            if expected_number.as_f64().is_some() && actual_number.as_f64().is_some() =>
+        {
+            let expected_float = expected_number.as_f64().unwrap_or_default();
+            let actual_float = actual_number.as_f64().unwrap_or_default();
+            if (expected_float - actual_float).abs() <= 1e-9 * expected_float.abs().max(1.0) {
+                vec![]
+            } else {
+                vec![Mismatch {
+                    path: path.to_string(),
+                    expected: expected.to_string(),
+                    actual: actual.to_string(),
+                }]
+            }
+        }
+        _ if expected == actual => vec![],
+        _ => vec![Mismatch {
+            path: path.to_string(),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        }],
+    }
+}
+
+/// Matches each expected element with a different actual element. When an
+/// element has no match, the mismatches with the closest one are reported.
+fn diff_unordered(path: &str, expected_list: &[Value], actual_list: &[Value]) -> Vec<Mismatch> {
+    let mut used: HashSet<usize> = HashSet::new();
+    let mut mismatches = vec![];
+    for (index, expected_value) in expected_list.iter().enumerate() {
+        let element_path = format!("{path}[{index}]");
+        let closest = actual_list
+            .iter()
+            .enumerate()
+            .filter(|(actual_index, _)| !used.contains(actual_index))
+            .map(|(actual_index, actual_value)| {
+                (
+                    actual_index,
+                    diff_json(&element_path, expected_value, actual_value),
+                )
+            })
+            .min_by_key(|(_, element_mismatches)| element_mismatches.len());
+        if let Some((actual_index, element_mismatches)) = closest {
+            used.insert(actual_index);
+            mismatches.extend(element_mismatches);
+        }
+    }
+    mismatches
+}
+
+impl Scenario {
+    fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(SCENARIO_FILE);
+        let data = fs::read_to_string(&path).with_context(|| format!("Error reading {path:?}"))?;
+        serde_json::from_str(&data).with_context(|| format!("Error parsing {path:?}"))
+    }
+
+    fn is_multi_contest(&self) -> bool {
+        self.ballot_styles.iter().any(|ballot_style| {
+            ballot_style
+                .election_event_presentation
+                .as_ref()
+                .and_then(|presentation| presentation.contest_encryption_policy.clone())
+                == Some(ContestEncryptionPolicy::MULTIPLE_CONTESTS)
+        })
+    }
+
+    fn find_contest(&self, contest_id: &str) -> Result<(&BallotStyle, &Contest)> {
+        self.ballot_styles
+            .iter()
+            .find_map(|ballot_style| {
+                ballot_style
+                    .contests
+                    .iter()
+                    .find(|contest| contest.id == contest_id)
+                    .map(|contest| (ballot_style, contest))
+            })
+            .ok_or(anyhow!(
+                "Contest {contest_id} not found in the ballot styles"
+            ))
+    }
+
+    fn find_ballot_style(&self, area_id: &str, contest_id: &str) -> Result<&BallotStyle> {
+        self.ballot_styles
+            .iter()
+            .find(|ballot_style| {
+                ballot_style.area_id == area_id
+                    && ballot_style
+                        .contests
+                        .iter()
+                        .any(|contest| contest.id == contest_id)
+            })
+            .ok_or(anyhow!(
+                "No ballot style for area {area_id} and contest {contest_id}"
+            ))
+    }
+
+    /// Writes the configs, ballots and tally sheets in the velvet input
+    /// folder layout.
+    fn write_inputs(&self, fixture: &TestFixture) -> Result<()> {
+        let mut elections: BTreeMap<&str, Vec<&BallotStyle>> = BTreeMap::new();
+        for ballot_style in &self.ballot_styles {
+            elections
+                .entry(ballot_style.election_id.as_str())
+                .or_default()
+                .push(ballot_style);
+        }
+
+        for (election_id, ballot_styles) in elections {
+            let election_uuid = parse_uuid(election_id)?;
+            let tenant_id = parse_uuid(&ballot_styles[0].tenant_id)?;
+            let election_event_id = parse_uuid(&ballot_styles[0].election_event_id)?;
+
+            let mut area_ids: Vec<&str> = vec![];
+            for area_id in ballot_styles
+                .iter()
+                .map(|ballot_style| ballot_style.area_id.as_str())
+                .chain(self.areas.iter().map(|area| area.id.as_str()))
+            {
+                if !area_ids.contains(&area_id) {
+                    area_ids.push(area_id);
+                }
+            }
+
+            let election = ElectionConfig {
+                id: election_uuid,
+                name: "Scenario election".to_string(),
+                alias: "Scenario election".to_string(),
+                description: "".to_string(),
+                annotations: Default::default(),
+                election_event_annotations: Default::default(),
+                tenant_id,
+                election_event_id,
+                census: 0,
+                total_votes: 0,
+                ballot_styles: ballot_styles.iter().map(|style| (*style).clone()).collect(),
+                areas: area_ids
+                    .iter()
+                    .map(|area_id| TreeNodeArea {
+                        id: area_id.to_string(),
+                        tenant_id: tenant_id.to_string(),
+                        annotations: Default::default(),
+                        election_event_id: election_event_id.to_string(),
+                        parent_id: self
+                            .get_area(area_id)
+                            .and_then(|area| area.parent_id.clone()),
+                    })
+                    .collect(),
+                dates: None,
+            };
+            let election_dir =
+                PipeInputs::build_path(&fixture.input_dir_configs, &election_uuid, None, None);
+            write_json(&election_dir.join(ELECTION_CONFIG_FILE), &election)?;
+
+            for ballot_style in ballot_styles {
+                let area_uuid = parse_uuid(&ballot_style.area_id)?;
+                let area = self.get_area(&ballot_style.area_id);
+                let area_config = AreaConfig {
+                    id: area_uuid,
+                    name: area.map(|area| area.name.clone()).unwrap_or_default(),
+                    tenant_id,
+                    election_event_id,
+                    election_id: election_uuid,
+                    census: area.map(|area| area.census).unwrap_or_default(),
+                    parent_id: area
+                        .and_then(|area| area.parent_id.as_deref())
+                        .map(parse_uuid)
+                        .transpose()?,
+                    auditable_votes: area.map(|area| area.auditable_votes).unwrap_or_default(),
+                };
+                for contest in &ballot_style.contests {
+                    let contest_uuid = parse_uuid(&contest.id)?;
+                    let contest_dir = PipeInputs::build_path(
+                        &fixture.input_dir_configs,
+                        &election_uuid,
+                        Some(&contest_uuid),
+                        None,
+                    );
+                    write_json(&contest_dir.join(CONTEST_CONFIG_FILE), contest)?;
+                    write_json(
+                        &contest_dir
+                            .join(format!("area__{area_uuid}"))
+                            .join(AREA_CONFIG_FILE),
+                        &area_config,
+                    )?;
+                    fs::create_dir_all(PipeInputs::build_path(
+                        &fixture.input_dir_ballots,
+                        &election_uuid,
+                        Some(&contest_uuid),
+                        Some(&area_uuid),
+                    ))?;
+                }
+            }
+        }
+
+        self.write_ballots(fixture)?;
+        self.write_tally_sheets(fixture)
+    }
+
+    fn get_area(&self, area_id: &str) -> Option<&ScenarioArea> {
+        self.areas.iter().find(|area| area.id == area_id)
+    }
+
+    fn write_ballots(&self, fixture: &TestFixture) -> Result<()> {
+        let is_multi_contest = self.is_multi_contest();
+        let mut ballot_files: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+        for vote in &self.votes {
+            let first_contest_id = vote
+                .contests
+                .first()
+                .map(|contest_vote| contest_vote.contest_id.as_str())
+                .ok_or(anyhow!("Vote in area {} without contests", vote.area_id))?;
+            let ballot_style = self.find_ballot_style(&vote.area_id, first_contest_id)?;
+            let election_uuid = parse_uuid(&ballot_style.election_id)?;
+            let area_uuid = parse_uuid(&vote.area_id)?;
+
+            let decoded_contests = vote
+                .contests
+                .iter()
+                .map(|contest_vote| {
+                    let contest = ballot_style
+                        .contests
+                        .iter()
+                        .find(|contest| contest.id == contest_vote.contest_id)
+                        .ok_or(anyhow!(
+                            "Contest {} not in the ballot style of area {}",
+                            contest_vote.contest_id,
+                            vote.area_id
+                        ))?;
+                    let decoded_contest =
+                        get_decoded_contest(contest, contest_vote, vote.is_explicit_invalid)?;
+                    Ok((contest, decoded_contest))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let lines: Vec<(PathBuf, String)> = if is_multi_contest {
+                let ballot = BallotChoices::new(
+                    vote.is_explicit_invalid,
+                    decoded_contests
+                        .iter()
+                        .map(|(_, decoded_contest)| {
+                            ContestChoices::from_decoded_vote_contest(decoded_contest)
+                        })
+                        .collect(),
+                );
+                let plaintext = ballot
+                    .encode_to_bigint(ballot_style)
+                    .map_err(|err| anyhow!("Error encoding ballot: {err}"))?;
+                let path = PipeInputs::mcballots_path(
+                    &fixture.input_dir_ballots,
+                    &election_uuid,
+                    &area_uuid,
+                );
+                vec![(path.join(BALLOTS_FILE), plaintext.to_string())]
+            } else {
+                decoded_contests
+                    .iter()
+                    .map(|(contest, decoded_contest)| {
+                        let plaintext = contest
+                            .encode_plaintext_contest_bigint(decoded_contest)
+                            .map_err(|err| anyhow!("Error encoding ballot: {err}"))?;
+                        let path = PipeInputs::build_path(
+                            &fixture.input_dir_ballots,
+                            &election_uuid,
+                            Some(&parse_uuid(&contest.id)?),
+                            Some(&area_uuid),
+                        );
+                        Ok((path.join(BALLOTS_FILE), plaintext.to_string()))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            };
+
+            for (path, line) in lines {
+                let file_lines = ballot_files.entry(path).or_default();
+                (0..vote.count).for_each(|_| file_lines.push(line.clone()));
+            }
+        }
+
+        for (path, lines) in ballot_files {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, lines.join("\n") + "\n")?;
+        }
+        Ok(())
+    }
+
+    fn write_tally_sheets(&self, fixture: &TestFixture) -> Result<()> {
+        let tally_sheets_dir = fixture
+            .root_dir
+            .join("tests")
+            .join("input-dir")
+            .join(DEFAULT_DIR_TALLY_SHEETS);
+        for tally_sheet in &self.tally_sheets {
+            let area_dir = PipeInputs::build_path(
+                &tally_sheets_dir,
+                &parse_uuid(&tally_sheet.election_id)?,
+                Some(&parse_uuid(&tally_sheet.contest_id)?),
+                Some(&parse_uuid(&tally_sheet.area_id)?),
+            );
+            let path = PipeInputs::build_tally_sheet_path(&area_dir, &tally_sheet.id)
+                .join(INPUT_TALLY_SHEET_FILE);
+            write_json(&path, tally_sheet)?;
+        }
+        Ok(())
+    }
+
+    fn check_expectations(&self, output_dir: &Path) -> Result<Vec<Mismatch>> {
+        let mut mismatches = vec![];
+        for expectation in &self.expected {
+            let (ballot_style, _) = self.find_contest(&expectation.contest_id)?;
+            let election_uuid = parse_uuid(&ballot_style.election_id)?;
+            let contest_uuid = parse_uuid(&expectation.contest_id)?;
+            let area_uuid = expectation.area_id.as_deref().map(parse_uuid).transpose()?;
+            let mut label = format!("contest {}", expectation.contest_id);
+            if let Some(area_id) = &expectation.area_id {
+                label.push_str(&format!(", area {area_id}"));
+            }
+            if let Some(tally_sheet_id) = &expectation.tally_sheet_id {
+                label.push_str(&format!(", tally sheet {tally_sheet_id}"));
+            }
+
+            let outputs = [
+                (
+                    &expectation.contest_result,
+                    PipeNameOutputDir::DoTally,
+                    OUTPUT_CONTEST_RESULT_FILE,
+                ),
+                (
+                    &expectation.winners,
+                    PipeNameOutputDir::MarkWinners,
+                    OUTPUT_WINNERS,
+                ),
+                (
+                    &expectation.report,
+                    PipeNameOutputDir::GenerateReports,
+                    OUTPUT_JSON,
+                ),
+            ];
+            for (expected, pipe_output_dir, file_name) in outputs {
+                let Some(expected) = expected else {
+                    continue;
+                };
+                let mut dir = PipeInputs::build_path(
+                    &output_dir.join(pipe_output_dir.as_ref()),
+                    &election_uuid,
+                    Some(&contest_uuid),
+                    area_uuid.as_ref(),
+                );
+                if let Some(tally_sheet_id) = &expectation.tally_sheet_id {
+                    dir = PipeInputs::build_tally_sheet_path(&dir, tally_sheet_id);
+                }
+                let path = dir.join(file_name);
+                let root_path = format!("{label}: {file_name}");
+                match fs::read_to_string(&path) {
+                    Ok(data) => {
+                        let actual: Value = serde_json::from_str(&data)
+                            .with_context(|| format!("Error parsing {path:?}"))?;
+                        mismatches.extend(diff_json(&root_path, expected, &actual));
+                    }
+                    Err(_) => mismatches.push(Mismatch {
+                        path: root_path,
+                        expected: "an output file".to_string(),
+                        actual: "no file".to_string(),
+                    }),
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid> {
+    Uuid::parse_str(id).with_context(|| format!("Invalid id {id}, ids must be UUIDs"))
+}
+
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string(value)?)?;
+    Ok(())
+}
+
+/// Lists every candidate of the contest, unselected unless the vote
+/// selects it.
+fn get_decoded_contest(
+    contest: &Contest,
+    contest_vote: &ScenarioContestVote,
+    is_ballot_invalid: bool,
+) -> Result<DecodedVoteContest> {
+    if let Some(choice) = contest_vote.choices.iter().find(|choice| {
+        !contest
+            .candidates
+            .iter()
+            .any(|candidate| candidate.id == choice.id)
+    }) {
+        return Err(anyhow!(
+            "Candidate {} not found in contest {}",
+            choice.id,
+            contest.id
+        ));
+    }
+    Ok(DecodedVoteContest {
+        contest_id: contest.id.clone(),
+        is_explicit_invalid: is_ballot_invalid || contest_vote.is_explicit_invalid,
+        invalid_errors: vec![],
+        invalid_alerts: vec![],
+        choices: contest
+            .candidates
+            .iter()
+            .map(|candidate| {
+                contest_vote
+                    .choices
+                    .iter()
+                    .find(|choice| choice.id == candidate.id)
+                    .cloned()
+                    .unwrap_or(DecodedVoteChoice {
+                        id: candidate.id.clone(),
+                        selected: -1,
+                        write_in_text: None,
+                    })
+            })
+            .collect(),
+    })
+}
+
+fn run_scenario(dir: &Path) -> Result<Vec<Mismatch>> {
+    let scenario = Scenario::load(dir)?;
+    let fixture = if scenario.is_multi_contest() {
+        TestFixture::new_mc()?
+    } else {
+        TestFixture::new()?
+    };
+    scenario.write_inputs(&fixture)?;
+
+    let cli = CliRun {
+        stage: "main".to_string(),
+        pipe_id: "decode-ballots".to_string(),
+        config: fixture.config_path.clone(),
+        input_dir: fixture.root_dir.join("tests").join("input-dir"),
+        output_dir: fixture.root_dir.join("tests").join("output-dir"),
+    };
+    let config = cli.validate()?;
+    let mut state = State::new(&cli, &config)?;
+    while state.get_next().is_some() {
+        state.exec_next()?;
+    }
+
+    scenario.check_expectations(&cli.output_dir)
+}
+
+fn list_scenarios() -> Result<Vec<PathBuf>> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(SCENARIOS_DIR);
+    let filter = env::var(SCENARIO_ENV_VAR).ok();
+    let mut dirs: Vec<PathBuf> = fs::read_dir(&root)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join(SCENARIO_FILE).is_file())
+        .filter(|path| match &filter {
+            Some(filter) => path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy() == *filter),
+            None => true,
+        })
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[test]
+fn test_scenarios() -> Result<()> {
+    let dirs = list_scenarios()?;
+    assert!(!dirs.is_empty(), "No scenarios found in {SCENARIOS_DIR}");
+
+    let mut failures = vec![];
+    for dir in &dirs {
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match run_scenario(dir) {
+            Ok(mismatches) if mismatches.is_empty() => {}
+            Ok(mismatches) => {
+                let description = Scenario::load(dir)
+                    .map(|scenario| scenario.description)
+                    .unwrap_or_default();
+                let lines: Vec<String> = mismatches
+                    .iter()
+                    .map(|mismatch| format!("  {mismatch}"))
+                    .collect();
+                failures.push(format!("{name} ({description}):\n{}", lines.join("\n")));
+            }
+            Err(err) => failures.push(format!("{name}: {err:?}")),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} scenarios failed:\n{}",
+        failures.len(),
+        dirs.len(),
+        failures.join("\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_diff_json_only_compares_expected_fields() {
+    let expected = serde_json::json!({"total_votes": 3, "invalid_votes": {"explicit": 1}});
+    let actual = serde_json::json!({
+        "total_votes": 3.0,
+        "census": 10,
+        "invalid_votes": {"explicit": 2, "implicit": 0}
+    });
+    assert_eq!(
+        diff_json("result", &expected, &actual),
+        vec![Mismatch {
+            path: "result.invalid_votes.explicit".to_string(),
+            expected: "1".to_string(),
+            actual: "2".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_diff_json_unordered_objects() {
+    let expected = serde_json::json!([{"id": "b", "count": 2}, {"id": "a", "count": 1}]);
+    let actual = serde_json::json!([{"id": "a", "count": 1}, {"id": "b", "count": 3}]);
+    assert_eq!(
+        diff_json("winners", &expected, &actual),
+        vec![Mismatch {
+            path: "winners[0].count".to_string(),
+            expected: "2".to_string(),
+            actual: "3".to_string(),
+        }]
+    );
+
+    let shorter = serde_json::json!([{"id": "a", "count": 1}]);
+    assert_eq!(diff_json("winners", &shorter, &actual).len(), 1);
+}
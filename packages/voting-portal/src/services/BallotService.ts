@@ -14,9 +14,11 @@ import {
     decodeAuditableBallot,
     decodeAuditableMultiBallot,
     checkIsBlank,
+    checkRankedChoices,
     signHashableBallot,
     signHashableMultiBallot,
     IDecodedVoteContest,
+    IInvalidPlaintextError,
     IBallotStyle,
     IAuditableBallot,
     IAuditableSingleBallot,
@@ -61,6 +63,7 @@ export interface IBallotService {
         auditableBallot: IAuditableMultiBallot
     ) => Array<IDecodedVoteContest> | null
     checkIsBlank: (contest: IDecodedVoteContest) => boolean | null
+    checkRankedChoices: (contest: IDecodedVoteContest) => Array<IInvalidPlaintextError> | null
     signHashableBallot: (
         ballotId: string,
         electionId: string,
@@ -86,6 +89,7 @@ export const provideBallotService = (): IBallotService => ({
     decodeAuditableBallot,
     decodeAuditableMultiBallot,
     checkIsBlank,
+    checkRankedChoices,
     signHashableBallot,
     signHashableMultiBallot,
 })
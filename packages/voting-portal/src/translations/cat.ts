@@ -321,6 +321,9 @@ const catalanTranslation: TranslationType = {
                 overVoteDisabled:
                     "Màxim assolit: Has seleccionat el màxim de {{numSelected}} opcions. Per canviar la teva selecció, si us plau, desmarca primer una altra opció.",
                 blankVote: "Vot en Blanc: 0 opcions seleccionades",
+                duplicateRank: "Un candidat ha estat classificat més d'una vegada",
+                skippedRank: "S'ha saltat la posició {{rank}}",
+                overvoteAtRank: "La posició {{rank}} s'ha assignat a {{numSelected}} opcions",
             },
             explicit: {
                 notAllowed: "Vot marcat explícitament com a invàlid però la pregunta no ho permet",
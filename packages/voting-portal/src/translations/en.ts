@@ -316,6 +316,9 @@ const englishTranslation = {
                 overVoteDisabled:
                     "Maximum reached: You have selected the maximum {{numSelected}} choices. To change your selection, please deselect another option first.",
                 blankVote: "Blank Vote: 0 choices selected",
+                duplicateRank: "A candidate has been ranked more than once",
+                skippedRank: "Rank {{rank}} has been skipped",
+                overvoteAtRank: "Rank {{rank}} has been given to {{numSelected}} choices",
             },
             explicit: {
                 notAllowed: "Ballot marked explicitly invalid but question doesn't allow it",
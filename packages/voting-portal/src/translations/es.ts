@@ -321,6 +321,9 @@ const spanishTranslation: TranslationType = {
                 overVoteDisabled:
                     "Máximo alcanzado: Has seleccionado el máximo de {{numSelected}} opciones. Para cambiar tu selección, por favor, desmarca primero otra opción.",
                 blankVote: "Voto en Blanco: 0 opciones seleccionadas",
+                duplicateRank: "Un candidato ha sido clasificado más de una vez",
+                skippedRank: "Se ha saltado la posición {{rank}}",
+                overvoteAtRank: "La posición {{rank}} se ha asignado a {{numSelected}} opciones",
             },
             explicit: {
                 notAllowed:
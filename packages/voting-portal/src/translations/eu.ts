@@ -319,6 +319,9 @@ const basqueTranslation: TranslationType = {
                 overVoteDisabled:
                     "Gehienezkora heldu: Gehienezko {{numSelected}} aukera hautatu dituzu. Zure hautaketa aldatzeko, mesedez ezgaitu beste aukera bat lehenik.",
                 blankVote: "Boto Zuria: 0 aukera hautatu",
+                duplicateRank: "Hautagai bat behin baino gehiagotan sailkatu da",
+                skippedRank: "{{rank}}. postua saltatu da",
+                overvoteAtRank: "{{rank}}. postua {{numSelected}} aukerari eman zaie",
             },
             explicit: {
                 notAllowed:
@@ -319,6 +319,9 @@ const frenchTranslation: TranslationType = {
                 overVoteDisabled:
                     "Maximum atteint : Vous avez sélectionné le maximum de {{numSelected}} choix. Pour changer votre sélection, veuillez d'abord désélectionner une autre option.",
                 blankVote: "Vote Blanc: 0 options sélectionnées",
+                duplicateRank: "Un candidat a été classé plus d'une fois",
+                skippedRank: "Le rang {{rank}} a été sauté",
+                overvoteAtRank: "Le rang {{rank}} a été attribué à {{numSelected}} options",
             },
             explicit: {
                 notAllowed:
@@ -319,6 +319,9 @@ const galegoTranslation: TranslationType = {
                 overVoteDisabled:
                     "Máximo alcanzado: Seleccionaches o máximo {{numSelected}} opcións. Para cambiar a selección, deselecciona primeiro outra opción.",
                 blankVote: "Voto en branco: 0 opcións seleccionadas",
+                duplicateRank: "Un candidato foi clasificado máis dunha vez",
+                skippedRank: "Saltouse a posición {{rank}}",
+                overvoteAtRank: "A posición {{rank}} asignouse a {{numSelected}} opcións",
             },
             explicit: {
                 notAllowed:
@@ -319,6 +319,9 @@ const dutchTranslation: TranslationType = {
                 overVoteDisabled:
                     "Maximum bereikt: U heeft het maximum aantal keuzes {{numSelected}} geselecteerd. Om uw selectie te wijzigen, deselecteer eerst een andere optie.",
                 blankVote: "Blanco stem: 0 keuzes geselecteerd",
+                duplicateRank: "Een kandidaat is meer dan één keer gerangschikt",
+                skippedRank: "Rang {{rank}} is overgeslagen",
+                overvoteAtRank: "Rang {{rank}} is aan {{numSelected}} keuzes toegekend",
             },
             explicit: {
                 notAllowed:
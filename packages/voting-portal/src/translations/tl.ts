@@ -319,6 +319,9 @@ const tagalogTranslation: TranslationType = {
                 overVoteDisabled:
                     "Naabot na ang maximum: Napili mo na ang maximum na {{numSelected}} na mga opsyon. Upang baguhin ang iyong pagpili, mangyaring alisin muna ang isa pang opsyon.",
                 blankVote: "Blank Vote: Walang pinili",
+                duplicateRank: "Ang isang kandidato ay nairanggo nang higit sa isang beses",
+                skippedRank: "Nilaktawan ang ranggo {{rank}}",
+                overvoteAtRank: "Ang ranggo {{rank}} ay ibinigay sa {{numSelected}} na pagpipilian",
             },
             explicit: {
                 notAllowed:
@@ -399,6 +399,67 @@ pub async fn get_previous_publication_election(
     Ok(results.get(0).cloned())
 }
 
+/// Last publication published for the election, either for the whole event
+/// or only for that election, other than the given one.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_latest_publication_election(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    election_id: &str,
+    exclude_ballot_publication_id: &str,
+) -> Result<Option<BallotPublication>> {
+    let query = hasura_transaction
+        .prepare(
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                election_event_id,
+                labels,
+                annotations,
+                created_at,
+                deleted_at,
+                created_by_user_id,
+                is_generated,
+                election_ids,
+                published_at,
+                election_id
+            FROM sequent_backend.ballot_publication
+            WHERE election_event_id = $1
+              AND tenant_id = $2
+              AND election_ids @> ARRAY[$3]::uuid[]
+              AND published_at IS NOT NULL
+              AND id <> $4
+            ORDER BY published_at DESC
+            LIMIT 1;
+            "#,
+        )
+        .await?;
+
+    let rows = hasura_transaction
+        .query(
+            &query,
+            &[
+                &Uuid::parse_str(election_event_id)?,
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_id)?,
+                &Uuid::parse_str(exclude_ballot_publication_id)?,
+            ],
+        )
+        .await?;
+
+    let results = rows
+        .into_iter()
+        .map(|row| -> Result<BallotPublication> {
+            row.try_into()
+                .map(|res: BallotPublicationWrapper| -> BallotPublication { res.0 })
+        })
+        .collect::<Result<Vec<BallotPublication>>>()?;
+
+    Ok(results.get(0).cloned())
+}
+
 #[instrument(skip(hasura_transaction), err)]
 pub async fn get_previous_publication(
     hasura_transaction: &Transaction<'_>,
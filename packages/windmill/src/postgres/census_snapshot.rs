@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Transaction;
+use sequent_core::serialization::deserialize_with_path::deserialize_value;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tokio_postgres::row::Row;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Number of eligible voters, as {election_id: {area_id: count}}.
+pub type SnapshotCensus = BTreeMap<String, BTreeMap<String, i64>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CensusSnapshot {
+    pub id: String,
+    pub tenant_id: String,
+    pub election_event_id: String,
+    /// Set when only the census of this election was frozen.
+    pub election_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by_user_id: Option<String>,
+    pub trigger: String,
+    pub document_id: String,
+    pub hash: String,
+    pub total_voters: i64,
+    pub census: SnapshotCensus,
+}
+
+impl CensusSnapshot {
+    /// Eligible voters of the area for the election, if the area was part of
+    /// the election when the snapshot was taken.
+    pub fn get_area_census(&self, election_id: &str, area_id: &str) -> Option<i64> {
+        self.census
+            .get(election_id)
+            .and_then(|areas| areas.get(area_id))
+            .cloned()
+    }
+
+    pub fn get_election_census(&self, election_id: &str) -> Option<i64> {
+        self.census
+            .get(election_id)
+            .map(|areas| areas.values().sum())
+    }
+}
+
+pub struct CensusSnapshotWrapper(pub CensusSnapshot);
+
+impl TryFrom<Row> for CensusSnapshotWrapper {
+    type Error = anyhow::Error;
+
+    fn try_from(item: Row) -> Result<Self> {
+        let census: Value = item.try_get("census")?;
+        Ok(CensusSnapshotWrapper(CensusSnapshot {
+            id: item.try_get::<_, Uuid>("id")?.to_string(),
+            tenant_id: item.try_get::<_, Uuid>("tenant_id")?.to_string(),
+            election_event_id: item.try_get::<_, Uuid>("election_event_id")?.to_string(),
+            election_id: item
+                .try_get::<_, Option<Uuid>>("election_id")?
+                .map(|election_id| election_id.to_string()),
+            created_at: item.try_get("created_at")?,
+            created_by_user_id: item.try_get("created_by_user_id")?,
+            trigger: item.try_get("trigger")?,
+            document_id: item.try_get::<_, Uuid>("document_id")?.to_string(),
+            hash: item.try_get("hash")?,
+            total_voters: item.try_get("total_voters")?,
+            census: deserialize_value(census)?,
+        }))
+    }
+}
+
+#[instrument(skip(hasura_transaction, census), err)]
+pub async fn insert_census_snapshot(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    election_id: Option<&str>,
+    id: &str,
+    created_by_user_id: Option<String>,
+    trigger: &str,
+    document_id: &str,
+    hash: &str,
+    total_voters: i64,
+    census: &SnapshotCensus,
+) -> Result<CensusSnapshot> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                INSERT INTO
+                    sequent_backend.census_snapshot
+                (id, tenant_id, election_event_id, election_id, created_by_user_id, trigger, document_id, hash, total_voters, census)
+                VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+                )
+                RETURNING
+                    *;
+            "#,
+        )
+        .await?;
+
+    let row = hasura_transaction
+        .query_one(
+            &statement,
+            &[
+                &Uuid::parse_str(id)?,
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &election_id.map(Uuid::parse_str).transpose()?,
+                &created_by_user_id,
+                &trigger,
+                &Uuid::parse_str(document_id)?,
+                &hash,
+                &total_voters,
+                &serde_json::to_value(census)?,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error inserting census snapshot: {err}"))?;
+
+    row.try_into()
+        .map(|wrapper: CensusSnapshotWrapper| wrapper.0)
+        .context("Error converting database row to CensusSnapshot")
+}
+
+/// Returns the snapshot the voting period of the election event started
+/// with, or the most recent one if the census wasn't frozen when voting
+/// opened, so that a manual snapshot taken later doesn't replace it. With an
+/// `election_id`, only snapshots with the census of that election are
+/// considered, so that freezing the census of an election doesn't replace the
+/// one frozen earlier for another.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_latest_census_snapshot(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    election_id: Option<&str>,
+) -> Result<Option<CensusSnapshot>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.census_snapshot
+                WHERE
+                    tenant_id = $1 AND
+                    election_event_id = $2 AND
+                    ($3::uuid IS NULL OR election_id IS NULL OR election_id = $3)
+                ORDER BY
+                    (trigger = 'START_VOTING_PERIOD') DESC,
+                    created_at DESC
+                LIMIT 1;
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &election_id.map(Uuid::parse_str).transpose()?,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error fetching census snapshot: {err}"))?;
+
+    rows.into_iter()
+        .next()
+        .map(|row| {
+            row.try_into()
+                .map(|wrapper: CensusSnapshotWrapper| wrapper.0)
+        })
+        .transpose()
+}
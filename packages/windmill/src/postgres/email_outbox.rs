@@ -0,0 +1,384 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::database::get_hasura_pool;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use deadpool_postgres::{Client as DbClient, Transaction};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::row::Row;
+use tracing::instrument;
+use uuid::Uuid;
+
+// Rows of the outbox are written outside of the transaction of the caller,
+// because there's no rollback for an email that was already sent.
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EmailOutboxEntry {
+    pub id: String,
+    pub tenant_id: String,
+    pub election_event_id: Option<String>,
+    pub task_execution_id: Option<String>,
+    pub receiver: String,
+    pub subject: Option<String>,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+pub struct EmailOutboxEntryWrapper(pub EmailOutboxEntry);
+
+impl TryFrom<Row> for EmailOutboxEntryWrapper {
+    type Error = anyhow::Error;
+
+    fn try_from(item: Row) -> Result<Self> {
+        Ok(EmailOutboxEntryWrapper(EmailOutboxEntry {
+            id: item.try_get::<_, Uuid>("id")?.to_string(),
+            tenant_id: item.try_get::<_, Uuid>("tenant_id")?.to_string(),
+            election_event_id: item
+                .try_get::<_, Option<Uuid>>("election_event_id")?
+                .map(|uuid| uuid.to_string()),
+            task_execution_id: item
+                .try_get::<_, Option<Uuid>>("task_execution_id")?
+                .map(|uuid| uuid.to_string()),
+            receiver: item.try_get("receiver")?,
+            subject: item.try_get("subject")?,
+            status: item.try_get("status")?,
+            attempts: item.try_get("attempts")?,
+            last_error: item.try_get("last_error")?,
+            provider_message_id: item.try_get("provider_message_id")?,
+            created_at: item.try_get("created_at")?,
+            updated_at: item.try_get("updated_at")?,
+            sent_at: item.try_get("sent_at")?,
+        }))
+    }
+}
+
+fn parse_optional_uuid(value: Option<&str>) -> Result<Option<Uuid>> {
+    value
+        .filter(|value| !value.is_empty())
+        .map(|value| Uuid::parse_str(value).map_err(|err| anyhow!("Error parsing UUID: {err}")))
+        .transpose()
+}
+
+async fn get_db_client() -> Result<DbClient> {
+    get_hasura_pool()
+        .await
+        .get()
+        .await
+        .map_err(|err| anyhow!("Error getting hasura db pool: {err}"))
+}
+
+#[instrument(err)]
+pub async fn insert_email_outbox_entry(
+    tenant_id: &str,
+    election_event_id: Option<&str>,
+    task_execution_id: Option<&str>,
+    receiver: &str,
+    subject: &str,
+    status: &str,
+) -> Result<EmailOutboxEntry> {
+    let db_client = get_db_client().await?;
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+
+    let statement = db_client
+        .prepare(
+            r#"
+                INSERT INTO
+                    sequent_backend.email_outbox
+                (tenant_id, election_event_id, task_execution_id, receiver, subject, status)
+                VALUES (
+                    $1, $2, $3, $4, $5, $6
+                )
+                RETURNING
+                    *;
+            "#,
+        )
+        .await?;
+
+    let row = db_client
+        .query_one(
+            &statement,
+            &[
+                &tenant_uuid,
+                &parse_optional_uuid(election_event_id)?,
+                &parse_optional_uuid(task_execution_id)?,
+                &receiver,
+                &subject,
+                &status,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error inserting email outbox entry: {err}"))?;
+
+    row.try_into()
+        .map(|wrapper: EmailOutboxEntryWrapper| wrapper.0)
+        .context("Error converting database row to EmailOutboxEntry")
+}
+
+/// Updates the status of an outbox entry after an attempt to send it.
+#[instrument(err)]
+pub async fn update_email_outbox_entry(
+    tenant_id: &str,
+    id: &str,
+    status: &str,
+    attempts: i32,
+    last_error: Option<&str>,
+    provider_message_id: Option<&str>,
+    is_sent: bool,
+) -> Result<()> {
+    let db_client = get_db_client().await?;
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+    let id_uuid = Uuid::parse_str(id).map_err(|err| anyhow!("Error parsing UUID: {err}"))?;
+
+    let statement = db_client
+        .prepare(
+            r#"
+                UPDATE
+                    sequent_backend.email_outbox
+                SET
+                    status = $3,
+                    attempts = $4,
+                    last_error = $5,
+                    provider_message_id = COALESCE($6, provider_message_id),
+                    sent_at = CASE WHEN $7 THEN now() ELSE sent_at END,
+                    updated_at = now()
+                WHERE
+                    tenant_id = $1 AND
+                    id = $2;
+            "#,
+        )
+        .await?;
+
+    db_client
+        .execute(
+            &statement,
+            &[
+                &tenant_uuid,
+                &id_uuid,
+                &status,
+                &attempts,
+                &last_error,
+                &provider_message_id,
+                &is_sent,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error updating email outbox entry: {err}"))?;
+
+    Ok(())
+}
+
+/// Updates the status of the entries of a message reported by the provider,
+/// returning the updated entries.
+#[instrument(err)]
+pub async fn update_email_outbox_by_provider_message_id(
+    provider_message_id: &str,
+    receivers: &[String],
+    status: &str,
+    last_error: Option<&str>,
+) -> Result<Vec<EmailOutboxEntry>> {
+    let db_client = get_db_client().await?;
+    let receivers: Vec<String> = receivers
+        .iter()
+        .map(|receiver| receiver.to_lowercase())
+        .collect();
+
+    let statement = db_client
+        .prepare(
+            r#"
+                UPDATE
+                    sequent_backend.email_outbox
+                SET
+                    status = $3,
+                    last_error = COALESCE($4, last_error),
+                    updated_at = now()
+                WHERE
+                    provider_message_id = $1 AND
+                    lower(receiver) = ANY($2)
+                RETURNING
+                    *;
+            "#,
+        )
+        .await?;
+
+    let rows = db_client
+        .query(
+            &statement,
+            &[&provider_message_id, &receivers, &status, &last_error],
+        )
+        .await
+        .map_err(|err| anyhow!("Error updating email outbox entries: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| {
+            row.try_into()
+                .map(|wrapper: EmailOutboxEntryWrapper| wrapper.0)
+        })
+        .collect::<Result<Vec<_>>>()
+        .context("Error converting database rows to EmailOutboxEntry")
+}
+
+#[instrument(err)]
+pub async fn upsert_email_suppression(
+    tenant_id: &str,
+    email: &str,
+    reason: &str,
+    detail: Option<&str>,
+) -> Result<()> {
+    let db_client = get_db_client().await?;
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+
+    let statement = db_client
+        .prepare(
+            r#"
+                INSERT INTO
+                    sequent_backend.email_suppression
+                (tenant_id, email, reason, detail)
+                VALUES (
+                    $1, lower($2), $3, $4
+                )
+                ON CONFLICT (tenant_id, email) DO UPDATE SET
+                    reason = EXCLUDED.reason,
+                    detail = EXCLUDED.detail;
+            "#,
+        )
+        .await?;
+
+    db_client
+        .execute(&statement, &[&tenant_uuid, &email, &reason, &detail])
+        .await
+        .map_err(|err| anyhow!("Error upserting email suppression: {err}"))?;
+
+    Ok(())
+}
+
+/// Returns which of the given emails are suppressed, in lowercase.
+#[instrument(err)]
+pub async fn get_suppressed_emails(tenant_id: &str, emails: &[String]) -> Result<Vec<String>> {
+    let db_client = get_db_client().await?;
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+    let emails: Vec<String> = emails.iter().map(|email| email.to_lowercase()).collect();
+
+    let statement = db_client
+        .prepare(
+            r#"
+                SELECT
+                    email
+                FROM
+                    sequent_backend.email_suppression
+                WHERE
+                    tenant_id = $1 AND
+                    email = ANY($2);
+            "#,
+        )
+        .await?;
+
+    let rows = db_client
+        .query(&statement, &[&tenant_uuid, &emails])
+        .await
+        .map_err(|err| anyhow!("Error fetching email suppressions: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| {
+            row.try_get::<_, String>("email")
+                .map_err(|err| anyhow!("{err}"))
+        })
+        .collect()
+}
+
+/// Number of outbox entries of a campaign by status.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn count_email_outbox_by_status(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    task_execution_id: &str,
+) -> Result<Vec<(String, i64)>> {
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+    let task_execution_uuid = Uuid::parse_str(task_execution_id)
+        .map_err(|err| anyhow!("Error parsing task execution UUID: {err}"))?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    status,
+                    COUNT(*) AS count
+                FROM
+                    sequent_backend.email_outbox
+                WHERE
+                    tenant_id = $1 AND
+                    task_execution_id = $2
+                GROUP BY
+                    status;
+            "#,
+        )
+        .await?;
+
+    let rows = hasura_transaction
+        .query(&statement, &[&tenant_uuid, &task_execution_uuid])
+        .await
+        .map_err(|err| anyhow!("Error counting email outbox entries: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| Ok((row.try_get("status")?, row.try_get("count")?)))
+        .collect()
+}
+
+/// Outbox entries of a campaign with the given statuses.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_email_outbox_by_status(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    task_execution_id: &str,
+    statuses: &[String],
+    limit: i64,
+) -> Result<Vec<EmailOutboxEntry>> {
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+    let task_execution_uuid = Uuid::parse_str(task_execution_id)
+        .map_err(|err| anyhow!("Error parsing task execution UUID: {err}"))?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.email_outbox
+                WHERE
+                    tenant_id = $1 AND
+                    task_execution_id = $2 AND
+                    status = ANY($3)
+                ORDER BY
+                    updated_at DESC
+                LIMIT $4;
+            "#,
+        )
+        .await?;
+
+    let rows = hasura_transaction
+        .query(
+            &statement,
+            &[&tenant_uuid, &task_execution_uuid, &statuses, &limit],
+        )
+        .await
+        .map_err(|err| anyhow!("Error fetching email outbox entries: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| {
+            row.try_into()
+                .map(|wrapper: EmailOutboxEntryWrapper| wrapper.0)
+        })
+        .collect::<Result<Vec<_>>>()
+        .context("Error converting database rows to EmailOutboxEntry")
+}
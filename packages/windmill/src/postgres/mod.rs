@@ -9,10 +9,12 @@ pub mod ballot_publication;
 pub mod ballot_style;
 pub mod candidate;
 pub mod cast_vote;
+pub mod census_snapshot;
 pub mod contest;
 pub mod document;
 pub mod election;
 pub mod election_event;
+pub mod email_outbox;
 pub mod keycloak_realm;
 pub mod keys_ceremony;
 pub mod lock;
@@ -28,6 +30,7 @@ pub mod results_election_area;
 pub mod results_event;
 pub mod scheduled_event;
 pub mod secret;
+pub mod sms_delivery;
 pub mod tally_session;
 pub mod tally_session_contest;
 pub mod tally_session_execution;
@@ -392,3 +392,46 @@ pub async fn insert_many_results_area_contest_candidates(
 
     Ok(inserted)
 }
+
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_results_event_area_contest_candidates(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    results_event_id: &str,
+) -> Result<Vec<ResultsAreaContestCandidate>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.results_area_contest_candidate
+                WHERE
+                    tenant_id = $1 AND
+                    election_event_id = $2 AND
+                    results_event_id = $3;
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &Uuid::parse_str(results_event_id)?,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error running the results query: {}", err))?;
+
+    rows.into_iter()
+        .map(|row| -> Result<ResultsAreaContestCandidate> {
+            row.try_into().map(
+                |res: ResultsAreaContestCandidateWrapper| -> ResultsAreaContestCandidate { res.0 },
+            )
+        })
+        .collect::<Result<Vec<ResultsAreaContestCandidate>>>()
+}
@@ -211,6 +211,48 @@ pub async fn get_event_results_contest_candidates(
     Ok(results_contest_candidate)
 }
 
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_results_event_contest_candidates(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    results_event_id: &str,
+) -> Result<Vec<ResultsContestCandidate>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.results_contest_candidate
+                WHERE
+                    tenant_id = $1 AND
+                    election_event_id = $2 AND
+                    results_event_id = $3;
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &Uuid::parse_str(results_event_id)?,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error running the results query: {}", err))?;
+
+    rows.into_iter()
+        .map(|row| -> Result<ResultsContestCandidate> {
+            row.try_into()
+                .map(|res: ResultsContestCandidateWrapper| -> ResultsContestCandidate { res.0 })
+        })
+        .collect::<Result<Vec<ResultsContestCandidate>>>()
+}
+
 #[derive(Debug, Serialize)]
 struct InsertableResultsContestCandidate {
     id: Uuid,
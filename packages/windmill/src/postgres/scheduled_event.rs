@@ -39,6 +39,17 @@ impl TryFrom<Row> for ScheduledEventWrapper {
             .map(|val| deserialize_value(val))
             .transpose()?;
 
+        let status_s: Option<String> = item
+            .try_get("status")
+            .map_err(|err| anyhow!("Error deserializing status: {err}"))?;
+        let status: Option<ScheduledEventStatus> = status_s
+            .map(|val| {
+                ScheduledEventStatus::from_str(&val).map_err(|err| {
+                    anyhow!("Error mapping {val:?} into a ScheduledEventStatus: {err:?}")
+                })
+            })
+            .transpose()?;
+
         Ok(ScheduledEventWrapper(ScheduledEvent {
             id: item
                 .try_get::<_, Uuid>("id")
@@ -61,6 +72,16 @@ impl TryFrom<Row> for ScheduledEventWrapper {
             cron_config: cron_config,
             event_payload: item.get("event_payload"),
             task_id: item.get("task_id"),
+            status,
+            attempts: item
+                .try_get("attempts")
+                .map_err(|err| anyhow!("Error deserializing attempts: {err}"))?,
+            next_attempt_at: item.get("next_attempt_at"),
+            last_error: item.get("last_error"),
+            alerted_at: item.get("alerted_at"),
+            alerted_receivers: item
+                .try_get("alerted_receivers")
+                .map_err(|err| anyhow!("Error deserializing alerted_receivers: {err}"))?,
         }))
     }
 }
@@ -213,7 +234,8 @@ pub async fn stop_scheduled_event(
             UPDATE
                 "sequent_backend".scheduled_event
             SET
-                stopped_at = NOW()
+                stopped_at = NOW(),
+                status = 'COMPLETED'
             WHERE
                 tenant_id = $1
                 AND id = $2
@@ -284,7 +306,13 @@ pub async fn update_scheduled_event(
             UPDATE
                 "sequent_backend".scheduled_event
             SET
-                cron_config = $3
+                cron_config = $3,
+                status = NULL,
+                attempts = 0,
+                next_attempt_at = NULL,
+                last_error = NULL,
+                alerted_at = NULL,
+                alerted_receivers = '{}'
             WHERE
                 tenant_id = $1
                 AND id = $2
@@ -301,6 +329,128 @@ pub async fn update_scheduled_event(
     Ok(())
 }
 
+/// Records the outcome of a failed execution attempt, see
+/// `services::scheduled_events::get_failed_attempt`.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn update_scheduled_event_attempt(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    id: &str,
+    status: &ScheduledEventStatus,
+    attempts: i32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_error: &str,
+) -> Result<()> {
+    let tenant_uuid: uuid::Uuid =
+        Uuid::parse_str(tenant_id).with_context(|| "Error parsing tenant_id as UUID")?;
+    let id_uuid: uuid::Uuid = Uuid::parse_str(id).with_context(|| "Error parsing id as UUID")?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+            UPDATE
+                "sequent_backend".scheduled_event
+            SET
+                status = $3,
+                attempts = $4,
+                next_attempt_at = $5,
+                last_error = $6
+            WHERE
+                tenant_id = $1
+                AND id = $2
+                AND stopped_at IS NULL
+            "#,
+        )
+        .await?;
+
+    let _rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &tenant_uuid,
+                &id_uuid,
+                &status.to_string(),
+                &attempts,
+                &next_attempt_at,
+                &last_error,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error running the update_scheduled_event_attempt query: {err}"))?;
+
+    Ok(())
+}
+
+/// Records that the alert of the event was sent to the receiver, so that it
+/// is not sent to them again if sending it to the others fails.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn add_scheduled_event_alerted_receiver(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    id: &str,
+    receiver: &str,
+) -> Result<()> {
+    let tenant_uuid: uuid::Uuid =
+        Uuid::parse_str(tenant_id).with_context(|| "Error parsing tenant_id as UUID")?;
+    let id_uuid: uuid::Uuid = Uuid::parse_str(id).with_context(|| "Error parsing id as UUID")?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+            UPDATE
+                "sequent_backend".scheduled_event
+            SET
+                alerted_receivers = array_append(alerted_receivers, $3)
+            WHERE
+                tenant_id = $1
+                AND id = $2
+                AND NOT ($3 = ANY(alerted_receivers))
+            "#,
+        )
+        .await?;
+
+    let _rows: Vec<Row> = hasura_transaction
+        .query(&statement, &[&tenant_uuid, &id_uuid, &receiver])
+        .await
+        .map_err(|err| {
+            anyhow!("Error running the add_scheduled_event_alerted_receiver query: {err}")
+        })?;
+
+    Ok(())
+}
+
+#[instrument(skip(hasura_transaction), err)]
+pub async fn set_scheduled_event_alerted(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    id: &str,
+) -> Result<()> {
+    let tenant_uuid: uuid::Uuid =
+        Uuid::parse_str(tenant_id).with_context(|| "Error parsing tenant_id as UUID")?;
+    let id_uuid: uuid::Uuid = Uuid::parse_str(id).with_context(|| "Error parsing id as UUID")?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+            UPDATE
+                "sequent_backend".scheduled_event
+            SET
+                alerted_at = NOW()
+            WHERE
+                tenant_id = $1
+                AND id = $2
+            "#,
+        )
+        .await?;
+
+    let _rows: Vec<Row> = hasura_transaction
+        .query(&statement, &[&tenant_uuid, &id_uuid])
+        .await
+        .map_err(|err| anyhow!("Error running the set_scheduled_event_alerted query: {err}"))?;
+
+    Ok(())
+}
+
 #[instrument(skip(hasura_transaction), err)]
 pub async fn insert_scheduled_event(
     hasura_transaction: &Transaction<'_>,
@@ -341,18 +491,7 @@ pub async fn insert_scheduled_event(
                     $6
                 )
                 RETURNING
-                    id,
-                    tenant_id,
-                    election_event_id,
-                    created_at,
-                    stopped_at,
-                    archived_at,
-                    labels,
-                    annotations,
-                    event_processor,
-                    cron_config,
-                    event_payload,
-                    task_id;
+                    *;
             "#,
         )
         .await
@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::database::get_hasura_pool;
+use anyhow::{anyhow, Result};
+use deadpool_postgres::{Client as DbClient, Transaction};
+use tracing::instrument;
+use uuid::Uuid;
+
+// Rows are written outside of the transaction of the caller, as they are
+// recorded after the message was sent or by the status callbacks of the
+// provider.
+
+async fn get_db_client() -> Result<DbClient> {
+    get_hasura_pool()
+        .await
+        .get()
+        .await
+        .map_err(|err| anyhow!("Error getting hasura db pool: {err}"))
+}
+
+/// Inserts or updates the delivery status of a message. With `overwrite`
+/// false the status already recorded is kept, and only missing fields are
+/// filled in. A status is never replaced by one of a lower `status_rank`, so
+/// that callbacks arriving out of order don't move the message back.
+#[instrument(err)]
+pub async fn upsert_sms_delivery(
+    tenant_id: &str,
+    task_execution_id: &str,
+    message_id: &str,
+    receiver: Option<&str>,
+    status: &str,
+    status_rank: i32,
+    error_code: Option<&str>,
+    overwrite: bool,
+) -> Result<()> {
+    let db_client = get_db_client().await?;
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+    let task_execution_uuid = Uuid::parse_str(task_execution_id)
+        .map_err(|err| anyhow!("Error parsing task execution UUID: {err}"))?;
+
+    let statement = db_client
+        .prepare(
+            r#"
+                INSERT INTO
+                    sequent_backend.sms_delivery
+                (tenant_id, task_execution_id, message_id, receiver, status, status_rank, error_code)
+                VALUES (
+                    $1, $2, $3, $4, $5, $6, $7
+                )
+                ON CONFLICT (tenant_id, task_execution_id, message_id) DO UPDATE SET
+                    receiver = CASE
+                        WHEN $8 THEN COALESCE(EXCLUDED.receiver, sms_delivery.receiver)
+                        ELSE COALESCE(sms_delivery.receiver, EXCLUDED.receiver)
+                    END,
+                    status = CASE
+                        WHEN $8 AND EXCLUDED.status_rank >= sms_delivery.status_rank
+                            THEN EXCLUDED.status
+                        ELSE sms_delivery.status
+                    END,
+                    status_rank = CASE
+                        WHEN $8 AND EXCLUDED.status_rank >= sms_delivery.status_rank
+                            THEN EXCLUDED.status_rank
+                        ELSE sms_delivery.status_rank
+                    END,
+                    error_code = CASE
+                        WHEN $8 AND EXCLUDED.status_rank >= sms_delivery.status_rank
+                            THEN COALESCE(EXCLUDED.error_code, sms_delivery.error_code)
+                        ELSE COALESCE(sms_delivery.error_code, EXCLUDED.error_code)
+                    END,
+                    updated_at = now();
+            "#,
+        )
+        .await?;
+
+    db_client
+        .execute(
+            &statement,
+            &[
+                &tenant_uuid,
+                &task_execution_uuid,
+                &message_id,
+                &receiver,
+                &status,
+                &status_rank,
+                &error_code,
+                &overwrite,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error upserting sms delivery: {err}"))?;
+
+    Ok(())
+}
+
+/// Number of messages sent by a task execution by delivery status.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn count_sms_delivery_by_status(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    task_execution_id: &str,
+) -> Result<Vec<(String, i64)>> {
+    let tenant_uuid =
+        Uuid::parse_str(tenant_id).map_err(|err| anyhow!("Error parsing tenant UUID: {err}"))?;
+    let task_execution_uuid = Uuid::parse_str(task_execution_id)
+        .map_err(|err| anyhow!("Error parsing task execution UUID: {err}"))?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    status,
+                    COUNT(*) AS count
+                FROM
+                    sequent_backend.sms_delivery
+                WHERE
+                    tenant_id = $1 AND
+                    task_execution_id = $2
+                GROUP BY
+                    status;
+            "#,
+        )
+        .await?;
+
+    let rows = hasura_transaction
+        .query(&statement, &[&tenant_uuid, &task_execution_uuid])
+        .await
+        .map_err(|err| anyhow!("Error counting sms deliveries: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| Ok((row.try_get("status")?, row.try_get("count")?)))
+        .collect()
+}
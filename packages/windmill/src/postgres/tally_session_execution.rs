@@ -209,6 +209,97 @@ pub async fn get_last_tally_session_execution(
     Ok(elements.first().cloned())
 }
 
+/// Same as `get_last_tally_session_execution`, locking the row until the
+/// transaction ends so that concurrent tally subtasks don't overwrite each
+/// other's checkpoints.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_last_tally_session_execution_for_update(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+) -> Result<Option<TallySessionExecution>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.tally_session_execution
+                WHERE
+                    tenant_id = $1 AND
+                    election_event_id = $2 AND
+                    tally_session_id = $3
+                ORDER BY created_at DESC
+                LIMIT 1
+                FOR UPDATE;
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &Uuid::parse_str(tally_session_id)?,
+            ],
+        )
+        .await?;
+
+    let elements: Vec<TallySessionExecution> = rows
+        .into_iter()
+        .map(|row| -> Result<TallySessionExecution> {
+            row.try_into()
+                .map(|res: TallySessionExecutionWrapper| -> TallySessionExecution { res.0 })
+        })
+        .collect::<Result<Vec<TallySessionExecution>>>()?;
+
+    Ok(elements.first().cloned())
+}
+
+#[instrument(skip(hasura_transaction, status), err)]
+pub async fn update_tally_session_execution_status(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_execution_id: &str,
+    status: &TallyCeremonyStatus,
+) -> Result<()> {
+    let status_value = serde_json::to_value(status)?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+            UPDATE
+                sequent_backend.tally_session_execution
+            SET
+                status = $1
+            WHERE
+                id = $2 AND
+                tenant_id = $3 AND
+                election_event_id = $4;
+        "#,
+        )
+        .await?;
+
+    hasura_transaction
+        .execute(
+            &statement,
+            &[
+                &status_value,
+                &Uuid::parse_str(tally_session_execution_id)?,
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error updating tally session execution status: {err}"))?;
+
+    Ok(())
+}
+
 pub async fn get_event_tally_session_executions(
     hasura_transaction: &Transaction<'_>,
     tenant_id: &str,
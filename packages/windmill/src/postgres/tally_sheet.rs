@@ -3,10 +3,12 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 use anyhow::anyhow;
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
 use deadpool_postgres::Transaction;
 use sequent_core::serialization::deserialize_with_path::deserialize_value;
 use sequent_core::types::hasura::core::TallySheet;
 use sequent_core::types::tally_sheets::AreaContestResults;
+use serde::Serialize;
 use serde_json::Value;
 use tokio_postgres::row::Row;
 use tokio_postgres::types::ToSql;
@@ -38,6 +40,9 @@ impl TryFrom<Row> for TallySheetWrapper {
             channel: item.try_get("channel")?,
             deleted_at: item.get("deleted_at"),
             created_by_user_id: item.try_get("created_by_user_id")?,
+            version: item.try_get("version")?,
+            approved_at: item.get("approved_at"),
+            approved_by_user_id: item.try_get("approved_by_user_id")?,
         }))
     }
 }
@@ -138,3 +143,260 @@ pub async fn publish_tally_sheet(
     }
     Ok(Some(()))
 }
+
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_tally_sheet_by_id(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_sheet_id: &str,
+) -> Result<Option<TallySheet>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.tally_sheet
+                WHERE
+                    tenant_id = $1 AND
+                    election_event_id = $2 AND
+                    id = $3 AND
+                    deleted_at IS NULL;
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &Uuid::parse_str(tally_sheet_id)?,
+            ],
+        )
+        .await?;
+
+    rows.into_iter()
+        .next()
+        .map(|row| -> Result<TallySheet> {
+            row.try_into()
+                .map(|res: TallySheetWrapper| -> TallySheet { res.0 })
+        })
+        .transpose()
+}
+
+/// Returns the non deleted tally sheets entered for an area and contest, of
+/// any channel.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_area_contest_tally_sheets(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    area_id: &str,
+    contest_id: &str,
+) -> Result<Vec<TallySheet>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+                SELECT
+                    *
+                FROM
+                    sequent_backend.tally_sheet
+                WHERE
+                    tenant_id = $1 AND
+                    election_event_id = $2 AND
+                    area_id = $3 AND
+                    contest_id = $4 AND
+                    deleted_at IS NULL
+                ORDER BY created_at;
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(
+            &statement,
+            &[
+                &Uuid::parse_str(tenant_id)?,
+                &Uuid::parse_str(election_event_id)?,
+                &Uuid::parse_str(area_id)?,
+                &Uuid::parse_str(contest_id)?,
+            ],
+        )
+        .await?;
+
+    rows.into_iter()
+        .map(|row| -> Result<TallySheet> {
+            row.try_into()
+                .map(|res: TallySheetWrapper| -> TallySheet { res.0 })
+        })
+        .collect::<Result<Vec<TallySheet>>>()
+}
+
+/// Approves the given version of a tally sheet. Returns None if the tally
+/// sheet doesn't exist, is already published or has a different version.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn approve_tally_sheet(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_sheet_id: &str,
+    version: i32,
+    user_id: &str,
+) -> Result<Option<()>> {
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+        UPDATE sequent_backend.tally_sheet tally_sheet
+        SET
+            approved_at = now(),
+            approved_by_user_id = $5
+        WHERE
+            tally_sheet.tenant_id = $1 AND
+            tally_sheet.election_event_id = $2 AND
+            tally_sheet.id = $3 AND
+            tally_sheet.version = $4 AND
+            tally_sheet.deleted_at IS NULL AND
+            tally_sheet.published_at IS NULL
+        RETURNING *
+    "#,
+        )
+        .await?;
+
+    let tenant_uuid: uuid::Uuid = Uuid::parse_str(tenant_id)
+        .map_err(|err| anyhow!("Error parsing tenant_id as UUID: {}", err))?;
+    let election_event_uuid: uuid::Uuid = Uuid::parse_str(election_event_id)
+        .map_err(|err| anyhow!("Error parsing election_event_id as UUID: {}", err))?;
+    let tally_sheet_uuid: uuid::Uuid = Uuid::parse_str(tally_sheet_id)
+        .map_err(|err| anyhow!("Error parsing tally_sheet_id as UUID: {}", err))?;
+    let params: Vec<&(dyn ToSql + Sync)> = vec![
+        &tenant_uuid,
+        &election_event_uuid,
+        &tally_sheet_uuid,
+        &version,
+        &user_id,
+    ];
+    let rows: Vec<Row> = hasura_transaction
+        .query(&statement, &params.as_slice())
+        .await
+        .map_err(|err| anyhow!("{}", err))?;
+    if rows.len() != 1 {
+        return Ok(None);
+    }
+    Ok(Some(()))
+}
+
+#[derive(Debug, Serialize)]
+struct InsertableTallySheet {
+    id: Uuid,
+    tenant_id: Uuid,
+    election_event_id: Uuid,
+    election_id: Uuid,
+    contest_id: Uuid,
+    area_id: Uuid,
+    created_at: Option<DateTime<Local>>,
+    last_updated_at: Option<DateTime<Local>>,
+    labels: Option<Value>,
+    annotations: Option<Value>,
+    published_at: Option<DateTime<Local>>,
+    published_by_user_id: Option<String>,
+    content: Option<AreaContestResults>,
+    channel: Option<String>,
+    deleted_at: Option<DateTime<Local>>,
+    created_by_user_id: String,
+    version: Option<i32>,
+    approved_at: Option<DateTime<Local>>,
+    approved_by_user_id: Option<String>,
+}
+
+#[instrument(skip(hasura_transaction, tally_sheets), err)]
+pub async fn insert_many_tally_sheets(
+    hasura_transaction: &Transaction<'_>,
+    tally_sheets: Vec<TallySheet>,
+) -> Result<Vec<TallySheet>> {
+    if tally_sheets.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let insertable: Vec<InsertableTallySheet> = tally_sheets
+        .into_iter()
+        .map(|tally_sheet| {
+            Ok(InsertableTallySheet {
+                id: Uuid::parse_str(&tally_sheet.id)?,
+                tenant_id: Uuid::parse_str(&tally_sheet.tenant_id)?,
+                election_event_id: Uuid::parse_str(&tally_sheet.election_event_id)?,
+                election_id: Uuid::parse_str(&tally_sheet.election_id)?,
+                contest_id: Uuid::parse_str(&tally_sheet.contest_id)?,
+                area_id: Uuid::parse_str(&tally_sheet.area_id)?,
+                created_at: tally_sheet.created_at,
+                last_updated_at: tally_sheet.last_updated_at,
+                labels: tally_sheet.labels,
+                annotations: tally_sheet.annotations,
+                published_at: tally_sheet.published_at,
+                published_by_user_id: tally_sheet.published_by_user_id,
+                content: tally_sheet.content,
+                channel: tally_sheet.channel,
+                deleted_at: tally_sheet.deleted_at,
+                created_by_user_id: tally_sheet.created_by_user_id,
+                version: tally_sheet.version,
+                approved_at: tally_sheet.approved_at,
+                approved_by_user_id: tally_sheet.approved_by_user_id,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let json_data = serde_json::to_value(&insertable)?;
+
+    let sql = r#"
+        WITH data AS (
+            SELECT * FROM jsonb_to_recordset($1::jsonb) AS t(
+                id UUID,
+                tenant_id UUID,
+                election_event_id UUID,
+                election_id UUID,
+                contest_id UUID,
+                area_id UUID,
+                created_at TIMESTAMPTZ,
+                last_updated_at TIMESTAMPTZ,
+                labels JSONB,
+                annotations JSONB,
+                published_at TIMESTAMPTZ,
+                published_by_user_id TEXT,
+                content JSONB,
+                channel TEXT,
+                deleted_at TIMESTAMPTZ,
+                created_by_user_id TEXT,
+                version INT,
+                approved_at TIMESTAMPTZ,
+                approved_by_user_id TEXT
+            )
+        )
+        INSERT INTO sequent_backend.tally_sheet (
+            id, tenant_id, election_event_id, election_id, contest_id,
+            area_id, created_at, last_updated_at, labels, annotations,
+            published_at, published_by_user_id, content, channel, deleted_at,
+            created_by_user_id, version, approved_at, approved_by_user_id
+        )
+        SELECT
+            id, tenant_id, election_event_id, election_id, contest_id,
+            area_id, COALESCE(created_at, now()),
+            COALESCE(last_updated_at, now()), labels, annotations,
+            published_at, published_by_user_id, content, channel, deleted_at,
+            created_by_user_id, COALESCE(version, 1), approved_at,
+            approved_by_user_id
+        FROM data
+        RETURNING *;
+    "#;
+
+    let statement = hasura_transaction.prepare(sql).await?;
+    let rows = hasura_transaction.query(&statement, &[&json_data]).await?;
+
+    rows.into_iter()
+        .map(|row| -> Result<TallySheet> {
+            row.try_into()
+                .map(|res: TallySheetWrapper| -> TallySheet { res.0 })
+        })
+        .collect::<Result<Vec<TallySheet>>>()
+}
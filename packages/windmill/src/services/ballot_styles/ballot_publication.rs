@@ -2,24 +2,27 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 use crate::postgres::ballot_publication::{
-    get_ballot_publication_by_id, get_previous_publication, get_previous_publication_election,
-    insert_ballot_publication, soft_delete_other_ballot_publications, update_ballot_publication,
+    get_ballot_publication_by_id, get_latest_publication_election, get_previous_publication,
+    get_previous_publication_election, insert_ballot_publication,
+    soft_delete_other_ballot_publications, update_ballot_publication,
 };
 use crate::postgres::ballot_style::get_publication_ballot_styles;
-use crate::postgres::election::{get_elections_ids, update_election_status};
+use crate::postgres::election::{get_election_by_id, get_elections_ids, update_election_status};
 use crate::postgres::election_event::{get_election_event_by_id, update_election_event_status};
 use crate::services::celery_app::get_celery_app;
 use crate::services::election_event_board::get_election_event_board;
-use crate::services::election_event_status::get_election_event_status;
+use crate::services::election_event_status::{get_election_event_status, get_election_status};
 use crate::services::electoral_log::*;
 use crate::tasks::update_election_event_ballot_styles::update_election_event_ballot_styles;
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
 use deadpool_postgres::Transaction;
-use sequent_core::ballot::ElectionEventStatus;
+use sequent_core::ballot::{BallotStyle, ElectionEventStatus};
+use sequent_core::ballot_diff::{diff_ballot_styles, BallotChangeImpact, BallotStylesDiff};
 use sequent_core::serialization::deserialize_with_path::*;
 use sequent_core::services::connection;
 use sequent_core::services::date::ISO8601;
+use sequent_core::types::hasura::core::BallotPublication;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{event, instrument, Level};
@@ -95,6 +98,7 @@ pub async fn update_publish_ballot(
     tenant_id: String,
     election_event_id: String,
     ballot_publication_id: String,
+    allow_encoding_changes: bool,
 ) -> Result<()> {
     let ballot_publication = get_ballot_publication_by_id(
         &hasura_transaction,
@@ -115,6 +119,50 @@ pub async fn update_publish_ballot(
         return Ok(());
     }
 
+    // Ballots cast with the current publication must still be decodable
+    // after publishing the new one.
+    let election_ids = ballot_publication.election_ids.clone().unwrap_or(vec![]);
+    if let Some(changes) =
+        get_ballot_publication_changes(hasura_transaction, &ballot_publication).await?
+    {
+        if changes.breaks_encoding()
+            && has_voting_started(
+                hasura_transaction,
+                &tenant_id,
+                &election_event_id,
+                &election_ids,
+            )
+            .await?
+        {
+            let encoding_changes = changes
+                .get_changes(BallotChangeImpact::ENCODING)
+                .iter()
+                .map(|change| {
+                    format!(
+                        "{} (election {}, area {}, contest {}, candidate {})",
+                        change.field,
+                        change.election_id,
+                        change.area_id,
+                        change.contest_id.clone().unwrap_or("-".into()),
+                        change.candidate_id.clone().unwrap_or("-".into()),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !allow_encoding_changes {
+                return Err(anyhow!(
+                    "Voting has started and the publication changes the ballot encoding, \
+                     which would make the ballots already cast undecodable: {encoding_changes}"
+                ));
+            }
+            event!(
+                Level::WARN,
+                "Publishing ballot publication {ballot_publication_id} with encoding changes \
+                 after voting started: {encoding_changes}"
+            );
+        }
+    }
+
     let _result = soft_delete_other_ballot_publications(
         &hasura_transaction,
         &ballot_publication_id,
@@ -155,7 +203,6 @@ pub async fn update_publish_ballot(
     .await?;
 
     // Update elections status
-    let election_ids = ballot_publication.election_ids.clone().unwrap_or(vec![]);
     for election_id in election_ids.clone() {
         update_election_status(
             &hasura_transaction,
@@ -235,6 +282,202 @@ pub async fn get_publication_json(
     Ok(serde_json::Value::Array(val_arr))
 }
 
+/// Id of the publication published before the given one, for the same
+/// election if the publication is for a single election.
+#[instrument(skip(hasura_transaction), err)]
+async fn get_previous_publication_id(
+    hasura_transaction: &Transaction<'_>,
+    ballot_publication: &BallotPublication,
+) -> Result<Option<String>> {
+    let tenant_id = &ballot_publication.tenant_id;
+    let election_event_id = &ballot_publication.election_event_id;
+    let previous_publication_id = if let Some(election_id) = ballot_publication.election_id.clone()
+    {
+        get_previous_publication_election(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            ballot_publication.created_at.clone(),
+            &election_id,
+        )
+        .await?
+        .map(|pub_data| pub_data.id)
+        .ok_or_else(|| {
+            anyhow!(
+                "Can't find ballot publication for election id {}",
+                election_id
+            )
+        })
+        .with_context(|| "Error retrieving previous ballot publication for election")
+        .ok()
+    } else {
+        get_previous_publication(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            ballot_publication.created_at.clone(),
+        )
+        .await?
+        .map(|pub_data| pub_data.id)
+        .ok_or_else(|| anyhow!("Can't find ballot publication"))
+        .with_context(|| "Error retrieving previous ballot publication")
+        .ok()
+    };
+
+    Ok(previous_publication_id)
+}
+
+/// Ballot styles of a publication, optionally only those of an election.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_publication_ballot_style_list(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    ballot_publication_id: &str,
+    election_id: Option<String>,
+) -> Result<Vec<BallotStyle>> {
+    let ballot_styles = get_publication_ballot_styles(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        ballot_publication_id,
+        None,
+    )
+    .await?;
+
+    ballot_styles
+        .into_iter()
+        .filter(|ballot_style| {
+            election_id
+                .as_ref()
+                .map(|id| &ballot_style.election_id == id)
+                .unwrap_or(true)
+        })
+        .filter_map(|ballot_style| ballot_style.ballot_eml)
+        .map(|ballot_eml| {
+            deserialize_str::<BallotStyle>(&ballot_eml)
+                .map_err(|err| anyhow!("Error parsing ballot style: {err}"))
+        })
+        .collect()
+}
+
+/// Ballot styles currently live for the elections of a publication. Each
+/// election takes the ones of the last publication published for it, be it
+/// for the whole event or only for that election, so it doesn't matter when
+/// the given publication was created. None if none of them was published.
+#[instrument(skip(hasura_transaction, ballot_publication), err)]
+async fn get_live_ballot_style_list(
+    hasura_transaction: &Transaction<'_>,
+    ballot_publication: &BallotPublication,
+) -> Result<Option<Vec<BallotStyle>>> {
+    let election_ids = match ballot_publication.election_id.clone() {
+        Some(election_id) => vec![election_id],
+        None => ballot_publication.election_ids.clone().unwrap_or_default(),
+    };
+
+    let mut live_ballot_styles = None;
+    for election_id in election_ids {
+        let Some(live_publication) = get_latest_publication_election(
+            hasura_transaction,
+            &ballot_publication.tenant_id,
+            &ballot_publication.election_event_id,
+            &election_id,
+            &ballot_publication.id,
+        )
+        .await?
+        else {
+            continue;
+        };
+        let ballot_styles = get_publication_ballot_style_list(
+            hasura_transaction,
+            &ballot_publication.tenant_id,
+            &ballot_publication.election_event_id,
+            &live_publication.id,
+            Some(election_id),
+        )
+        .await?;
+        live_ballot_styles
+            .get_or_insert_with(Vec::new)
+            .extend(ballot_styles);
+    }
+
+    Ok(live_ballot_styles)
+}
+
+/// Semantic diff between the ballot styles of a publication and the ones it
+/// replaces: the live ones if it isn't published yet, otherwise the ones of
+/// the publication published before it, if any.
+#[instrument(skip(hasura_transaction, ballot_publication), err)]
+pub async fn get_ballot_publication_changes(
+    hasura_transaction: &Transaction<'_>,
+    ballot_publication: &BallotPublication,
+) -> Result<Option<BallotStylesDiff>> {
+    let previous = if ballot_publication.published_at.is_none() {
+        get_live_ballot_style_list(hasura_transaction, ballot_publication).await?
+    } else if let Some(previous_publication_id) =
+        get_previous_publication_id(hasura_transaction, ballot_publication).await?
+    {
+        Some(
+            get_publication_ballot_style_list(
+                hasura_transaction,
+                &ballot_publication.tenant_id,
+                &ballot_publication.election_event_id,
+                &previous_publication_id,
+                ballot_publication.election_id.clone(),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+    let Some(previous) = previous else {
+        return Ok(None);
+    };
+
+    let current = get_publication_ballot_style_list(
+        hasura_transaction,
+        &ballot_publication.tenant_id,
+        &ballot_publication.election_event_id,
+        &ballot_publication.id,
+        ballot_publication.election_id.clone(),
+    )
+    .await?;
+
+    Ok(Some(diff_ballot_styles(&previous, &current)?))
+}
+
+/// Whether voting has started in any channel of the given elections.
+#[instrument(skip(hasura_transaction), err)]
+async fn has_voting_started(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    election_ids: &[String],
+) -> Result<bool> {
+    for election_id in election_ids {
+        let Some(election) = get_election_by_id(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            election_id,
+        )
+        .await?
+        else {
+            continue;
+        };
+        let Some(status) = get_election_status(election.status) else {
+            continue;
+        };
+        if status.voting_status.is_started()
+            || status.kiosk_voting_status.is_started()
+            || status.early_voting_status.is_started()
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PublicationStyles {
     ballot_publication_id: String,
@@ -245,6 +488,8 @@ pub struct PublicationStyles {
 pub struct PublicationDiff {
     current: PublicationStyles,
     previous: Option<PublicationStyles>,
+    /// Semantic changes over all the ballot styles, not limited by `limit`.
+    changes: Option<BallotStylesDiff>,
 }
 
 #[instrument(err)]
@@ -264,38 +509,8 @@ pub async fn get_ballot_publication_diff(
     .await?
     .with_context(|| "Can't find ballot publication")?;
 
-    let previous_publication_id = if let Some(election_id) = ballot_publication.election_id.clone()
-    {
-        get_previous_publication_election(
-            &hasura_transaction,
-            &tenant_id,
-            &election_event_id,
-            ballot_publication.created_at.clone(),
-            &election_id,
-        )
-        .await?
-        .map(|pub_data| pub_data.id)
-        .ok_or_else(|| {
-            anyhow!(
-                "Can't find ballot publication for election id {}",
-                election_id
-            )
-        })
-        .with_context(|| "Error retrieving previous ballot publication for election")
-        .ok()
-    } else {
-        get_previous_publication(
-            &hasura_transaction,
-            &tenant_id,
-            &election_event_id,
-            ballot_publication.created_at.clone(),
-        )
-        .await?
-        .map(|pub_data| pub_data.id)
-        .ok_or_else(|| anyhow!("Can't find ballot publication"))
-        .with_context(|| "Error retrieving previous ballot publication")
-        .ok()
-    };
+    let previous_publication_id =
+        get_previous_publication_id(&hasura_transaction, &ballot_publication).await?;
 
     let current_json = get_publication_json(
         &hasura_transaction,
@@ -331,5 +546,11 @@ pub async fn get_ballot_publication_diff(
         None
     };
 
-    Ok(PublicationDiff { current, previous })
+    let changes = get_ballot_publication_changes(&hasura_transaction, &ballot_publication).await?;
+
+    Ok(PublicationDiff {
+        current,
+        previous,
+        changes,
+    })
 }
@@ -197,6 +197,46 @@ pub async fn count_cast_votes_election(
     Ok(count_data)
 }
 
+/// Returns which of the given voters have cast a ballot online in the
+/// election event.
+#[instrument(skip(hasura_transaction, voter_ids), err)]
+pub async fn find_voters_with_cast_votes(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    voter_ids: &Vec<String>,
+) -> Result<Vec<String>> {
+    if voter_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let tenant_uuid: uuid::Uuid = Uuid::parse_str(tenant_id)
+        .map_err(|err| anyhow!("Error parsing tenant_id as UUID: {}", err))?;
+    let election_event_uuid: uuid::Uuid = Uuid::parse_str(election_event_id)
+        .map_err(|err| anyhow!("Error parsing election_event_id as UUID: {}", err))?;
+
+    let statement = hasura_transaction
+        .prepare(
+            r#"
+            SELECT DISTINCT voter_id_string
+            FROM sequent_backend.cast_vote
+            WHERE
+                tenant_id = $1 AND
+                election_event_id = $2 AND
+                voter_id_string = ANY($3::text[])
+            "#,
+        )
+        .await?;
+
+    let rows: Vec<Row> = hasura_transaction
+        .query(&statement, &[&tenant_uuid, &election_event_uuid, voter_ids])
+        .await
+        .map_err(|err| anyhow!("Error running the query: {}", err))?;
+
+    rows.into_iter()
+        .map(|row| -> Result<String> { Ok(row.try_get("voter_id_string")?) })
+        .collect()
+}
+
 #[instrument(skip(transaction), err)]
 pub async fn get_count_votes_per_day(
     transaction: &Transaction<'_>,
@@ -15,16 +15,22 @@ use tracing::{event, info, instrument, Level};
 
 use crate::tasks::activity_logs_report::generate_activity_logs_report;
 use crate::tasks::create_ballot_receipt::create_ballot_receipt;
+use crate::tasks::create_census_snapshot::create_census_snapshot_task;
 use crate::tasks::create_keys::create_keys;
 use crate::tasks::delete_election_event::delete_election_event_t;
 use crate::tasks::electoral_log::{
     electoral_log_batch_dispatcher, enqueue_electoral_log_event, process_electoral_log_events_batch,
 };
+use crate::tasks::execute_tally_election::execute_tally_election;
+use crate::tasks::execute_tally_recount::execute_tally_recount_task;
 use crate::tasks::execute_tally_session::execute_tally_session;
 use crate::tasks::export_application::export_application;
 use crate::tasks::export_ballot_publication::export_ballot_publication;
 use crate::tasks::export_election_event::export_election_event;
-use crate::tasks::export_tally_results::export_tally_results_to_xlsx_task;
+use crate::tasks::export_tally_results::{
+    export_tally_results_to_cdf_task, export_tally_results_to_eml_task,
+    export_tally_results_to_xlsx_task,
+};
 use crate::tasks::export_tasks_execution::export_tasks_execution;
 use crate::tasks::export_templates::export_templates;
 use crate::tasks::export_tenant_config::export_tenant_config;
@@ -51,14 +57,18 @@ use crate::tasks::miru_plugin_tasks::send_transmission_package_task;
 use crate::tasks::post_tally::post_tally_task;
 use crate::tasks::prepare_publication_preview::prepare_publication_preview;
 use crate::tasks::process_board::process_board;
+use crate::tasks::publish_provisional_results::publish_provisional_results;
 use crate::tasks::render_document_pdf::render_document_pdf;
 use crate::tasks::render_report::render_report;
 use crate::tasks::review_boards::review_boards;
+use crate::tasks::scheduled_event_alert::send_scheduled_event_alert_task;
 use crate::tasks::scheduled_events::scheduled_events;
 use crate::tasks::scheduled_reports::scheduled_reports;
 use crate::tasks::send_template::send_template;
 use crate::tasks::set_public_key::set_public_key;
+use crate::tasks::sync_census::sync_census_task;
 use crate::tasks::update_election_event_ballot_styles::update_election_event_ballot_styles;
+use crate::tasks::validate_import::validate_import_task;
 
 #[derive(AsRefStr, Debug)]
 pub enum Queue {
@@ -242,11 +252,15 @@ pub async fn generate_celery_app() -> Result<Arc<Celery>> {
             create_ballot_receipt,
             set_public_key,
             execute_tally_session,
+            execute_tally_election,
+            execute_tally_recount_task,
             update_election_event_ballot_styles,
             insert_election_event_t,
             insert_tenant,
             send_template,
             import_users,
+            sync_census_task,
+            validate_import_task,
             export_users,
             import_election_event,
             scheduled_events,
@@ -278,7 +292,12 @@ pub async fn generate_celery_app() -> Result<Arc<Celery>> {
             render_document_pdf,
             prepare_publication_preview,
             export_tally_results_to_xlsx_task,
+            export_tally_results_to_cdf_task,
+            export_tally_results_to_eml_task,
             post_tally_task,
+            publish_provisional_results,
+            create_census_snapshot_task,
+            send_scheduled_event_alert_task,
         ],
         task_routes = [
             create_keys::NAME => &Queue::Short.queue_name(&slug),
@@ -291,11 +310,15 @@ pub async fn generate_celery_app() -> Result<Arc<Celery>> {
             render_document_pdf::NAME => &Queue::Reports.queue_name(&slug),
             set_public_key::NAME => &Queue::Short.queue_name(&slug),
             execute_tally_session::NAME => &Queue::Tally.queue_name(&slug),
+            execute_tally_election::NAME => &Queue::Tally.queue_name(&slug),
+            execute_tally_recount_task::NAME => &Queue::Tally.queue_name(&slug),
             update_election_event_ballot_styles::NAME => &Queue::Short.queue_name(&slug),
             insert_election_event_t::NAME => &Queue::Short.queue_name(&slug),
             insert_tenant::NAME => &Queue::Short.queue_name(&slug),
             send_template::NAME => &Queue::Communication.queue_name(&slug),
             import_users::NAME => &Queue::ImportExport.queue_name(&slug),
+            sync_census_task::NAME => &Queue::ImportExport.queue_name(&slug),
+            validate_import_task::NAME => &Queue::ImportExport.queue_name(&slug),
             export_users::NAME => &Queue::ImportExport.queue_name(&slug),
             export_election_event::NAME => &Queue::ImportExport.queue_name(&slug),
             generate_activity_logs_report::NAME => &Queue::ImportExport.queue_name(&slug),
@@ -326,7 +349,12 @@ pub async fn generate_celery_app() -> Result<Arc<Celery>> {
             electoral_log_batch_dispatcher::NAME => &Queue::ElectoralLogBeat.queue_name(&slug),
             prepare_publication_preview::NAME => &Queue::Beat.queue_name(&slug),
             export_tally_results_to_xlsx_task::NAME => &Queue::ImportExport.queue_name(&slug),
+            export_tally_results_to_cdf_task::NAME => &Queue::ImportExport.queue_name(&slug),
+            export_tally_results_to_eml_task::NAME => &Queue::ImportExport.queue_name(&slug),
             post_tally_task::NAME => &Queue::Reports.queue_name(&slug),
+            publish_provisional_results::NAME => &Queue::Short.queue_name(&slug),
+            create_census_snapshot_task::NAME => &Queue::ImportExport.queue_name(&slug),
+            send_scheduled_event_alert_task::NAME => &Queue::Communication.queue_name(&slug),
         ],
         prefetch_count = prefetch_count,
         acks_late = acks_late,
@@ -0,0 +1,453 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Census snapshots freeze the voter roll of an election event, so that
+//! turnout and the eligible census of the results don't change when voters
+//! are edited after voting starts. The roll is stored as a csv document and
+//! its sha256 hash is posted to the electoral log.
+
+use crate::postgres::area::get_areas_by_election_id;
+use crate::postgres::census_snapshot::{insert_census_snapshot, CensusSnapshot, SnapshotCensus};
+use crate::postgres::election::get_election_by_id;
+use crate::postgres::election_event::get_election_event_by_id;
+use crate::services::celery_app::get_celery_app;
+use crate::services::database::get_keycloak_pool;
+use crate::services::documents::upload_and_return_document;
+use crate::services::election::get_election_event_elections;
+use crate::services::election_event_board::get_election_event_board;
+use crate::services::election_event_status::{get_election_event_status, get_election_status};
+use crate::services::electoral_log::ElectoralLog;
+use crate::tasks::create_census_snapshot::create_census_snapshot_task;
+use anyhow::{anyhow, Context, Result};
+use deadpool_postgres::Transaction;
+use sequent_core::services::keycloak::get_event_realm;
+use sequent_core::types::keycloak::{AREA_ID_ATTR_NAME, AUTHORIZED_ELECTION_IDS_NAME};
+use sequent_core::util::temp_path::{generate_temp_file, get_file_size};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use strand::hash::hash_sha256;
+use strum_macros::{Display, EnumString};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+#[derive(Display, Debug, PartialEq, Eq, Clone, Copy, EnumString)]
+pub enum CensusSnapshotTrigger {
+    START_VOTING_PERIOD,
+    MANUAL,
+}
+
+/// An enabled voter of the election event realm.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotVoter {
+    pub id: String,
+    pub area_ids: Vec<String>,
+    /// Aliases of the elections the voter is authorized to vote in. Empty
+    /// means all of them.
+    pub authorized_elections: Vec<String>,
+}
+
+/// Election of the event with its alias and areas.
+#[derive(Debug, Clone)]
+pub struct SnapshotElection {
+    pub id: String,
+    pub alias: String,
+    pub area_ids: Vec<String>,
+}
+
+/// Counts the eligible voters per election and area, with the same criteria
+/// the tally uses to filter the ballots: enabled voters of the area who are
+/// either authorized to vote in the election or in all of them.
+pub fn compute_snapshot_census(
+    voters: &[SnapshotVoter],
+    elections: &[SnapshotElection],
+) -> SnapshotCensus {
+    let mut census = SnapshotCensus::new();
+    for election in elections {
+        let areas = census.entry(election.id.clone()).or_default();
+        for area_id in election.area_ids.iter() {
+            areas.insert(area_id.clone(), 0);
+        }
+        for voter in voters {
+            let is_authorized = voter.authorized_elections.is_empty()
+                || voter.authorized_elections.contains(&election.alias);
+            if !is_authorized {
+                continue;
+            }
+            for area_id in voter.area_ids.iter() {
+                if let Some(count) = areas.get_mut(area_id) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+    census
+}
+
+/// Serializes the roll as a csv file, with one row per voter ordered by id,
+/// so that the same roll always has the same hash.
+pub fn serialize_snapshot_voters(voters: &[SnapshotVoter]) -> Result<Vec<u8>> {
+    let mut sorted_voters = voters.to_vec();
+    sorted_voters.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(["voter_id", AREA_ID_ATTR_NAME, AUTHORIZED_ELECTION_IDS_NAME])?;
+    for voter in sorted_voters {
+        let mut area_ids = voter.area_ids.clone();
+        area_ids.sort();
+        let mut authorized_elections = voter.authorized_elections.clone();
+        authorized_elections.sort();
+        writer.write_record([
+            voter.id.clone(),
+            area_ids.join("|"),
+            authorized_elections.join("|"),
+        ])?;
+    }
+    writer
+        .into_inner()
+        .map_err(|err| anyhow!("Error writing census snapshot: {err}"))
+}
+
+/// Lowercase hex sha256 hash, the same format used for the integrity check of
+/// imported files.
+pub fn hash_snapshot(bytes: &[u8]) -> Result<String> {
+    let hash = hash_sha256(bytes).map_err(|err| anyhow!("Error hashing census snapshot: {err}"))?;
+    Ok(hex::encode(hash))
+}
+
+#[instrument(skip(keycloak_transaction), err)]
+async fn get_snapshot_voters(
+    keycloak_transaction: &Transaction<'_>,
+    realm: &str,
+) -> Result<Vec<SnapshotVoter>> {
+    let statement = keycloak_transaction
+        .prepare(
+            r#"
+            SELECT
+                u.id,
+                COALESCE(
+                    array_agg(DISTINCT ua_area.value) FILTER (WHERE ua_area.value IS NOT NULL),
+                    '{}'
+                ) AS area_ids,
+                COALESCE(
+                    array_agg(DISTINCT ua_elections.value) FILTER (WHERE ua_elections.value IS NOT NULL),
+                    '{}'
+                ) AS authorized_elections
+            FROM
+                user_entity AS u
+            JOIN
+                realm ra ON u.realm_id = ra.id
+            LEFT JOIN
+                user_attribute ua_area ON u.id = ua_area.user_id AND ua_area.name = $2
+            LEFT JOIN
+                user_attribute ua_elections ON u.id = ua_elections.user_id AND ua_elections.name = $3
+            WHERE
+                ra.name = $1 AND
+                u.enabled IS TRUE
+            GROUP BY
+                u.id
+            ORDER BY
+                u.id;
+            "#,
+        )
+        .await?;
+
+    let rows = keycloak_transaction
+        .query(
+            &statement,
+            &[&realm, &AREA_ID_ATTR_NAME, &AUTHORIZED_ELECTION_IDS_NAME],
+        )
+        .await
+        .map_err(|err| anyhow!("Error fetching the voters of the realm: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(SnapshotVoter {
+                id: row.try_get("id")?,
+                area_ids: row.try_get("area_ids")?,
+                authorized_elections: row.try_get("authorized_elections")?,
+            })
+        })
+        .collect()
+}
+
+#[instrument(skip(hasura_transaction), err)]
+async fn get_snapshot_elections(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<Vec<SnapshotElection>> {
+    let elections =
+        get_election_event_elections(hasura_transaction, tenant_id, election_event_id).await?;
+    let mut snapshot_elections = vec![];
+    for election in elections {
+        let areas = get_areas_by_election_id(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            &election.id,
+        )
+        .await?;
+        snapshot_elections.push(SnapshotElection {
+            id: election.id.clone(),
+            alias: election.alias.clone().unwrap_or_default(),
+            area_ids: areas.into_iter().map(|area| area.id).collect(),
+        });
+    }
+    Ok(snapshot_elections)
+}
+
+/// Takes a snapshot of the enabled voters of the election event, uploads it
+/// as a document, stores the census per election and area and posts the hash
+/// of the snapshot to the electoral log. With an `election_id`, only the
+/// census of that election is frozen.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn create_census_snapshot(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    election_id: Option<&str>,
+    trigger: CensusSnapshotTrigger,
+    user_id: Option<&str>,
+    username: Option<&str>,
+) -> Result<CensusSnapshot> {
+    let mut keycloak_db_client = get_keycloak_pool()
+        .await
+        .get()
+        .await
+        .map_err(|err| anyhow!("Error getting Keycloak DB pool: {err}"))?;
+    let keycloak_transaction = keycloak_db_client
+        .transaction()
+        .await
+        .map_err(|err| anyhow!("Error starting Keycloak transaction: {err}"))?;
+    keycloak_transaction
+        .simple_query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ;")
+        .await
+        .with_context(|| "can't set transaction isolation level")?;
+
+    let realm = get_event_realm(tenant_id, election_event_id);
+    let voters = get_snapshot_voters(&keycloak_transaction, &realm).await?;
+    let elections: Vec<SnapshotElection> =
+        get_snapshot_elections(hasura_transaction, tenant_id, election_event_id)
+            .await?
+            .into_iter()
+            .filter(|election| election_id.is_none() || election_id == Some(election.id.as_str()))
+            .collect();
+    if let Some(election_id) = election_id {
+        if elections.is_empty() {
+            return Err(anyhow!("Election {election_id} not found"));
+        }
+    }
+    let census = compute_snapshot_census(&voters, &elections);
+
+    let snapshot_bytes = serialize_snapshot_voters(&voters)?;
+    let hash = hash_snapshot(&snapshot_bytes)?;
+
+    let snapshot_id = Uuid::new_v4().to_string();
+    let mut snapshot_file = generate_temp_file("census-snapshot-", ".csv")?;
+    snapshot_file.write_all(&snapshot_bytes)?;
+    snapshot_file.flush()?;
+    let snapshot_path = snapshot_file.into_temp_path();
+    let snapshot_path_str = snapshot_path.to_string_lossy().to_string();
+    let snapshot_size = get_file_size(&snapshot_path_str)?;
+    let document = upload_and_return_document(
+        hasura_transaction,
+        &snapshot_path_str,
+        snapshot_size,
+        "text/csv",
+        tenant_id,
+        Some(election_event_id.to_string()),
+        &format!("census-snapshot-{snapshot_id}.csv"),
+        None,
+        false,
+    )
+    .await?;
+
+    let snapshot = insert_census_snapshot(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        election_id,
+        &snapshot_id,
+        user_id.map(str::to_string),
+        &trigger.to_string(),
+        &document.id,
+        &hash,
+        voters.len() as i64,
+        &census,
+    )
+    .await?;
+
+    let election_event =
+        get_election_event_by_id(hasura_transaction, tenant_id, election_event_id).await?;
+    let board_name = get_election_event_board(election_event.bulletin_board_reference.clone())
+        .with_context(|| "missing bulletin board")?;
+    let electoral_log = if let Some(user_id) = user_id {
+        ElectoralLog::for_admin_user(
+            hasura_transaction,
+            &board_name,
+            tenant_id,
+            election_event_id,
+            user_id,
+            username.map(str::to_string),
+            None,
+            None,
+        )
+        .await?
+    } else {
+        ElectoralLog::new(
+            hasura_transaction,
+            tenant_id,
+            Some(election_event_id),
+            &board_name,
+        )
+        .await?
+    };
+    electoral_log
+        .post_census_snapshot(
+            election_event_id.to_string(),
+            snapshot.id.clone(),
+            hash.clone(),
+            user_id.map(str::to_string),
+            username.map(str::to_string),
+        )
+        .await
+        .with_context(|| "error posting to the electoral log")?;
+
+    info!(
+        "Created census snapshot {} with {} voters and hash {hash}",
+        snapshot.id, snapshot.total_voters
+    );
+
+    Ok(snapshot)
+}
+
+/// Whether voting has started in any channel of the election, or of the
+/// election event without an election. Used to freeze the census only when
+/// the voting period opens, not when it's resumed after a pause.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn is_voting_started(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    election_id: Option<&str>,
+) -> Result<bool> {
+    let statuses = if let Some(election_id) = election_id {
+        get_election_by_id(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            election_id,
+        )
+        .await?
+        .and_then(|election| get_election_status(election.status))
+        .map(|status| {
+            [
+                status.voting_status,
+                status.kiosk_voting_status,
+                status.early_voting_status,
+            ]
+        })
+    } else {
+        let election_event =
+            get_election_event_by_id(hasura_transaction, tenant_id, election_event_id).await?;
+        get_election_event_status(election_event.status).map(|status| {
+            [
+                status.voting_status,
+                status.kiosk_voting_status,
+                status.early_voting_status,
+            ]
+        })
+    };
+
+    Ok(statuses
+        .map(|statuses| statuses.iter().any(|status| status.is_started()))
+        .unwrap_or(false))
+}
+
+/// Freezes the census in its own task, so that it only runs once the
+/// transaction that opened the voting period has been committed.
+#[instrument(err)]
+pub async fn enqueue_census_snapshot(
+    tenant_id: &str,
+    election_event_id: &str,
+    election_id: Option<&str>,
+) -> Result<()> {
+    let celery_app = get_celery_app().await;
+    let task = celery_app
+        .send_task(create_census_snapshot_task::new(
+            tenant_id.to_string(),
+            election_event_id.to_string(),
+            election_id.map(str::to_string),
+        ))
+        .await?;
+    info!("Sent task {}", task.task_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voter(id: &str, area_ids: &[&str], authorized_elections: &[&str]) -> SnapshotVoter {
+        SnapshotVoter {
+            id: id.to_string(),
+            area_ids: area_ids.iter().map(|val| val.to_string()).collect(),
+            authorized_elections: authorized_elections
+                .iter()
+                .map(|val| val.to_string())
+                .collect(),
+        }
+    }
+
+    fn election(id: &str, alias: &str, area_ids: &[&str]) -> SnapshotElection {
+        SnapshotElection {
+            id: id.to_string(),
+            alias: alias.to_string(),
+            area_ids: area_ids.iter().map(|val| val.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_compute_snapshot_census() {
+        let voters = vec![
+            voter("1", &["north"], &[]),
+            voter("2", &["north"], &["mayor"]),
+            voter("3", &["south"], &["council"]),
+            voter("4", &["east"], &[]),
+            voter("5", &[], &[]),
+        ];
+        let elections = vec![
+            election("e1", "mayor", &["north", "south"]),
+            election("e2", "council", &["south"]),
+        ];
+
+        let census = compute_snapshot_census(&voters, &elections);
+        assert_eq!(census["e1"]["north"], 2);
+        assert_eq!(census["e1"]["south"], 0);
+        assert_eq!(census["e2"]["south"], 1);
+        assert!(!census["e1"].contains_key("east"));
+    }
+
+    #[test]
+    fn test_snapshot_hash_is_stable() {
+        let voters = vec![
+            voter("2", &["north"], &["mayor", "council"]),
+            voter("1", &["north"], &[]),
+        ];
+        let reordered = vec![
+            voter("1", &["north"], &[]),
+            voter("2", &["north"], &["council", "mayor"]),
+        ];
+
+        let bytes = serialize_snapshot_voters(&voters).unwrap();
+        assert_eq!(
+            String::from_utf8(bytes.clone()).unwrap(),
+            "voter_id,area-id,authorized-election-ids\n1,north,\n2,north,council|mayor\n"
+        );
+        assert_eq!(
+            hash_snapshot(&bytes).unwrap(),
+            hash_snapshot(&serialize_snapshot_voters(&reordered).unwrap()).unwrap()
+        );
+    }
+}
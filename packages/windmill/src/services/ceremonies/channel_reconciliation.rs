@@ -0,0 +1,432 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::cast_votes::find_voters_with_cast_votes;
+use crate::services::users::{list_keycloak_voted_channel_markers, VotedChannelMarker};
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Transaction;
+use sequent_core::serialization::deserialize_with_path::deserialize_value;
+use sequent_core::services::keycloak::get_event_realm;
+use sequent_core::types::hasura::core::{
+    TallySessionContest, TallySessionContestAnnotations, TallySheet,
+};
+use sequent_core::types::keycloak::VOTED_CHANNEL_INTERNET_VALUE;
+use sequent_core::types::tally_sheets::VotingChannel;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use strum_macros::{Display, EnumString};
+use tracing::{event, instrument, Level};
+
+pub const CHANNEL_RECONCILIATION_FILE: &str = "channel_reconciliation.json";
+
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, EnumString, Display)]
+pub enum ChannelDiscrepancyKind {
+    /// More votes were counted across all channels than voters in the census.
+    IMPOSSIBLE_TURNOUT,
+    /// A voter marked as voted through another channel also voted online.
+    DOUBLE_VOTING,
+    /// A tally sheet reports a census different from the area census.
+    CENSUS_MISMATCH,
+    /// The votes of a tally sheet channel don't match the voters marked as
+    /// voted through that channel.
+    CHANNEL_MARKER_MISMATCH,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChannelDiscrepancy {
+    pub kind: ChannelDiscrepancyKind,
+    pub area_id: String,
+    pub contest_id: Option<String>,
+    pub channel: Option<String>,
+    pub expected: Option<u64>,
+    pub found: Option<u64>,
+    pub voter_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChannelTotals {
+    pub channel: String,
+    /// Highest number of votes reported by the channel tally sheets of any
+    /// contest of the area.
+    pub tally_sheet_votes: u64,
+    pub voter_markers: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AreaChannelReconciliation {
+    pub area_id: String,
+    pub census: u64,
+    pub online_cast_votes: u64,
+    pub channels: Vec<ChannelTotals>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChannelReconciliationReport {
+    pub areas: Vec<AreaChannelReconciliation>,
+    pub discrepancies: Vec<ChannelDiscrepancy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AreaCensus {
+    pub census: u64,
+    pub online_cast_votes: u64,
+}
+
+/// Census and online cast votes per area, from the tally session contest
+/// annotations. All contests of an area share the same annotations.
+pub fn get_area_census(
+    tally_session_contests: &[TallySessionContest],
+) -> Result<BTreeMap<String, AreaCensus>> {
+    let mut area_census: BTreeMap<String, AreaCensus> = BTreeMap::new();
+    for area_contest in tally_session_contests {
+        if area_census.contains_key(&area_contest.area_id) {
+            continue;
+        }
+        let Some(annotations) = area_contest.annotations.clone() else {
+            continue;
+        };
+        let annotations: TallySessionContestAnnotations = deserialize_value(annotations)?;
+        area_census.insert(
+            area_contest.area_id.clone(),
+            AreaCensus {
+                census: annotations.elegible_voters,
+                online_cast_votes: annotations.casted_ballots,
+            },
+        );
+    }
+    Ok(area_census)
+}
+
+fn normalize_channel(channel: &str) -> String {
+    channel.trim().to_uppercase()
+}
+
+/// Compares the census and online cast votes of each area with the tally
+/// sheets and the voted channel markers. `double_voters` are the voters with
+/// a non internet marker that also have an online cast vote.
+pub fn build_channel_reconciliation_report(
+    area_census: &BTreeMap<String, AreaCensus>,
+    tally_sheets: &[TallySheet],
+    markers: &[VotedChannelMarker],
+    double_voters: &HashSet<String>,
+) -> ChannelReconciliationReport {
+    let mut report = ChannelReconciliationReport::default();
+
+    // markers are only set by datafix events, skip the comparison otherwise
+    let has_markers = markers.iter().any(|marker| {
+        normalize_channel(&marker.channel) != normalize_channel(VOTED_CHANNEL_INTERNET_VALUE)
+    });
+
+    for (area_id, census) in area_census {
+        let area_sheets: Vec<&TallySheet> = tally_sheets
+            .iter()
+            .filter(|sheet| &sheet.area_id == area_id)
+            .collect();
+
+        // (contest_id, channel) -> total votes
+        let mut contest_channel_votes: BTreeMap<(String, String), u64> = BTreeMap::new();
+        for sheet in &area_sheets {
+            let Some(content) = &sheet.content else {
+                continue;
+            };
+            let channel = VotingChannel::from(sheet.channel.clone()).to_string();
+            *contest_channel_votes
+                .entry((sheet.contest_id.clone(), channel.clone()))
+                .or_insert(0) += content.total_votes.unwrap_or(0);
+
+            if let Some(sheet_census) = content.census {
+                if sheet_census != census.census {
+                    report.discrepancies.push(ChannelDiscrepancy {
+                        kind: ChannelDiscrepancyKind::CENSUS_MISMATCH,
+                        area_id: area_id.clone(),
+                        contest_id: Some(sheet.contest_id.clone()),
+                        channel: Some(channel),
+                        expected: Some(census.census),
+                        found: Some(sheet_census),
+                        voter_ids: vec![],
+                    });
+                }
+            }
+        }
+
+        let mut contest_votes: BTreeMap<String, u64> = BTreeMap::new();
+        let mut channel_votes: BTreeMap<String, u64> = BTreeMap::new();
+        for ((contest_id, channel), votes) in &contest_channel_votes {
+            *contest_votes.entry(contest_id.clone()).or_insert(0) += votes;
+            let channel_entry = channel_votes.entry(channel.clone()).or_insert(0);
+            *channel_entry = (*channel_entry).max(*votes);
+        }
+
+        for (contest_id, votes) in &contest_votes {
+            let turnout = census.online_cast_votes + votes;
+            if turnout > census.census {
+                report.discrepancies.push(ChannelDiscrepancy {
+                    kind: ChannelDiscrepancyKind::IMPOSSIBLE_TURNOUT,
+                    area_id: area_id.clone(),
+                    contest_id: Some(contest_id.clone()),
+                    channel: None,
+                    expected: Some(census.census),
+                    found: Some(turnout),
+                    voter_ids: vec![],
+                });
+            }
+        }
+
+        let mut channel_markers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for marker in markers
+            .iter()
+            .filter(|marker| marker.area_id.as_ref() == Some(area_id))
+        {
+            let channel = normalize_channel(&marker.channel);
+            if channel == normalize_channel(VOTED_CHANNEL_INTERNET_VALUE) {
+                continue;
+            }
+            channel_markers
+                .entry(channel)
+                .or_default()
+                .push(marker.user_id.clone());
+        }
+
+        let mut double_voting: Vec<String> = channel_markers
+            .values()
+            .flatten()
+            .filter(|user_id| double_voters.contains(*user_id))
+            .cloned()
+            .collect();
+        if !double_voting.is_empty() {
+            double_voting.sort();
+            report.discrepancies.push(ChannelDiscrepancy {
+                kind: ChannelDiscrepancyKind::DOUBLE_VOTING,
+                area_id: area_id.clone(),
+                contest_id: None,
+                channel: None,
+                expected: None,
+                found: Some(double_voting.len() as u64),
+                voter_ids: double_voting,
+            });
+        }
+
+        let channels: HashSet<String> = channel_votes
+            .keys()
+            .chain(channel_markers.keys())
+            .cloned()
+            .collect();
+        let mut channels: Vec<String> = channels.into_iter().collect();
+        channels.sort();
+
+        let mut totals = vec![];
+        for channel in channels {
+            let tally_sheet_votes = channel_votes.get(&channel).cloned().unwrap_or(0);
+            let voter_markers = channel_markers
+                .get(&channel)
+                .map(|user_ids| user_ids.len() as u64)
+                .unwrap_or(0);
+            if has_markers && tally_sheet_votes != voter_markers {
+                report.discrepancies.push(ChannelDiscrepancy {
+                    kind: ChannelDiscrepancyKind::CHANNEL_MARKER_MISMATCH,
+                    area_id: area_id.clone(),
+                    contest_id: None,
+                    channel: Some(channel.clone()),
+                    expected: Some(voter_markers),
+                    found: Some(tally_sheet_votes),
+                    voter_ids: vec![],
+                });
+            }
+            totals.push(ChannelTotals {
+                channel,
+                tally_sheet_votes,
+                voter_markers,
+            });
+        }
+
+        report.areas.push(AreaChannelReconciliation {
+            area_id: area_id.clone(),
+            census: census.census,
+            online_cast_votes: census.online_cast_votes,
+            channels: totals,
+        });
+    }
+
+    report
+}
+
+/// Builds the cross-channel reconciliation report of a tally and writes it
+/// in the tally output folder, so that it's included in the tally documents.
+#[instrument(skip_all, err)]
+pub async fn generate_channel_reconciliation_report(
+    hasura_transaction: &Transaction<'_>,
+    keycloak_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_contests: &[TallySessionContest],
+    tally_sheets: &[TallySheet],
+    base_tally_path: &Path,
+) -> Result<ChannelReconciliationReport> {
+    let area_census = get_area_census(tally_session_contests)?;
+
+    let realm = get_event_realm(tenant_id, election_event_id);
+    let markers = list_keycloak_voted_channel_markers(keycloak_transaction, &realm).await?;
+    let channel_voter_ids: Vec<String> = markers
+        .iter()
+        .filter(|marker| {
+            normalize_channel(&marker.channel) != normalize_channel(VOTED_CHANNEL_INTERNET_VALUE)
+        })
+        .map(|marker| marker.user_id.clone())
+        .collect();
+    let double_voters: HashSet<String> = find_voters_with_cast_votes(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &channel_voter_ids,
+    )
+    .await?
+    .into_iter()
+    .collect();
+
+    let report =
+        build_channel_reconciliation_report(&area_census, tally_sheets, &markers, &double_voters);
+    if !report.discrepancies.is_empty() {
+        event!(
+            Level::WARN,
+            "Found {} cross-channel discrepancies in the tally",
+            report.discrepancies.len()
+        );
+    }
+
+    let output_dir: PathBuf = base_tally_path.join("output");
+    fs::create_dir_all(&output_dir)?;
+    let report_path = output_dir.join(CHANNEL_RECONCILIATION_FILE);
+    fs::write(&report_path, serde_json::to_vec_pretty(&report)?)
+        .map_err(|err| anyhow!("Error writing {}: {err:?}", report_path.display()))?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sequent_core::types::tally_sheets::AreaContestResults;
+
+    fn census(census: u64, online_cast_votes: u64) -> BTreeMap<String, AreaCensus> {
+        BTreeMap::from([(
+            "area".to_string(),
+            AreaCensus {
+                census,
+                online_cast_votes,
+            },
+        )])
+    }
+
+    fn sheet(contest_id: &str, channel: &str, census: u64, total_votes: u64) -> TallySheet {
+        TallySheet {
+            id: format!("{contest_id}-{channel}"),
+            tenant_id: "tenant".to_string(),
+            election_event_id: "event".to_string(),
+            election_id: "election".to_string(),
+            contest_id: contest_id.to_string(),
+            area_id: "area".to_string(),
+            created_at: None,
+            last_updated_at: None,
+            labels: None,
+            annotations: None,
+            published_at: None,
+            published_by_user_id: None,
+            content: Some(AreaContestResults {
+                area_id: "area".to_string(),
+                contest_id: contest_id.to_string(),
+                total_votes: Some(total_votes),
+                total_valid_votes: None,
+                invalid_votes: None,
+                total_blank_votes: None,
+                census: Some(census),
+                candidate_results: HashMap::new(),
+            }),
+            channel: Some(channel.to_string()),
+            deleted_at: None,
+            created_by_user_id: "operator".to_string(),
+            version: None,
+            approved_at: None,
+            approved_by_user_id: None,
+        }
+    }
+
+    fn marker(user_id: &str, channel: &str) -> VotedChannelMarker {
+        VotedChannelMarker {
+            user_id: user_id.to_string(),
+            area_id: Some("area".to_string()),
+            channel: channel.to_string(),
+        }
+    }
+
+    fn kinds(report: &ChannelReconciliationReport) -> Vec<ChannelDiscrepancyKind> {
+        report
+            .discrepancies
+            .iter()
+            .map(|discrepancy| discrepancy.kind.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_consistent_channels() {
+        let report = build_channel_reconciliation_report(
+            &census(10, 5),
+            &[
+                sheet("contest", "PAPER", 10, 2),
+                sheet("contest", "POSTAL", 10, 1),
+            ],
+            &[
+                marker("a", "paper"),
+                marker("b", "PAPER"),
+                marker("c", "POSTAL"),
+            ],
+            &HashSet::new(),
+        );
+        assert!(report.discrepancies.is_empty());
+        assert_eq!(report.areas[0].channels.len(), 2);
+        assert_eq!(report.areas[0].channels[0].tally_sheet_votes, 2);
+        assert_eq!(report.areas[0].channels[0].voter_markers, 2);
+    }
+
+    #[test]
+    fn test_impossible_turnout_and_census_mismatch() {
+        let report = build_channel_reconciliation_report(
+            &census(10, 8),
+            &[sheet("contest", "PAPER", 12, 3)],
+            &[],
+            &HashSet::new(),
+        );
+        assert_eq!(
+            kinds(&report),
+            vec![
+                ChannelDiscrepancyKind::CENSUS_MISMATCH,
+                ChannelDiscrepancyKind::IMPOSSIBLE_TURNOUT,
+            ]
+        );
+        assert_eq!(report.discrepancies[1].found, Some(11));
+    }
+
+    #[test]
+    fn test_double_voting_and_marker_mismatch() {
+        let report = build_channel_reconciliation_report(
+            &census(10, 2),
+            &[sheet("contest", "PAPER", 10, 1)],
+            &[
+                marker("a", "PAPER"),
+                marker("b", "PAPER"),
+                marker("c", "Internet"),
+            ],
+            &HashSet::from(["b".to_string()]),
+        );
+        assert_eq!(
+            kinds(&report),
+            vec![
+                ChannelDiscrepancyKind::DOUBLE_VOTING,
+                ChannelDiscrepancyKind::CHANNEL_MARKER_MISMATCH,
+            ]
+        );
+        assert_eq!(report.discrepancies[0].voter_ids, vec!["b".to_string()]);
+    }
+}
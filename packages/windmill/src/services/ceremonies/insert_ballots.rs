@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 // use crate::hasura::trustee::get_trustees_by_name;
+use crate::postgres::census_snapshot::{get_latest_census_snapshot, CensusSnapshot};
 use crate::postgres::election::get_elections;
 use crate::postgres::trustee::get_trustees_by_name;
 use crate::services::cast_votes::{find_area_ballots, CastVote};
@@ -103,6 +104,25 @@ pub async fn insert_ballots_messages(
             .filter_map(|election| election.alias.map(|x| (election.id.clone(), x)))
             .collect();
 
+    // The census is taken from the last snapshot of the voter roll of each
+    // election if there's any, so that later changes to the voters don't
+    // change it
+    let mut census_snapshots: HashMap<String, Option<CensusSnapshot>> = HashMap::new();
+    for tally_session_contest in tally_session_contests.iter() {
+        let election_id = &tally_session_contest.election_id;
+        if census_snapshots.contains_key(election_id) {
+            continue;
+        }
+        let census_snapshot = get_latest_census_snapshot(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            Some(election_id),
+        )
+        .await?;
+        census_snapshots.insert(election_id.clone(), census_snapshot);
+    }
+
     // Collect all futures for parallel execution
     let mut tally_session_contests_updated = Vec::with_capacity(tally_session_contests.len());
 
@@ -122,6 +142,10 @@ pub async fn insert_ballots_messages(
             let election_ids_alias_clone = election_ids_alias.clone();
             let contest_encryption_policy_clone = contest_encryption_policy.clone();
             let realm_clone = realm.clone();
+            let census_snapshot_clone = census_snapshots
+                .get(&tally_session_contest.election_id)
+                .cloned()
+                .flatten();
             let board_messages_clone = Arc::clone(&board_messages); // board_messages also needs to be cloned if it's not Sync + Send
 
             let task = tokio::task::spawn_blocking(move || {
@@ -251,10 +275,24 @@ pub async fn insert_ballots_messages(
                         })
                         .collect::<Result<Vec<_>>>()?;
 
+                    let snapshot_census = census_snapshot_clone.as_ref().and_then(|snapshot| {
+                        snapshot
+                            .get_area_census(
+                                &tally_session_contest.election_id,
+                                &tally_session_contest.area_id,
+                            )
+                            .map(|census| (snapshot.id.clone(), census as u64))
+                    });
+                    let (elegible_voters, census_snapshot_id) = match snapshot_census {
+                        Some((snapshot_id, census)) => (census, Some(snapshot_id)),
+                        None => (elegible_voters, None),
+                    };
+
                     let annotations = TallySessionContestAnnotations {
                         elegible_voters,
                         ballots_without_voter,
                         casted_ballots,
+                        census_snapshot_id,
                     };
 
                     let annotations = serde_json::to_value(&annotations)?;
@@ -2,14 +2,17 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+pub mod channel_reconciliation;
 pub mod encrypter;
 pub mod insert_ballots;
 pub mod keys_ceremony;
+pub mod recount;
 pub mod renamer;
 pub mod result_documents;
 pub mod results;
 pub mod serialize_logs;
 pub mod tally_ceremony;
+pub mod tally_checkpoints;
 pub mod tally_progress;
 pub mod tally_session_error;
 pub mod velvet_tally;
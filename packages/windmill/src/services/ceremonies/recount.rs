@@ -0,0 +1,761 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::area::get_event_areas;
+use crate::postgres::document::get_document;
+use crate::postgres::election_event::get_election_event_by_id;
+use crate::postgres::results_area_contest_candidate::get_results_event_area_contest_candidates;
+use crate::postgres::results_contest_candidate::get_results_event_contest_candidates;
+use crate::postgres::tally_session::get_tally_session_by_id;
+use crate::postgres::tally_session_execution::{
+    get_last_tally_session_execution_for_update, insert_tally_session_execution,
+};
+use crate::postgres::tally_sheet::get_published_tally_sheets_by_event;
+use crate::services::ceremonies::results::populate_results_tables;
+use crate::services::ceremonies::serialize_logs::append_tally_recounted;
+use crate::services::ceremonies::tally_ceremony::get_tally_ceremony_status;
+use crate::services::ceremonies::velvet_tally::{call_velvet, write_tally_sheet_input};
+use crate::services::compress::{create_archive_from_folder, extract_archive_to_temp_dir};
+use crate::services::documents::{get_document_as_temp_file, upload_and_return_document};
+use crate::services::electoral_log::ElectoralLog;
+use crate::services::protocol_manager::get_event_board;
+use crate::services::tally_sheets::reconciliation::requires_double_entry;
+use crate::services::tally_sheets::validation::validate_tally_sheet;
+use anyhow::{anyhow, Context, Result};
+use deadpool_postgres::Transaction;
+use fs_extra::dir::{self, CopyOptions};
+use sequent_core::ballot::{Contest, ContestPresentation};
+use sequent_core::serialization::deserialize_with_path::deserialize_value;
+use sequent_core::types::ceremonies::{
+    ContestRecountOverride, RecountOverrides, TallyRecount, TallySessionDocuments, TallyType,
+};
+use sequent_core::types::hasura::core::{TallySessionExecution, TallySheet};
+use sequent_core::types::results::{ResultsAreaContestCandidate, ResultsContestCandidate};
+use sequent_core::util::temp_path::{generate_temp_file, get_file_size};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tempfile::tempdir;
+use tracing::{event, instrument, Level};
+use velvet::pipes::pipe_inputs::{
+    ElectionConfig, CONTEST_CONFIG_FILE, DEFAULT_DIR_CONFIGS, DEFAULT_DIR_TALLY_SHEETS,
+    ELECTION_CONFIG_FILE, PREFIX_TALLY_SHEET,
+};
+use walkdir::WalkDir;
+
+/// (election_id, contest_id, area_id, candidate_id)
+type CandidateResultKey = (String, String, Option<String>, String);
+/// (cast_votes, winning_position)
+type CandidateResultValue = (Option<i64>, Option<i64>);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CandidateResultComparison {
+    pub election_id: String,
+    pub contest_id: String,
+    pub area_id: Option<String>,
+    pub candidate_id: String,
+    pub original_cast_votes: Option<i64>,
+    pub recount_cast_votes: Option<i64>,
+    pub difference: i64,
+    pub original_winning_position: Option<i64>,
+    pub recount_winning_position: Option<i64>,
+    pub changed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecountComparisonReport {
+    pub tally_session_id: String,
+    pub original_execution_id: String,
+    pub original_results_event_id: String,
+    pub recount_execution_id: String,
+    pub recount_results_event_id: String,
+    pub overrides: RecountOverrides,
+    pub has_differences: bool,
+    /// Compared with `results_contest_candidate`.
+    pub contest_candidates: Vec<CandidateResultComparison>,
+    /// Compared with `results_area_contest_candidate`.
+    pub area_contest_candidates: Vec<CandidateResultComparison>,
+}
+
+/// Archives the velvet input and configuration of a completed tally, so that
+/// it can be recounted later with the same decrypted plaintexts. Returns the
+/// id of the snapshot document.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn freeze_tally_snapshot(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    base_tally_path: &Path,
+) -> Result<String> {
+    let snapshot_dir = tempdir()?;
+    dir::copy(
+        base_tally_path.join("input"),
+        snapshot_dir.path(),
+        &CopyOptions::new(),
+    )
+    .with_context(|| "Error copying the tally input into the snapshot")?;
+    fs::copy(
+        base_tally_path.join("velvet-config.json"),
+        snapshot_dir.path().join("velvet-config.json"),
+    )
+    .with_context(|| "Error copying the velvet config into the snapshot")?;
+
+    let (_temp_path, archive_path, archive_size) =
+        create_archive_from_folder(snapshot_dir.path(), true)
+            .map_err(|err| anyhow!("Error archiving tally snapshot: {err:?}"))?;
+    let document = upload_and_return_document(
+        hasura_transaction,
+        &archive_path,
+        archive_size,
+        "application/gzip",
+        tenant_id,
+        Some(election_event_id.to_string()),
+        &format!("tally-snapshot-{}.tar.gz", tally_session_id),
+        None,
+        false,
+    )
+    .await?;
+
+    Ok(document.id)
+}
+
+fn apply_contest_override(contest: &mut Contest, contest_override: &ContestRecountOverride) {
+    if let Some(winning_candidates_num) = contest_override.winning_candidates_num {
+        contest.winning_candidates_num = winning_candidates_num;
+    }
+    let has_policies = contest_override.invalid_vote_policy.is_some()
+        || contest_override.blank_vote_policy.is_some()
+        || contest_override.under_vote_policy.is_some()
+        || contest_override.over_vote_policy.is_some();
+    if !has_policies {
+        return;
+    }
+    let presentation = contest
+        .presentation
+        .get_or_insert_with(ContestPresentation::default);
+    if let Some(policy) = contest_override.invalid_vote_policy.clone() {
+        presentation.invalid_vote_policy = Some(policy);
+    }
+    if let Some(policy) = contest_override.blank_vote_policy {
+        presentation.blank_vote_policy = Some(policy);
+    }
+    if let Some(policy) = contest_override.under_vote_policy {
+        presentation.under_vote_policy = Some(policy);
+    }
+    if let Some(policy) = contest_override.over_vote_policy {
+        presentation.over_vote_policy = Some(policy);
+    }
+}
+
+/// Applies the contest overrides to the contest configs of a frozen velvet
+/// input, including the contests of its ballot styles, which are the ones
+/// used to decode the ballots. Returns the resulting contests by id.
+#[instrument(skip(contest_overrides), err)]
+pub fn apply_contest_overrides(
+    velvet_input_dir: &Path,
+    contest_overrides: &[ContestRecountOverride],
+) -> Result<HashMap<String, Contest>> {
+    let overrides_map: HashMap<String, &ContestRecountOverride> = contest_overrides
+        .iter()
+        .map(|contest_override| (contest_override.contest_id.clone(), contest_override))
+        .collect();
+    let mut contests: HashMap<String, Contest> = HashMap::new();
+
+    for entry in WalkDir::new(velvet_input_dir.join(DEFAULT_DIR_CONFIGS)).max_depth(3) {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name == CONTEST_CONFIG_FILE {
+            let mut contest: Contest = serde_json::from_str(&fs::read_to_string(entry.path())?)?;
+            if let Some(contest_override) = overrides_map.get(&contest.id) {
+                apply_contest_override(&mut contest, contest_override);
+                fs::write(entry.path(), serde_json::to_string(&contest)?)?;
+            }
+            contests.insert(contest.id.clone(), contest);
+        } else if file_name == ELECTION_CONFIG_FILE {
+            let mut election_config: ElectionConfig =
+                serde_json::from_str(&fs::read_to_string(entry.path())?)?;
+            for ballot_style in election_config.ballot_styles.iter_mut() {
+                for contest in ballot_style.contests.iter_mut() {
+                    if let Some(contest_override) = overrides_map.get(&contest.id) {
+                        apply_contest_override(contest, contest_override);
+                    }
+                }
+            }
+            fs::write(entry.path(), serde_json::to_string(&election_config)?)?;
+        }
+    }
+
+    if let Some(missing) = overrides_map
+        .keys()
+        .find(|contest_id| !contests.contains_key(*contest_id))
+    {
+        return Err(anyhow!(
+            "Contest {} not found in the tally snapshot",
+            missing
+        ));
+    }
+    Ok(contests)
+}
+
+/// Published tally sheets of the recounted contests, with the same checks the
+/// tally applies.
+#[instrument(skip_all, err)]
+pub fn get_recount_tally_sheets(
+    tally_sheets: &[TallySheet],
+    contests: &HashMap<String, Contest>,
+    excluded_tally_sheet_ids: &[String],
+) -> Result<Vec<TallySheet>> {
+    tally_sheets
+        .iter()
+        .filter(|tally_sheet| !excluded_tally_sheet_ids.contains(&tally_sheet.id))
+        .filter_map(|tally_sheet| {
+            contests
+                .get(&tally_sheet.contest_id)
+                .map(|contest| (tally_sheet, contest))
+        })
+        .map(|(tally_sheet, contest)| -> Result<TallySheet> {
+            if requires_double_entry(tally_sheet) && tally_sheet.approved_at.is_none() {
+                return Err(anyhow!(
+                    "Invalid tally sheet {}, double entry not approved",
+                    tally_sheet.id
+                ));
+            }
+            validate_tally_sheet(tally_sheet, contest)
+                .map_err(|err| anyhow!("Invalid tally sheet {}: {err:?}", tally_sheet.id))?;
+            Ok(tally_sheet.clone())
+        })
+        .collect()
+}
+
+#[instrument(skip(tally_sheets), err)]
+fn replace_tally_sheets(velvet_input_dir: &Path, tally_sheets: &[TallySheet]) -> Result<()> {
+    let tally_sheets_dir = velvet_input_dir.join(DEFAULT_DIR_TALLY_SHEETS);
+    if tally_sheets_dir.exists() {
+        fs::remove_dir_all(&tally_sheets_dir)?;
+    }
+    for tally_sheet in tally_sheets {
+        write_tally_sheet_input(velvet_input_dir, &tally_sheet.election_id, tally_sheet)?;
+    }
+    Ok(())
+}
+
+#[instrument(err)]
+fn remove_excluded_tally_sheets(
+    velvet_input_dir: &Path,
+    excluded_tally_sheet_ids: &[String],
+) -> Result<()> {
+    let excluded_names: HashSet<String> = excluded_tally_sheet_ids
+        .iter()
+        .map(|id| format!("{PREFIX_TALLY_SHEET}{id}"))
+        .collect();
+    let tally_sheets_dir = velvet_input_dir.join(DEFAULT_DIR_TALLY_SHEETS);
+    if excluded_names.is_empty() || !tally_sheets_dir.exists() {
+        return Ok(());
+    }
+    let mut excluded_dirs = vec![];
+    for entry in WalkDir::new(&tally_sheets_dir) {
+        let entry = entry?;
+        if entry.file_type().is_dir()
+            && excluded_names.contains(entry.file_name().to_string_lossy().as_ref())
+        {
+            excluded_dirs.push(entry.path().to_path_buf());
+        }
+    }
+    for excluded_dir in excluded_dirs {
+        fs::remove_dir_all(excluded_dir)?;
+    }
+    Ok(())
+}
+
+fn contest_candidate_results(
+    rows: &[ResultsContestCandidate],
+) -> BTreeMap<CandidateResultKey, CandidateResultValue> {
+    rows.iter()
+        .map(|row| {
+            (
+                (
+                    row.election_id.clone(),
+                    row.contest_id.clone(),
+                    None,
+                    row.candidate_id.clone(),
+                ),
+                (row.cast_votes, row.winning_position),
+            )
+        })
+        .collect()
+}
+
+fn area_contest_candidate_results(
+    rows: &[ResultsAreaContestCandidate],
+) -> BTreeMap<CandidateResultKey, CandidateResultValue> {
+    rows.iter()
+        .map(|row| {
+            (
+                (
+                    row.election_id.clone(),
+                    row.contest_id.clone(),
+                    Some(row.area_id.clone()),
+                    row.candidate_id.clone(),
+                ),
+                (row.cast_votes, row.winning_position),
+            )
+        })
+        .collect()
+}
+
+/// Candidate by candidate comparison, including candidates only present in
+/// one of the results.
+pub fn compare_candidate_results(
+    original: &BTreeMap<CandidateResultKey, CandidateResultValue>,
+    recount: &BTreeMap<CandidateResultKey, CandidateResultValue>,
+) -> Vec<CandidateResultComparison> {
+    let keys: BTreeSet<&CandidateResultKey> = original.keys().chain(recount.keys()).collect();
+    keys.into_iter()
+        .map(|key| {
+            let (election_id, contest_id, area_id, candidate_id) = key.clone();
+            let (original_cast_votes, original_winning_position) =
+                original.get(key).cloned().unwrap_or_default();
+            let (recount_cast_votes, recount_winning_position) =
+                recount.get(key).cloned().unwrap_or_default();
+            let difference =
+                recount_cast_votes.unwrap_or_default() - original_cast_votes.unwrap_or_default();
+            CandidateResultComparison {
+                election_id,
+                contest_id,
+                area_id,
+                candidate_id,
+                original_cast_votes,
+                recount_cast_votes,
+                difference,
+                original_winning_position,
+                recount_winning_position,
+                changed: original_cast_votes != recount_cast_votes
+                    || original_winning_position != recount_winning_position,
+            }
+        })
+        .collect()
+}
+
+#[instrument(skip(hasura_transaction), err)]
+async fn get_results_event_candidate_results(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    results_event_id: &str,
+) -> Result<(
+    BTreeMap<CandidateResultKey, CandidateResultValue>,
+    BTreeMap<CandidateResultKey, CandidateResultValue>,
+)> {
+    let contest_candidates = get_results_event_contest_candidates(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        results_event_id,
+    )
+    .await?;
+    let area_contest_candidates = get_results_event_area_contest_candidates(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        results_event_id,
+    )
+    .await?;
+    Ok((
+        contest_candidate_results(&contest_candidates),
+        area_contest_candidate_results(&area_contest_candidates),
+    ))
+}
+
+#[instrument(skip(hasura_transaction, report), err)]
+async fn upload_recount_report(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    report: &RecountComparisonReport,
+    document_id: &str,
+) -> Result<()> {
+    let mut report_file = generate_temp_file("tally-recount-", ".json")?;
+    report_file.write_all(&serde_json::to_vec_pretty(report)?)?;
+    report_file.flush()?;
+    let report_path = report_file.into_temp_path();
+    let report_path_str = report_path.to_string_lossy().to_string();
+    let report_size = get_file_size(&report_path_str)?;
+
+    upload_and_return_document(
+        hasura_transaction,
+        &report_path_str,
+        report_size,
+        "application/json",
+        tenant_id,
+        Some(election_event_id.to_string()),
+        &format!("tally-recount-{}.json", report.recount_execution_id),
+        Some(document_id.to_string()),
+        false,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Recounts a completed tally session from its frozen snapshot with the given
+/// overrides. The recount is stored as a new tally session execution and
+/// recorded in the electoral log, and its comparison with the original
+/// results is uploaded as the document `report_document_id`.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn execute_tally_recount(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    overrides: &RecountOverrides,
+    report_document_id: &str,
+    user_id: &str,
+    username: Option<String>,
+) -> Result<RecountComparisonReport> {
+    let tally_session = get_tally_session_by_id(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?;
+    if !tally_session.is_execution_completed {
+        return Err(anyhow!(
+            "Tally session {} is not completed",
+            tally_session_id
+        ));
+    }
+
+    // locked so that concurrent recounts don't base on the same execution
+    let last_execution = get_last_tally_session_execution_for_update(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?
+    .ok_or_else(|| anyhow!("Tally session {} has no executions", tally_session_id))?;
+    let last_status = get_tally_ceremony_status(last_execution.status.clone())?;
+    let documents: TallySessionDocuments = last_execution
+        .documents
+        .clone()
+        .map(deserialize_value)
+        .transpose()?
+        .unwrap_or_default();
+    let snapshot_document_id = documents.snapshot.clone().ok_or_else(|| {
+        anyhow!(
+            "Tally session {} has no frozen snapshot to recount",
+            tally_session_id
+        )
+    })?;
+
+    // recounts are always compared with the original count
+    let (original_execution_id, original_results_event_id) = match last_status.recount.clone() {
+        Some(recount) => (
+            recount.original_execution_id,
+            recount.original_results_event_id,
+        ),
+        None => (
+            last_execution.id.clone(),
+            last_execution
+                .results_event_id
+                .clone()
+                .ok_or_else(|| anyhow!("Missing results in execution {}", last_execution.id))?,
+        ),
+    };
+
+    let snapshot_document = get_document(
+        hasura_transaction,
+        tenant_id,
+        Some(election_event_id.to_string()),
+        &snapshot_document_id,
+    )
+    .await?
+    .ok_or_else(|| anyhow!("Can't find document {}", snapshot_document_id))?;
+    let snapshot_archive = get_document_as_temp_file(tenant_id, &snapshot_document).await?;
+    let base_tempdir = extract_archive_to_temp_dir(snapshot_archive.path(), true)
+        .map_err(|err| anyhow!("Error extracting tally snapshot: {err:?}"))?;
+    let base_tally_path = base_tempdir.path().to_path_buf();
+    let velvet_input_dir = base_tally_path.join("input");
+
+    let contests = apply_contest_overrides(&velvet_input_dir, &overrides.contests)?;
+    if overrides.use_current_tally_sheets {
+        let published_tally_sheets =
+            get_published_tally_sheets_by_event(hasura_transaction, tenant_id, election_event_id)
+                .await?;
+        let tally_sheets = get_recount_tally_sheets(
+            &published_tally_sheets,
+            &contests,
+            &overrides.excluded_tally_sheet_ids,
+        )?;
+        replace_tally_sheets(&velvet_input_dir, &tally_sheets)?;
+    }
+    remove_excluded_tally_sheets(&velvet_input_dir, &overrides.excluded_tally_sheet_ids)?;
+
+    let state = call_velvet(base_tally_path.clone(), "decode-ballots").await?;
+
+    let election_event =
+        get_election_event_by_id(hasura_transaction, tenant_id, election_event_id).await?;
+    let areas = get_event_areas(hasura_transaction, tenant_id, election_event_id).await?;
+    let tally_type = tally_session
+        .tally_type
+        .clone()
+        .map(|value| TallyType::try_from(value.as_str()).unwrap_or_default())
+        .unwrap_or_default();
+    let session_ids: Option<Vec<i64>> = last_execution
+        .session_ids
+        .clone()
+        .map(|ids| ids.into_iter().map(|id| id as i64).collect());
+    // results are only stored when the execution has more batches than the
+    // previous one, which is never the case for a recount
+    let previous_execution = TallySessionExecution {
+        session_ids: None,
+        ..last_execution.clone()
+    };
+    let (results_event_id, recount_documents) = populate_results_tables(
+        hasura_transaction,
+        &base_tally_path,
+        Some(state),
+        tenant_id,
+        election_event_id,
+        session_ids,
+        previous_execution,
+        &areas,
+        &election_event.get_default_language(),
+        tally_type,
+        false,
+    )
+    .await?;
+    let recount_results_event_id = results_event_id
+        .filter(|id| Some(id) != last_execution.results_event_id.as_ref())
+        .ok_or_else(|| anyhow!("The recount didn't generate any results"))?;
+
+    let mut recount_status = last_status.clone();
+    recount_status.logs = append_tally_recounted(&last_status.logs, &original_execution_id);
+    recount_status.recount = Some(TallyRecount {
+        original_execution_id: original_execution_id.clone(),
+        original_results_event_id: original_results_event_id.clone(),
+        overrides: overrides.clone(),
+        report_document_id: report_document_id.to_string(),
+    });
+    let recount_documents = TallySessionDocuments {
+        snapshot: Some(snapshot_document_id),
+        ..recount_documents.unwrap_or_default()
+    };
+    let recount_execution = insert_tally_session_execution(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        last_execution.current_message_id,
+        tally_session_id,
+        Some(recount_status),
+        Some(recount_results_event_id.clone()),
+        last_execution.session_ids.clone(),
+        Some(recount_documents),
+    )
+    .await?;
+
+    let (original_contest_candidates, original_area_contest_candidates) =
+        get_results_event_candidate_results(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            &original_results_event_id,
+        )
+        .await?;
+    let (recount_contest_candidates, recount_area_contest_candidates) =
+        get_results_event_candidate_results(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            &recount_results_event_id,
+        )
+        .await?;
+    let contest_candidates =
+        compare_candidate_results(&original_contest_candidates, &recount_contest_candidates);
+    let area_contest_candidates = compare_candidate_results(
+        &original_area_contest_candidates,
+        &recount_area_contest_candidates,
+    );
+    let has_differences = contest_candidates
+        .iter()
+        .chain(area_contest_candidates.iter())
+        .any(|comparison| comparison.changed);
+
+    let report = RecountComparisonReport {
+        tally_session_id: tally_session_id.to_string(),
+        original_execution_id,
+        original_results_event_id,
+        recount_execution_id: recount_execution.id.clone(),
+        recount_results_event_id,
+        overrides: overrides.clone(),
+        has_differences,
+        contest_candidates,
+        area_contest_candidates,
+    };
+    upload_recount_report(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &report,
+        report_document_id,
+    )
+    .await?;
+
+    let slug = std::env::var("ENV_SLUG").with_context(|| "missing env var ENV_SLUG")?;
+    let board_name = get_event_board(tenant_id, election_event_id, &slug);
+    let electoral_log = ElectoralLog::for_admin_user(
+        hasura_transaction,
+        &board_name,
+        tenant_id,
+        election_event_id,
+        user_id,
+        username.clone(),
+        tally_session.election_ids.clone(),
+        None,
+    )
+    .await?;
+    electoral_log
+        .post_tally_recount(
+            election_event_id.to_string(),
+            tally_session.election_ids.clone(),
+            tally_session_id.to_string(),
+            Some(user_id.to_string()),
+            username,
+        )
+        .await
+        .with_context(|| "error posting to the electoral log")?;
+    event!(
+        Level::INFO,
+        "Recounted tally session {}, has differences: {}",
+        tally_session_id,
+        has_differences
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sequent_core::ballot::InvalidVotePolicy;
+
+    fn write_contest(velvet_input_dir: &Path, contest: &Contest) {
+        let contest_dir = velvet_input_dir.join(format!(
+            "{DEFAULT_DIR_CONFIGS}/election__{}/contest__{}",
+            contest.election_id, contest.id
+        ));
+        fs::create_dir_all(&contest_dir).unwrap();
+        fs::write(
+            contest_dir.join(CONTEST_CONFIG_FILE),
+            serde_json::to_string(contest).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_apply_contest_overrides() {
+        let input_dir = tempdir().unwrap();
+        for id in ["contest-1", "contest-2"] {
+            write_contest(
+                input_dir.path(),
+                &Contest {
+                    id: id.to_string(),
+                    election_id: "election".to_string(),
+                    winning_candidates_num: 1,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let contests = apply_contest_overrides(
+            input_dir.path(),
+            &[ContestRecountOverride {
+                contest_id: "contest-2".to_string(),
+                invalid_vote_policy: Some(InvalidVotePolicy::NOT_ALLOWED),
+                winning_candidates_num: Some(2),
+                ..Default::default()
+            }],
+        )
+        .unwrap();
+
+        assert_eq!(contests.len(), 2);
+        assert_eq!(contests["contest-1"].winning_candidates_num, 1);
+        assert!(contests["contest-1"].presentation.is_none());
+        let contest_2 = &contests["contest-2"];
+        assert_eq!(contest_2.winning_candidates_num, 2);
+        assert_eq!(
+            contest_2
+                .presentation
+                .as_ref()
+                .and_then(|presentation| presentation.invalid_vote_policy.clone()),
+            Some(InvalidVotePolicy::NOT_ALLOWED)
+        );
+
+        // the override is persisted in the velvet input
+        let persisted = apply_contest_overrides(input_dir.path(), &[]).unwrap();
+        assert_eq!(persisted["contest-2"], *contest_2);
+    }
+
+    #[test]
+    fn test_apply_contest_overrides_unknown_contest() {
+        let input_dir = tempdir().unwrap();
+        let result = apply_contest_overrides(
+            input_dir.path(),
+            &[ContestRecountOverride {
+                contest_id: "missing".to_string(),
+                ..Default::default()
+            }],
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_counting_algorithm_override_rejected() {
+        let result = serde_json::from_value::<ContestRecountOverride>(serde_json::json!({
+            "contest_id": "contest",
+            "counting_algorithm": "borda",
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_compare_candidate_results() {
+        let key = |candidate_id: &str| -> CandidateResultKey {
+            (
+                "election".to_string(),
+                "contest".to_string(),
+                None,
+                candidate_id.to_string(),
+            )
+        };
+        let original = BTreeMap::from([
+            (key("a"), (Some(10), Some(1))),
+            (key("b"), (Some(8), Some(2))),
+            (key("c"), (Some(3), None)),
+        ]);
+        let recount = BTreeMap::from([
+            (key("a"), (Some(10), Some(1))),
+            (key("b"), (Some(11), Some(1))),
+            (key("d"), (Some(1), None)),
+        ]);
+
+        let comparisons = compare_candidate_results(&original, &recount);
+        let by_candidate: HashMap<String, CandidateResultComparison> = comparisons
+            .into_iter()
+            .map(|comparison| (comparison.candidate_id.clone(), comparison))
+            .collect();
+
+        assert_eq!(by_candidate.len(), 4);
+        assert!(!by_candidate["a"].changed);
+        assert_eq!(by_candidate["b"].difference, 3);
+        assert_eq!(by_candidate["b"].recount_winning_position, Some(1));
+        assert!(by_candidate["b"].changed);
+        assert_eq!(by_candidate["c"].recount_cast_votes, None);
+        assert_eq!(by_candidate["c"].difference, -3);
+        assert_eq!(by_candidate["d"].original_cast_votes, None);
+        assert_eq!(by_candidate["d"].difference, 1);
+    }
+}
@@ -131,6 +131,16 @@ pub fn append_tally_finished(current_logs: &Vec<Log>, election_ids: &Vec<String>
     sort_logs(&logs)
 }
 
+#[instrument(skip(current_logs))]
+pub fn append_tally_recounted(current_logs: &Vec<Log>, original_execution_id: &str) -> Vec<Log> {
+    let mut logs: Vec<Log> = current_logs.clone();
+    logs.push(Log {
+        created_date: ISO8601::to_string(&ISO8601::now()),
+        log_text: format!("Recounted Tally Ceremony execution {original_execution_id}"),
+    });
+    sort_logs(&logs)
+}
+
 #[instrument(skip(current_logs))]
 pub fn append_tally_updated(current_logs: &Vec<Log>, election_ids: &Vec<String>) -> Vec<Log> {
     let mut logs: Vec<Log> = current_logs.clone();
@@ -192,6 +192,8 @@ fn generate_initial_tally_status(
                 progress: 0.0,
             })
             .collect(),
+        checkpoints: vec![],
+        recount: None,
     }
 }
 
@@ -0,0 +1,657 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::document::get_document;
+use crate::postgres::tally_session_execution::{
+    get_last_tally_session_execution_for_update, update_tally_session_execution_status,
+};
+use crate::services::cast_votes::ElectionCastVotes;
+use crate::services::celery_app::get_celery_app;
+use crate::services::ceremonies::tally_progress::apply_checkpoints_progress;
+use crate::services::ceremonies::velvet_tally::{
+    call_velvet, prepare_velvet_tally, AreaContestDataType,
+};
+use crate::services::compress::{create_archive_from_folder, extract_archive_to_temp_dir};
+use crate::services::documents::{get_document_as_temp_file, upload_and_return_document};
+use crate::tasks::execute_tally_election::execute_tally_election;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Local};
+use deadpool_postgres::Transaction;
+use fs_extra::dir::{self, CopyOptions};
+use sequent_core::serialization::deserialize_with_path::deserialize_value;
+use sequent_core::services::date::ISO8601;
+use sequent_core::types::ceremonies::{
+    TallyCeremonyStatus, TallyCheckpoint, TallyCheckpointStatus, TallyElection, TallyType,
+};
+use sequent_core::types::hasura::core::{
+    Area, ElectionEvent, TallySession, TallySessionContest, TallySessionExecution, TallySheet,
+};
+use sequent_core::types::templates::PrintToPdfOptionsLocal;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+use tracing::{event, instrument, Level};
+use velvet::cli::state::State;
+use velvet::pipes::pipe_name::PipeNameOutputDir;
+
+/// Times a failed election tally is retried before failing the tally session.
+pub const MAX_TALLY_CHECKPOINT_ATTEMPTS: u32 = 3;
+/// Pending checkpoints not updated in this time are considered lost, for
+/// example because the worker running them died, and are dispatched again.
+pub const TALLY_CHECKPOINT_TIMEOUT_SECS: i64 = 4 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TallyCheckpointPlan {
+    /// Elections whose tally subtask has to be (re)dispatched.
+    pub to_dispatch: Vec<String>,
+    /// All elections have a successful checkpoint.
+    pub is_complete: bool,
+}
+
+/// Sorted batch ids per election.
+pub fn get_election_session_ids(
+    tally_session_contests: &[TallySessionContest],
+) -> BTreeMap<String, Vec<i64>> {
+    let mut election_session_ids: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for contest in tally_session_contests {
+        election_session_ids
+            .entry(contest.election_id.clone())
+            .or_default()
+            .push(contest.session_id as i64);
+    }
+    for session_ids in election_session_ids.values_mut() {
+        session_ids.sort();
+        session_ids.dedup();
+    }
+    election_session_ids
+}
+
+fn is_checkpoint_stale(checkpoint: &TallyCheckpoint, now: &DateTime<Local>) -> bool {
+    let Some(updated_at) = checkpoint
+        .updated_at
+        .as_ref()
+        .and_then(|updated_at| DateTime::parse_from_rfc3339(updated_at).ok())
+    else {
+        return true;
+    };
+    *now - updated_at.with_timezone(&Local) > Duration::seconds(TALLY_CHECKPOINT_TIMEOUT_SECS)
+}
+
+/// Decides which election tallies have to be dispatched. Successful
+/// checkpoints are reused while the batches of the election don't change, so
+/// that resuming a tally only runs the elections that failed.
+pub fn plan_tally_checkpoints(
+    checkpoints: &[TallyCheckpoint],
+    election_session_ids: &BTreeMap<String, Vec<i64>>,
+    now: &DateTime<Local>,
+) -> Result<TallyCheckpointPlan> {
+    let mut plan = TallyCheckpointPlan {
+        to_dispatch: vec![],
+        is_complete: !election_session_ids.is_empty(),
+    };
+    for (election_id, session_ids) in election_session_ids {
+        let checkpoint = checkpoints.iter().find(|checkpoint| {
+            &checkpoint.election_id == election_id && &checkpoint.session_ids == session_ids
+        });
+        let Some(checkpoint) = checkpoint else {
+            plan.is_complete = false;
+            plan.to_dispatch.push(election_id.clone());
+            continue;
+        };
+        let needs_retry = match checkpoint.status {
+            TallyCheckpointStatus::SUCCESS => continue,
+            TallyCheckpointStatus::PENDING => is_checkpoint_stale(checkpoint, now),
+            TallyCheckpointStatus::ERROR => true,
+        };
+        plan.is_complete = false;
+        if !needs_retry {
+            continue;
+        }
+        if checkpoint.attempts >= MAX_TALLY_CHECKPOINT_ATTEMPTS {
+            return Err(anyhow!(
+                "Tally of election {} failed after {} attempts: {}",
+                election_id,
+                checkpoint.attempts,
+                checkpoint.error.clone().unwrap_or_default()
+            ));
+        }
+        plan.to_dispatch.push(election_id.clone());
+    }
+    Ok(plan)
+}
+
+/// Replaces the checkpoint of the same election.
+pub fn set_tally_checkpoint(checkpoints: &mut Vec<TallyCheckpoint>, checkpoint: TallyCheckpoint) {
+    checkpoints.retain(|value| value.election_id != checkpoint.election_id);
+    checkpoints.push(checkpoint);
+    checkpoints.sort_by(|a, b| a.election_id.cmp(&b.election_id));
+}
+
+fn get_execution_status(execution: &TallySessionExecution) -> Result<TallyCeremonyStatus> {
+    execution
+        .status
+        .clone()
+        .map(deserialize_value::<TallyCeremonyStatus>)
+        .transpose()
+        .map_err(|err| anyhow!("Error deserializing tally session execution status: {err:?}"))
+        .map(|status| status.unwrap_or_default())
+}
+
+/// Copies the velvet output of each checkpoint into the output folder of the
+/// tally.
+#[instrument(skip(hasura_transaction, checkpoints), err)]
+async fn restore_tally_checkpoints(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    checkpoints: &[TallyCheckpoint],
+    base_tally_path: &Path,
+) -> Result<()> {
+    let output_dir = base_tally_path.join("output");
+    fs::create_dir_all(&output_dir)?;
+    let mut copy_options = CopyOptions::new();
+    copy_options.content_only = true;
+    copy_options.overwrite = true;
+
+    for checkpoint in checkpoints {
+        let document_id = checkpoint.output_document_id.clone().ok_or(anyhow!(
+            "Missing output document in checkpoint of election {}",
+            checkpoint.election_id
+        ))?;
+        let document = get_document(
+            hasura_transaction,
+            tenant_id,
+            Some(election_event_id.to_string()),
+            &document_id,
+        )
+        .await?
+        .ok_or_else(|| anyhow!("Can't find document {}", document_id))?;
+        let archive = get_document_as_temp_file(tenant_id, &document).await?;
+        let extracted = extract_archive_to_temp_dir(archive.path(), true)
+            .map_err(|err| anyhow!("Error extracting checkpoint {document_id}: {err:?}"))?;
+        dir::copy(extracted.path(), &output_dir, &copy_options)
+            .with_context(|| format!("Error restoring checkpoint {document_id}"))?;
+    }
+    Ok(())
+}
+
+/// Runs the tally with one subtask per election. Elections without a
+/// successful checkpoint are dispatched to the tally queue and `None` is
+/// returned until all of them finish, then their outputs are merged and only
+/// the database generation runs here.
+#[instrument(skip_all, err)]
+pub async fn run_checkpointed_velvet_tally(
+    base_tally_path: PathBuf,
+    area_contests: &Vec<AreaContestDataType>,
+    cast_votes_count: &Vec<ElectionCastVotes>,
+    tally_sheets: &Vec<TallySheet>,
+    report_content_template: Option<String>,
+    report_system_template: String,
+    pdf_options: Option<PrintToPdfOptionsLocal>,
+    areas: &Vec<Area>,
+    hasura_transaction: &Transaction<'_>,
+    election_event: &ElectionEvent,
+    tally_session: &TallySession,
+    tally_type: TallyType,
+    elections_status: &mut [TallyElection],
+) -> Result<(Option<State>, Vec<TallyCheckpoint>)> {
+    let tenant_id = &tally_session.tenant_id;
+    let election_event_id = &tally_session.election_event_id;
+
+    // lock the execution so that subtasks can't update it meanwhile
+    let execution = get_last_tally_session_execution_for_update(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &tally_session.id,
+    )
+    .await?
+    .ok_or(anyhow!("Missing tally session execution"))?;
+    let mut execution_status = get_execution_status(&execution)?;
+    let mut checkpoints = execution_status.checkpoints.clone();
+
+    let tally_session_contests: Vec<TallySessionContest> = area_contests
+        .iter()
+        .map(|area_contest| area_contest.last_tally_session_execution.clone())
+        .collect();
+    let election_session_ids = get_election_session_ids(&tally_session_contests);
+    let plan = plan_tally_checkpoints(&checkpoints, &election_session_ids, &ISO8601::now())?;
+
+    if plan.is_complete {
+        event!(
+            Level::INFO,
+            "All {} election checkpoints are ready, merging them",
+            election_session_ids.len()
+        );
+        prepare_velvet_tally(
+            base_tally_path.clone(),
+            area_contests,
+            cast_votes_count,
+            tally_sheets,
+            report_content_template,
+            report_system_template,
+            pdf_options,
+            areas,
+            hasura_transaction,
+            election_event,
+            tally_session,
+            tally_type,
+        )
+        .await?;
+        let election_checkpoints: Vec<TallyCheckpoint> = checkpoints
+            .iter()
+            .filter(|checkpoint| election_session_ids.contains_key(&checkpoint.election_id))
+            .cloned()
+            .collect();
+        restore_tally_checkpoints(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            &election_checkpoints,
+            &base_tally_path,
+        )
+        .await?;
+        let state = call_velvet(base_tally_path.clone(), "gen-db").await?;
+        return Ok((Some(state), checkpoints));
+    }
+
+    let celery_app = get_celery_app().await;
+    for election_id in &plan.to_dispatch {
+        let session_ids = election_session_ids
+            .get(election_id)
+            .cloned()
+            .unwrap_or_default();
+        let previous_attempts = checkpoints
+            .iter()
+            .find(|checkpoint| {
+                &checkpoint.election_id == election_id && checkpoint.session_ids == session_ids
+            })
+            .map(|checkpoint| checkpoint.attempts)
+            .unwrap_or(0);
+
+        let election_area_contests: Vec<AreaContestDataType> = area_contests
+            .iter()
+            .filter(|area_contest| &area_contest.contest.election_id == election_id)
+            .cloned()
+            .collect();
+        let election_cast_votes: Vec<ElectionCastVotes> = cast_votes_count
+            .iter()
+            .filter(|cast_votes| &cast_votes.election_id == election_id)
+            .cloned()
+            .collect();
+        let election_tally_sheets: Vec<TallySheet> = tally_sheets
+            .iter()
+            .filter(|tally_sheet| &tally_sheet.election_id == election_id)
+            .cloned()
+            .collect();
+
+        let election_tempdir = tempdir()?;
+        prepare_velvet_tally(
+            election_tempdir.path().to_path_buf(),
+            &election_area_contests,
+            &election_cast_votes,
+            &election_tally_sheets,
+            report_content_template.clone(),
+            report_system_template.clone(),
+            pdf_options.clone(),
+            areas,
+            hasura_transaction,
+            election_event,
+            tally_session,
+            tally_type.clone(),
+        )
+        .await?;
+        let (_temp_path, archive_path, archive_size) =
+            create_archive_from_folder(election_tempdir.path(), true)
+                .map_err(|err| anyhow!("Error archiving tally input: {err:?}"))?;
+        let input_document = upload_and_return_document(
+            hasura_transaction,
+            &archive_path,
+            archive_size,
+            "application/gzip",
+            tenant_id,
+            Some(election_event_id.to_string()),
+            &format!("tally-checkpoint-input-{}.tar.gz", election_id),
+            None,
+            false,
+        )
+        .await?;
+
+        set_tally_checkpoint(
+            &mut checkpoints,
+            TallyCheckpoint {
+                election_id: election_id.clone(),
+                session_ids,
+                status: TallyCheckpointStatus::PENDING,
+                attempts: previous_attempts + 1,
+                input_document_id: Some(input_document.id.clone()),
+                output_document_id: None,
+                error: None,
+                updated_at: Some(ISO8601::to_string(&ISO8601::now())),
+            },
+        );
+
+        // The subtask waits for this transaction to commit, as it locks the
+        // execution before reading the checkpoint.
+        let task = celery_app
+            .send_task(execute_tally_election::new(
+                tenant_id.clone(),
+                election_event_id.clone(),
+                tally_session.id.clone(),
+                election_id.clone(),
+                input_document.id.clone(),
+            ))
+            .await
+            .map_err(|err| anyhow!("Error sending execute_tally_election task: {err:?}"))?;
+        event!(
+            Level::INFO,
+            "Sent execute_tally_election task {} for election {}",
+            task.task_id,
+            election_id
+        );
+    }
+
+    apply_checkpoints_progress(elections_status, &checkpoints);
+    execution_status.elections_status = elections_status.to_vec();
+    execution_status.checkpoints = checkpoints.clone();
+    update_tally_session_execution_status(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &execution.id,
+        &execution_status,
+    )
+    .await?;
+
+    Ok((None, checkpoints))
+}
+
+/// Returns the pending checkpoint of an election, if it still expects the
+/// given input.
+fn find_pending_checkpoint(
+    status: &TallyCeremonyStatus,
+    election_id: &str,
+    input_document_id: &str,
+) -> Option<TallyCheckpoint> {
+    status
+        .checkpoints
+        .iter()
+        .find(|checkpoint| {
+            checkpoint.election_id == election_id
+                && checkpoint.status == TallyCheckpointStatus::PENDING
+                && checkpoint.input_document_id.as_deref() == Some(input_document_id)
+        })
+        .cloned()
+}
+
+/// Downloads the input of a pending election checkpoint. Returns `None` if
+/// the checkpoint is no longer pending, so that duplicated subtasks are
+/// no-ops.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_tally_checkpoint_input(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    election_id: &str,
+    input_document_id: &str,
+) -> Result<Option<tempfile::NamedTempFile>> {
+    let execution = get_last_tally_session_execution_for_update(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?
+    .ok_or(anyhow!("Missing tally session execution"))?;
+    let status = get_execution_status(&execution)?;
+    if find_pending_checkpoint(&status, election_id, input_document_id).is_none() {
+        return Ok(None);
+    }
+
+    let document = get_document(
+        hasura_transaction,
+        tenant_id,
+        Some(election_event_id.to_string()),
+        input_document_id,
+    )
+    .await?
+    .ok_or_else(|| anyhow!("Can't find document {}", input_document_id))?;
+    Ok(Some(get_document_as_temp_file(tenant_id, &document).await?))
+}
+
+/// Runs velvet on the input of an election checkpoint and returns the path
+/// of the archived output. The database is generated once all checkpoints
+/// are merged, so it's not included.
+#[instrument(err)]
+pub async fn run_tally_checkpoint(
+    input_archive: &Path,
+) -> Result<(tempfile::TempPath, String, u64)> {
+    let tally_dir = extract_archive_to_temp_dir(input_archive, true)
+        .map_err(|err| anyhow!("Error extracting tally input: {err:?}"))?;
+    call_velvet(tally_dir.path().to_path_buf(), "decode-ballots").await?;
+
+    let output_dir = tally_dir.path().join("output");
+    let database_dir = output_dir.join(PipeNameOutputDir::GenerateDatabase.as_ref());
+    if database_dir.exists() {
+        fs::remove_dir_all(&database_dir)?;
+    }
+    create_archive_from_folder(&output_dir, true)
+        .map_err(|err| anyhow!("Error archiving tally output: {err:?}"))
+}
+
+/// Stores the result of an election checkpoint in the last tally session
+/// execution.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn record_tally_checkpoint(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    election_id: &str,
+    input_document_id: &str,
+    output_document_id: Option<String>,
+    error: Option<String>,
+) -> Result<()> {
+    let execution = get_last_tally_session_execution_for_update(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?
+    .ok_or(anyhow!("Missing tally session execution"))?;
+    let mut status = get_execution_status(&execution)?;
+    let Some(mut checkpoint) = find_pending_checkpoint(&status, election_id, input_document_id)
+    else {
+        event!(
+            Level::WARN,
+            "Checkpoint of election {} is no longer pending, discarding result",
+            election_id
+        );
+        return Ok(());
+    };
+
+    checkpoint.status = match output_document_id {
+        Some(_) => TallyCheckpointStatus::SUCCESS,
+        None => TallyCheckpointStatus::ERROR,
+    };
+    checkpoint.output_document_id = output_document_id;
+    checkpoint.error = error;
+    checkpoint.updated_at = Some(ISO8601::to_string(&ISO8601::now()));
+    set_tally_checkpoint(&mut status.checkpoints, checkpoint);
+    apply_checkpoints_progress(&mut status.elections_status, &status.checkpoints);
+
+    update_tally_session_execution_status(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &execution.id,
+        &status,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ceremonies::tally_progress::TALLYING_PROGRESS;
+    use sequent_core::types::ceremonies::TallyElectionStatus;
+
+    fn contest(election_id: &str, session_id: i32) -> TallySessionContest {
+        TallySessionContest {
+            id: format!("{election_id}-{session_id}"),
+            tenant_id: "tenant".to_string(),
+            election_event_id: "event".to_string(),
+            area_id: "area".to_string(),
+            contest_id: None,
+            session_id,
+            created_at: None,
+            last_updated_at: None,
+            labels: None,
+            annotations: None,
+            tally_session_id: "tally-session".to_string(),
+            election_id: election_id.to_string(),
+        }
+    }
+
+    fn checkpoint(
+        election_id: &str,
+        session_ids: Vec<i64>,
+        status: TallyCheckpointStatus,
+        attempts: u32,
+        updated_at: &DateTime<Local>,
+    ) -> TallyCheckpoint {
+        TallyCheckpoint {
+            election_id: election_id.to_string(),
+            session_ids,
+            status,
+            attempts,
+            updated_at: Some(ISO8601::to_string(updated_at)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_tally_checkpoints() {
+        let now = ISO8601::now();
+        let election_session_ids = get_election_session_ids(&[
+            contest("a", 2),
+            contest("a", 1),
+            contest("b", 3),
+            contest("c", 4),
+            contest("d", 5),
+        ]);
+        assert_eq!(election_session_ids.get("a"), Some(&vec![1, 2]));
+
+        let checkpoints = vec![
+            checkpoint("a", vec![1, 2], TallyCheckpointStatus::SUCCESS, 1, &now),
+            checkpoint("b", vec![3], TallyCheckpointStatus::PENDING, 1, &now),
+            checkpoint("c", vec![4], TallyCheckpointStatus::ERROR, 1, &now),
+            // new batches invalidate the checkpoint
+            checkpoint("d", vec![], TallyCheckpointStatus::SUCCESS, 1, &now),
+        ];
+        let plan = plan_tally_checkpoints(&checkpoints, &election_session_ids, &now).unwrap();
+        assert!(!plan.is_complete);
+        assert_eq!(plan.to_dispatch, vec!["c".to_string(), "d".to_string()]);
+
+        let stale = now - Duration::seconds(TALLY_CHECKPOINT_TIMEOUT_SECS + 1);
+        let checkpoints = vec![checkpoint(
+            "b",
+            vec![3],
+            TallyCheckpointStatus::PENDING,
+            1,
+            &stale,
+        )];
+        let plan = plan_tally_checkpoints(
+            &checkpoints,
+            &get_election_session_ids(&[contest("b", 3)]),
+            &now,
+        )
+        .unwrap();
+        assert_eq!(plan.to_dispatch, vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_plan_tally_checkpoints_complete_and_exhausted() {
+        let now = ISO8601::now();
+        let election_session_ids = get_election_session_ids(&[contest("a", 1)]);
+        let success = vec![checkpoint(
+            "a",
+            vec![1],
+            TallyCheckpointStatus::SUCCESS,
+            2,
+            &now,
+        )];
+        let plan = plan_tally_checkpoints(&success, &election_session_ids, &now).unwrap();
+        assert!(plan.is_complete);
+        assert!(plan.to_dispatch.is_empty());
+
+        let failed = vec![checkpoint(
+            "a",
+            vec![1],
+            TallyCheckpointStatus::ERROR,
+            MAX_TALLY_CHECKPOINT_ATTEMPTS,
+            &now,
+        )];
+        assert!(plan_tally_checkpoints(&failed, &election_session_ids, &now).is_err());
+    }
+
+    #[test]
+    fn test_apply_checkpoints_progress() {
+        let now = ISO8601::now();
+        let mut elections_status = vec![
+            TallyElection {
+                election_id: "a".to_string(),
+                status: TallyElectionStatus::SUCCESS,
+                progress: 100.0,
+            },
+            TallyElection {
+                election_id: "b".to_string(),
+                status: TallyElectionStatus::SUCCESS,
+                progress: 100.0,
+            },
+            TallyElection {
+                election_id: "c".to_string(),
+                status: TallyElectionStatus::DECRYPTING,
+                progress: 40.0,
+            },
+            TallyElection {
+                election_id: "d".to_string(),
+                status: TallyElectionStatus::TALLYING,
+                progress: TALLYING_PROGRESS,
+            },
+        ];
+        let mut checkpoints = vec![];
+        set_tally_checkpoint(
+            &mut checkpoints,
+            checkpoint("a", vec![1], TallyCheckpointStatus::PENDING, 1, &now),
+        );
+        set_tally_checkpoint(
+            &mut checkpoints,
+            checkpoint("a", vec![1], TallyCheckpointStatus::SUCCESS, 1, &now),
+        );
+        assert_eq!(checkpoints.len(), 1);
+        set_tally_checkpoint(
+            &mut checkpoints,
+            checkpoint("d", vec![4], TallyCheckpointStatus::ERROR, 1, &now),
+        );
+
+        apply_checkpoints_progress(&mut elections_status, &checkpoints);
+        assert_eq!(elections_status[0].status, TallyElectionStatus::SUCCESS);
+        assert_eq!(elections_status[1].status, TallyElectionStatus::TALLYING);
+        assert_eq!(elections_status[1].progress, TALLYING_PROGRESS);
+        assert_eq!(elections_status[2].status, TallyElectionStatus::DECRYPTING);
+        assert_eq!(elections_status[3].status, TallyElectionStatus::ERROR);
+
+        // the progress reported while tallying is updated once it succeeds
+        set_tally_checkpoint(
+            &mut checkpoints,
+            checkpoint("d", vec![4], TallyCheckpointStatus::SUCCESS, 1, &now),
+        );
+        apply_checkpoints_progress(&mut elections_status, &checkpoints);
+        assert_eq!(elections_status[3].status, TallyElectionStatus::SUCCESS);
+        assert_eq!(elections_status[3].progress, 100.0);
+    }
+}
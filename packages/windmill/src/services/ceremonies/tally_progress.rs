@@ -4,12 +4,16 @@
 use anyhow::{anyhow, Context, Result};
 use b3::messages::{artifact::Plaintexts, message::Message, statement::StatementType};
 use sequent_core::types::{
-    ceremonies::{TallyElection, TallyElectionStatus},
+    ceremonies::{TallyCheckpoint, TallyCheckpointStatus, TallyElection, TallyElectionStatus},
     hasura::core::{TallySession, TallySessionContest},
 };
 use std::collections::{HashMap, HashSet};
 use tracing::{event, instrument, Level};
 
+/// Progress shown for elections with all their plaintexts while their
+/// checkpoint is being tallied.
+pub const TALLYING_PROGRESS: f64 = 90.0;
+
 #[instrument(skip_all)]
 fn get_session_ids_by_type(messages: &Vec<Message>, kind: StatementType) -> Vec<i64> {
     let mut plaintext_batch_ids: Vec<i64> = messages
@@ -131,3 +135,40 @@ pub async fn generate_tally_progress(
     tally_elections_status.sort_by_key(|status| status.election_id.clone());
     Ok(tally_elections_status)
 }
+
+/// Elections with all their plaintexts are only finished once their
+/// checkpoint has been tallied, and fail when tallying it fails.
+pub fn apply_checkpoints_progress(
+    elections_status: &mut [TallyElection],
+    checkpoints: &[TallyCheckpoint],
+) {
+    for election_status in elections_status.iter_mut() {
+        let has_plaintexts = matches!(
+            election_status.status,
+            TallyElectionStatus::SUCCESS
+                | TallyElectionStatus::TALLYING
+                | TallyElectionStatus::ERROR
+        );
+        if !has_plaintexts {
+            continue;
+        }
+        let checkpoint_status = checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.election_id == election_status.election_id)
+            .map(|checkpoint| &checkpoint.status);
+        match checkpoint_status {
+            Some(TallyCheckpointStatus::SUCCESS) => {
+                election_status.status = TallyElectionStatus::SUCCESS;
+                election_status.progress = 100.0;
+            }
+            Some(TallyCheckpointStatus::ERROR) => {
+                election_status.status = TallyElectionStatus::ERROR;
+                election_status.progress = TALLYING_PROGRESS;
+            }
+            _ => {
+                election_status.status = TallyElectionStatus::TALLYING;
+                election_status.progress = TALLYING_PROGRESS;
+            }
+        }
+    }
+}
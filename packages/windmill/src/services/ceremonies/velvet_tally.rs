@@ -26,7 +26,7 @@ use rusqlite::Connection;
 use sequent_core::ballot::{
     Annotations, BallotStyle, Contest, ContestEncryptionPolicy, DecodedBallotsInclusionPolicy,
 };
-use sequent_core::ballot_codec::PlaintextCodec;
+use sequent_core::ballot_codec::{get_plaintext_codec_version, PlaintextCodec};
 use sequent_core::serialization::deserialize_with_path::deserialize_value;
 use sequent_core::services::area_tree::TreeNodeArea;
 use sequent_core::services::s3;
@@ -40,7 +40,8 @@ use sequent_core::sqlite::election::create_election_sqlite;
 use sequent_core::sqlite::election_event::create_election_event_sqlite;
 use sequent_core::types::ceremonies::TallyType;
 use sequent_core::types::hasura::core::{
-    Area, Election, ElectionEvent, TallySession, TallySessionContest, TallySheet,
+    Area, AreaContest, Contest as ContestHasura, Election, ElectionEvent, TallySession,
+    TallySessionContest, TallySheet,
 };
 use sequent_core::types::scheduled_event::ScheduledEvent;
 use sequent_core::types::templates::{PrintToPdfOptionsLocal, ReportExtraConfig, SendTemplateBody};
@@ -62,7 +63,7 @@ use velvet::cli::CliRun;
 use velvet::config::ballot_images_config::PipeConfigBallotImages;
 use velvet::config::generate_reports::PipeConfigGenerateReports;
 use velvet::pipes::generate_db::{PipeConfigGenerateDatabase, DATABASE_FILENAME};
-use velvet::pipes::pipe_inputs::{AreaConfig, ElectionConfig};
+use velvet::pipes::pipe_inputs::{format_ballot_line, AreaConfig, ElectionConfig};
 use velvet::pipes::pipe_inputs::{
     DEFAULT_DIR_BALLOTS, DEFAULT_DIR_CONFIGS, DEFAULT_DIR_DATABASE, DEFAULT_DIR_TALLY_SHEETS,
 };
@@ -79,6 +80,9 @@ pub struct AreaContestDataType {
     pub auditable_votes: u64,
 }
 
+/// Decodes the plaintexts into the lines of the velvet ballots file. Each line
+/// is tagged with the codec version of its plaintext, as ballots cast before
+/// the election was republished may use a different one than the ballot style.
 #[instrument(skip_all)]
 fn decode_plaintexts_to_biguints(
     plaintexts: &Vec<<RistrettoCtx as Ctx>::P>,
@@ -92,17 +96,18 @@ fn decode_plaintexts_to_biguints(
                 .map(|b| format!("{:02X}", b))
                 .collect::<Vec<String>>()
                 .join(" ");
+            let codec_version = get_plaintext_codec_version(plaintext);
             let biguint = contest.decode_plaintext_contest_to_biguint(plaintext);
 
             match biguint {
                 Ok(v) => {
-                    let biguit_str = v.to_str_radix(10);
+                    let ballot_line = format_ballot_line(codec_version, &v);
                     event!(
                         Level::INFO,
-                        "Decoding plaintext {plaintext_format} into string '{biguit_str}'"
+                        "Decoding plaintext {plaintext_format} into string '{ballot_line}'"
                     );
 
-                    Some(biguit_str)
+                    Some(ballot_line)
                 }
                 Err(e) => {
                     event!(
@@ -214,26 +219,36 @@ pub fn prepare_tally_for_area_contest(
     )?;
 
     //// create tally sheets files
-    if relevant_sheets.len() > 0 {
-        for tally_sheet in relevant_sheets {
-            let Some(content) = tally_sheet.content.clone() else {
-                continue;
-            };
-            //// create tally sheets folder
-            let tally_sheet_path: PathBuf = velvet_input_dir.join(format!(
-                "{DEFAULT_DIR_TALLY_SHEETS}/election__{}/contest__{}/area__{}/tally_sheet__{}",
-                election_id, content.contest_id, content.area_id, tally_sheet.id
-            ));
-            fs::create_dir_all(&tally_sheet_path)?;
-            let tally_sheet_file_path: PathBuf = tally_sheet_path.join("tally-sheet.json");
-            let mut tally_sheet_file = fs::File::create(tally_sheet_file_path)?;
-            writeln!(tally_sheet_file, "{}", serde_json::to_string(&tally_sheet)?)?;
-        }
+    for tally_sheet in relevant_sheets {
+        write_tally_sheet_input(&velvet_input_dir, &election_id, &tally_sheet)?;
     }
 
     Ok(())
 }
 
+/// Writes a tally sheet into the velvet input folder. Tally sheets without
+/// content are skipped.
+#[instrument(skip_all, err)]
+pub fn write_tally_sheet_input(
+    velvet_input_dir: &Path,
+    election_id: &str,
+    tally_sheet: &TallySheet,
+) -> Result<()> {
+    let Some(content) = tally_sheet.content.clone() else {
+        return Ok(());
+    };
+    //// create tally sheets folder
+    let tally_sheet_path: PathBuf = velvet_input_dir.join(format!(
+        "{DEFAULT_DIR_TALLY_SHEETS}/election__{}/contest__{}/area__{}/tally_sheet__{}",
+        election_id, content.contest_id, content.area_id, tally_sheet.id
+    ));
+    fs::create_dir_all(&tally_sheet_path)?;
+    let tally_sheet_file_path: PathBuf = tally_sheet_path.join("tally-sheet.json");
+    let mut tally_sheet_file = fs::File::create(tally_sheet_file_path)?;
+    writeln!(tally_sheet_file, "{}", serde_json::to_string(tally_sheet)?)?;
+    Ok(())
+}
+
 #[instrument(skip_all, err)]
 pub fn create_election_configs_blocking(
     base_tempdir: PathBuf,
@@ -552,7 +567,7 @@ pub async fn build_ballot_images_pipe_config(
     Ok(ballot_images_pipe_config)
 }
 
-async fn build_reports_pipe_config(
+pub async fn build_reports_pipe_config(
     tally_session: &TallySession,
     minio_endpoint_base: String,
     public_asset_path: String,
@@ -614,16 +629,6 @@ pub async fn create_config_file(
     tally_session: &TallySession,
     tally_type: TallyType,
 ) -> Result<()> {
-    let contest_encryption_policy = tally_session
-        .configuration
-        .clone()
-        .unwrap_or_default()
-        .get_contest_encryption_policy();
-    let decoded_ballots_policy = tally_session
-        .configuration
-        .clone()
-        .unwrap_or_default()
-        .get_decoded_ballots_policy();
     let public_asset_path = get_public_assets_path_env_var()?;
 
     let minio_endpoint_base = s3::get_minio_url()?;
@@ -639,6 +644,28 @@ pub async fn create_config_file(
     )
     .await?;
 
+    write_velvet_config(&base_tally_path, tally_session, gen_report_pipe_config)
+}
+
+/// Writes the velvet configuration of the tally pipeline into
+/// `base_tally_path`.
+#[instrument(skip_all, err)]
+pub fn write_velvet_config(
+    base_tally_path: &Path,
+    tally_session: &TallySession,
+    gen_report_pipe_config: PipeConfigGenerateReports,
+) -> Result<()> {
+    let contest_encryption_policy = tally_session
+        .configuration
+        .clone()
+        .unwrap_or_default()
+        .get_contest_encryption_policy();
+    let decoded_ballots_policy = tally_session
+        .configuration
+        .clone()
+        .unwrap_or_default()
+        .get_decoded_ballots_policy();
+
     let gen_db_pipe_config = PipeConfigGenerateDatabase {
         include_decoded_ballots: decoded_ballots_policy == DecodedBallotsInclusionPolicy::INCLUDED,
         tenant_id: tally_session.tenant_id.clone(),
@@ -716,126 +743,146 @@ async fn populate_sqlite_election_event_data(
     tally_session: &TallySession,
 ) -> Result<String> {
     let document_id = Uuid::new_v4().to_string();
-    let velvet_input_dir = base_tempdir.join("input");
-
-    let base_database_path = velvet_input_dir.join(format!("{DEFAULT_DIR_DATABASE}/"));
-    let database_path = base_database_path.join(format!("results.db"));
 
     let tenant_id = &tally_session.tenant_id;
     let election_event_id = &tally_session.election_event_id;
     let election_ids = tally_session.election_ids.clone();
     let areas_ids = tally_session.area_ids.clone();
 
-    let database_path_ref = &database_path;
+    let election_event = get_election_event_by_id(hasura_transaction, tenant_id, election_event_id)
+        .await
+        .context("Failed to get election event by ID")?;
+
+    let elections = match election_ids.clone() {
+        Some(ids) => {
+            get_elections_by_ids(hasura_transaction, tenant_id, election_event_id, &ids).await
+        }
+        None => get_elections(hasura_transaction, tenant_id, election_event_id, None).await,
+    }
+    .context("Failed to get elections")?;
+
+    let contests = match election_ids {
+        Some(ids) => {
+            get_contest_by_election_ids(hasura_transaction, tenant_id, election_event_id, &ids)
+                .await
+                .context("Failed to export contests")?
+        }
+        None => export_contests(hasura_transaction, tenant_id, election_event_id)
+            .await
+            .context("Failed to export contests")?,
+    };
+
+    let contests_ids: Vec<String> = contests.iter().map(|c| c.id.clone()).collect();
+
+    let contests_csv_temp =
+        NamedTempFile::new().context("Failed to create temporary file for candidates csv")?;
+
+    let contests_csv = contests_csv_temp.path();
+
+    export_candidate_csv(
+        hasura_transaction,
+        contests_csv,
+        &contests_ids,
+        tenant_id,
+        election_event_id,
+    )
+    .await
+    .context("Failed exporting candidates to csv")?;
+
+    let areas = match areas_ids.clone() {
+        Some(ids) => get_areas_by_ids(hasura_transaction, tenant_id, election_event_id, &ids)
+            .await
+            .context("Failed to get event areas by IDs")?,
+        None => get_event_areas(hasura_transaction, tenant_id, election_event_id)
+            .await
+            .context("Failed to get event areas")?,
+    };
+
+    let area_contests = match areas_ids {
+        Some(ids) => get_area_contests_by_area_contest_ids(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            &ids,
+            &contests_ids,
+        )
+        .await
+        .context("Failed to get areas contestby IDs")?,
+        None => export_area_contests(hasura_transaction, tenant_id, election_event_id)
+            .await
+            .context("Failed to export area contests")?,
+    };
+
+    write_sqlite_election_event_data(
+        base_tempdir,
+        election_event,
+        elections,
+        contests,
+        contests_csv,
+        areas,
+        area_contests,
+    )?;
+
+    Ok(document_id)
+}
+
+/// Writes the election event data into the sqlite database of the velvet
+/// input, from which the results database is generated. `candidates_csv` has
+/// the columns of the candidate table, as given by `export_candidate_csv`.
+#[instrument(skip_all, err)]
+pub fn write_sqlite_election_event_data(
+    base_tempdir: &Path,
+    election_event: ElectionEvent,
+    elections: Vec<Election>,
+    contests: Vec<ContestHasura>,
+    candidates_csv: &Path,
+    areas: Vec<Area>,
+    area_contests: Vec<AreaContest>,
+) -> Result<()> {
+    let velvet_input_dir = base_tempdir.join("input");
+
+    let base_database_path = velvet_input_dir.join(format!("{DEFAULT_DIR_DATABASE}/"));
+    let database_path = base_database_path.join(format!("results.db"));
+
+    let tenant_id = election_event.tenant_id.clone();
+    let election_event_id = election_event.id.clone();
 
     task::block_in_place(move || -> anyhow::Result<()> {
         Handle::current().block_on(async move {
             // Make sure the directory exists
             fs::create_dir_all(&base_database_path)?;
 
-            let mut sqlite_connection = Connection::open(database_path_ref)?;
+            let mut sqlite_connection = Connection::open(&database_path)?;
             let sqlite_transaction = sqlite_connection.transaction()?;
 
-            let election_event =
-                get_election_event_by_id(hasura_transaction, tenant_id, election_event_id)
-                    .await
-                    .context("Failed to get election event by ID")?;
             create_election_event_sqlite(&sqlite_transaction, election_event)
                 .await
                 .context("Failed to create election event table")?;
 
-            let elections = match election_ids.clone() {
-                Some(ids) => {
-                    get_elections_by_ids(hasura_transaction, tenant_id, election_event_id, &ids)
-                        .await
-                }
-                None => get_elections(hasura_transaction, tenant_id, election_event_id, None).await,
-            }
-            .context("Failed to get elections")?;
-
             create_election_sqlite(&sqlite_transaction, elections)
                 .await
                 .context("Failed to create election table")?;
 
-            let contests = match election_ids {
-                Some(ids) => get_contest_by_election_ids(
-                    hasura_transaction,
-                    tenant_id,
-                    election_event_id,
-                    &ids,
-                )
-                .await
-                .context("Failed to export contests")?,
-                None => export_contests(hasura_transaction, tenant_id, election_event_id)
-                    .await
-                    .context("Failed to export contests")?,
-            };
-
-            create_contest_sqlite(&sqlite_transaction, contests.clone())
+            create_contest_sqlite(&sqlite_transaction, contests)
                 .await
                 .context("Failed to create contest table")?;
 
-            let contests_ids: Vec<String> = contests.iter().map(|c| c.id.clone()).collect();
-
-            // TODO Create csv with candidates
-
             create_candidate_sqlite(&sqlite_transaction)
                 .await
                 .context("Failed to create candidate table")?;
 
-            let contests_csv_temp = NamedTempFile::new()
-                .context("Failed to create temporary file for candidates csv")?;
-
-            let contests_csv = contests_csv_temp.path();
-
-            export_candidate_csv(
-                hasura_transaction,
-                contests_csv,
-                &contests_ids,
-                tenant_id,
-                election_event_id,
-            )
-            .await
-            .context("Failed exporting candidates to csv")?;
-
-            import_candidate_sqlite(&sqlite_transaction, contests_csv)
+            import_candidate_sqlite(&sqlite_transaction, candidates_csv)
                 .await
                 .context("Failed importing candidates to sqlite database")?;
 
-            let areas = match areas_ids.clone() {
-                Some(ids) => {
-                    get_areas_by_ids(hasura_transaction, tenant_id, election_event_id, &ids)
-                        .await
-                        .context("Failed to get event areas by IDs")?
-                }
-                None => get_event_areas(hasura_transaction, tenant_id, election_event_id)
-                    .await
-                    .context("Failed to get event areas")?,
-            };
-
             create_area_sqlite(&sqlite_transaction, areas)
                 .await
                 .context("Failed to create area table")?;
 
-            let area_contests = match areas_ids {
-                Some(ids) => get_area_contests_by_area_contest_ids(
-                    hasura_transaction,
-                    tenant_id,
-                    election_event_id,
-                    &ids,
-                    &contests_ids,
-                )
-                .await
-                .context("Failed to get areas contestby IDs")?,
-                None => export_area_contests(hasura_transaction, tenant_id, election_event_id)
-                    .await
-                    .context("Failed to export area contests")?,
-            };
-
             create_area_contest_sqlite(
                 &sqlite_transaction,
-                tenant_id,
-                election_event_id,
+                &tenant_id,
+                &election_event_id,
                 area_contests,
             )
             .await
@@ -844,13 +891,13 @@ async fn populate_sqlite_election_event_data(
             sqlite_transaction.commit()?;
             Ok(())
         })
-    })?;
-
-    Ok(document_id)
+    })
 }
 
+/// Writes the velvet input and configuration of a tally into
+/// `base_tally_path`.
 #[instrument(skip_all, err)]
-pub async fn run_velvet_tally(
+pub async fn prepare_velvet_tally(
     base_tally_path: PathBuf,
     area_contests: &Vec<AreaContestDataType>,
     cast_votes_count: &Vec<ElectionCastVotes>,
@@ -863,7 +910,7 @@ pub async fn run_velvet_tally(
     election_event: &ElectionEvent,
     tally_session: &TallySession,
     tally_type: TallyType,
-) -> Result<State> {
+) -> Result<()> {
     let basic_areas: Vec<TreeNodeArea> = areas.into_iter().map(|area| area.into()).collect();
     // map<(area_id,contest_id), tally_sheet>
     let tally_sheet_map = create_tally_sheets_map(tally_sheets);
@@ -899,6 +946,38 @@ pub async fn run_velvet_tally(
         tally_session,
         tally_type,
     )
+    .await
+}
+
+#[instrument(skip_all, err)]
+pub async fn run_velvet_tally(
+    base_tally_path: PathBuf,
+    area_contests: &Vec<AreaContestDataType>,
+    cast_votes_count: &Vec<ElectionCastVotes>,
+    tally_sheets: &Vec<TallySheet>,
+    report_content_template: Option<String>,
+    report_system_template: String,
+    pdf_options: Option<PrintToPdfOptionsLocal>,
+    areas: &Vec<Area>,
+    hasura_transaction: &Transaction<'_>,
+    election_event: &ElectionEvent,
+    tally_session: &TallySession,
+    tally_type: TallyType,
+) -> Result<State> {
+    prepare_velvet_tally(
+        base_tally_path.clone(),
+        area_contests,
+        cast_votes_count,
+        tally_sheets,
+        report_content_template,
+        report_system_template,
+        pdf_options,
+        areas,
+        hasura_transaction,
+        election_event,
+        tally_session,
+        tally_type,
+    )
     .await?;
     call_velvet(base_tally_path.clone(), "decode-ballots").await
 }
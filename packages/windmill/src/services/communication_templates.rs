@@ -0,0 +1,550 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::election_event::get_election_event_by_id_if_exist;
+use crate::services::documents::upload_and_return_document;
+use crate::services::providers::email_sender::{Attachment, EmailSender};
+use crate::services::providers::sms_sender::SmsSender;
+use crate::tasks::send_template::get_variables;
+use anyhow::{anyhow, Context, Result};
+use deadpool_postgres::Transaction;
+use sequent_core::ballot::ElectionEventPresentation;
+use sequent_core::services::generate_urls::AuthAction;
+use sequent_core::services::keycloak::{get_event_realm, get_tenant_realm, KeycloakAdminClient};
+use sequent_core::services::pdf::PdfRenderer;
+use sequent_core::services::reports::{find_missing_variables, render_template_text};
+use sequent_core::services::translations::{Name, DEFAULT_LANG};
+use sequent_core::temp_path::write_into_named_temp_file;
+use sequent_core::types::hasura::core::ElectionEvent;
+use sequent_core::types::keycloak::{User, MOBILE_PHONE_ATTR_NAME};
+use sequent_core::types::templates::{EmailConfig, SendTemplateBody, SmsConfig, TemplateMethod};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use tracing::{event, instrument, Level};
+
+/// Keycloak attribute with the preferred language of the user.
+pub const LOCALE_ATTR_NAME: &str = "locale";
+
+/// Language of the communications sent to a user: the locale of the user,
+/// falling back to the default language of the election event.
+pub fn get_user_language(user: &User, election_event: &Option<ElectionEvent>) -> String {
+    user.attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get(LOCALE_ATTR_NAME))
+        .and_then(|locales| locales.first())
+        .filter(|locale| !locale.is_empty())
+        .cloned()
+        .unwrap_or_else(|| match election_event {
+            Some(election_event) => election_event.get_default_language(),
+            None => DEFAULT_LANG.to_string(),
+        })
+}
+
+/// Sets the variables that depend on the language of the communication.
+pub fn localize_variables(
+    variables: &mut Map<String, Value>,
+    election_event: &Option<ElectionEvent>,
+    language: &str,
+) {
+    variables.insert("language".to_string(), json!(language));
+    if let (Some(election_event), Some(Value::Object(election_event_variables))) =
+        (election_event, variables.get_mut("election_event"))
+    {
+        election_event_variables
+            .insert("name".to_string(), json!(election_event.get_name(language)));
+    }
+}
+
+/// Template texts translated to a language.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LocalizedTemplate {
+    pub language: String,
+    pub email: Option<EmailConfig>,
+    pub sms: Option<SmsConfig>,
+    pub document: Option<String>,
+    /// Fields without a translation to the language, with the language or
+    /// text they fall back to.
+    pub fallbacks: Vec<String>,
+}
+
+/// Translates the texts of a template with the
+/// `template.<alias>.<field>` keys of the election event presentation i18n,
+/// falling back to the default language and then to the template texts.
+pub fn localize_template(
+    template: &SendTemplateBody,
+    election_event: &Option<ElectionEvent>,
+    language: &str,
+) -> Result<LocalizedTemplate> {
+    let mut localized = LocalizedTemplate {
+        language: language.to_string(),
+        email: template.email.clone(),
+        sms: template.sms.clone(),
+        document: template.document.clone(),
+        fallbacks: vec![],
+    };
+    let (Some(alias), Some(election_event)) = (template.alias.as_ref(), election_event) else {
+        return Ok(localized);
+    };
+    let presentation: ElectionEventPresentation = election_event
+        .get_presentation()
+        .map_err(|err| anyhow!("Error parsing election event presentation: {err:?}"))?
+        .unwrap_or_default();
+    let i18n = presentation.i18n.unwrap_or_default();
+    let default_language = election_event.get_default_language();
+
+    let mut translate = |field: &str, text: &mut String| {
+        let key = format!("template.{alias}.{field}");
+        let get_translation = |lang: &str| {
+            i18n.get(lang)
+                .and_then(|lang_i18n| lang_i18n.get(&key).cloned().flatten())
+        };
+        if let Some(translation) = get_translation(language) {
+            *text = translation;
+        } else if language == default_language {
+            // the template texts are in the default language
+        } else if let Some(translation) = get_translation(&default_language) {
+            *text = translation;
+            localized
+                .fallbacks
+                .push(format!("{field}: {default_language}"));
+        } else {
+            localized.fallbacks.push(format!("{field}: template"));
+        }
+    };
+
+    let mut email = localized.email.take();
+    if let Some(ref mut email) = email {
+        translate("email.subject", &mut email.subject);
+        translate("email.plaintext_body", &mut email.plaintext_body);
+        if let Some(ref mut html_body) = email.html_body {
+            translate("email.html_body", html_body);
+        }
+    }
+    let mut sms = localized.sms.take();
+    if let Some(ref mut sms) = sms {
+        translate("sms.message", &mut sms.message);
+    }
+    let mut document = localized.document.take();
+    if let Some(ref mut document) = document {
+        translate("document", document);
+    }
+    localized.email = email;
+    localized.sms = sms;
+    localized.document = document;
+
+    Ok(localized)
+}
+
+/// Synthetic voter used to preview templates when no user is chosen.
+pub fn get_sample_user(language: &str) -> User {
+    User {
+        id: None,
+        attributes: Some(HashMap::from([
+            (
+                MOBILE_PHONE_ATTR_NAME.to_string(),
+                vec!["+34600000000".to_string()],
+            ),
+            (LOCALE_ATTR_NAME.to_string(), vec![language.to_string()]),
+        ])),
+        email: Some("jane.doe@example.com".to_string()),
+        email_verified: Some(true),
+        enabled: Some(true),
+        first_name: Some("Jane".to_string()),
+        last_name: Some("Doe".to_string()),
+        username: Some("jane.doe".to_string()),
+        area: None,
+        votes_info: None,
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TemplatePreviewInput {
+    pub election_event_id: Option<String>,
+    pub template: SendTemplateBody,
+    /// Voter whose data is used, or None for the synthetic sample.
+    pub user_id: Option<String>,
+    /// Overrides the language of the voter.
+    pub language: Option<String>,
+    /// Renders the document of the template to a PDF.
+    pub render_pdf: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemplatePreviewAttachment {
+    pub filename: String,
+    pub mimetype: String,
+    pub size: u64,
+    pub document_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TemplatePreview {
+    pub language: String,
+    pub is_sample: bool,
+    pub fallbacks: Vec<String>,
+    /// Missing variables and syntax errors of the template.
+    pub errors: Vec<String>,
+    pub email: Option<EmailConfig>,
+    pub sms: Option<SmsConfig>,
+    pub document: Option<String>,
+    pub attachments: Vec<TemplatePreviewAttachment>,
+}
+
+fn render_preview_text(
+    field: &str,
+    text: &str,
+    variables: &Map<String, Value>,
+    errors: &mut Vec<String>,
+) -> String {
+    match find_missing_variables(text, variables.clone()) {
+        Ok(missing) => errors.extend(
+            missing
+                .into_iter()
+                .map(|variable| format!("{field}: missing variable {variable}")),
+        ),
+        Err(err) => errors.push(format!("{field}: {err}")),
+    }
+    render_template_text(text, variables.clone()).unwrap_or_else(|err| {
+        errors.push(format!("{field}: {err}"));
+        String::new()
+    })
+}
+
+#[instrument(err)]
+async fn get_preview_user(
+    tenant_id: &str,
+    election_event_id: &Option<String>,
+    user_id: &Option<String>,
+) -> Result<Option<User>> {
+    let Some(user_id) = user_id else {
+        return Ok(None);
+    };
+    let realm = match election_event_id {
+        Some(election_event_id) => get_event_realm(tenant_id, election_event_id),
+        None => get_tenant_realm(tenant_id),
+    };
+    let client = KeycloakAdminClient::new()
+        .await
+        .map_err(|err| anyhow!("Error creating keycloak admin client: {err:?}"))?;
+    let user = client
+        .get_user(&realm, user_id)
+        .await
+        .map_err(|err| anyhow!("Error fetching user {user_id}: {err:?}"))?;
+    Ok(Some(user))
+}
+
+/// Renders the template, returning the preview and the rendered PDF if
+/// requested.
+#[instrument(skip(hasura_transaction), err)]
+async fn render_preview(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    input: &TemplatePreviewInput,
+) -> Result<(TemplatePreview, Option<Vec<u8>>)> {
+    let election_event = match input.election_event_id {
+        Some(ref election_event_id) => Some(
+            get_election_event_by_id_if_exist(hasura_transaction, tenant_id, election_event_id)
+                .await?
+                .ok_or_else(|| anyhow!("Election event not found: {election_event_id}"))?,
+        ),
+        None => None,
+    };
+
+    let user = get_preview_user(tenant_id, &input.election_event_id, &input.user_id).await?;
+    let is_sample = user.is_none();
+    let user =
+        user.unwrap_or_else(|| get_sample_user(input.language.as_deref().unwrap_or(DEFAULT_LANG)));
+    let language = input
+        .language
+        .clone()
+        .unwrap_or_else(|| get_user_language(&user, &election_event));
+
+    let mut variables = get_variables(
+        &user,
+        election_event.clone(),
+        tenant_id.to_string(),
+        AuthAction::Login,
+    )
+    .map_err(|err| anyhow!("Error getting template variables: {err:?}"))?;
+    localize_variables(&mut variables, &election_event, &language);
+
+    let localized = localize_template(&input.template, &election_event, &language)?;
+    let mut errors = vec![];
+    let mut preview = TemplatePreview {
+        language,
+        is_sample,
+        fallbacks: localized.fallbacks.clone(),
+        ..Default::default()
+    };
+    let mut pdf_bytes = None;
+
+    match input.template.communication_method {
+        Some(TemplateMethod::EMAIL) => {
+            let email = localized
+                .email
+                .ok_or_else(|| anyhow!("Missing email template"))?;
+            preview.email = Some(EmailConfig {
+                subject: render_preview_text(
+                    "email.subject",
+                    &email.subject,
+                    &variables,
+                    &mut errors,
+                ),
+                plaintext_body: render_preview_text(
+                    "email.plaintext_body",
+                    &email.plaintext_body,
+                    &variables,
+                    &mut errors,
+                ),
+                html_body: email.html_body.map(|html_body| {
+                    render_preview_text("email.html_body", &html_body, &variables, &mut errors)
+                }),
+            });
+        }
+        Some(TemplateMethod::SMS) => {
+            let sms = localized
+                .sms
+                .ok_or_else(|| anyhow!("Missing sms template"))?;
+            preview.sms = Some(SmsConfig {
+                message: render_preview_text("sms.message", &sms.message, &variables, &mut errors),
+            });
+        }
+        Some(TemplateMethod::DOCUMENT) => {
+            let document = localized
+                .document
+                .ok_or_else(|| anyhow!("Missing document template"))?;
+            let rendered = render_preview_text("document", &document, &variables, &mut errors);
+            // the email the document is attached to when sent
+            if let Some(email) = localized.email {
+                preview.email = Some(EmailConfig {
+                    subject: render_preview_text(
+                        "email.subject",
+                        &email.subject,
+                        &variables,
+                        &mut errors,
+                    ),
+                    plaintext_body: render_preview_text(
+                        "email.plaintext_body",
+                        &email.plaintext_body,
+                        &variables,
+                        &mut errors,
+                    ),
+                    html_body: email.html_body.map(|html_body| {
+                        render_preview_text("email.html_body", &html_body, &variables, &mut errors)
+                    }),
+                });
+            }
+            if input.render_pdf.unwrap_or(false) {
+                let bytes = PdfRenderer::render_pdf(
+                    rendered.clone(),
+                    input
+                        .template
+                        .pdf_options
+                        .as_ref()
+                        .map(|pdf_options| pdf_options.to_print_to_pdf_options()),
+                )
+                .await
+                .with_context(|| "Error rendering the document to pdf")?;
+                pdf_bytes = Some(bytes);
+            }
+            preview.document = Some(rendered);
+        }
+        None => return Err(anyhow!("Missing communication method")),
+    }
+    preview.errors = errors;
+
+    Ok((preview, pdf_bytes))
+}
+
+fn get_attachment_filename(template: &SendTemplateBody) -> String {
+    let name = template
+        .alias
+        .clone()
+        .or(template.name.clone())
+        .unwrap_or_else(|| "document".to_string());
+    format!("{name}.pdf")
+}
+
+/// Renders a template for a voter, or for a synthetic sample voter, as it
+/// would be sent. The missing variables are reported as errors instead of
+/// being rendered empty. The rendered PDF is uploaded as a private document.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn preview_template(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    input: &TemplatePreviewInput,
+) -> Result<TemplatePreview> {
+    let (mut preview, pdf_bytes) = render_preview(hasura_transaction, tenant_id, input).await?;
+
+    if let Some(bytes) = pdf_bytes {
+        let filename = get_attachment_filename(&input.template);
+        let (_temp_path, temp_path_string, file_size) =
+            write_into_named_temp_file(&bytes, "template-preview-", ".pdf")
+                .with_context(|| "Error writing to file")?;
+        let document = upload_and_return_document(
+            hasura_transaction,
+            &temp_path_string,
+            file_size,
+            "application/pdf",
+            tenant_id,
+            input.election_event_id.clone(),
+            &filename,
+            None,
+            false,
+        )
+        .await
+        .map_err(|err| anyhow!("Error uploading document: {err:?}"))?;
+        preview.attachments.push(TemplatePreviewAttachment {
+            filename,
+            mimetype: "application/pdf".to_string(),
+            size: file_size,
+            document_id: Some(document.id),
+        });
+    }
+
+    Ok(preview)
+}
+
+/// Sends the rendered template to a single address, refusing to send a
+/// template with errors. Documents are sent as a PDF attached to the email
+/// of the template.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn send_template_test(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    input: &TemplatePreviewInput,
+    receiver: &str,
+) -> Result<TemplatePreview> {
+    let input = TemplatePreviewInput {
+        render_pdf: Some(input.template.communication_method == Some(TemplateMethod::DOCUMENT)),
+        ..input.clone()
+    };
+    let (mut preview, pdf_bytes) = render_preview(hasura_transaction, tenant_id, &input).await?;
+    if !preview.errors.is_empty() {
+        return Err(anyhow!(
+            "The template has errors: {}",
+            preview.errors.join(", ")
+        ));
+    }
+
+    match input.template.communication_method {
+        Some(TemplateMethod::SMS) => {
+            let sms = preview
+                .sms
+                .clone()
+                .ok_or_else(|| anyhow!("Missing sms template"))?;
+            SmsSender::new()
+                .await?
+                .send(receiver.to_string(), sms.message)
+                .await?;
+        }
+        _ => {
+            let email = preview
+                .email
+                .clone()
+                .ok_or_else(|| anyhow!("Missing email template"))?;
+            let attachments = match pdf_bytes {
+                Some(content) => {
+                    let attachment = Attachment {
+                        filename: get_attachment_filename(&input.template),
+                        mimetype: "application/pdf".to_string(),
+                        content,
+                    };
+                    preview.attachments.push(TemplatePreviewAttachment {
+                        filename: attachment.filename.clone(),
+                        mimetype: attachment.mimetype.clone(),
+                        size: attachment.content.len() as u64,
+                        document_id: None,
+                    });
+                    vec![attachment]
+                }
+                None => vec![],
+            };
+            EmailSender::new()
+                .await?
+                .send(
+                    vec![receiver.to_string()],
+                    email.subject,
+                    email.plaintext_body,
+                    email.html_body,
+                    attachments,
+                )
+                .await?;
+        }
+    }
+    event!(Level::INFO, "Sent test of the template to {receiver}");
+
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_election_event(presentation: Value) -> ElectionEvent {
+        serde_json::from_value(json!({
+            "id": "election-event",
+            "tenant_id": "tenant",
+            "name": "Election",
+            "presentation": presentation,
+            "is_archived": false,
+            "encryption_protocol": "RSA256",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_localize_template() {
+        let election_event = Some(get_election_event(json!({
+            "language_conf": {"default_language_code": "en"},
+            "i18n": {
+                "en": {"template.invite.email.subject": "Invitation"},
+                "es": {"template.invite.email.plaintext_body": "Hola"}
+            }
+        })));
+        let template = SendTemplateBody {
+            audience_selection: None,
+            audience_voter_ids: None,
+            communication_method: Some(TemplateMethod::EMAIL),
+            schedule_now: None,
+            schedule_date: None,
+            email: Some(EmailConfig {
+                subject: "Subject".to_string(),
+                plaintext_body: "Hello".to_string(),
+                html_body: None,
+            }),
+            sms: None,
+            document: None,
+            name: None,
+            alias: Some("invite".to_string()),
+            pdf_options: None,
+            report_options: None,
+        };
+
+        let localized = localize_template(&template, &election_event, "es").unwrap();
+        let email = localized.email.unwrap();
+        assert_eq!(email.subject, "Invitation");
+        assert_eq!(email.plaintext_body, "Hola");
+        assert_eq!(localized.fallbacks, vec!["email.subject: en".to_string()]);
+
+        let localized = localize_template(&template, &election_event, "en").unwrap();
+        assert_eq!(localized.email.unwrap().plaintext_body, "Hello");
+        assert!(localized.fallbacks.is_empty());
+
+        let localized = localize_template(&template, &None, "es").unwrap();
+        assert_eq!(localized.email.unwrap().subject, "Subject");
+    }
+
+    #[test]
+    fn test_get_user_language() {
+        let election_event = Some(get_election_event(json!({
+            "language_conf": {"default_language_code": "es"}
+        })));
+        let mut user = get_sample_user("fr");
+        assert_eq!(get_user_language(&user, &election_event), "fr");
+        user.attributes = None;
+        assert_eq!(get_user_language(&user, &election_event), "es");
+        assert_eq!(get_user_language(&user, &None), DEFAULT_LANG);
+    }
+}
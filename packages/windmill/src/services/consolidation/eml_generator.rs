@@ -49,8 +49,8 @@ pub const MIRU_ROOT_CA: &str = "root-ca";
 pub const MIRU_INTERMEDIATE_CAS: &str = "intermediate-cas";
 pub const MIRU_USE_ROOT_CA: &str = "use-root-ca";
 
-const ISSUE_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
-const OFFICIAL_STATUS_DATE_FORMAT: &str = "%Y-%m-%d";
+pub const ISSUE_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+pub const OFFICIAL_STATUS_DATE_FORMAT: &str = "%Y-%m-%d";
 
 /*COMELEC ELECTION DATA -> to be change if revice different keys  */
 pub const MIRU_GEOGRAPHICAL_REGION: &str = "geographical-region";
@@ -64,6 +64,7 @@ pub const MIRU_POLLCENTER_CODE: &str = "pollcenter_code";
 #[strum(serialize_all = "lowercase")]
 pub enum OfficialStatus {
     OFFICIAL,
+    UNOFFICIAL,
 }
 
 pub trait GetMetrics {
@@ -862,6 +863,7 @@ pub fn render_eml_contest(
             Ok(EMLSelection {
                 candidates: vec![candidate.clone()],
                 valid_votes: candidate_result.total_count as i64,
+                elected: None,
             })
         })
         .collect::<Result<Vec<_>, _>>()?;
@@ -874,7 +876,12 @@ pub fn render_eml_contest(
         total_votes: EMLTotalVotes {
             count_metrics,
             selections,
+            cast: None,
+            total_counted: None,
+            rejected_votes: vec![],
+            uncounted_votes: vec![],
         },
+        reporting_unit_votes: vec![],
     };
 
     Ok(contests)
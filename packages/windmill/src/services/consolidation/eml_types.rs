@@ -56,6 +56,14 @@ pub struct EMLIdentifier {
 pub struct EMLContest {
     pub identifier: EMLIdentifier,
     pub total_votes: EMLTotalVotes,
+    #[serde(default)]
+    pub reporting_unit_votes: Vec<EMLReportingUnitVotes>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct EMLReportingUnitVotes {
+    pub identifier: EMLIdentifier,
+    pub total_votes: EMLTotalVotes,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -80,6 +88,8 @@ pub struct EMLCandidate {
 pub struct EMLSelection {
     pub candidates: Vec<EMLCandidate>,
     pub valid_votes: i64,
+    #[serde(default)]
+    pub elected: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -93,6 +103,20 @@ pub struct EMLCountMetric {
 pub struct EMLTotalVotes {
     pub count_metrics: Vec<EMLCountMetric>,
     pub selections: Vec<EMLSelection>,
+    #[serde(default)]
+    pub cast: Option<i64>,
+    #[serde(default)]
+    pub total_counted: Option<i64>,
+    #[serde(default)]
+    pub rejected_votes: Vec<EMLReasonCount>,
+    #[serde(default)]
+    pub uncounted_votes: Vec<EMLReasonCount>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct EMLReasonCount {
+    pub reason: String,
+    pub datum: i64,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -4,6 +4,7 @@
 use crate::postgres::election::*;
 use crate::postgres::scheduled_event::*;
 use crate::services::election_event_status::get_election_event_status;
+use crate::services::scheduled_events::{get_election_event_time_zone, normalize_scheduled_date};
 use anyhow::{anyhow, Result};
 use deadpool_postgres::Transaction;
 use sequent_core::ballot::{
@@ -22,6 +23,7 @@ pub async fn manage_dates(
     election_id: &str,
     scheduled_date: Option<&str>,
     event_processor: &str,
+    policy: Option<ScheduledEventPolicy>,
 ) -> Result<()> {
     let found_election = get_election_by_id(
         hasura_transaction,
@@ -55,9 +57,22 @@ pub async fn manage_dates(
 
     // if there's an schedule date, we have to either insert or create this
     if let Some(date) = scheduled_date {
+        // keep the current policy of the event unless a new one is given
+        let policy = policy.unwrap_or_else(|| {
+            old_scheduled_event_opt
+                .as_ref()
+                .filter(|old_scheduled_event| old_scheduled_event.archived_at.is_none())
+                .and_then(|old_scheduled_event| old_scheduled_event.cron_config.clone())
+                .map(|cron_config| cron_config.policy)
+                .unwrap_or_default()
+        });
+        let time_zone =
+            get_election_event_time_zone(hasura_transaction, tenant_id, election_event_id).await?;
         let cron_config = CronConfig {
             cron: None,
-            scheduled_date: Some(date.to_string()),
+            scheduled_date: Some(normalize_scheduled_date(date, time_zone.as_deref())?),
+            time_zone,
+            policy,
         };
 
         match old_scheduled_event_opt {
@@ -626,6 +626,31 @@ impl ElectoralLog {
         self.post(&message).await
     }
 
+    #[instrument(skip(self))]
+    pub async fn post_tally_recount(
+        &self,
+        event_id: String,
+        election_ids_vec: Option<Vec<String>>,
+        tally_session_id: String,
+        user_id: Option<String>,
+        username: Option<String>,
+    ) -> Result<()> {
+        let event = EventIdString(event_id);
+        let election_ids = flatten_election_ids(election_ids_vec);
+        let election = ElectionIdString(election_ids);
+
+        let message = Message::tally_recount_message(
+            event,
+            election,
+            TallySessionIdString(tally_session_id),
+            &self.sd,
+            user_id,
+            username,
+        )?;
+
+        self.post(&message).await
+    }
+
     #[instrument(skip(self))]
     pub async fn post_send_template(
         &self,
@@ -647,10 +672,68 @@ impl ElectoralLog {
         self.post(&message).await
     }
 
+    /// Posts a message for each voter added, updated or disabled by a census
+    /// synchronisation. Each change is a tuple with the description of the
+    /// change and the area of the voter.
+    #[instrument(skip(self, changes), err)]
+    pub async fn post_census_changes(
+        &self,
+        event_id: String,
+        changes: Vec<(String, Option<String>)>,
+        user_id: Option<String>,
+        username: Option<String>,
+    ) -> Result<()> {
+        let messages = changes
+            .into_iter()
+            .map(|(change, area_id)| {
+                Message::census_change_message(
+                    EventIdString(event_id.clone()),
+                    &self.sd,
+                    user_id.clone(),
+                    username.clone(),
+                    CensusChangeString(change),
+                    area_id,
+                )
+            })
+            .collect::<Result<Vec<Message>>>()?;
+
+        self.post_batch(&messages).await
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn post_census_snapshot(
+        &self,
+        event_id: String,
+        snapshot_id: String,
+        hash: String,
+        user_id: Option<String>,
+        username: Option<String>,
+    ) -> Result<()> {
+        let message = Message::census_snapshot_message(
+            EventIdString(event_id),
+            &self.sd,
+            user_id,
+            username,
+            CensusSnapshotIdString(snapshot_id),
+            CensusSnapshotHashString(hash),
+        )?;
+        self.post(&message).await
+    }
+
     #[instrument(skip(self), err)]
     async fn post(&self, message: &Message) -> Result<()> {
-        let board_message: ElectoralLogMessage = message.try_into()?;
-        let ms = vec![board_message];
+        self.post_batch(std::slice::from_ref(message)).await
+    }
+
+    #[instrument(skip_all, err)]
+    async fn post_batch(&self, messages: &[Message]) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let ms = messages
+            .iter()
+            .map(|message| message.try_into())
+            .collect::<Result<Vec<ElectoralLogMessage>>>()?;
 
         retry_with_exponential_backoff(
             // The closure we want to call repeatedly
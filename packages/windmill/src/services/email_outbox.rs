@@ -0,0 +1,433 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::email_outbox::{
+    count_email_outbox_by_status, get_email_outbox_by_status,
+    update_email_outbox_by_provider_message_id, upsert_email_suppression, EmailOutboxEntry,
+};
+use anyhow::{anyhow, Context, Result};
+use deadpool_postgres::Transaction;
+use reqwest::Url;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use tracing::{event, instrument, Level};
+
+/// Maximum number of failed entries returned in a campaign summary.
+const MAX_SUMMARY_FAILURES: i64 = 100;
+
+#[derive(Display, Debug, PartialEq, Eq, Clone, Copy, EnumString)]
+pub enum EmailOutboxStatus {
+    PENDING,
+    SENT,
+    FAILED,
+    BOUNCED,
+    COMPLAINED,
+    SUPPRESSED,
+}
+
+/// Where the emails sent by an `EmailSender` are recorded, one entry per
+/// recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailOutbox {
+    pub tenant_id: String,
+    pub election_event_id: Option<String>,
+    pub task_execution_id: Option<String>,
+}
+
+/// Delivery notification of the email provider, as published by SES through
+/// SNS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailNotification {
+    SubscriptionConfirmation {
+        subscribe_url: String,
+    },
+    Bounce {
+        message_id: String,
+        recipients: Vec<String>,
+        permanent: bool,
+        detail: Option<String>,
+    },
+    Complaint {
+        message_id: String,
+        recipients: Vec<String>,
+        detail: Option<String>,
+    },
+    Other,
+}
+
+fn get_str(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(|field| field.as_str())
+        .map(|field| field.to_string())
+}
+
+fn get_recipients(value: &Value, pointer: &str) -> Vec<String> {
+    value
+        .pointer(pointer)
+        .and_then(|recipients| recipients.as_array())
+        .map(|recipients| {
+            recipients
+                .iter()
+                .filter_map(|recipient| get_str(recipient, "/emailAddress"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses a SES notification, either wrapped in a SNS envelope or raw.
+pub fn parse_email_notification(body: &str) -> Result<EmailNotification> {
+    let value: Value =
+        serde_json::from_str(body).with_context(|| "Invalid email notification body")?;
+
+    let notification: Value = match get_str(&value, "/Type").as_deref() {
+        Some("SubscriptionConfirmation") => {
+            return Ok(EmailNotification::SubscriptionConfirmation {
+                subscribe_url: get_str(&value, "/SubscribeURL")
+                    .ok_or_else(|| anyhow!("Missing SubscribeURL"))?,
+            });
+        }
+        Some("Notification") => {
+            let message = get_str(&value, "/Message").ok_or_else(|| anyhow!("Missing Message"))?;
+            serde_json::from_str(&message).with_context(|| "Invalid SNS message")?
+        }
+        _ => value,
+    };
+
+    // notifications use notificationType while event publishing uses
+    // eventType
+    let notification_type = get_str(&notification, "/notificationType")
+        .or_else(|| get_str(&notification, "/eventType"));
+    let message_id = || {
+        get_str(&notification, "/mail/messageId").ok_or_else(|| anyhow!("Missing mail messageId"))
+    };
+
+    match notification_type.as_deref() {
+        Some("Bounce") => {
+            let bounce_type = get_str(&notification, "/bounce/bounceType");
+            let bounce_sub_type = get_str(&notification, "/bounce/bounceSubType");
+            let diagnostic_code = notification
+                .pointer("/bounce/bouncedRecipients/0")
+                .and_then(|recipient| get_str(recipient, "/diagnosticCode"));
+            let detail = [bounce_type.clone(), bounce_sub_type, diagnostic_code]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(": ");
+            Ok(EmailNotification::Bounce {
+                message_id: message_id()?,
+                recipients: get_recipients(&notification, "/bounce/bouncedRecipients"),
+                permanent: bounce_type.as_deref() == Some("Permanent"),
+                detail: Some(detail).filter(|detail| !detail.is_empty()),
+            })
+        }
+        Some("Complaint") => Ok(EmailNotification::Complaint {
+            message_id: message_id()?,
+            recipients: get_recipients(&notification, "/complaint/complainedRecipients"),
+            detail: get_str(&notification, "/complaint/complaintFeedbackType"),
+        }),
+        _ => Ok(EmailNotification::Other),
+    }
+}
+
+fn get_notifications_secret() -> Result<String> {
+    std::env::var("EMAIL_NOTIFICATIONS_SECRET")
+        .map_err(|_err| anyhow!("EMAIL_NOTIFICATIONS_SECRET env var missing"))
+}
+
+/// Compares the token in constant time, through the HMAC of both values.
+pub fn check_notifications_token(secret: &str, token: &str) -> Result<()> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, secret.as_bytes());
+    hmac::verify(&key, token.as_bytes(), tag.as_ref())
+        .map_err(|_err| anyhow!("Invalid email notifications token"))
+}
+
+#[instrument(skip(token), err)]
+pub fn verify_notifications_token(token: &str) -> Result<()> {
+    check_notifications_token(&get_notifications_secret()?, token)
+}
+
+#[instrument(err)]
+async fn suppress_recipients(
+    entries: &[EmailOutboxEntry],
+    reason: EmailOutboxStatus,
+    detail: Option<&str>,
+) -> Result<()> {
+    for entry in entries {
+        upsert_email_suppression(
+            &entry.tenant_id,
+            &entry.receiver,
+            &reason.to_string(),
+            detail,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Only subscriptions confirmed by SNS itself are accepted, so that the
+/// endpoint can't be used to make requests to arbitrary urls.
+pub fn check_subscribe_url(subscribe_url: &str) -> Result<()> {
+    let url = Url::parse(subscribe_url).with_context(|| "Invalid SubscribeURL")?;
+    let is_sns_host = url
+        .host_str()
+        .and_then(|host| host.strip_prefix("sns."))
+        .and_then(|host| host.strip_suffix(".amazonaws.com"))
+        .is_some_and(|region| {
+            !region.is_empty()
+                && region
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        });
+    if url.scheme() != "https"
+        || !is_sns_host
+        || url.port().is_some()
+        || !url.username().is_empty()
+        || url.password().is_some()
+    {
+        return Err(anyhow!("SubscribeURL {subscribe_url} is not a SNS url"));
+    }
+    Ok(())
+}
+
+/// Applies a delivery notification of the email provider to the outbox.
+/// Recipients that bounced permanently or complained are suppressed, so that
+/// they are not sent emails anymore.
+#[instrument(skip(body), err)]
+pub async fn process_email_notification(body: &str) -> Result<()> {
+    match parse_email_notification(body)? {
+        EmailNotification::SubscriptionConfirmation { subscribe_url } => {
+            check_subscribe_url(&subscribe_url)?;
+            event!(Level::INFO, "Confirming email notifications subscription");
+            reqwest::get(&subscribe_url)
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|err| anyhow!("Error confirming subscription: {err}"))?;
+        }
+        EmailNotification::Bounce {
+            message_id,
+            recipients,
+            permanent: true,
+            detail,
+        } => {
+            let entries = update_email_outbox_by_provider_message_id(
+                &message_id,
+                &recipients,
+                &EmailOutboxStatus::BOUNCED.to_string(),
+                detail.as_deref(),
+            )
+            .await?;
+            suppress_recipients(&entries, EmailOutboxStatus::BOUNCED, detail.as_deref()).await?;
+        }
+        EmailNotification::Bounce {
+            message_id,
+            recipients,
+            permanent: false,
+            detail,
+        } => {
+            // transient bounces are retried by the provider, so the email is
+            // still considered sent
+            update_email_outbox_by_provider_message_id(
+                &message_id,
+                &recipients,
+                &EmailOutboxStatus::SENT.to_string(),
+                detail.as_deref(),
+            )
+            .await?;
+        }
+        EmailNotification::Complaint {
+            message_id,
+            recipients,
+            detail,
+        } => {
+            let entries = update_email_outbox_by_provider_message_id(
+                &message_id,
+                &recipients,
+                &EmailOutboxStatus::COMPLAINED.to_string(),
+                detail.as_deref(),
+            )
+            .await?;
+            suppress_recipients(&entries, EmailOutboxStatus::COMPLAINED, detail.as_deref()).await?;
+        }
+        EmailNotification::Other => {
+            event!(Level::INFO, "Ignoring email notification");
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmailCampaignSummary {
+    pub task_execution_id: String,
+    pub total: i64,
+    pub pending: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub bounced: i64,
+    pub complained: i64,
+    pub suppressed: i64,
+    pub failures: Vec<EmailOutboxEntry>,
+}
+
+impl EmailCampaignSummary {
+    fn add_count(&mut self, status: &str, count: i64) {
+        self.total += count;
+        match EmailOutboxStatus::from_str(status) {
+            Ok(EmailOutboxStatus::PENDING) => self.pending += count,
+            Ok(EmailOutboxStatus::SENT) => self.sent += count,
+            Ok(EmailOutboxStatus::FAILED) => self.failed += count,
+            Ok(EmailOutboxStatus::BOUNCED) => self.bounced += count,
+            Ok(EmailOutboxStatus::COMPLAINED) => self.complained += count,
+            Ok(EmailOutboxStatus::SUPPRESSED) => self.suppressed += count,
+            Err(_) => event!(Level::WARN, "Unknown email outbox status {status}"),
+        }
+    }
+}
+
+/// Delivery summary of the emails sent by a task execution, with the latest
+/// entries that were not delivered.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_email_campaign_summary(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    task_execution_id: &str,
+) -> Result<EmailCampaignSummary> {
+    let mut summary = EmailCampaignSummary {
+        task_execution_id: task_execution_id.to_string(),
+        ..Default::default()
+    };
+    for (status, count) in
+        count_email_outbox_by_status(hasura_transaction, tenant_id, task_execution_id).await?
+    {
+        summary.add_count(&status, count);
+    }
+
+    let failed_statuses: Vec<String> = [
+        EmailOutboxStatus::FAILED,
+        EmailOutboxStatus::BOUNCED,
+        EmailOutboxStatus::COMPLAINED,
+        EmailOutboxStatus::SUPPRESSED,
+    ]
+    .iter()
+    .map(|status| status.to_string())
+    .collect();
+    summary.failures = get_email_outbox_by_status(
+        hasura_transaction,
+        tenant_id,
+        task_execution_id,
+        &failed_statuses,
+        MAX_SUMMARY_FAILURES,
+    )
+    .await?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_email_notification() {
+        let bounce = json!({
+            "notificationType": "Bounce",
+            "bounce": {
+                "bounceType": "Permanent",
+                "bounceSubType": "General",
+                "bouncedRecipients": [{
+                    "emailAddress": "voter@example.com",
+                    "diagnosticCode": "smtp; 550 user unknown"
+                }]
+            },
+            "mail": {"messageId": "msg-1"}
+        });
+        let envelope = json!({
+            "Type": "Notification",
+            "Message": bounce.to_string(),
+        });
+        assert_eq!(
+            parse_email_notification(&envelope.to_string()).unwrap(),
+            EmailNotification::Bounce {
+                message_id: "msg-1".to_string(),
+                recipients: vec!["voter@example.com".to_string()],
+                permanent: true,
+                detail: Some("Permanent: General: smtp; 550 user unknown".to_string()),
+            }
+        );
+
+        let complaint = json!({
+            "eventType": "Complaint",
+            "complaint": {
+                "complainedRecipients": [{"emailAddress": "voter@example.com"}],
+                "complaintFeedbackType": "abuse"
+            },
+            "mail": {"messageId": "msg-2"}
+        });
+        assert_eq!(
+            parse_email_notification(&complaint.to_string()).unwrap(),
+            EmailNotification::Complaint {
+                message_id: "msg-2".to_string(),
+                recipients: vec!["voter@example.com".to_string()],
+                detail: Some("abuse".to_string()),
+            }
+        );
+
+        let confirmation = json!({
+            "Type": "SubscriptionConfirmation",
+            "SubscribeURL": "https://sns.eu-west-1.amazonaws.com/?Action=ConfirmSubscription",
+        });
+        assert_eq!(
+            parse_email_notification(&confirmation.to_string()).unwrap(),
+            EmailNotification::SubscriptionConfirmation {
+                subscribe_url: "https://sns.eu-west-1.amazonaws.com/?Action=ConfirmSubscription"
+                    .to_string(),
+            }
+        );
+
+        let delivery = json!({"notificationType": "Delivery", "mail": {"messageId": "msg-3"}});
+        assert_eq!(
+            parse_email_notification(&delivery.to_string()).unwrap(),
+            EmailNotification::Other
+        );
+        assert!(parse_email_notification("not json").is_err());
+    }
+
+    #[test]
+    fn test_check_subscribe_url() {
+        assert!(check_subscribe_url(
+            "https://sns.eu-west-1.amazonaws.com/?Action=ConfirmSubscription&Token=abc"
+        )
+        .is_ok());
+        assert!(check_subscribe_url("http://sns.eu-west-1.amazonaws.com/").is_err());
+        assert!(check_subscribe_url("https://sns.example.com/confirm").is_err());
+        assert!(check_subscribe_url("https://sns.eu-west-1.amazonaws.com.example.com/").is_err());
+        assert!(check_subscribe_url("https://sns.eu-west-1.amazonaws.com:8080/").is_err());
+        assert!(check_subscribe_url("https://user@sns.eu-west-1.amazonaws.com/").is_err());
+        assert!(check_subscribe_url("https://169.254.169.254/latest/meta-data").is_err());
+        assert!(check_subscribe_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_check_notifications_token() {
+        assert!(check_notifications_token("secret", "secret").is_ok());
+        assert!(check_notifications_token("secret", "other").is_err());
+        assert!(check_notifications_token("secret", "").is_err());
+    }
+
+    #[test]
+    fn test_campaign_summary_counts() {
+        let mut summary = EmailCampaignSummary::default();
+        summary.add_count("SENT", 3);
+        summary.add_count("BOUNCED", 1);
+        summary.add_count("SUPPRESSED", 2);
+        assert_eq!(summary.total, 6);
+        assert_eq!(summary.sent, 3);
+        assert_eq!(summary.bounced, 1);
+        assert_eq!(summary.suppressed, 2);
+    }
+}
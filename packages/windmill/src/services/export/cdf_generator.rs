@@ -0,0 +1,490 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use super::cdf_types::*;
+use super::results_event_data::{ResultsEventData, ResultsState};
+use crate::services::election_event_status::get_election_status;
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sequent_core::services::area_tree::{TreeNode, TreeNodeArea};
+use sequent_core::types::hasura::core::{Area, Candidate, Contest, Election};
+use sequent_core::types::results::ResultsContest;
+use serde_json::Value;
+use tracing::instrument;
+
+const CDF_DATE_FORMAT: &str = "%Y-%m-%d";
+const OTHER_TYPE_ELECTION_EVENT: &str = "election-event";
+const OTHER_TYPE_AREA: &str = "area";
+const OTHER_TYPE_SEQUENT_ID: &str = "sequent-id";
+
+// CDF ids must be valid XML ids, which can't start with a digit as uuids do.
+fn get_gp_unit_id(id: &str) -> String {
+    format!("gpu-{id}")
+}
+
+fn get_election_id(id: &str) -> String {
+    format!("election-{id}")
+}
+
+fn get_contest_id(id: &str) -> String {
+    format!("contest-{id}")
+}
+
+fn get_candidate_id(id: &str) -> String {
+    format!("candidate-{id}")
+}
+
+fn get_selection_id(id: &str) -> String {
+    format!("selection-{id}")
+}
+
+fn get_external_identifiers(id: &str) -> Vec<ExternalIdentifier> {
+    vec![ExternalIdentifier {
+        other_type: Some(OTHER_TYPE_SEQUENT_ID.to_string()),
+        identifier_type: IdentifierType::Other,
+        value: id.to_string(),
+    }]
+}
+
+fn get_text(content: &str, language: &str) -> InternationalizedText {
+    InternationalizedText {
+        text: vec![LanguageString {
+            content: content.to_string(),
+            language: language.to_string(),
+        }],
+    }
+}
+
+fn get_count(value: Option<i64>) -> u64 {
+    value.unwrap_or(0).max(0) as u64
+}
+
+/// Maps the counting algorithm of a contest to the CDF vote variation, with
+/// the algorithm name as other vote variation when there's no equivalent.
+pub fn get_vote_variation(
+    counting_algorithm: Option<&str>,
+    votes_allowed: u64,
+) -> (VoteVariation, Option<String>) {
+    let counting_algorithm = counting_algorithm.unwrap_or("plurality-at-large");
+    match counting_algorithm {
+        "plurality-at-large" if votes_allowed > 1 => (VoteVariation::NOfM, None),
+        "plurality-at-large" => (VoteVariation::Plurality, None),
+        "cumulative" => (VoteVariation::Cumulative, None),
+        "instant-runoff" => (VoteVariation::Rcv, None),
+        "single-transferable-vote" => (VoteVariation::Proportional, None),
+        algorithm if algorithm.starts_with("borda") => (VoteVariation::Borda, None),
+        algorithm => (VoteVariation::Other, Some(algorithm.to_string())),
+    }
+}
+
+/// Maps the state of the tally session to the CDF results status.
+pub fn get_results_status(results_state: ResultsState) -> ResultsStatus {
+    match results_state {
+        ResultsState::Partial => ResultsStatus::UnofficialPartial,
+        ResultsState::Final => ResultsStatus::Certified,
+        ResultsState::Recount => ResultsStatus::Recount,
+    }
+}
+
+/// Maps the area type to a CDF reporting unit type, keeping the area type as
+/// other type when it's not one of the CDF types.
+fn get_reporting_unit_type(area_type: Option<&str>) -> (ReportingUnitType, Option<String>) {
+    let Some(area_type) = area_type.filter(|area_type| !area_type.is_empty()) else {
+        return (ReportingUnitType::Other, Some(OTHER_TYPE_AREA.to_string()));
+    };
+    match serde_json::from_value(Value::String(area_type.to_lowercase())) {
+        Ok(ReportingUnitType::Other) | Err(_) => {
+            (ReportingUnitType::Other, Some(area_type.to_string()))
+        }
+        Ok(reporting_unit_type) => (reporting_unit_type, None),
+    }
+}
+
+/// Creates a reporting unit for each area, composed by its children areas,
+/// under a reporting unit for the whole election event.
+fn get_reporting_units(data: &ResultsEventData) -> Result<Vec<ReportingUnit>> {
+    let tree = TreeNode::<()>::from_areas(data.areas.iter().map(TreeNodeArea::from).collect())?;
+    let mut reporting_units = vec![ReportingUnit {
+        id: get_gp_unit_id(&data.election_event.id),
+        composing_gp_unit_ids: get_composing_gp_unit_ids(&tree),
+        external_identifier: get_external_identifiers(&data.election_event.id),
+        name: Some(data.election_event.name.clone()),
+        other_type: Some(OTHER_TYPE_ELECTION_EVENT.to_string()),
+        reporting_unit_type: ReportingUnitType::Other,
+    }];
+    add_area_reporting_units(&tree, &data.areas, &mut reporting_units);
+    Ok(reporting_units)
+}
+
+fn get_composing_gp_unit_ids(node: &TreeNode<()>) -> Vec<String> {
+    node.children
+        .iter()
+        .filter_map(|child| child.area.as_ref())
+        .map(|area| get_gp_unit_id(&area.id))
+        .collect()
+}
+
+fn add_area_reporting_units(
+    node: &TreeNode<()>,
+    areas: &[Area],
+    reporting_units: &mut Vec<ReportingUnit>,
+) {
+    if let Some(area) = node
+        .area
+        .as_ref()
+        .and_then(|tree_area| areas.iter().find(|area| area.id == tree_area.id))
+    {
+        let (reporting_unit_type, other_type) = get_reporting_unit_type(area.r#type.as_deref());
+        reporting_units.push(ReportingUnit {
+            id: get_gp_unit_id(&area.id),
+            composing_gp_unit_ids: get_composing_gp_unit_ids(node),
+            external_identifier: get_external_identifiers(&area.id),
+            name: area.name.clone(),
+            other_type,
+            reporting_unit_type,
+        });
+    }
+    for child in &node.children {
+        add_area_reporting_units(child, areas, reporting_units);
+    }
+}
+
+fn get_candidate_selection(
+    data: &ResultsEventData,
+    contest: &Contest,
+    candidate: &Candidate,
+    scope_gp_unit_id: &str,
+) -> CandidateSelection {
+    let contest_vote_counts = data
+        .results_contest_candidates
+        .iter()
+        .filter(|result| result.contest_id == contest.id && result.candidate_id == candidate.id)
+        .map(|result| VoteCounts {
+            count: get_count(result.cast_votes),
+            gp_unit_id: scope_gp_unit_id.to_string(),
+            count_item_type: CountItemType::Total,
+        });
+    let area_vote_counts = data
+        .results_area_contest_candidates
+        .iter()
+        .filter(|result| result.contest_id == contest.id && result.candidate_id == candidate.id)
+        .map(|result| VoteCounts {
+            count: get_count(result.cast_votes),
+            gp_unit_id: get_gp_unit_id(&result.area_id),
+            count_item_type: CountItemType::Total,
+        });
+
+    CandidateSelection {
+        id: get_selection_id(&candidate.id),
+        candidate_ids: vec![get_candidate_id(&candidate.id)],
+        is_write_in: None,
+        vote_counts: contest_vote_counts.chain(area_vote_counts).collect(),
+    }
+}
+
+/// Contest ballot counts: total, blank votes as undervotes and invalid votes
+/// as rejected ballots, for the whole contest and for each area.
+fn get_summary_counts(
+    data: &ResultsEventData,
+    results_contest: &ResultsContest,
+    scope_gp_unit_id: &str,
+) -> Vec<SummaryCounts> {
+    let contest_summary = SummaryCounts {
+        ballots_cast: Some(get_count(results_contest.total_votes)),
+        ballots_rejected: Some(get_count(results_contest.total_invalid_votes)),
+        gp_unit_id: scope_gp_unit_id.to_string(),
+        count_item_type: CountItemType::Total,
+        undervotes: Some(get_count(results_contest.blank_votes)),
+    };
+    let area_summaries = data
+        .results_area_contests
+        .iter()
+        .filter(|result| result.contest_id == results_contest.contest_id)
+        .map(|result| SummaryCounts {
+            ballots_cast: Some(get_count(result.total_votes)),
+            ballots_rejected: Some(get_count(result.total_invalid_votes)),
+            gp_unit_id: get_gp_unit_id(&result.area_id),
+            count_item_type: CountItemType::Total,
+            undervotes: Some(get_count(result.blank_votes)),
+        });
+    std::iter::once(contest_summary)
+        .chain(area_summaries)
+        .collect()
+}
+
+fn get_post_election_status(
+    data: &ResultsEventData,
+    contest: &Contest,
+    candidate: &Candidate,
+) -> Option<CandidatePostElectionStatus> {
+    data.results_contest_candidates
+        .iter()
+        .find(|result| result.contest_id == contest.id && result.candidate_id == candidate.id)
+        .map(|result| match result.winning_position {
+            Some(_) => CandidatePostElectionStatus::Winner,
+            None => CandidatePostElectionStatus::Defeated,
+        })
+}
+
+fn get_election(
+    data: &ResultsEventData,
+    election: &Election,
+    scope_gp_unit_id: &str,
+    language: &str,
+    generated_date: &DateTime<Utc>,
+) -> CdfElection {
+    let mut candidates: Vec<CdfCandidate> = vec![];
+    let mut contests: Vec<CandidateContest> = vec![];
+
+    for contest in data
+        .contests
+        .iter()
+        .filter(|contest| contest.election_id == election.id)
+    {
+        let Some(results_contest) = data
+            .results_contests
+            .iter()
+            .find(|result| result.contest_id == contest.id)
+        else {
+            continue;
+        };
+        let contest_candidates: Vec<&Candidate> = data
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.contest_id.as_deref() == Some(contest.id.as_str()))
+            .collect();
+
+        candidates.extend(contest_candidates.iter().map(|candidate| CdfCandidate {
+            id: get_candidate_id(&candidate.id),
+            ballot_name: get_text(&candidate.name.clone().unwrap_or_default(), language),
+            external_identifier: get_external_identifiers(&candidate.id),
+            post_election_status: get_post_election_status(data, contest, candidate),
+        }));
+
+        let votes_allowed = get_count(contest.max_votes).max(1);
+        let counting_algorithm = results_contest
+            .counting_algorithm
+            .as_deref()
+            .or(contest.counting_algorithm.as_deref());
+        let (vote_variation, other_vote_variation) =
+            get_vote_variation(counting_algorithm, votes_allowed);
+        contests.push(CandidateContest {
+            id: get_contest_id(&contest.id),
+            ballot_selection: contest_candidates
+                .iter()
+                .map(|candidate| {
+                    get_candidate_selection(data, contest, candidate, scope_gp_unit_id)
+                })
+                .collect(),
+            election_district_id: scope_gp_unit_id.to_string(),
+            external_identifier: get_external_identifiers(&contest.id),
+            name: contest.name.clone().unwrap_or_default(),
+            number_elected: contest.winning_candidates_num.map(|num| num.max(0) as u64),
+            other_vote_variation,
+            summary_counts: get_summary_counts(data, results_contest, scope_gp_unit_id),
+            vote_variation: Some(vote_variation),
+            votes_allowed,
+        });
+    }
+
+    let period_dates = get_election_status(election.status.clone())
+        .unwrap_or_default()
+        .voting_period_dates;
+    let start_date = period_dates.first_started_at.unwrap_or(*generated_date);
+    let end_date = period_dates.last_stopped_at.unwrap_or(*generated_date);
+
+    CdfElection {
+        id: get_election_id(&election.id),
+        candidate: candidates,
+        contest: contests,
+        election_scope_id: scope_gp_unit_id.to_string(),
+        end_date: end_date.format(CDF_DATE_FORMAT).to_string(),
+        external_identifier: get_external_identifiers(&election.id),
+        name: get_text(&election.name, language),
+        start_date: start_date.format(CDF_DATE_FORMAT).to_string(),
+        election_type: ElectionType::Other,
+    }
+}
+
+/// Generates the CDF election report of the results. Only the elections and
+/// contests with results are included.
+#[instrument(skip(data), err)]
+pub fn generate_election_report(
+    data: &ResultsEventData,
+    generated_date: DateTime<Utc>,
+) -> Result<ElectionReport> {
+    let language = data.election_event.get_default_language();
+    let gp_units = get_reporting_units(data)?;
+    let scope_gp_unit_id = get_gp_unit_id(&data.election_event.id);
+
+    let elections = data
+        .elections
+        .iter()
+        .filter(|election| {
+            data.results_contests
+                .iter()
+                .any(|result| result.election_id == election.id)
+        })
+        .map(|election| {
+            get_election(
+                data,
+                election,
+                &scope_gp_unit_id,
+                &language,
+                &generated_date,
+            )
+        })
+        .collect();
+
+    let format = if data.results_area_contests.is_empty() {
+        ReportDetailLevel::SummaryContest
+    } else {
+        ReportDetailLevel::PrecinctLevel
+    };
+
+    Ok(ElectionReport {
+        election: elections,
+        format,
+        generated_date: generated_date.to_rfc3339_opts(SecondsFormat::Secs, true),
+        gp_unit: gp_units,
+        issuer: data.election_event.name.clone(),
+        issuer_abbreviation: data
+            .election_event
+            .alias
+            .clone()
+            .unwrap_or(data.election_event.name.clone()),
+        is_test: None,
+        sequence_start: 1,
+        sequence_end: 1,
+        status: get_results_status(data.results_state),
+        vendor_application_id: format!("windmill {}", env!("CARGO_PKG_VERSION")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::export::results_event_data::test_data::*;
+    use jsonschema::JSONSchema;
+
+    /// Validates the report against the official NIST schema, vendored in
+    /// `src/resources/cdf` by `scripts/fetch-results-schemas.sh`.
+    #[test]
+    fn test_election_report_matches_schema() {
+        let schema_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/resources/cdf/NIST_V2_election_results_reporting.json");
+        let schema_data = std::fs::read_to_string(&schema_path).unwrap_or_else(|err| {
+            panic!("Error reading {schema_path:?}, run scripts/fetch-results-schemas.sh: {err}")
+        });
+        let schema: Value = serde_json::from_str(&schema_data).unwrap();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let report = generate_election_report(&get_data(), get_generated_date()).unwrap();
+        let instance = serde_json::to_value(&report).unwrap();
+        if let Err(errors) = compiled.validate(&instance) {
+            let messages: Vec<String> = errors
+                .map(|error| format!("{} at {}", error, error.instance_path))
+                .collect();
+            panic!("Invalid CDF election report: {}", messages.join(", "));
+        }
+        assert_eq!(instance["@type"], "ElectionResults.ElectionReport");
+        assert_eq!(instance["Format"], "precinct-level");
+        assert_eq!(instance["GeneratedDate"], "2025-05-06T10:00:00Z");
+    }
+
+    #[test]
+    fn test_election_report_contents() {
+        let report = generate_election_report(&get_data(), get_generated_date()).unwrap();
+        let event_gp_unit_id = get_gp_unit_id(ELECTION_EVENT_ID);
+
+        let gp_unit_ids: Vec<&str> = report.gp_unit.iter().map(|unit| unit.id.as_str()).collect();
+        assert_eq!(
+            gp_unit_ids,
+            vec![
+                event_gp_unit_id.as_str(),
+                &get_gp_unit_id(CITY_ID),
+                &get_gp_unit_id(DISTRICT_ID)
+            ]
+        );
+        assert_eq!(
+            report.gp_unit[0].composing_gp_unit_ids,
+            vec![get_gp_unit_id(CITY_ID)]
+        );
+        assert_eq!(
+            report.gp_unit[1].composing_gp_unit_ids,
+            vec![get_gp_unit_id(DISTRICT_ID)]
+        );
+        assert_eq!(
+            report.gp_unit[1].reporting_unit_type,
+            ReportingUnitType::City
+        );
+        assert_eq!(
+            report.gp_unit[2].reporting_unit_type,
+            ReportingUnitType::Other
+        );
+        assert_eq!(report.gp_unit[2].other_type.as_deref(), Some("district"));
+
+        let election = &report.election[0];
+        assert_eq!(election.start_date, "2025-05-04");
+        assert_eq!(election.end_date, "2025-05-05");
+        assert_eq!(election.name.text[0].language, "es");
+        assert_eq!(
+            election.candidate[0].post_election_status,
+            Some(CandidatePostElectionStatus::Winner)
+        );
+        assert_eq!(
+            election.candidate[1].post_election_status,
+            Some(CandidatePostElectionStatus::Defeated)
+        );
+
+        let contest = &election.contest[0];
+        assert_eq!(contest.vote_variation, Some(VoteVariation::Plurality));
+        assert_eq!(contest.summary_counts.len(), 2);
+        assert_eq!(contest.summary_counts[0].ballots_cast, Some(13));
+        assert_eq!(contest.summary_counts[0].undervotes, Some(1));
+        let alice_counts: Vec<(u64, &str)> = contest.ballot_selection[0]
+            .vote_counts
+            .iter()
+            .map(|counts| (counts.count, counts.gp_unit_id.as_str()))
+            .collect();
+        assert_eq!(
+            alice_counts,
+            vec![
+                (7, event_gp_unit_id.as_str()),
+                (7, &get_gp_unit_id(DISTRICT_ID))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_results_status() {
+        let report = generate_election_report(&get_data(), get_generated_date()).unwrap();
+        assert_eq!(report.status, ResultsStatus::Certified);
+
+        let data = ResultsEventData {
+            results_state: ResultsState::Partial,
+            ..get_data()
+        };
+        let report = generate_election_report(&data, get_generated_date()).unwrap();
+        assert_eq!(report.status, ResultsStatus::UnofficialPartial);
+        assert_eq!(
+            get_results_status(ResultsState::Recount),
+            ResultsStatus::Recount
+        );
+    }
+
+    #[test]
+    fn test_vote_variation() {
+        assert_eq!(
+            get_vote_variation(Some("plurality-at-large"), 3),
+            (VoteVariation::NOfM, None)
+        );
+        assert_eq!(
+            get_vote_variation(Some("borda-nauru"), 1),
+            (VoteVariation::Borda, None)
+        );
+        assert_eq!(
+            get_vote_variation(Some("desborda3"), 1),
+            (VoteVariation::Other, Some("desborda3".to_string()))
+        );
+    }
+}
@@ -0,0 +1,288 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Classes of the NIST SP 1500-100 Election Results Common Data Format (V2),
+//! in its JSON serialization. Only the classes and attributes needed to
+//! publish tally results are included.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.ElectionReport",
+    rename_all = "PascalCase"
+)]
+pub struct ElectionReport {
+    pub election: Vec<CdfElection>,
+    pub format: ReportDetailLevel,
+    pub generated_date: String,
+    pub gp_unit: Vec<ReportingUnit>,
+    pub issuer: String,
+    pub issuer_abbreviation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_test: Option<bool>,
+    pub sequence_start: u64,
+    pub sequence_end: u64,
+    pub status: ResultsStatus,
+    pub vendor_application_id: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.Election",
+    rename_all = "PascalCase"
+)]
+pub struct CdfElection {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub candidate: Vec<CdfCandidate>,
+    pub contest: Vec<CandidateContest>,
+    pub election_scope_id: String,
+    pub end_date: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_identifier: Vec<ExternalIdentifier>,
+    pub name: InternationalizedText,
+    pub start_date: String,
+    #[serde(rename = "Type")]
+    pub election_type: ElectionType,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.Candidate",
+    rename_all = "PascalCase"
+)]
+pub struct CdfCandidate {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub ballot_name: InternationalizedText,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_identifier: Vec<ExternalIdentifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_election_status: Option<CandidatePostElectionStatus>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.CandidateContest",
+    rename_all = "PascalCase"
+)]
+pub struct CandidateContest {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub ballot_selection: Vec<CandidateSelection>,
+    pub election_district_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_identifier: Vec<ExternalIdentifier>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_elected: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_vote_variation: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub summary_counts: Vec<SummaryCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vote_variation: Option<VoteVariation>,
+    pub votes_allowed: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.CandidateSelection",
+    rename_all = "PascalCase"
+)]
+pub struct CandidateSelection {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub candidate_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_write_in: Option<bool>,
+    pub vote_counts: Vec<VoteCounts>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.VoteCounts",
+    rename_all = "PascalCase"
+)]
+pub struct VoteCounts {
+    pub count: u64,
+    pub gp_unit_id: String,
+    #[serde(rename = "Type")]
+    pub count_item_type: CountItemType,
+}
+
+/// Ballot counts of a contest in a reporting unit.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.SummaryCounts",
+    rename_all = "PascalCase"
+)]
+pub struct SummaryCounts {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ballots_cast: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ballots_rejected: Option<u64>,
+    pub gp_unit_id: String,
+    #[serde(rename = "Type")]
+    pub count_item_type: CountItemType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undervotes: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.ReportingUnit",
+    rename_all = "PascalCase"
+)]
+pub struct ReportingUnit {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub composing_gp_unit_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub external_identifier: Vec<ExternalIdentifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_type: Option<String>,
+    #[serde(rename = "Type")]
+    pub reporting_unit_type: ReportingUnitType,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.ExternalIdentifier",
+    rename_all = "PascalCase"
+)]
+pub struct ExternalIdentifier {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_type: Option<String>,
+    #[serde(rename = "Type")]
+    pub identifier_type: IdentifierType,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.InternationalizedText",
+    rename_all = "PascalCase"
+)]
+pub struct InternationalizedText {
+    pub text: Vec<LanguageString>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(
+    tag = "@type",
+    rename = "ElectionResults.LanguageString",
+    rename_all = "PascalCase"
+)]
+pub struct LanguageString {
+    pub content: String,
+    pub language: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportDetailLevel {
+    PrecinctLevel,
+    SummaryContest,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResultsStatus {
+    Certified,
+    Correction,
+    PreElection,
+    Recount,
+    UnofficialComplete,
+    UnofficialPartial,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum ElectionType {
+    General,
+    Primary,
+    Runoff,
+    Special,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum CandidatePostElectionStatus {
+    AdvancedToRunoff,
+    Defeated,
+    ProjectedWinner,
+    Winner,
+    Withdrawn,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum VoteVariation {
+    Approval,
+    Borda,
+    Cumulative,
+    Majority,
+    NOfM,
+    Plurality,
+    Proportional,
+    Range,
+    Rcv,
+    SuperMajority,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum CountItemType {
+    Absentee,
+    Early,
+    ElectionDay,
+    Provisional,
+    Total,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportingUnitType {
+    BallotStyleArea,
+    City,
+    Country,
+    County,
+    Municipality,
+    PollingPlace,
+    Precinct,
+    SplitPrecinct,
+    State,
+    Town,
+    VoteCenter,
+    Ward,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdentifierType {
+    Fips,
+    LocalLevel,
+    NationalLevel,
+    OcdId,
+    StateLevel,
+    Other,
+}
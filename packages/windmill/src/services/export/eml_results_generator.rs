@@ -0,0 +1,477 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Generic OASIS EML v7 export of the tally results. Unlike the Miru
+//! transmission package, identifiers come from the standard election event
+//! fields instead of annotations.
+use super::results_event_data::{ResultsEventData, ResultsState};
+use crate::services::consolidation::eml_generator::{
+    OfficialStatus, ISSUE_DATE_FORMAT, OFFICIAL_STATUS_DATE_FORMAT,
+};
+use crate::services::consolidation::eml_types::*;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use sequent_core::services::reports::render_template_text;
+use sequent_core::types::hasura::core::{Candidate, Contest, Election};
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use tracing::instrument;
+
+const EML_510_COUNT_TEMPLATE: &str = include_str!("../../resources/eml_510_count.hbs");
+const EML_520_RESULT_TEMPLATE: &str = include_str!("../../resources/eml_520_result.hbs");
+
+const REASON_EXPLICIT_INVALID: &str = "explicit-invalid";
+const REASON_IMPLICIT_INVALID: &str = "implicit-invalid";
+const REASON_INVALID: &str = "invalid";
+const REASON_BLANK: &str = "blank";
+
+/// EML messages the tally results can be exported as.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EMLResultsMessage {
+    /// EML 510: votes of each candidate, in total and per area.
+    Count,
+    /// EML 520: votes of each candidate and whether it was elected.
+    Result,
+}
+
+impl EMLResultsMessage {
+    pub fn get_id(&self) -> &'static str {
+        match self {
+            EMLResultsMessage::Count => "510",
+            EMLResultsMessage::Result => "520",
+        }
+    }
+
+    fn get_template(&self) -> &'static str {
+        match self {
+            EMLResultsMessage::Count => EML_510_COUNT_TEMPLATE,
+            EMLResultsMessage::Result => EML_520_RESULT_TEMPLATE,
+        }
+    }
+}
+
+/// Uses the alias as the EML id when there's one, as it's the code election
+/// officials know the entity by.
+fn get_identifier(id: &str, alias: Option<&str>, name: &str) -> EMLIdentifier {
+    EMLIdentifier {
+        id_number: alias
+            .filter(|alias| !alias.is_empty())
+            .unwrap_or(id)
+            .to_string(),
+        name: name.to_string(),
+    }
+}
+
+fn get_candidate_identifier(candidate: &Candidate) -> EMLIdentifier {
+    get_identifier(
+        &candidate.id,
+        candidate.alias.as_deref(),
+        &candidate.name.clone().unwrap_or_default(),
+    )
+}
+
+fn get_selection(
+    candidate: &Candidate,
+    cast_votes: Option<i64>,
+    winning_position: Option<i64>,
+) -> EMLSelection {
+    EMLSelection {
+        candidates: vec![EMLCandidate {
+            identifier: get_candidate_identifier(candidate),
+            status_details: vec![],
+            affiliation: EMLAffiliation {
+                identifier: get_identifier("", None, ""),
+                party: String::new(),
+            },
+        }],
+        valid_votes: cast_votes.unwrap_or_default(),
+        elected: Some(winning_position.is_some()),
+    }
+}
+
+/// Invalid votes are rejected, detailing whether they were explicit or
+/// implicit when known. Blank votes are uncounted.
+fn get_total_votes(
+    selections: Vec<EMLSelection>,
+    total_votes: Option<i64>,
+    total_valid_votes: Option<i64>,
+    explicit_invalid_votes: Option<i64>,
+    implicit_invalid_votes: Option<i64>,
+    total_invalid_votes: Option<i64>,
+    blank_votes: Option<i64>,
+) -> EMLTotalVotes {
+    let reason_count = |reason: &str, datum: i64| EMLReasonCount {
+        reason: reason.to_string(),
+        datum,
+    };
+    let rejected_votes = if explicit_invalid_votes.is_some() || implicit_invalid_votes.is_some() {
+        vec![
+            reason_count(
+                REASON_EXPLICIT_INVALID,
+                explicit_invalid_votes.unwrap_or_default(),
+            ),
+            reason_count(
+                REASON_IMPLICIT_INVALID,
+                implicit_invalid_votes.unwrap_or_default(),
+            ),
+        ]
+    } else {
+        total_invalid_votes
+            .map(|datum| reason_count(REASON_INVALID, datum))
+            .into_iter()
+            .collect()
+    };
+
+    EMLTotalVotes {
+        count_metrics: vec![],
+        selections,
+        cast: Some(total_votes.unwrap_or_default()),
+        total_counted: Some(total_valid_votes.unwrap_or_default()),
+        rejected_votes,
+        uncounted_votes: blank_votes
+            .map(|datum| reason_count(REASON_BLANK, datum))
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn get_reporting_unit_votes(
+    data: &ResultsEventData,
+    contest: &Contest,
+    contest_candidates: &[&Candidate],
+) -> Vec<EMLReportingUnitVotes> {
+    data.results_area_contests
+        .iter()
+        .filter(|result| result.contest_id == contest.id)
+        .map(|result| {
+            let area_name = data
+                .areas
+                .iter()
+                .find(|area| area.id == result.area_id)
+                .and_then(|area| area.name.clone())
+                .unwrap_or_default();
+            let selections = contest_candidates
+                .iter()
+                .map(|candidate| {
+                    let candidate_result =
+                        data.results_area_contest_candidates
+                            .iter()
+                            .find(|candidate_result| {
+                                candidate_result.contest_id == contest.id
+                                    && candidate_result.area_id == result.area_id
+                                    && candidate_result.candidate_id == candidate.id
+                            });
+                    get_selection(
+                        candidate,
+                        candidate_result.and_then(|candidate_result| candidate_result.cast_votes),
+                        candidate_result
+                            .and_then(|candidate_result| candidate_result.winning_position),
+                    )
+                })
+                .collect();
+
+            EMLReportingUnitVotes {
+                identifier: get_identifier(&result.area_id, None, &area_name),
+                total_votes: get_total_votes(
+                    selections,
+                    result.total_votes,
+                    result.total_valid_votes,
+                    result.explicit_invalid_votes,
+                    result.implicit_invalid_votes,
+                    result.total_invalid_votes,
+                    result.blank_votes,
+                ),
+            }
+        })
+        .collect()
+}
+
+fn get_election(data: &ResultsEventData, election: &Election) -> EMLElection {
+    let contests = data
+        .contests
+        .iter()
+        .filter(|contest| contest.election_id == election.id)
+        .filter_map(|contest| {
+            let results_contest = data
+                .results_contests
+                .iter()
+                .find(|result| result.contest_id == contest.id)?;
+            let contest_candidates: Vec<&Candidate> = data
+                .candidates
+                .iter()
+                .filter(|candidate| candidate.contest_id.as_deref() == Some(contest.id.as_str()))
+                .collect();
+            let selections = contest_candidates
+                .iter()
+                .map(|candidate| {
+                    let candidate_result =
+                        data.results_contest_candidates
+                            .iter()
+                            .find(|candidate_result| {
+                                candidate_result.contest_id == contest.id
+                                    && candidate_result.candidate_id == candidate.id
+                            });
+                    get_selection(
+                        candidate,
+                        candidate_result.and_then(|candidate_result| candidate_result.cast_votes),
+                        candidate_result
+                            .and_then(|candidate_result| candidate_result.winning_position),
+                    )
+                })
+                .collect();
+
+            Some(EMLContest {
+                identifier: get_identifier(
+                    &contest.id,
+                    contest.alias.as_deref(),
+                    &contest.name.clone().unwrap_or_default(),
+                ),
+                total_votes: get_total_votes(
+                    selections,
+                    results_contest.total_votes,
+                    results_contest.total_valid_votes,
+                    results_contest.explicit_invalid_votes,
+                    results_contest.implicit_invalid_votes,
+                    results_contest.total_invalid_votes,
+                    results_contest.blank_votes,
+                ),
+                reporting_unit_votes: get_reporting_unit_votes(data, contest, &contest_candidates),
+            })
+        })
+        .collect();
+
+    EMLElection {
+        identifier: get_identifier(&election.id, election.alias.as_deref(), &election.name),
+        contests,
+    }
+}
+
+/// Only the results of a completed tally session are official.
+fn get_official_status(results_state: ResultsState) -> OfficialStatus {
+    match results_state {
+        ResultsState::Partial => OfficialStatus::UNOFFICIAL,
+        ResultsState::Final | ResultsState::Recount => OfficialStatus::OFFICIAL,
+    }
+}
+
+/// Generates the EML file of the results. Only the elections and contests
+/// with results are included.
+#[instrument(skip(data), err)]
+pub fn generate_eml_results_file(
+    data: &ResultsEventData,
+    results_event_id: &str,
+    generated_date: DateTime<Utc>,
+) -> Result<EMLFile> {
+    let elections: Vec<EMLElection> = data
+        .elections
+        .iter()
+        .filter(|election| {
+            data.results_contests
+                .iter()
+                .any(|result| result.election_id == election.id)
+        })
+        .map(|election| get_election(data, election))
+        .collect();
+    if elections.is_empty() {
+        return Err(anyhow!(
+            "No results found for results event id: {}",
+            results_event_id
+        ));
+    }
+
+    Ok(EMLFile {
+        id: results_event_id.to_string(),
+        header: EMLHeader {
+            transaction_id: results_event_id.to_string(),
+            issue_date: generated_date.format(ISSUE_DATE_FORMAT).to_string(),
+            official_status_detail: EMLOfficialStatusDetail {
+                official_status: get_official_status(data.results_state).to_string(),
+                status_date: generated_date
+                    .format(OFFICIAL_STATUS_DATE_FORMAT)
+                    .to_string(),
+            },
+        },
+        counts: vec![EMLCount {
+            identifier: get_identifier(
+                &data.election_event.id,
+                data.election_event.alias.as_deref(),
+                &data.election_event.name,
+            ),
+            elections,
+        }],
+    })
+}
+
+/// Renders the EML file as the XML of the given message.
+#[instrument(skip(eml_file), err)]
+pub fn render_eml_results(eml_file: &EMLFile, message: EMLResultsMessage) -> Result<String> {
+    let mut variables_map = Map::new();
+    variables_map.insert("data".to_string(), serde_json::to_value(eml_file)?);
+    render_template_text(message.get_template(), variables_map)
+        .map_err(|err| anyhow!("Error rendering EML {}: {}", message.get_id(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::export::results_event_data::test_data::*;
+    use roxmltree::{Document, Node};
+    use std::path::Path;
+    use std::process::Command;
+
+    const EML_NAMESPACE: &str = "urn:oasis:names:tc:evs:schema:eml";
+
+    fn render(message: EMLResultsMessage) -> String {
+        let eml_file =
+            generate_eml_results_file(&get_data(), TENANT_ID, get_generated_date()).unwrap();
+        render_eml_results(&eml_file, message).unwrap()
+    }
+
+    fn children<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Vec<Node<'a, 'input>> {
+        node.children()
+            .filter(|child| child.has_tag_name((EML_NAMESPACE, name)))
+            .collect()
+    }
+
+    fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Node<'a, 'input> {
+        children(node, name)[0]
+    }
+
+    fn text(node: Node, name: &str) -> String {
+        child(node, name).text().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn test_eml_510_count() {
+        let xml = render(EMLResultsMessage::Count);
+        assert!(xml.starts_with("<?xml"));
+        let document = Document::parse(&xml).unwrap();
+        let root = document.root_element();
+        assert!(root.has_tag_name((EML_NAMESPACE, "EML")));
+        assert_eq!(root.attribute("Id"), Some("510"));
+        assert_eq!(text(root, "TransactionId"), TENANT_ID);
+        assert_eq!(text(root, "IssueDate"), "2025-05-06T10:00:00");
+
+        let count = child(root, "Count");
+        assert_eq!(child(count, "EventIdentifier").attribute("Id"), Some("ME"));
+        let election = child(count, "Election");
+        assert_eq!(
+            child(election, "ElectionIdentifier").attribute("Id"),
+            Some(ELECTION_ID)
+        );
+        let contest = child(child(election, "Contests"), "Contest");
+        assert_eq!(
+            text(child(contest, "ContestIdentifier"), "ContestName"),
+            "Mayor"
+        );
+
+        let total_votes = child(contest, "TotalVotes");
+        let votes: Vec<(String, String)> = children(total_votes, "Selection")
+            .into_iter()
+            .map(|selection| {
+                let identifier = child(child(selection, "Candidate"), "CandidateIdentifier");
+                (
+                    identifier.attribute("Id").unwrap().to_string(),
+                    text(selection, "ValidVotes"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            votes,
+            vec![
+                (ALICE_ID.to_string(), "7".to_string()),
+                (BOB_ID.to_string(), "4".to_string())
+            ]
+        );
+        assert_eq!(text(total_votes, "Cast"), "13");
+        assert_eq!(text(total_votes, "TotalCounted"), "11");
+        assert_eq!(
+            child(total_votes, "RejectedVotes").attribute("ReasonCode"),
+            Some("invalid")
+        );
+        assert_eq!(
+            child(total_votes, "UncountedVotes").attribute("Reason"),
+            Some("blank")
+        );
+
+        let reporting_unit = child(contest, "ReportingUnitVotes");
+        let reporting_unit_identifier = child(reporting_unit, "ReportingUnitIdentifier");
+        assert_eq!(reporting_unit_identifier.attribute("Id"), Some(DISTRICT_ID));
+        assert_eq!(reporting_unit_identifier.text(), Some("North district"));
+        assert_eq!(children(reporting_unit, "Selection").len(), 2);
+    }
+
+    #[test]
+    fn test_eml_520_result() {
+        let xml = render(EMLResultsMessage::Result);
+        let document = Document::parse(&xml).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.attribute("Id"), Some("520"));
+
+        let contest = child(child(child(root, "Result"), "Election"), "Contest");
+        let elected: Vec<String> = children(contest, "Selection")
+            .into_iter()
+            .map(|selection| text(selection, "Elected"))
+            .collect();
+        assert_eq!(elected, vec!["yes", "no"]);
+    }
+
+    #[test]
+    fn test_official_status() {
+        let xml = render(EMLResultsMessage::Count);
+        let document = Document::parse(&xml).unwrap();
+        let status = child(document.root_element(), "OfficialStatusDetail");
+        assert_eq!(text(status, "OfficialStatus"), "official");
+
+        let data = ResultsEventData {
+            results_state: ResultsState::Partial,
+            ..get_data()
+        };
+        let eml_file = generate_eml_results_file(&data, TENANT_ID, get_generated_date()).unwrap();
+        assert_eq!(
+            eml_file.header.official_status_detail.official_status,
+            "unofficial"
+        );
+    }
+
+    /// Validates the messages against the OASIS EML v7 schemas vendored in
+    /// `src/resources/eml` by `scripts/fetch-results-schemas.sh`. Requires
+    /// `xmllint`, so it's ignored by default and run by the CI tests job.
+    #[test]
+    #[ignore]
+    fn test_eml_matches_xsd() {
+        let xsd_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/resources/eml");
+        let xsds = [
+            (EMLResultsMessage::Count, "510-count-v7-0.xsd"),
+            (EMLResultsMessage::Result, "520-result-v7-0.xsd"),
+        ];
+        for (_, xsd) in &xsds {
+            assert!(
+                xsd_dir.join(xsd).exists(),
+                "Missing {xsd}, run scripts/fetch-results-schemas.sh"
+            );
+        }
+        Command::new("xmllint")
+            .arg("--version")
+            .output()
+            .expect("xmllint is required to validate the EML schemas");
+        for (message, xsd) in xsds {
+            let xml_path = std::env::temp_dir().join(format!("eml-{}.xml", message.get_id()));
+            std::fs::write(&xml_path, render(message)).unwrap();
+            let output = Command::new("xmllint")
+                .arg("--noout")
+                .arg("--schema")
+                .arg(xsd_dir.join(xsd))
+                .arg(&xml_path)
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "Invalid EML {}: {}",
+                message.get_id(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+}
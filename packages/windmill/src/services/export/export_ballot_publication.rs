@@ -85,6 +85,7 @@ pub async fn process_export_ballot_publication(
 
         let ballot_design = json!({
             "ballot_publication_id": &ballot_publication.id,
+            "published_at": &ballot_publication.published_at,
             "ballot_styles": ballot_emls,
         });
 
@@ -13,6 +13,7 @@ use crate::{
         tally_session::get_tally_sessions_by_election_event_id,
         tally_session_contest::get_event_tally_session_contest,
         tally_session_execution::get_event_tally_session_executions,
+        tally_sheet::get_published_tally_sheets_by_event,
     },
     types::documents::ETallyDocuments,
 };
@@ -22,7 +23,7 @@ use deadpool_postgres::Transaction;
 use futures::future::join_all;
 use sequent_core::{
     types::{
-        hasura::core::{TallySession, TallySessionContest, TallySessionExecution},
+        hasura::core::{TallySession, TallySessionContest, TallySessionExecution, TallySheet},
         results::{
             ResultsAreaContest, ResultsAreaContestCandidate, ResultsContest,
             ResultsContestCandidate, ResultsElection, ResultsElectionArea, ResultsEvent,
@@ -646,6 +647,70 @@ pub async fn export_results_area_contest_candidate(
     Ok((file_name, temp_path))
 }
 
+/// Exports the published tally sheets, the ones taken into account by the
+/// tally.
+#[instrument(err, skip(hasura_transaction))]
+pub async fn export_tally_sheets(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<(String, TempPath)> {
+    let tally_sheets: Vec<TallySheet> =
+        get_published_tally_sheets_by_event(hasura_transaction, tenant_id, election_event_id)
+            .await
+            .map_err(|e| anyhow!("Error in get_published_tally_sheets_by_event: {e:?}"))?;
+
+    let file_name = ETallyDocuments::TALLY_SHEET.to_file_name().to_string();
+
+    let mut writer = csv::WriterBuilder::new().delimiter(b',').from_writer(
+        generate_temp_file(&file_name, ".csv").with_context(|| "Error creating temporary file")?,
+    );
+
+    writer.write_record(&[
+        "id".to_string(),
+        "tenant_id".to_string(),
+        "election_event_id".to_string(),
+        "election_id".to_string(),
+        "contest_id".to_string(),
+        "area_id".to_string(),
+        "created_at".to_string(),
+        "last_updated_at".to_string(),
+        "labels".to_string(),
+        "annotations".to_string(),
+        "published_at".to_string(),
+        "published_by_user_id".to_string(),
+        "content".to_string(),
+        "channel".to_string(),
+        "deleted_at".to_string(),
+        "created_by_user_id".to_string(),
+        "version".to_string(),
+        "approved_at".to_string(),
+        "approved_by_user_id".to_string(),
+    ])?;
+
+    for tally_sheet in tally_sheets {
+        let values: Vec<String> = serde_json::to_value(tally_sheet)?
+            .as_object()
+            .ok_or_else(|| anyhow!("Failed to convert tally_sheet to JSON object"))?
+            .values()
+            .map(|value| value.to_string())
+            .collect();
+
+        writer.write_record(&values)?;
+    }
+
+    writer
+        .flush()
+        .with_context(|| "Error flushing CSV writer")?;
+
+    let temp_path = writer
+        .into_inner()
+        .with_context(|| "Error getting inner writer")?
+        .into_temp_path();
+
+    Ok((file_name, temp_path))
+}
+
 fn get_export_tasks<'a>(
     hasura_transaction: &'a Transaction<'a>,
     tenant_id: &'a str,
@@ -702,6 +767,11 @@ fn get_export_tasks<'a>(
             tenant_id,
             election_event_id,
         )),
+        Box::pin(export_tally_sheets(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+        )),
     ]
 }
 
@@ -6,7 +6,15 @@ use crate::postgres::tally_session_execution::get_last_tally_session_execution;
 use crate::postgres::tally_session_execution::update_tally_session_execution_documents;
 use crate::services::documents::get_document_as_temp_file;
 use crate::services::documents::upload_and_return_document;
+use crate::services::export::cdf_generator::generate_election_report;
+use crate::services::export::eml_results_generator::{
+    generate_eml_results_file, render_eml_results, EMLResultsMessage,
+};
+use crate::services::export::results_event_data::{
+    get_results_event_data, get_tally_session_results, TallySessionResults,
+};
 use anyhow::{anyhow, Result};
+use chrono::Utc;
 use deadpool_postgres::Transaction;
 use rusqlite::{types::Type, Connection};
 use rust_xlsxwriter::Workbook;
@@ -14,6 +22,7 @@ use sequent_core::serialization::deserialize_with_path::deserialize_str;
 use sequent_core::temp_path::generate_temp_file;
 use sequent_core::temp_path::get_file_size;
 use sequent_core::types::ceremonies::TallySessionDocuments;
+use std::io::Write;
 use std::path::Path;
 use tracing::instrument;
 
@@ -226,3 +235,128 @@ pub async fn get_tally_session_execution_results_sqlite_file(
 
     Ok((documents, results_event_id, tally_session_execution.id))
 }
+
+/// Uploads an export of the tally results as the document with the given id.
+#[instrument(skip(hasura_transaction, contents), err)]
+async fn upload_tally_results_document(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    file_name: &str,
+    extension: &str,
+    media_type: &str,
+    contents: &[u8],
+    document_id: &str,
+) -> Result<()> {
+    let mut file = generate_temp_file(file_name, extension)?;
+    file.write_all(contents)?;
+    file.flush()?;
+    let temp_path = file.into_temp_path();
+    let temp_path_str = temp_path.to_string_lossy().to_string();
+    let file_size = get_file_size(&temp_path_str)
+        .map_err(|e| anyhow!("Failed to get {} file size: {}", file_name, e))?;
+
+    upload_and_return_document(
+        hasura_transaction,
+        &temp_path_str,
+        file_size,
+        media_type,
+        tenant_id,
+        Some(election_event_id.to_string()),
+        &format!("{}{}", file_name, extension),
+        Some(document_id.to_string()),
+        false,
+    )
+    .await
+    .map_err(|e| anyhow!("Failed to upload {} document: {}", file_name, e))?;
+
+    Ok(())
+}
+
+/// Exports the results of the last execution of the tally session as a NIST
+/// SP 1500-100 Common Data Format election report, in JSON.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn export_tally_results_to_cdf(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    document_id: &str,
+) -> Result<()> {
+    let TallySessionResults {
+        results_event_id,
+        results_state,
+    } = get_tally_session_results(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?;
+    let data = get_results_event_data(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &results_event_id,
+        results_state,
+    )
+    .await?;
+    let report = generate_election_report(&data, Utc::now())?;
+
+    upload_tally_results_document(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &format!("results-cdf-{}", results_event_id),
+        ".json",
+        "application/json",
+        &serde_json::to_vec_pretty(&report)?,
+        document_id,
+    )
+    .await
+}
+
+/// Exports the results of the last execution of the tally session as an
+/// OASIS EML 510 (count) or 520 (result) message.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn export_tally_results_to_eml(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    message: EMLResultsMessage,
+    document_id: &str,
+) -> Result<()> {
+    let TallySessionResults {
+        results_event_id,
+        results_state,
+    } = get_tally_session_results(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?;
+    let data = get_results_event_data(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &results_event_id,
+        results_state,
+    )
+    .await?;
+    let eml_file = generate_eml_results_file(&data, &results_event_id, Utc::now())?;
+    let xml = render_eml_results(&eml_file, message)?;
+
+    upload_tally_results_document(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        &format!("results-eml-{}-{}", message.get_id(), results_event_id),
+        ".xml",
+        "application/xml",
+        xml.as_bytes(),
+        document_id,
+    )
+    .await
+}
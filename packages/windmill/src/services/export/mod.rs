@@ -2,6 +2,9 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+pub mod cdf_generator;
+pub mod cdf_types;
+pub mod eml_results_generator;
 pub mod export_application;
 pub mod export_ballot_publication;
 pub mod export_bulletin_boards;
@@ -15,3 +18,4 @@ pub mod export_tenant;
 pub mod export_tenant_config;
 pub mod export_trustees;
 pub mod export_users;
+pub mod results_event_data;
@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::area::get_event_areas;
+use crate::postgres::candidate::export_candidates;
+use crate::postgres::contest::export_contests;
+use crate::postgres::election::get_elections;
+use crate::postgres::election_event::get_election_event_by_id;
+use crate::postgres::results_area_contest::get_event_results_area_contest;
+use crate::postgres::results_area_contest_candidate::get_results_event_area_contest_candidates;
+use crate::postgres::results_contest::get_event_results_contest;
+use crate::postgres::results_contest_candidate::get_results_event_contest_candidates;
+use crate::postgres::tally_session::get_tally_session_by_id;
+use crate::postgres::tally_session_execution::get_last_tally_session_execution;
+use crate::services::ceremonies::tally_ceremony::get_tally_ceremony_status;
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Transaction;
+use sequent_core::types::ceremonies::TallyExecutionStatus;
+use sequent_core::types::hasura::core::{Area, Candidate, Contest, Election, ElectionEvent};
+use sequent_core::types::results::{
+    ResultsAreaContest, ResultsAreaContestCandidate, ResultsContest, ResultsContestCandidate,
+};
+use tracing::instrument;
+
+/// Results of a results event, together with the election event entities
+/// they refer to.
+#[derive(Debug, Clone)]
+pub struct ResultsEventData {
+    pub election_event: ElectionEvent,
+    pub elections: Vec<Election>,
+    pub contests: Vec<Contest>,
+    pub candidates: Vec<Candidate>,
+    pub areas: Vec<Area>,
+    pub results_contests: Vec<ResultsContest>,
+    pub results_contest_candidates: Vec<ResultsContestCandidate>,
+    pub results_area_contests: Vec<ResultsAreaContest>,
+    pub results_area_contest_candidates: Vec<ResultsAreaContestCandidate>,
+    pub results_state: ResultsState,
+}
+
+/// State of the tally session the results come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultsState {
+    /// The tally session is still running, so not all the results are in.
+    #[default]
+    Partial,
+    /// The tally session completed successfully.
+    Final,
+    /// The tally session completed successfully, recounting a previous
+    /// execution.
+    Recount,
+}
+
+/// Results event of the last execution of a tally session.
+#[derive(Debug, Clone)]
+pub struct TallySessionResults {
+    pub results_event_id: String,
+    pub results_state: ResultsState,
+}
+
+/// Returns the results event of the last execution of the tally session,
+/// and whether its results are final.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_tally_session_results(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+) -> Result<TallySessionResults> {
+    let tally_session = get_tally_session_by_id(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await?;
+    let tally_session_execution = get_last_tally_session_execution(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+    )
+    .await
+    .map_err(|e| anyhow!("Failed to get last tally session execution: {}", e))?
+    .ok_or(anyhow!(
+        "No tally session execution found for tally session id: {}",
+        tally_session_id
+    ))?;
+
+    let results_event_id = tally_session_execution
+        .results_event_id
+        .clone()
+        .ok_or(anyhow!(
+            "No results event id found for tally session id: {}",
+            tally_session_id
+        ))?;
+
+    let is_final = tally_session.is_execution_completed
+        && tally_session.execution_status == Some(TallyExecutionStatus::SUCCESS.to_string());
+    let results_state = if !is_final {
+        ResultsState::Partial
+    } else if get_tally_ceremony_status(tally_session_execution.status)
+        .map(|status| status.recount.is_some())
+        .unwrap_or(false)
+    {
+        ResultsState::Recount
+    } else {
+        ResultsState::Final
+    };
+
+    Ok(TallySessionResults {
+        results_event_id,
+        results_state,
+    })
+}
+
+/// Reads the results of a results event, and the elections, contests,
+/// candidates and areas of the election event.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_results_event_data(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    results_event_id: &str,
+    results_state: ResultsState,
+) -> Result<ResultsEventData> {
+    let election_event =
+        get_election_event_by_id(hasura_transaction, tenant_id, election_event_id).await?;
+    let elections = get_elections(hasura_transaction, tenant_id, election_event_id, None).await?;
+    let contests = export_contests(hasura_transaction, tenant_id, election_event_id).await?;
+    let candidates = export_candidates(hasura_transaction, tenant_id, election_event_id).await?;
+    let areas = get_event_areas(hasura_transaction, tenant_id, election_event_id).await?;
+
+    let results_contests =
+        get_event_results_contest(hasura_transaction, tenant_id, election_event_id)
+            .await?
+            .into_iter()
+            .filter(|result| result.results_event_id == results_event_id)
+            .collect();
+    let results_area_contests =
+        get_event_results_area_contest(hasura_transaction, tenant_id, election_event_id)
+            .await?
+            .into_iter()
+            .filter(|result| result.results_event_id == results_event_id)
+            .collect();
+    let results_contest_candidates = get_results_event_contest_candidates(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        results_event_id,
+    )
+    .await?;
+    let results_area_contest_candidates = get_results_event_area_contest_candidates(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        results_event_id,
+    )
+    .await?;
+
+    Ok(ResultsEventData {
+        election_event,
+        elections,
+        contests,
+        candidates,
+        areas,
+        results_contests,
+        results_contest_candidates,
+        results_area_contests,
+        results_area_contest_candidates,
+        results_state,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod test_data {
+    use super::*;
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::de::DeserializeOwned;
+    use serde_json::{json, Value};
+
+    pub const TENANT_ID: &str = "90505c8a-23a9-4cdf-a26b-4e19f6a097d5";
+    pub const ELECTION_EVENT_ID: &str = "9f48606b-3159-4c6a-9c3f-f7f49badfd8b";
+    pub const ELECTION_ID: &str = "09af46e9-a40e-4ad5-a209-90654d3aecc2";
+    pub const CONTEST_ID: &str = "1dea377d-4ec0-4436-aa2c-0c8f5ec7a5be";
+    pub const CITY_ID: &str = "5c1a3b1e-0b7c-4d0e-9a53-1f2a0c7e8d01";
+    pub const DISTRICT_ID: &str = "5c1a3b1e-0b7c-4d0e-9a53-1f2a0c7e8d02";
+    pub const ALICE_ID: &str = "ca39ad00-2927-4279-a0fc-1d9010900b76";
+    pub const BOB_ID: &str = "bb39ad00-2927-4279-a0fc-1d9010900b77";
+
+    pub fn from_json<T: DeserializeOwned>(value: Value) -> T {
+        serde_json::from_value(value).unwrap()
+    }
+
+    pub fn ids(value: Value) -> Value {
+        let mut value = value;
+        let object = value.as_object_mut().unwrap();
+        object.insert("tenant_id".into(), json!(TENANT_ID));
+        object.insert("election_event_id".into(), json!(ELECTION_EVENT_ID));
+        value
+    }
+
+    pub fn candidate_result(candidate_id: &str, area_id: Option<&str>, votes: i64) -> Value {
+        ids(json!({
+            "id": format!("{candidate_id}{}", area_id.unwrap_or_default()),
+            "election_id": ELECTION_ID,
+            "contest_id": CONTEST_ID,
+            "candidate_id": candidate_id,
+            "area_id": area_id,
+            "results_event_id": TENANT_ID,
+            "cast_votes": votes,
+            "winning_position": (candidate_id == ALICE_ID).then_some(1),
+        }))
+    }
+
+    pub fn contest_result(area_id: Option<&str>, total: i64, invalid: i64, blank: i64) -> Value {
+        ids(json!({
+            "id": area_id.unwrap_or(CONTEST_ID),
+            "election_id": ELECTION_ID,
+            "contest_id": CONTEST_ID,
+            "area_id": area_id,
+            "results_event_id": TENANT_ID,
+            "total_votes": total,
+            "total_valid_votes": total - invalid - blank,
+            "total_invalid_votes": invalid,
+            "blank_votes": blank,
+            "counting_algorithm": "plurality-at-large",
+        }))
+    }
+
+    pub fn get_data() -> ResultsEventData {
+        ResultsEventData {
+            election_event: from_json(json!({
+                "id": ELECTION_EVENT_ID,
+                "tenant_id": TENANT_ID,
+                "name": "Municipal elections",
+                "alias": "ME",
+                "is_archived": false,
+                "encryption_protocol": "RSA256",
+                "presentation": {"language_conf": {"default_language_code": "es"}},
+            })),
+            elections: vec![from_json(ids(json!({
+                "id": ELECTION_ID,
+                "name": "Mayor election",
+                "status": {
+                    "voting_period_dates": {
+                        "first_started_at": "2025-05-04T08:00:00Z",
+                        "last_stopped_at": "2025-05-05T20:00:00Z",
+                    },
+                },
+            })))],
+            contests: vec![from_json(ids(json!({
+                "id": CONTEST_ID,
+                "election_id": ELECTION_ID,
+                "name": "Mayor",
+                "max_votes": 1,
+                "winning_candidates_num": 1,
+            })))],
+            candidates: vec![
+                from_json(ids(
+                    json!({"id": ALICE_ID, "contest_id": CONTEST_ID, "name": "Alice"}),
+                )),
+                from_json(ids(
+                    json!({"id": BOB_ID, "contest_id": CONTEST_ID, "name": "Bob"}),
+                )),
+            ],
+            areas: vec![
+                from_json(ids(json!({"id": CITY_ID, "name": "City", "type": "City"}))),
+                from_json(ids(json!({
+                    "id": DISTRICT_ID,
+                    "name": "North district",
+                    "type": "district",
+                    "parent_id": CITY_ID,
+                }))),
+            ],
+            results_contests: vec![from_json(contest_result(None, 13, 1, 1))],
+            results_contest_candidates: vec![
+                from_json(candidate_result(ALICE_ID, None, 7)),
+                from_json(candidate_result(BOB_ID, None, 4)),
+            ],
+            results_area_contests: vec![from_json(contest_result(Some(DISTRICT_ID), 13, 1, 1))],
+            results_area_contest_candidates: vec![
+                from_json(candidate_result(ALICE_ID, Some(DISTRICT_ID), 7)),
+                from_json(candidate_result(BOB_ID, Some(DISTRICT_ID), 4)),
+            ],
+            results_state: ResultsState::Final,
+        }
+    }
+
+    pub fn get_generated_date() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 5, 6, 10, 0, 0).unwrap()
+    }
+}
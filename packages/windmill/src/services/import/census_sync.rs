@@ -0,0 +1,925 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Incremental synchronisation of the voters of an election event with a
+//! full electoral roll. Unlike `import_users_file`, which can only add users,
+//! the roll is compared with the existing voters of the event realm, matched
+//! by a key attribute, to compute the voters to add, update and disable.
+
+use super::import_users::{
+    AREA_NAME_COL_NAME, ELECTION_COL_PREFIX, EMAIL_COL_NAME, EMAIL_VERIFIED_COL_NAME,
+    GROUP_COL_NAME, HASHED_PASSWORD_COL_NAME, HEADER_RE, NUMBER_OF_ITERATIONS_COL_NAME,
+    PASSWORD_COL_NAME, SALT_COL_NAME, USERNAME_COL_NAME,
+};
+use crate::postgres::area::get_areas_by_name;
+use crate::postgres::election_event::get_election_event_by_id;
+use crate::postgres::keycloak_realm;
+use crate::services::database::get_keycloak_pool;
+use crate::services::documents::upload_and_return_document;
+use crate::services::election_event_board::get_election_event_board;
+use crate::services::electoral_log::ElectoralLog;
+use anyhow::{anyhow, bail, Context, Result};
+use deadpool_postgres::Transaction;
+use sequent_core::services::keycloak::{get_event_realm, MULTIVALUE_USER_ATTRIBUTE_SEPARATOR};
+use sequent_core::types::keycloak::{AREA_ID_ATTR_NAME, TENANT_ID_ATTR_NAME};
+use sequent_core::util::temp_path::{generate_temp_file, get_file_size};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Write};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+pub const DEFAULT_CENSUS_KEY_ATTRIBUTE: &str = "username";
+/// Number of rows of each kind of change included in the report.
+pub const CENSUS_SYNC_SAMPLE_SIZE: usize = 20;
+const ENABLED_COL_NAME: &str = "enabled";
+const DEFAULT_VOTER_GROUP: &str = "voter";
+
+/// Fields stored in the user_entity table, the rest are user attributes.
+const USER_ENTITY_FIELDS: [&str; 5] = ["username", "email", "first_name", "last_name", "enabled"];
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CensusSyncInput {
+    pub tenant_id: String,
+    pub election_event_id: String,
+    /// Document with the electoral roll, as a csv or tsv file.
+    pub document_id: String,
+    pub sha256: Option<String>,
+    /// Attribute used to match the rows of the roll with the existing voters,
+    /// `username` by default.
+    pub key_attribute: Option<String>,
+    /// Only computes the changes, without applying them.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Document id of the uploaded report.
+    pub report_document_id: String,
+    pub user_id: String,
+    pub username: Option<String>,
+}
+
+impl CensusSyncInput {
+    pub fn get_key_attribute(&self) -> String {
+        self.key_attribute
+            .clone()
+            .filter(|key_attribute| !key_attribute.is_empty())
+            .unwrap_or(DEFAULT_CENSUS_KEY_ATTRIBUTE.to_string())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CensusRecord {
+    pub key: String,
+    pub group: Option<String>,
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExistingVoter {
+    pub id: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CensusFieldChange {
+    pub previous: Option<String>,
+    pub current: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CensusUpdate {
+    pub user_id: String,
+    pub key: String,
+    pub area_id: Option<String>,
+    pub changes: BTreeMap<String, CensusFieldChange>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CensusDisable {
+    pub user_id: String,
+    pub key: String,
+    pub area_id: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CensusDiff {
+    pub adds: Vec<CensusRecord>,
+    pub updates: Vec<CensusUpdate>,
+    pub disables: Vec<CensusDisable>,
+    pub unchanged: usize,
+    /// Existing voters without the key attribute, which are left untouched.
+    pub without_key: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CensusSyncReport {
+    pub dry_run: bool,
+    /// Whether the changes were applied. They are not applied in dry runs or
+    /// if the roll has errors.
+    pub applied: bool,
+    pub key_attribute: String,
+    pub total_records: usize,
+    pub adds: usize,
+    pub updates: usize,
+    pub disables: usize,
+    pub unchanged: usize,
+    pub without_key: usize,
+    pub errors: Vec<String>,
+    pub sample_adds: Vec<CensusRecord>,
+    pub sample_updates: Vec<CensusUpdate>,
+    pub sample_disables: Vec<CensusDisable>,
+}
+
+impl CensusSyncReport {
+    pub fn new(
+        diff: &CensusDiff,
+        census_file: &CensusFile,
+        key_attribute: &str,
+        dry_run: bool,
+    ) -> Self {
+        CensusSyncReport {
+            dry_run,
+            applied: false,
+            key_attribute: key_attribute.to_string(),
+            total_records: census_file.records.len(),
+            adds: diff.adds.len(),
+            updates: diff.updates.len(),
+            disables: diff.disables.len(),
+            unchanged: diff.unchanged,
+            without_key: diff.without_key,
+            errors: census_file.errors.clone(),
+            sample_adds: diff
+                .adds
+                .iter()
+                .take(CENSUS_SYNC_SAMPLE_SIZE)
+                .cloned()
+                .collect(),
+            sample_updates: diff
+                .updates
+                .iter()
+                .take(CENSUS_SYNC_SAMPLE_SIZE)
+                .cloned()
+                .collect(),
+            sample_disables: diff
+                .disables
+                .iter()
+                .take(CENSUS_SYNC_SAMPLE_SIZE)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CensusFile {
+    /// Fields present in the roll, compared with the existing voters.
+    pub fields: Vec<String>,
+    pub records: Vec<CensusRecord>,
+    /// Invalid rows. The changes are not applied if there's any.
+    pub errors: Vec<String>,
+}
+
+/// Normalizes a value the same way the import does, so that it can be
+/// compared with the stored one. Multivalue attributes are sorted.
+fn normalize_value(field: &str, value: &str) -> String {
+    if field == *USERNAME_COL_NAME || field == *EMAIL_COL_NAME || field == ENABLED_COL_NAME {
+        value.trim().to_lowercase()
+    } else if value.contains(MULTIVALUE_USER_ATTRIBUTE_SEPARATOR) {
+        let mut values: Vec<&str> = value.split(MULTIVALUE_USER_ATTRIBUTE_SEPARATOR).collect();
+        values.sort();
+        values.join(MULTIVALUE_USER_ATTRIBUTE_SEPARATOR)
+    } else {
+        value.to_string()
+    }
+}
+
+fn get_area_id(fields: &BTreeMap<String, String>) -> Option<String> {
+    fields
+        .get(AREA_ID_ATTR_NAME)
+        .filter(|area_id| !area_id.is_empty())
+        .cloned()
+}
+
+/// Reads an electoral roll. The columns are the same as in the import,
+/// except for the credentials, which are not supported. `area_name` is
+/// converted to the `area-id` attribute using `areas_by_name`.
+#[instrument(skip(reader, areas_by_name), err)]
+pub fn read_census_file<R: Read>(
+    reader: R,
+    separator: u8,
+    key_attribute: &str,
+    areas_by_name: &HashMap<String, String>,
+) -> Result<CensusFile> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(separator)
+        .from_reader(reader);
+    let headers = rdr
+        .headers()
+        .map_err(|err| anyhow!("Error reading CSV headers from census file: {err}"))?
+        .clone();
+
+    let credential_columns = [
+        &*PASSWORD_COL_NAME,
+        &*HASHED_PASSWORD_COL_NAME,
+        &*SALT_COL_NAME,
+        &*NUMBER_OF_ITERATIONS_COL_NAME,
+    ];
+    let mut columns: Vec<Option<String>> = vec![];
+    for header in headers.iter() {
+        if !HEADER_RE.is_match(header) {
+            bail!("CSV Header contains characters not allowed: {header}");
+        }
+        if credential_columns
+            .iter()
+            .any(|column| column.as_str() == header)
+        {
+            bail!("Column {header} is not supported when synchronising the census");
+        }
+        let column = match header {
+            header if header == *AREA_NAME_COL_NAME => Some(AREA_ID_ATTR_NAME.to_string()),
+            header if header == *GROUP_COL_NAME || header == *EMAIL_VERIFIED_COL_NAME => None,
+            header if header.starts_with(&*ELECTION_COL_PREFIX) => None,
+            header => Some(header.to_string()),
+        };
+        columns.push(column);
+    }
+    let group_index = headers.iter().position(|header| header == *GROUP_COL_NAME);
+
+    let mut fields: Vec<String> = columns.iter().flatten().cloned().collect();
+    if !fields.iter().any(|field| field == key_attribute) {
+        bail!("Key attribute {key_attribute} is not a column of the census file");
+    }
+    // Voters in the roll are enabled unless stated otherwise
+    let has_enabled_column = fields.iter().any(|field| field == ENABLED_COL_NAME);
+    if !has_enabled_column {
+        fields.push(ENABLED_COL_NAME.to_string());
+    }
+
+    let mut census_file = CensusFile {
+        fields,
+        ..Default::default()
+    };
+    let mut keys: HashSet<String> = HashSet::new();
+    for (index, result) in rdr.records().enumerate() {
+        // the first line is the header
+        let line = index + 2;
+        let record = result.map_err(|err| anyhow!("Error reading CSV record: {err}"))?;
+
+        let mut record_fields: BTreeMap<String, String> = BTreeMap::new();
+        let mut error: Option<String> = None;
+        for (data, column) in record.iter().zip(columns.iter()) {
+            let Some(field) = column else {
+                continue;
+            };
+            let value = if field == AREA_ID_ATTR_NAME && !data.is_empty() {
+                match areas_by_name.get(data) {
+                    Some(area_id) => area_id.clone(),
+                    None => {
+                        error = Some(format!("Line {line}: area `{data}` not found"));
+                        break;
+                    }
+                }
+            } else {
+                normalize_value(field, data)
+            };
+            record_fields.insert(field.clone(), value);
+        }
+        if !has_enabled_column {
+            record_fields.insert(ENABLED_COL_NAME.to_string(), "true".to_string());
+        }
+
+        let key = record_fields
+            .get(key_attribute)
+            .cloned()
+            .unwrap_or_default();
+        if error.is_none() && key.is_empty() {
+            error = Some(format!("Line {line}: missing {key_attribute}"));
+        }
+        if error.is_none() && !keys.insert(key.clone()) {
+            error = Some(format!("Line {line}: duplicated {key_attribute} `{key}`"));
+        }
+        if let Some(error) = error {
+            census_file.errors.push(error);
+            continue;
+        }
+
+        census_file.records.push(CensusRecord {
+            key,
+            group: group_index
+                .and_then(|group_index| record.get(group_index))
+                .filter(|group| !group.is_empty())
+                .map(str::to_string),
+            fields: record_fields,
+        });
+    }
+
+    Ok(census_file)
+}
+
+/// Compares the roll with the existing voters. Only the fields present in
+/// the roll are compared, and enabled voters missing from the roll are
+/// disabled.
+pub fn compute_census_diff(
+    existing_voters: Vec<ExistingVoter>,
+    records: &[CensusRecord],
+    fields: &[String],
+    key_attribute: &str,
+) -> CensusDiff {
+    let mut diff = CensusDiff::default();
+    let mut voters_by_key: BTreeMap<String, ExistingVoter> = BTreeMap::new();
+    for voter in existing_voters {
+        match voter
+            .fields
+            .get(key_attribute)
+            .map(|key| normalize_value(key_attribute, key))
+            .filter(|key| !key.is_empty())
+        {
+            Some(key) => {
+                voters_by_key.insert(key, voter);
+            }
+            None => diff.without_key += 1,
+        }
+    }
+
+    for record in records {
+        let Some(voter) = voters_by_key.remove(&record.key) else {
+            diff.adds.push(record.clone());
+            continue;
+        };
+
+        let changes: BTreeMap<String, CensusFieldChange> = fields
+            .iter()
+            .filter_map(|field| {
+                let current = record.fields.get(field)?;
+                let previous = voter.fields.get(field);
+                if previous.map(String::as_str).unwrap_or("") == current.as_str() {
+                    return None;
+                }
+                Some((
+                    field.clone(),
+                    CensusFieldChange {
+                        previous: previous.cloned(),
+                        current: current.clone(),
+                    },
+                ))
+            })
+            .collect();
+
+        if changes.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            diff.updates.push(CensusUpdate {
+                user_id: voter.id.clone(),
+                key: record.key.clone(),
+                area_id: get_area_id(&record.fields).or(get_area_id(&voter.fields)),
+                changes,
+            });
+        }
+    }
+
+    for (key, voter) in voters_by_key {
+        let is_enabled = voter
+            .fields
+            .get(ENABLED_COL_NAME)
+            .map(|enabled| enabled == "true")
+            .unwrap_or(true);
+        if is_enabled {
+            diff.disables.push(CensusDisable {
+                user_id: voter.id.clone(),
+                key,
+                area_id: get_area_id(&voter.fields),
+            });
+        } else {
+            diff.unchanged += 1;
+        }
+    }
+
+    diff
+}
+
+/// Voters of the realm, with their user_entity fields and attributes.
+/// Multivalue attributes are joined with the multivalue separator.
+#[instrument(skip(keycloak_transaction), err)]
+async fn get_existing_voters(
+    keycloak_transaction: &Transaction<'_>,
+    realm_id: &str,
+) -> Result<Vec<ExistingVoter>> {
+    let statement = keycloak_transaction
+        .prepare(
+            r#"
+            SELECT
+                u.id,
+                u.username,
+                u.email,
+                u.first_name,
+                u.last_name,
+                u.enabled,
+                COALESCE(attr_json.attributes, '{}'::json) AS attributes
+            FROM
+                user_entity u
+            LEFT JOIN LATERAL (
+                SELECT
+                    json_object_agg(a.name, a.values) AS attributes
+                FROM (
+                    SELECT
+                        ua.name,
+                        json_agg(ua.value ORDER BY ua.value) AS values
+                    FROM user_attribute ua
+                    WHERE ua.user_id = u.id
+                    GROUP BY ua.name
+                ) a
+            ) attr_json ON true
+            WHERE
+                u.realm_id = $1
+                AND u.service_account_client_link IS NULL;
+            "#,
+        )
+        .await?;
+
+    let rows = keycloak_transaction
+        .query(&statement, &[&realm_id])
+        .await
+        .map_err(|err| anyhow!("Error fetching the voters of the realm: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| {
+            let mut fields: BTreeMap<String, String> = BTreeMap::new();
+            for field in ["username", "email", "first_name", "last_name"] {
+                if let Some(value) = row.try_get::<_, Option<String>>(field)? {
+                    fields.insert(field.to_string(), value);
+                }
+            }
+            let enabled: bool = row.try_get("enabled")?;
+            fields.insert(ENABLED_COL_NAME.to_string(), enabled.to_string());
+
+            let attributes: Value = row.try_get("attributes")?;
+            if let Value::Object(attributes) = attributes {
+                for (name, values) in attributes {
+                    let values: Vec<String> = serde_json::from_value(values)?;
+                    fields.insert(name, values.join(MULTIVALUE_USER_ATTRIBUTE_SEPARATOR));
+                }
+            }
+
+            Ok(ExistingVoter {
+                id: row.try_get("id")?,
+                fields,
+            })
+        })
+        .collect()
+}
+
+async fn insert_user_attribute(
+    keycloak_transaction: &Transaction<'_>,
+    user_id: &str,
+    name: &str,
+    value: &str,
+) -> Result<()> {
+    let values = value
+        .split(MULTIVALUE_USER_ATTRIBUTE_SEPARATOR)
+        .filter(|value| !value.is_empty());
+    for value in values {
+        keycloak_transaction
+            .execute(
+                r#"
+                INSERT INTO user_attribute (id, user_id, name, value)
+                VALUES (gen_random_uuid()::VARCHAR, $1, $2, $3);
+                "#,
+                &[&user_id, &name, &value],
+            )
+            .await
+            .map_err(|err| anyhow!("Error inserting user attribute {name}: {err}"))?;
+    }
+    Ok(())
+}
+
+#[instrument(skip(keycloak_transaction, record), err)]
+async fn insert_voter(
+    keycloak_transaction: &Transaction<'_>,
+    realm_id: &str,
+    tenant_id: &str,
+    record: &CensusRecord,
+) -> Result<String> {
+    let user_id = Uuid::new_v4().to_string();
+    let get_field = |field: &str| record.fields.get(field).cloned().unwrap_or_default();
+    let username = Some(get_field("username"))
+        .filter(|username| !username.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let enabled = get_field(ENABLED_COL_NAME) != "false";
+
+    keycloak_transaction
+        .execute(
+            r#"
+            INSERT INTO user_entity (
+                id,
+                realm_id,
+                email,
+                email_verified,
+                enabled,
+                first_name,
+                last_name,
+                username,
+                created_timestamp
+            )
+            VALUES (
+                $1, $2, $3, true, $4, $5, $6, $7,
+                (extract(epoch from now()) * 1000)::bigint
+            );
+            "#,
+            &[
+                &user_id,
+                &realm_id,
+                &get_field("email"),
+                &enabled,
+                &get_field("first_name"),
+                &get_field("last_name"),
+                &username,
+            ],
+        )
+        .await
+        .map_err(|err| anyhow!("Error inserting voter {}: {err}", record.key))?;
+
+    for (name, value) in record.fields.iter() {
+        if !USER_ENTITY_FIELDS.contains(&name.as_str()) {
+            insert_user_attribute(keycloak_transaction, &user_id, name, value).await?;
+        }
+    }
+    insert_user_attribute(
+        keycloak_transaction,
+        &user_id,
+        TENANT_ID_ATTR_NAME,
+        tenant_id,
+    )
+    .await?;
+
+    let group = record
+        .group
+        .clone()
+        .unwrap_or(DEFAULT_VOTER_GROUP.to_string());
+    keycloak_transaction
+        .execute(
+            r#"
+            INSERT INTO user_group_membership (group_id, user_id)
+            SELECT
+                kg.id,
+                $1
+            FROM
+                keycloak_group kg
+            WHERE
+                kg.name = $2
+                AND kg.realm_id = $3;
+            "#,
+            &[&user_id, &group, &realm_id],
+        )
+        .await
+        .map_err(|err| anyhow!("Error adding voter {} to group {group}: {err}", record.key))?;
+
+    Ok(user_id)
+}
+
+#[instrument(skip(keycloak_transaction, update), err)]
+async fn update_voter(keycloak_transaction: &Transaction<'_>, update: &CensusUpdate) -> Result<()> {
+    for (field, change) in update.changes.iter() {
+        if field == ENABLED_COL_NAME {
+            keycloak_transaction
+                .execute(
+                    "UPDATE user_entity SET enabled = $1 WHERE id = $2;",
+                    &[&(change.current != "false"), &update.user_id],
+                )
+                .await
+                .map_err(|err| anyhow!("Error updating voter {}: {err}", update.key))?;
+        } else if USER_ENTITY_FIELDS.contains(&field.as_str()) {
+            // The field is one of USER_ENTITY_FIELDS, so it's safe to use it
+            // as the column name
+            keycloak_transaction
+                .execute(
+                    &format!("UPDATE user_entity SET {field} = $1 WHERE id = $2;"),
+                    &[&change.current, &update.user_id],
+                )
+                .await
+                .map_err(|err| anyhow!("Error updating voter {}: {err}", update.key))?;
+        } else {
+            keycloak_transaction
+                .execute(
+                    "DELETE FROM user_attribute WHERE user_id = $1 AND name = $2;",
+                    &[&update.user_id, &field],
+                )
+                .await
+                .map_err(|err| anyhow!("Error updating voter {}: {err}", update.key))?;
+            insert_user_attribute(
+                keycloak_transaction,
+                &update.user_id,
+                field,
+                &change.current,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+#[instrument(skip(keycloak_transaction, disables), err)]
+async fn disable_voters(
+    keycloak_transaction: &Transaction<'_>,
+    realm_id: &str,
+    disables: &[CensusDisable],
+) -> Result<u64> {
+    let user_ids: Vec<String> = disables
+        .iter()
+        .map(|disable| disable.user_id.clone())
+        .collect();
+    keycloak_transaction
+        .execute(
+            r#"
+            UPDATE user_entity
+            SET enabled = false
+            WHERE
+                realm_id = $1
+                AND id = ANY($2);
+            "#,
+            &[&realm_id, &user_ids],
+        )
+        .await
+        .map_err(|err| anyhow!("Error disabling voters: {err}"))
+}
+
+/// Applies the changes and returns the electoral log entries describing
+/// them, with the area of each voter.
+#[instrument(skip(keycloak_transaction, diff), err)]
+async fn apply_census_diff(
+    keycloak_transaction: &Transaction<'_>,
+    realm_id: &str,
+    tenant_id: &str,
+    diff: &CensusDiff,
+) -> Result<Vec<(String, Option<String>)>> {
+    let mut log_entries: Vec<(String, Option<String>)> = vec![];
+
+    for record in diff.adds.iter() {
+        let user_id = insert_voter(keycloak_transaction, realm_id, tenant_id, record).await?;
+        log_entries.push((
+            format!("added voter {user_id}"),
+            get_area_id(&record.fields),
+        ));
+    }
+    for update in diff.updates.iter() {
+        update_voter(keycloak_transaction, update).await?;
+        let fields = update
+            .changes
+            .keys()
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(", ");
+        log_entries.push((
+            format!("updated voter {}: {fields}", update.user_id),
+            update.area_id.clone(),
+        ));
+    }
+    disable_voters(keycloak_transaction, realm_id, &diff.disables).await?;
+    for disable in diff.disables.iter() {
+        log_entries.push((
+            format!("disabled voter {}", disable.user_id),
+            disable.area_id.clone(),
+        ));
+    }
+
+    Ok(log_entries)
+}
+
+#[instrument(skip(hasura_transaction, report), err)]
+async fn upload_census_sync_report(
+    hasura_transaction: &Transaction<'_>,
+    input: &CensusSyncInput,
+    report: &CensusSyncReport,
+) -> Result<()> {
+    let mut report_file = generate_temp_file("census-sync-", ".json")?;
+    report_file.write_all(&serde_json::to_vec_pretty(report)?)?;
+    report_file.flush()?;
+    let report_path = report_file.into_temp_path();
+    let report_path_str = report_path.to_string_lossy().to_string();
+    let report_size = get_file_size(&report_path_str)?;
+
+    upload_and_return_document(
+        hasura_transaction,
+        &report_path_str,
+        report_size,
+        "application/json",
+        &input.tenant_id,
+        Some(input.election_event_id.clone()),
+        &format!("census-sync-{}.json", input.report_document_id),
+        Some(input.report_document_id.clone()),
+        false,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Synchronises the voters of the election event with the electoral roll.
+/// The changes are applied in a single Keycloak transaction, and each of them
+/// is posted to the electoral log before committing it. In dry runs, or if
+/// the roll has invalid rows, nothing is changed. In all cases a report with
+/// the counts and a sample of the changes is uploaded as
+/// `report_document_id`.
+#[instrument(skip(hasura_transaction, roll_reader), err)]
+pub async fn sync_census<R: Read>(
+    hasura_transaction: &Transaction<'_>,
+    roll_reader: R,
+    separator: u8,
+    input: &CensusSyncInput,
+) -> Result<CensusSyncReport> {
+    let key_attribute = input.get_key_attribute();
+    let areas_by_name = get_areas_by_name(
+        hasura_transaction,
+        &input.tenant_id,
+        &input.election_event_id,
+    )
+    .await?;
+    let census_file = read_census_file(roll_reader, separator, &key_attribute, &areas_by_name)?;
+
+    let mut keycloak_db_client = get_keycloak_pool()
+        .await
+        .get()
+        .await
+        .map_err(|err| anyhow!("Error getting Keycloak DB pool: {err}"))?;
+    let keycloak_transaction = keycloak_db_client
+        .transaction()
+        .await
+        .map_err(|err| anyhow!("Error starting Keycloak transaction: {err}"))?;
+    keycloak_transaction
+        .simple_query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ;")
+        .await
+        .with_context(|| "can't set transaction isolation level")?;
+
+    let realm_name = get_event_realm(&input.tenant_id, &input.election_event_id);
+    let realm_id = keycloak_realm::get_realm_id(&keycloak_transaction, realm_name).await?;
+    let existing_voters = get_existing_voters(&keycloak_transaction, &realm_id).await?;
+
+    let diff = compute_census_diff(
+        existing_voters,
+        &census_file.records,
+        &census_file.fields,
+        &key_attribute,
+    );
+    let mut report = CensusSyncReport::new(&diff, &census_file, &key_attribute, input.dry_run);
+    info!(
+        "census sync: adds={} updates={} disables={} unchanged={} errors={}",
+        report.adds,
+        report.updates,
+        report.disables,
+        report.unchanged,
+        report.errors.len()
+    );
+
+    if !input.dry_run && report.errors.is_empty() {
+        let log_entries =
+            apply_census_diff(&keycloak_transaction, &realm_id, &input.tenant_id, &diff).await?;
+
+        if !log_entries.is_empty() {
+            let election_event = get_election_event_by_id(
+                hasura_transaction,
+                &input.tenant_id,
+                &input.election_event_id,
+            )
+            .await?;
+            let board_name =
+                get_election_event_board(election_event.bulletin_board_reference.clone())
+                    .with_context(|| "missing bulletin board")?;
+            let electoral_log = ElectoralLog::for_admin_user(
+                hasura_transaction,
+                &board_name,
+                &input.tenant_id,
+                &input.election_event_id,
+                &input.user_id,
+                input.username.clone(),
+                None,
+                None,
+            )
+            .await?;
+            electoral_log
+                .post_census_changes(
+                    input.election_event_id.clone(),
+                    log_entries,
+                    Some(input.user_id.clone()),
+                    input.username.clone(),
+                )
+                .await
+                .map_err(|err| anyhow!("error posting to the electoral log: {err}"))?;
+        }
+
+        keycloak_transaction
+            .commit()
+            .await
+            .map_err(|err| anyhow!("Error committing Keycloak transaction: {err}"))?;
+        report.applied = true;
+    }
+
+    upload_census_sync_report(hasura_transaction, input, &report).await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLL: &str = "\
+username,email,first_name,area_name,sequent.read-only.id-card-number
+alice,Alice@example.com,Alice,North,111
+bob,bob@example.com,Bob,South,222
+carol,carol@example.com,Carol,North,333|334
+";
+
+    fn areas() -> HashMap<String, String> {
+        HashMap::from([
+            ("North".to_string(), "area-north".to_string()),
+            ("South".to_string(), "area-south".to_string()),
+        ])
+    }
+
+    fn voter(id: &str, fields: &[(&str, &str)]) -> ExistingVoter {
+        ExistingVoter {
+            id: id.to_string(),
+            fields: fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_read_census_file() {
+        let census_file = read_census_file(ROLL.as_bytes(), b',', "username", &areas()).unwrap();
+        assert!(census_file.errors.is_empty());
+        assert_eq!(census_file.records.len(), 3);
+        let alice = &census_file.records[0];
+        assert_eq!(alice.key, "alice");
+        assert_eq!(alice.fields["email"], "alice@example.com");
+        assert_eq!(alice.fields[AREA_ID_ATTR_NAME], "area-north");
+        assert_eq!(alice.fields[ENABLED_COL_NAME], "true");
+
+        let invalid_roll = "username,area_name\nalice,North\nalice,South\n,North\ndave,East\n";
+        let census_file =
+            read_census_file(invalid_roll.as_bytes(), b',', "username", &areas()).unwrap();
+        assert_eq!(census_file.records.len(), 1);
+        assert_eq!(census_file.errors.len(), 3);
+
+        assert!(read_census_file(ROLL.as_bytes(), b',', "missing", &areas()).is_err());
+        assert!(
+            read_census_file("username,password\n".as_bytes(), b',', "username", &areas()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_compute_census_diff() {
+        let census_file = read_census_file(ROLL.as_bytes(), b',', "username", &areas()).unwrap();
+        let existing_voters = vec![
+            voter(
+                "1",
+                &[
+                    ("username", "alice"),
+                    ("email", "alice@example.com"),
+                    ("first_name", "Alice"),
+                    ("enabled", "true"),
+                    (AREA_ID_ATTR_NAME, "area-north"),
+                    ("sequent.read-only.id-card-number", "111"),
+                ],
+            ),
+            voter(
+                "2",
+                &[
+                    ("username", "bob"),
+                    ("email", "bob@example.com"),
+                    ("first_name", "Bob"),
+                    ("enabled", "false"),
+                    (AREA_ID_ATTR_NAME, "area-north"),
+                    ("sequent.read-only.id-card-number", "222"),
+                ],
+            ),
+            voter("3", &[("username", "dave"), ("enabled", "true")]),
+            voter("4", &[("username", "erin"), ("enabled", "false")]),
+            voter("5", &[("enabled", "true")]),
+        ];
+
+        let diff = compute_census_diff(
+            existing_voters,
+            &census_file.records,
+            &census_file.fields,
+            "username",
+        );
+        assert_eq!(diff.unchanged, 2);
+        assert_eq!(diff.without_key, 1);
+        assert_eq!(diff.adds.len(), 1);
+        assert_eq!(diff.adds[0].key, "carol");
+        assert_eq!(
+            diff.adds[0].fields["sequent.read-only.id-card-number"],
+            "333|334"
+        );
+        assert_eq!(diff.updates.len(), 1);
+        assert_eq!(diff.updates[0].user_id, "2");
+        assert_eq!(
+            diff.updates[0].changes.keys().collect::<Vec<_>>(),
+            vec![AREA_ID_ATTR_NAME, ENABLED_COL_NAME]
+        );
+        assert_eq!(diff.disables.len(), 1);
+        assert_eq!(diff.disables[0].user_id, "3");
+    }
+}
@@ -22,7 +22,7 @@ use tempfile::NamedTempFile;
 use tracing::{info, instrument};
 
 #[instrument]
-fn get_board_record(record: StringRecord) -> Result<(String, B3MessageRow)> {
+pub fn get_board_record(record: StringRecord) -> Result<(String, B3MessageRow)> {
     let fields: Vec<String> = record.iter().map(|val| val.to_string()).collect();
 
     if fields.len() < 10 {
@@ -1273,6 +1273,7 @@ pub async fn maybe_create_scheduled_event(
     let cron_config = CronConfig {
         cron: None,
         scheduled_date: Some(start_date.to_string()),
+        ..Default::default()
     };
     insert_scheduled_event(
         hasura_transaction,
@@ -76,6 +76,24 @@ pub async fn process_record(
     replacement_map: HashMap<String, String>,
 ) -> Result<()> {
     info!("record: {:?}", record);
+    let scheduled_event =
+        get_scheduled_event_from_record(tenant_id, election_event_id, record, &replacement_map)?;
+
+    insert_new_scheduled_event(hasura_transaction, scheduled_event.clone())
+        .await
+        .map_err(|e| anyhow!("Error inserting scheduled_event into the database: {e:?}"))?;
+
+    Ok(())
+}
+
+/// Reads a scheduled event of the export, replacing the election id.
+#[instrument(err, skip_all)]
+pub fn get_scheduled_event_from_record(
+    tenant_id: &str,
+    election_event_id: &str,
+    record: &StringRecord,
+    replacement_map: &HashMap<String, String>,
+) -> Result<ScheduledEvent> {
     let old_election_id = record
         .get(10)
         .map(|v| deserialize_str::<JsonValue>(v))
@@ -149,11 +167,13 @@ pub async fn process_record(
         cron_config,
         event_payload,
         task_id,
+        status: None,
+        attempts: 0,
+        next_attempt_at: None,
+        last_error: None,
+        alerted_at: None,
+        alerted_receivers: vec![],
     };
 
-    insert_new_scheduled_event(hasura_transaction, scheduled_event.clone())
-        .await
-        .map_err(|e| anyhow!("Error inserting scheduled_event into the database: {e:?}"))?;
-
-    Ok(())
+    Ok(scheduled_event)
 }
@@ -12,6 +12,7 @@ use crate::{
         results_event::insert_many_results_events, tally_session::insert_many_tally_sessions,
         tally_session_contest::insert_many_tally_session_contests,
         tally_session_execution::insert_many_tally_session_executions,
+        tally_sheet::insert_many_tally_sheets,
     },
     types::documents::ETallyDocuments,
 };
@@ -20,15 +21,16 @@ use chrono::{DateTime, Local};
 use csv::StringRecord;
 use deadpool_postgres::Transaction;
 use ordered_float::NotNan;
-use sequent_core::serialization::deserialize_with_path::deserialize_str;
+use sequent_core::serialization::deserialize_with_path::{deserialize_str, deserialize_value};
 use sequent_core::{
     services::date::ISO8601,
     types::{
-        hasura::core::{TallySession, TallySessionContest, TallySessionExecution},
+        hasura::core::{TallySession, TallySessionContest, TallySessionExecution, TallySheet},
         results::{
             ResultsAreaContest, ResultsAreaContestCandidate, ResultsContest,
             ResultsContestCandidate, ResultsElection, ResultsElectionArea, ResultsEvent,
         },
+        tally_sheets::AreaContestResults,
     },
 };
 use serde_json::Value;
@@ -390,62 +392,186 @@ async fn process_tally_session_contest_file(
 
     for result in rdr.records() {
         let record = result.map_err(|e| anyhow!("Error reading CSV record: {e:?}"))?;
+        let tally_session_contest = process_tally_session_contest_record(
+            tenant_id,
+            election_event_id,
+            &record,
+            &replacement_map,
+        )
+        .await
+        .with_context(|| "Error proccess tally_session_contest record")?;
 
-        let area_id = get_replaced_id(&record, 3, &replacement_map).await?;
-        let contest_id: Option<String> = get_string_or_null_item(&record, 4).await?;
+        tally_session_contests.push(tally_session_contest);
+    }
 
-        let new_contest_id = match contest_id {
-            Some(contest_id) => Some(
-                replacement_map
-                    .get(&contest_id)
-                    .ok_or_else(|| {
-                        anyhow!("Can't find contest_id={contest_id:?} in replacement map")
-                    })?
-                    .clone(),
-            ),
-            None => None,
-        };
+    let _ = insert_many_tally_session_contests(hasura_transaction, tally_session_contests)
+        .await
+        .map_err(|err| anyhow!("Error at insert_many_tally_session_contests {:?}", err))?;
 
-        let session_id = record
-            .get(5)
-            .unwrap_or("0")
-            .parse::<i32>()
-            .map_err(|err| anyhow!("Error at process session_id {:?}", err))?;
+    Ok(())
+}
 
-        let created_at = get_opt_date(&record, 6).await?;
-        let last_updated_at = get_opt_date(&record, 7).await?;
+#[instrument(err, skip_all)]
+pub async fn process_tally_session_contest_record(
+    tenant_id: &str,
+    election_event_id: &str,
+    record: &StringRecord,
+    replacement_map: &HashMap<String, String>,
+) -> Result<TallySessionContest> {
+    let area_id = get_replaced_id(record, 3, replacement_map).await?;
+    let contest_id: Option<String> = get_string_or_null_item(record, 4).await?;
+
+    let new_contest_id = match contest_id {
+        Some(contest_id) => Some(
+            replacement_map
+                .get(&contest_id)
+                .ok_or_else(|| anyhow!("Can't find contest_id={contest_id:?} in replacement map"))?
+                .clone(),
+        ),
+        None => None,
+    };
 
-        let labels = get_opt_json_value_item(&record, 8).await?;
-        let annotations = get_opt_json_value_item(&record, 9).await?;
-        let tally_session_id = get_replaced_id(&record, 10, &replacement_map).await?;
+    let session_id = record
+        .get(5)
+        .unwrap_or("0")
+        .parse::<i32>()
+        .map_err(|err| anyhow!("Error at process session_id {:?}", err))?;
 
-        let election_id = get_replaced_id(&record, 11, &replacement_map).await?;
+    let created_at = get_opt_date(record, 6).await?;
+    let last_updated_at = get_opt_date(record, 7).await?;
 
-        let tally_session_contest = TallySessionContest {
-            id: Uuid::new_v4().to_string(),
-            tenant_id: tenant_id.to_string(),
-            election_event_id: election_event_id.to_string(),
-            area_id,
-            contest_id: new_contest_id,
-            session_id,
-            created_at,
-            last_updated_at,
-            labels,
-            annotations,
-            tally_session_id,
-            election_id,
-        };
+    let labels = get_opt_json_value_item(record, 8).await?;
+    let annotations = get_opt_json_value_item(record, 9).await?;
+    let tally_session_id = get_replaced_id(record, 10, replacement_map).await?;
 
-        tally_session_contests.push(tally_session_contest);
+    let election_id = get_replaced_id(record, 11, replacement_map).await?;
+
+    Ok(TallySessionContest {
+        id: Uuid::new_v4().to_string(),
+        tenant_id: tenant_id.to_string(),
+        election_event_id: election_event_id.to_string(),
+        area_id,
+        contest_id: new_contest_id,
+        session_id,
+        created_at,
+        last_updated_at,
+        labels,
+        annotations,
+        tally_session_id,
+        election_id,
+    })
+}
+
+#[instrument(err, skip_all)]
+async fn process_tally_sheet_file(
+    hasura_transaction: &Transaction<'_>,
+    temp_file: &NamedTempFile,
+    tenant_id: &str,
+    election_event_id: &str,
+    replacement_map: HashMap<String, String>,
+) -> Result<()> {
+    let file = File::open(temp_file)?;
+    let mut rdr = csv::Reader::from_reader(file);
+
+    let mut tally_sheets: Vec<TallySheet> = Vec::new();
+
+    for result in rdr.records() {
+        let record = result.map_err(|e| anyhow!("Error reading CSV record: {e:?}"))?;
+        let tally_sheet =
+            process_tally_sheet_record(tenant_id, election_event_id, &record, &replacement_map)
+                .await
+                .with_context(|| "Error proccess tally_sheet record")?;
+
+        tally_sheets.push(tally_sheet);
     }
 
-    let _ = insert_many_tally_session_contests(hasura_transaction, tally_session_contests)
+    let _ = insert_many_tally_sheets(hasura_transaction, tally_sheets)
         .await
-        .map_err(|err| anyhow!("Error at insert_many_tally_session_contests {:?}", err))?;
+        .map_err(|err| anyhow!("Error at insert_many_tally_sheets {:?}", err))?;
 
     Ok(())
 }
 
+/// Reads a tally sheet exported by `export_tally_sheets`. The area, contest
+/// and candidate ids of its content are replaced too.
+#[instrument(err, skip_all)]
+pub async fn process_tally_sheet_record(
+    tenant_id: &str,
+    election_event_id: &str,
+    record: &StringRecord,
+    replacement_map: &HashMap<String, String>,
+) -> Result<TallySheet> {
+    let election_id = get_replaced_id(record, 3, replacement_map).await?;
+    let contest_id = get_replaced_id(record, 4, replacement_map).await?;
+    let area_id = get_replaced_id(record, 5, replacement_map).await?;
+    let created_at = get_opt_date(record, 6).await?;
+    let last_updated_at = get_opt_date(record, 7).await?;
+    let labels = get_opt_json_value_item(record, 8).await?;
+    let annotations = get_opt_json_value_item(record, 9).await?;
+    let published_at = get_opt_date(record, 10).await?;
+    let published_by_user_id = get_string_or_null_item(record, 11).await?;
+
+    let content: Option<AreaContestResults> = get_opt_json_value_item(record, 12)
+        .await?
+        .filter(|value| !value.is_null())
+        .map(deserialize_value)
+        .transpose()
+        .map_err(|err| anyhow!("Error parsing tally sheet content: {err:?}"))?;
+    let content = content
+        .map(|content| -> Result<AreaContestResults> {
+            let replace = |id: &String| -> Result<String> {
+                replacement_map
+                    .get(id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Can't find id:{id} in replacement map"))
+            };
+            Ok(AreaContestResults {
+                area_id: replace(&content.area_id)?,
+                contest_id: replace(&content.contest_id)?,
+                candidate_results: content
+                    .candidate_results
+                    .iter()
+                    .map(|(candidate_id, results)| Ok((replace(candidate_id)?, results.clone())))
+                    .collect::<Result<_>>()?,
+                ..content
+            })
+        })
+        .transpose()?;
+
+    let channel = get_string_or_null_item(record, 13).await?;
+    let deleted_at = get_opt_date(record, 14).await?;
+    let created_by_user_id = get_string_or_null_item(record, 15)
+        .await?
+        .unwrap_or_default();
+    let version = get_opt_i64_item(record, 16)
+        .await?
+        .map(|version| version as i32);
+    let approved_at = get_opt_date(record, 17).await?;
+    let approved_by_user_id = get_string_or_null_item(record, 18).await?;
+
+    Ok(TallySheet {
+        id: Uuid::new_v4().to_string(),
+        tenant_id: tenant_id.to_string(),
+        election_event_id: election_event_id.to_string(),
+        election_id,
+        contest_id,
+        area_id,
+        created_at,
+        last_updated_at,
+        labels,
+        annotations,
+        published_at,
+        published_by_user_id,
+        content,
+        channel,
+        deleted_at,
+        created_by_user_id,
+        version,
+        approved_at,
+        approved_by_user_id,
+    })
+}
+
 #[instrument(err, skip_all)]
 async fn process_tally_session_execution_file(
     hasura_transaction: &Transaction<'_>,
@@ -1052,6 +1178,15 @@ pub async fn process_tally_file(
             replacement_map.clone(),
         )
         .await?;
+    } else if file_name == ETallyDocuments::TALLY_SHEET.to_file_name().to_string() {
+        process_tally_sheet_file(
+            hasura_transaction,
+            temp_file,
+            tenant_id,
+            election_event_id,
+            replacement_map.clone(),
+        )
+        .await?;
     }
 
     Ok(())
@@ -29,16 +29,16 @@ use uuid::Uuid;
 lazy_static! {
     pub static ref HEADER_RE: Regex = Regex::new(r"^[a-zA-Z0-9._-]+$").unwrap();
     static ref PBKDF2_ITERATIONS: NonZeroU32 = NonZeroU32::new(27_500).unwrap();
-    static ref NUMBER_OF_ITERATIONS_COL_NAME: String = String::from("num_of_iterations");
-    static ref SALT_COL_NAME: String = String::from("password_salt");
-    static ref HASHED_PASSWORD_COL_NAME: String = String::from("hashed_password");
-    static ref PASSWORD_COL_NAME: String = String::from("password");
-    static ref USERNAME_COL_NAME: String = String::from("username");
-    static ref EMAIL_COL_NAME: String = String::from("email");
-    static ref EMAIL_VERIFIED_COL_NAME: String = String::from("email_verified");
-    static ref GROUP_COL_NAME: String = String::from("group_name");
-    static ref AREA_NAME_COL_NAME: String = String::from("area_name");
-    static ref ELECTION_COL_PREFIX: String = String::from("election__");
+    pub(crate) static ref NUMBER_OF_ITERATIONS_COL_NAME: String = String::from("num_of_iterations");
+    pub(crate) static ref SALT_COL_NAME: String = String::from("password_salt");
+    pub(crate) static ref HASHED_PASSWORD_COL_NAME: String = String::from("hashed_password");
+    pub(crate) static ref PASSWORD_COL_NAME: String = String::from("password");
+    pub(crate) static ref USERNAME_COL_NAME: String = String::from("username");
+    pub(crate) static ref EMAIL_COL_NAME: String = String::from("email");
+    pub(crate) static ref EMAIL_VERIFIED_COL_NAME: String = String::from("email_verified");
+    pub(crate) static ref GROUP_COL_NAME: String = String::from("group_name");
+    pub(crate) static ref AREA_NAME_COL_NAME: String = String::from("area_name");
+    pub(crate) static ref ELECTION_COL_PREFIX: String = String::from("election__");
     static ref RESERVED_COL_NAMES: Vec<String> = vec![
         HASHED_PASSWORD_COL_NAME.clone(),
         SALT_COL_NAME.clone(),
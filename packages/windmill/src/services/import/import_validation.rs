@@ -0,0 +1,773 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Validation-only pass over the files accepted by `import_users`,
+//! `import_areas` and `import_candidates`. The file is streamed once and every
+//! row is checked against the same rules the import applies (or silently
+//! ignores), without writing anything. The result is a per-row error report.
+
+use super::import_users::{
+    AREA_NAME_COL_NAME, EMAIL_COL_NAME, EMAIL_VERIFIED_COL_NAME, HASHED_PASSWORD_COL_NAME,
+    HEADER_RE, NUMBER_OF_ITERATIONS_COL_NAME, PASSWORD_COL_NAME, SALT_COL_NAME, USERNAME_COL_NAME,
+};
+use crate::postgres::area::get_areas_by_name;
+use crate::postgres::contest::export_contests;
+use crate::postgres::election::get_elections;
+use crate::postgres::keycloak_realm;
+use crate::services::database::get_keycloak_pool;
+use crate::services::documents::upload_and_return_document;
+use crate::tasks::import_candidates::get_contest_from_postcode;
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Transaction;
+use encoding_rs::WINDOWS_1252;
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use regex::Regex;
+use sequent_core::ballot::EarlyVotingPolicy;
+use sequent_core::services::keycloak::{get_event_realm, MULTIVALUE_USER_ATTRIBUTE_SEPARATOR};
+use sequent_core::types::hasura::core::Contest;
+use sequent_core::types::keycloak::{AUTHORIZED_ELECTION_IDS_NAME, DATE_OF_BIRTH};
+use sequent_core::util::date_time::verify_date_format_ymd;
+use sequent_core::util::temp_path::{generate_temp_file, get_file_size};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use tracing::{info, instrument};
+
+lazy_static! {
+    static ref EMAIL_RE: Regex = Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap();
+}
+
+const ENABLED_COL_NAME: &str = "enabled";
+
+/// Columns of the areas file, which has no header.
+const AREAS_COLUMNS: [&str; 6] = [
+    "EMB_ID",
+    "CTRY_CODE",
+    "EMB_CODE",
+    "AREANAME",
+    "isdelete",
+    "EARLY_VOTING_POLICY",
+];
+const AREA_EMB_ID_INDEX: usize = 0;
+const AREA_IS_DELETE_INDEX: usize = 4;
+const AREA_EARLY_VOTING_INDEX: usize = 5;
+
+/// Minimum number of columns of the candidates file, which has no header.
+const CANDIDATES_MIN_COLUMNS: usize = 27;
+const CANDIDATE_POSTCODE_INDEX: usize = 2;
+const CANDIDATE_NAME_INDEX: usize = 26;
+
+#[derive(Display, Debug, PartialEq, Eq, Clone, Copy, EnumString, Serialize, Deserialize)]
+pub enum ImportValidationKind {
+    USERS,
+    AREAS,
+    CANDIDATES,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ImportValidationInput {
+    pub tenant_id: String,
+    pub election_event_id: String,
+    /// Document to validate, as uploaded for the import.
+    pub document_id: String,
+    pub sha256: Option<String>,
+    pub kind: ImportValidationKind,
+    /// Document id of the uploaded error report.
+    pub report_document_id: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Line of the file, starting at 1. Errors about the whole file, like
+    /// invalid headers, are reported in line 1.
+    pub line: usize,
+    pub column: Option<String>,
+    pub value: Option<String>,
+    pub message: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ImportValidationReport {
+    pub kind: ImportValidationKind,
+    pub total_rows: usize,
+    pub invalid_rows: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportValidationReport {
+    fn new(kind: ImportValidationKind) -> Self {
+        ImportValidationReport {
+            kind,
+            total_rows: 0,
+            invalid_rows: 0,
+            errors: vec![],
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Adds the errors of a row, counting the row as invalid if there's any.
+    fn add_row(&mut self, errors: Vec<RowError>) {
+        self.total_rows += 1;
+        if !errors.is_empty() {
+            self.invalid_rows += 1;
+            self.errors.extend(errors);
+        }
+    }
+}
+
+fn row_error(line: usize, column: Option<&str>, value: Option<&str>, message: &str) -> RowError {
+    RowError {
+        line,
+        column: column.map(str::to_string),
+        value: value.map(str::to_string),
+        message: message.to_string(),
+    }
+}
+
+/// Existing data of the election event the rows are checked against.
+#[derive(Debug, Clone, Default)]
+pub struct ImportValidationContext {
+    /// Area ids by area name.
+    pub areas_by_name: HashMap<String, String>,
+    pub election_aliases: HashSet<String>,
+    /// Usernames already present in the election event realm.
+    pub existing_usernames: HashSet<String>,
+    pub contests: Vec<Contest>,
+}
+
+/// Validates a voters file as accepted by `import_users_file`.
+#[instrument(skip(reader, context), err)]
+pub fn validate_users_file<R: Read>(
+    reader: R,
+    separator: u8,
+    context: &ImportValidationContext,
+) -> Result<ImportValidationReport> {
+    let mut report = ImportValidationReport::new(ImportValidationKind::USERS);
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(separator)
+        .flexible(true)
+        .from_reader(reader);
+    let headers = rdr
+        .headers()
+        .map_err(|err| anyhow!("Error reading CSV headers from voters file: {err}"))?
+        .clone();
+
+    let mut header_errors: Vec<RowError> = vec![];
+    let mut seen_headers: HashSet<&str> = HashSet::new();
+    for header in headers.iter() {
+        if !HEADER_RE.is_match(header) {
+            header_errors.push(row_error(
+                1,
+                Some(header),
+                None,
+                "header contains characters not allowed",
+            ));
+        }
+        if !seen_headers.insert(header) {
+            header_errors.push(row_error(1, Some(header), None, "duplicated column"));
+        }
+    }
+    if seen_headers.contains(PASSWORD_COL_NAME.as_str())
+        && seen_headers.contains(HASHED_PASSWORD_COL_NAME.as_str())
+    {
+        header_errors.push(row_error(
+            1,
+            Some(HASHED_PASSWORD_COL_NAME.as_str()),
+            None,
+            "password and hashed_password columns can't be used together",
+        ));
+    }
+    if seen_headers.contains(HASHED_PASSWORD_COL_NAME.as_str())
+        && !seen_headers.contains(SALT_COL_NAME.as_str())
+    {
+        header_errors.push(row_error(
+            1,
+            Some(SALT_COL_NAME.as_str()),
+            None,
+            "hashed_password requires the password_salt column",
+        ));
+    }
+    // Header errors make every row fail, so there's no point in going on
+    if !header_errors.is_empty() {
+        report.errors = header_errors;
+        return Ok(report);
+    }
+
+    // First line of each username and email, to point to the duplicate
+    let mut usernames: HashMap<String, usize> = HashMap::new();
+    let mut emails: HashMap<String, usize> = HashMap::new();
+    for (index, result) in rdr.records().enumerate() {
+        // the first line is the header
+        let line = index + 2;
+        let record = match result {
+            Ok(record) => record,
+            Err(err) => {
+                report.add_row(vec![row_error(
+                    line,
+                    None,
+                    None,
+                    &format!("invalid row: {err}"),
+                )]);
+                continue;
+            }
+        };
+
+        let mut errors: Vec<RowError> = vec![];
+        if record.len() != headers.len() {
+            errors.push(row_error(
+                line,
+                None,
+                None,
+                &format!("expected {} columns, found {}", headers.len(), record.len()),
+            ));
+        }
+
+        for (data, column) in record.iter().zip(headers.iter()) {
+            let mut error = |message: &str| {
+                errors.push(row_error(line, Some(column), Some(data), message));
+            };
+            match column {
+                column if column == *USERNAME_COL_NAME => {
+                    let username = data.to_lowercase();
+                    if username.is_empty() {
+                        error("missing username");
+                    } else if let Some(first_line) = usernames.get(&username) {
+                        error(&format!(
+                            "duplicated username, first found in line {first_line}"
+                        ));
+                    } else if context.existing_usernames.contains(&username) {
+                        error("username already exists in the election event");
+                    } else {
+                        usernames.insert(username, line);
+                    }
+                }
+                column if column == *EMAIL_COL_NAME && !data.is_empty() => {
+                    let email = data.to_lowercase();
+                    if !EMAIL_RE.is_match(&email) {
+                        error("invalid email");
+                    } else if let Some(first_line) = emails.get(&email) {
+                        error(&format!(
+                            "duplicated email, first found in line {first_line}"
+                        ));
+                    } else {
+                        emails.insert(email, line);
+                    }
+                }
+                column
+                    if (column == *EMAIL_VERIFIED_COL_NAME || column == ENABLED_COL_NAME)
+                        && !data.is_empty()
+                        && !["true", "false"].contains(&data.to_lowercase().as_str()) =>
+                {
+                    error("expected TRUE or FALSE");
+                }
+                column if column == *AREA_NAME_COL_NAME => {
+                    if data.is_empty() {
+                        error("missing area");
+                    } else if !context.areas_by_name.contains_key(data) {
+                        error("area not found in the election event");
+                    }
+                }
+                column if column == DATE_OF_BIRTH && !data.is_empty() => {
+                    if let Err(err) = verify_date_format_ymd(data) {
+                        error(&format!("{err}, expected YYYY-MM-DD"));
+                    }
+                }
+                column if column == AUTHORIZED_ELECTION_IDS_NAME => {
+                    let unknown: Vec<&str> = data
+                        .split(MULTIVALUE_USER_ATTRIBUTE_SEPARATOR)
+                        .filter(|alias| {
+                            !alias.is_empty() && !context.election_aliases.contains(*alias)
+                        })
+                        .collect();
+                    if !unknown.is_empty() {
+                        error(&format!("elections not found: {}", unknown.join(", ")));
+                    }
+                }
+                column
+                    if column == *NUMBER_OF_ITERATIONS_COL_NAME
+                        && !data.is_empty()
+                        && !data.parse::<u32>().is_ok_and(|value| value > 0) =>
+                {
+                    error("expected a positive number");
+                }
+                _ => (),
+            }
+        }
+
+        report.add_row(errors);
+    }
+
+    Ok(report)
+}
+
+/// Validates an areas file as accepted by `import_areas_task`. Deleted rows
+/// are not imported, so only their columns are checked.
+#[instrument(skip(reader, context), err)]
+pub fn validate_areas_file<R: Read>(
+    reader: R,
+    context: &ImportValidationContext,
+) -> Result<ImportValidationReport> {
+    let mut report = ImportValidationReport::new(ImportValidationKind::AREAS);
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(b',')
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+
+    let mut emb_ids: HashMap<String, usize> = HashMap::new();
+    for (index, result) in rdr.records().enumerate() {
+        let line = index + 1;
+        let record = match result {
+            Ok(record) => record,
+            Err(err) => {
+                report.add_row(vec![row_error(
+                    line,
+                    None,
+                    None,
+                    &format!("invalid row: {err}"),
+                )]);
+                continue;
+            }
+        };
+
+        let mut errors: Vec<RowError> = vec![];
+        if record.len() != AREAS_COLUMNS.len() {
+            errors.push(row_error(
+                line,
+                None,
+                None,
+                &format!(
+                    "expected {} columns ({}), found {}",
+                    AREAS_COLUMNS.len(),
+                    AREAS_COLUMNS.join(","),
+                    record.len()
+                ),
+            ));
+        }
+
+        let is_delete = record.get(AREA_IS_DELETE_INDEX).unwrap_or_default();
+        if !["0", "1"].contains(&is_delete) {
+            errors.push(row_error(
+                line,
+                Some(AREAS_COLUMNS[AREA_IS_DELETE_INDEX]),
+                Some(is_delete),
+                "expected 0 or 1",
+            ));
+        }
+
+        if is_delete == "0" {
+            let emb_id = record.get(AREA_EMB_ID_INDEX).unwrap_or_default();
+            let mut error = |message: &str| {
+                errors.push(row_error(
+                    line,
+                    Some(AREAS_COLUMNS[AREA_EMB_ID_INDEX]),
+                    Some(emb_id),
+                    message,
+                ));
+            };
+            if emb_id.is_empty() {
+                error("missing area name");
+            } else if let Some(first_line) = emb_ids.get(emb_id) {
+                error(&format!(
+                    "duplicated area, first found in line {first_line}"
+                ));
+            } else if context.areas_by_name.contains_key(emb_id) {
+                error("area already exists in the election event");
+            } else {
+                emb_ids.insert(emb_id.to_string(), line);
+            }
+
+            let early_voting = record.get(AREA_EARLY_VOTING_INDEX).unwrap_or_default();
+            if !early_voting.is_empty() && EarlyVotingPolicy::from_str(early_voting).is_err() {
+                errors.push(row_error(
+                    line,
+                    Some(AREAS_COLUMNS[AREA_EARLY_VOTING_INDEX]),
+                    Some(early_voting),
+                    &format!(
+                        "expected {} or {}",
+                        EarlyVotingPolicy::AllowEarlyVoting,
+                        EarlyVotingPolicy::NoEarlyVoting
+                    ),
+                ));
+            }
+        }
+
+        report.add_row(errors);
+    }
+
+    Ok(report)
+}
+
+/// Validates a candidates file as accepted by `import_candidates_task`,
+/// which is encoded in Windows-1252.
+#[instrument(skip(reader, context), err)]
+pub fn validate_candidates_file<R: Read>(
+    reader: R,
+    context: &ImportValidationContext,
+) -> Result<ImportValidationReport> {
+    let mut report = ImportValidationReport::new(ImportValidationKind::CANDIDATES);
+    let transcoded_reader = DecodeReaderBytesBuilder::new()
+        .encoding(Some(WINDOWS_1252))
+        .build(reader);
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(b',')
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(transcoded_reader);
+
+    // First line of each candidate, by contest and name
+    let mut candidates: HashMap<(String, String), usize> = HashMap::new();
+    for (index, result) in rdr.records().enumerate() {
+        let line = index + 1;
+        let record = match result {
+            Ok(record) => record,
+            Err(err) => {
+                report.add_row(vec![row_error(
+                    line,
+                    None,
+                    None,
+                    &format!("invalid row: {err}"),
+                )]);
+                continue;
+            }
+        };
+
+        let mut errors: Vec<RowError> = vec![];
+        if record.len() < CANDIDATES_MIN_COLUMNS {
+            errors.push(row_error(
+                line,
+                None,
+                None,
+                &format!(
+                    "expected at least {CANDIDATES_MIN_COLUMNS} columns, found {}",
+                    record.len()
+                ),
+            ));
+        }
+
+        let name = record.get(CANDIDATE_NAME_INDEX).unwrap_or_default();
+        if name.is_empty() {
+            errors.push(row_error(
+                line,
+                Some("name_on_ballot"),
+                None,
+                "missing candidate name",
+            ));
+        }
+
+        let postcode = record.get(CANDIDATE_POSTCODE_INDEX).unwrap_or_default();
+        match get_contest_from_postcode(&context.contests, postcode)? {
+            None => errors.push(row_error(
+                line,
+                Some("postcode"),
+                Some(postcode),
+                "no contest found for the postcode, the candidate would be skipped",
+            )),
+            Some(contest_id) if !name.is_empty() => {
+                let key = (contest_id, name.to_string());
+                if let Some(first_line) = candidates.get(&key) {
+                    errors.push(row_error(
+                        line,
+                        Some("name_on_ballot"),
+                        Some(name),
+                        &format!("duplicated candidate, first found in line {first_line}"),
+                    ));
+                } else {
+                    candidates.insert(key, line);
+                }
+            }
+            Some(_) => (),
+        }
+
+        report.add_row(errors);
+    }
+
+    Ok(report)
+}
+
+#[instrument(skip(keycloak_transaction), err)]
+async fn get_realm_usernames(
+    keycloak_transaction: &Transaction<'_>,
+    realm_id: &str,
+) -> Result<HashSet<String>> {
+    let rows = keycloak_transaction
+        .query(
+            r#"
+            SELECT
+                username
+            FROM
+                user_entity
+            WHERE
+                realm_id = $1
+                AND service_account_client_link IS NULL;
+            "#,
+            &[&realm_id],
+        )
+        .await
+        .map_err(|err| anyhow!("Error fetching the usernames of the realm: {err}"))?;
+
+    rows.into_iter()
+        .map(|row| Ok(row.try_get::<_, String>("username")?))
+        .collect()
+}
+
+/// Loads the existing data of the election event needed to validate a file
+/// of the given kind.
+#[instrument(skip(hasura_transaction), err)]
+async fn get_validation_context(
+    hasura_transaction: &Transaction<'_>,
+    input: &ImportValidationInput,
+) -> Result<ImportValidationContext> {
+    let tenant_id = input.tenant_id.as_str();
+    let election_event_id = input.election_event_id.as_str();
+    let mut context = ImportValidationContext {
+        areas_by_name: get_areas_by_name(hasura_transaction, tenant_id, election_event_id).await?,
+        ..Default::default()
+    };
+
+    match input.kind {
+        ImportValidationKind::USERS => {
+            context.election_aliases =
+                get_elections(hasura_transaction, tenant_id, election_event_id, None)
+                    .await?
+                    .into_iter()
+                    .filter_map(|election| election.alias)
+                    .collect();
+
+            let mut keycloak_db_client = get_keycloak_pool()
+                .await
+                .get()
+                .await
+                .map_err(|err| anyhow!("Error getting Keycloak DB pool: {err}"))?;
+            let keycloak_transaction = keycloak_db_client
+                .transaction()
+                .await
+                .map_err(|err| anyhow!("Error starting Keycloak transaction: {err}"))?;
+            let realm_name = get_event_realm(tenant_id, election_event_id);
+            let realm_id = keycloak_realm::get_realm_id(&keycloak_transaction, realm_name).await?;
+            context.existing_usernames =
+                get_realm_usernames(&keycloak_transaction, &realm_id).await?;
+        }
+        ImportValidationKind::CANDIDATES => {
+            context.contests =
+                export_contests(hasura_transaction, tenant_id, election_event_id).await?;
+        }
+        ImportValidationKind::AREAS => (),
+    }
+
+    Ok(context)
+}
+
+#[instrument(skip(hasura_transaction, report), err)]
+async fn upload_import_validation_report(
+    hasura_transaction: &Transaction<'_>,
+    input: &ImportValidationInput,
+    report: &ImportValidationReport,
+) -> Result<()> {
+    let report_file = generate_temp_file("import-validation-", ".csv")?;
+    let mut writer = csv::Writer::from_writer(report_file);
+    writer.write_record(["line", "column", "value", "message"])?;
+    for error in report.errors.iter() {
+        writer.write_record([
+            error.line.to_string(),
+            error.column.clone().unwrap_or_default(),
+            error.value.clone().unwrap_or_default(),
+            error.message.clone(),
+        ])?;
+    }
+    let mut report_file = writer
+        .into_inner()
+        .map_err(|err| anyhow!("Error writing the validation report: {err}"))?;
+    report_file.flush()?;
+    let report_path = report_file.into_temp_path();
+    let report_path_str = report_path.to_string_lossy().to_string();
+    let report_size = get_file_size(&report_path_str)?;
+
+    upload_and_return_document(
+        hasura_transaction,
+        &report_path_str,
+        report_size,
+        "text/csv",
+        &input.tenant_id,
+        Some(input.election_event_id.clone()),
+        &format!("import-validation-{}.csv", input.report_document_id),
+        Some(input.report_document_id.clone()),
+        false,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Validates an import file against the election event without importing
+/// it, and uploads the per-row error report as `report_document_id`. The
+/// report only has the header if the file is valid.
+#[instrument(skip(hasura_transaction, reader), err)]
+pub async fn validate_import_file<R: Read>(
+    hasura_transaction: &Transaction<'_>,
+    reader: R,
+    separator: u8,
+    input: &ImportValidationInput,
+) -> Result<ImportValidationReport> {
+    let context = get_validation_context(hasura_transaction, input).await?;
+    let report = match input.kind {
+        ImportValidationKind::USERS => validate_users_file(reader, separator, &context)?,
+        ImportValidationKind::AREAS => validate_areas_file(reader, &context)?,
+        ImportValidationKind::CANDIDATES => validate_candidates_file(reader, &context)?,
+    };
+    info!(
+        "import validation: kind={} total_rows={} invalid_rows={} errors={}",
+        report.kind,
+        report.total_rows,
+        report.invalid_rows,
+        report.errors.len()
+    );
+
+    upload_import_validation_report(hasura_transaction, input, &report).await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn contest(id: &str, alias: &str) -> Contest {
+        serde_json::from_value(json!({
+            "id": id,
+            "tenant_id": "tenant",
+            "election_event_id": "election-event",
+            "election_id": "election",
+            "alias": alias,
+        }))
+        .unwrap()
+    }
+
+    fn candidate_row(postcode: &str, name: &str) -> String {
+        let mut columns = vec![""; CANDIDATES_MIN_COLUMNS];
+        columns[CANDIDATE_POSTCODE_INDEX] = postcode;
+        columns[CANDIDATE_NAME_INDEX] = name;
+        columns.join(",")
+    }
+
+    fn context() -> ImportValidationContext {
+        ImportValidationContext {
+            areas_by_name: HashMap::from([
+                ("North".to_string(), "area-north".to_string()),
+                ("South".to_string(), "area-south".to_string()),
+            ]),
+            election_aliases: HashSet::from(["president".to_string(), "mayor".to_string()]),
+            existing_usernames: HashSet::from(["zoe".to_string()]),
+            contests: vec![
+                contest("contest-president", "PRESIDENT"),
+                contest("contest-mayor", "MAYOR"),
+            ],
+        }
+    }
+
+    fn error_lines(report: &ImportValidationReport) -> Vec<(usize, Option<String>)> {
+        report
+            .errors
+            .iter()
+            .map(|error| (error.line, error.column.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_users_file() {
+        let voters = "\
+username,email,area_name,dateOfBirth,authorized-election-ids,enabled
+alice,alice@example.com,North,1990-01-31,president|mayor,TRUE
+Alice,bob@example.com,South,1990-02-30,president,true
+carol,alice@EXAMPLE.com,East,,senate,yes
+zoe,not-an-email,North,,,
+dave,dave@example.com
+";
+        let report = validate_users_file(voters.as_bytes(), b',', &context()).unwrap();
+        assert_eq!(report.total_rows, 5);
+        assert_eq!(report.invalid_rows, 4);
+        assert_eq!(
+            error_lines(&report),
+            vec![
+                (3, Some("username".to_string())),
+                (3, Some("dateOfBirth".to_string())),
+                (4, Some("email".to_string())),
+                (4, Some("area_name".to_string())),
+                (4, Some("authorized-election-ids".to_string())),
+                (4, Some("enabled".to_string())),
+                (5, Some("username".to_string())),
+                (5, Some("email".to_string())),
+                (6, None),
+            ]
+        );
+
+        let report = validate_users_file("user name,email\n".as_bytes(), b',', &context()).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(report.total_rows, 0);
+    }
+
+    #[test]
+    fn test_validate_areas_file() {
+        let areas = "\
+A1,PH,1,Area one,0,allow_early_voting
+A2,PH,2,Area two,0,
+A1,PH,3,Area three,0,no_early_voting
+North,PH,4,Area four,0,sometimes
+A1,PH,5,Deleted area,1,
+A5,PH,6,Area six,2
+";
+        let report = validate_areas_file(areas.as_bytes(), &context()).unwrap();
+        assert_eq!(report.total_rows, 6);
+        assert_eq!(report.invalid_rows, 3);
+        assert_eq!(
+            error_lines(&report),
+            vec![
+                (3, Some("EMB_ID".to_string())),
+                (4, Some("EMB_ID".to_string())),
+                (4, Some("EARLY_VOTING_POLICY".to_string())),
+                (6, None),
+                (6, Some("isdelete".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_candidates_file() {
+        let candidates = [
+            candidate_row("1", "Alice"),
+            candidate_row("8", "Bob"),
+            candidate_row("1", "Alice"),
+            candidate_row("3", "Carol"),
+            candidate_row("99", "Dave"),
+            candidate_row("8", "Alice"),
+            "1,2".to_string(),
+            candidate_row("8", ""),
+        ]
+        .join("\n");
+        let report = validate_candidates_file(candidates.as_bytes(), &context()).unwrap();
+        assert_eq!(report.total_rows, 8);
+        assert_eq!(report.invalid_rows, 5);
+        assert_eq!(
+            error_lines(&report),
+            vec![
+                (3, Some("name_on_ballot".to_string())),
+                (4, Some("postcode".to_string())),
+                (5, Some("postcode".to_string())),
+                (7, None),
+                (7, Some("name_on_ballot".to_string())),
+                (7, Some("postcode".to_string())),
+                (8, Some("name_on_ballot".to_string())),
+            ]
+        );
+        assert_eq!(
+            report.errors[0].message,
+            "duplicated candidate, first found in line 1"
+        );
+    }
+}
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+pub mod census_sync;
 pub mod import_bulletin_boards;
 pub mod import_election_event;
 pub mod import_publications;
@@ -10,3 +11,4 @@ pub mod import_tally;
 pub mod import_tenant;
 pub mod import_tenant_config;
 pub mod import_users;
+pub mod import_validation;
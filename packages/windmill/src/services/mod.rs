@@ -6,8 +6,10 @@ pub mod application;
 pub mod ballot_styles;
 pub mod cast_votes;
 pub mod celery_app;
+pub mod census_snapshot;
 pub mod ceremonies;
 pub mod cloudflare;
+pub mod communication_templates;
 pub mod compress;
 pub mod consolidation;
 pub mod custom_url;
@@ -23,6 +25,7 @@ pub mod election_event_statistics;
 pub mod election_event_status;
 pub mod election_statistics;
 pub mod electoral_log;
+pub mod email_outbox;
 pub mod event_list;
 pub mod export;
 pub mod folders;
@@ -40,10 +43,13 @@ pub mod private_keys;
 pub mod probe;
 pub mod protocol_manager;
 pub mod providers;
+pub mod provisional_results;
 pub mod public_keys;
 pub mod reports;
 pub mod reports_vault;
+pub mod scheduled_events;
 pub mod serialize_tasks_logs;
+pub mod sms_delivery;
 pub mod tally_sheets;
 pub mod tasks_execution;
 pub mod tasks_semaphore;
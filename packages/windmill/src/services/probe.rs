@@ -194,6 +194,26 @@ async fn check_sms_sender(app_name: &AppName) -> Option<bool> {
                 }
             }
         }
+        SmsTransport::Twilio((client, config)) => {
+            // Fetching the account doesn't send any SMS
+            match client
+                .get(format!("{}.json", config.get_account_url()))
+                .basic_auth(&config.account_sid, Some(&config.auth_token))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                Ok(_) => Some(true),
+                Err(error) => {
+                    error!("Twilio connection error: {error:?}");
+                    Some(false)
+                }
+            }
+        }
+        SmsTransport::Http(_) => {
+            // There's no standard way to check a generic gateway
+            Some(true)
+        }
         SmsTransport::Console => {
             // Console transport always works
             Some(true)
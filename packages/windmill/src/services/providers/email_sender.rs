@@ -1,15 +1,24 @@
 // SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
 //
 // SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::email_outbox::{
+    get_suppressed_emails, insert_email_outbox_entry, update_email_outbox_entry,
+};
+use crate::services::email_outbox::{EmailOutbox, EmailOutboxStatus};
 use crate::types::error::Result;
 
 use anyhow::anyhow;
+use aws_sdk_sesv2::error::SdkError;
+use aws_sdk_sesv2::operation::send_email::SendEmailError;
 use aws_sdk_sesv2::types::{EmailContent, RawMessage};
 use aws_sdk_sesv2::Client as AwsSesClient;
 use aws_smithy_types::Blob;
 use lettre::message::header::{ContentDisposition, ContentType};
 use lettre::message::{MultiPart, SinglePart};
 use lettre::Message;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
 use tracing::{event, instrument, Level};
 
 // Import required for SMTP transport
@@ -38,9 +47,121 @@ pub enum EmailTransport {
     Console,
 }
 
+/// Throttling and retries of the emails sent by an `EmailSender`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailSendPolicy {
+    /// Maximum emails sent per second, or None for no limit.
+    pub rate_per_second: Option<f64>,
+    /// Times a send that failed with a transient error is retried.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each following retry.
+    pub retry_backoff: Duration,
+}
+
+impl Default for EmailSendPolicy {
+    fn default() -> Self {
+        EmailSendPolicy {
+            rate_per_second: None,
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(1000),
+        }
+    }
+}
+
+/// Minimum time between two sends at the given rate. Fails when the rate is
+/// not a positive number or when it's so low that the interval overflows.
+fn get_send_interval(rate_per_second: f64) -> anyhow::Result<Duration> {
+    if rate_per_second.is_nan() || rate_per_second <= 0.0 {
+        return Err(anyhow!(
+            "send rate must be a positive number, got {rate_per_second}"
+        ));
+    }
+    Duration::try_from_secs_f64(1.0 / rate_per_second)
+        .map_err(|err| anyhow!("send rate {rate_per_second} is too low: {err}"))
+}
+
+impl EmailSendPolicy {
+    #[instrument(err)]
+    pub fn from_env() -> Result<Self> {
+        let default = EmailSendPolicy::default();
+        let rate_per_second = match std::env::var("EMAIL_SEND_RATE_PER_SECOND") {
+            Ok(rate) if !rate.is_empty() => {
+                let rate = rate.parse::<f64>().map_err(|err| {
+                    anyhow!("Invalid EMAIL_SEND_RATE_PER_SECOND env var: {err:?}")
+                })?;
+                get_send_interval(rate).map_err(|err| {
+                    anyhow!("Invalid EMAIL_SEND_RATE_PER_SECOND env var: {err:?}")
+                })?;
+                Some(rate)
+            }
+            _ => None,
+        };
+        let max_retries = match std::env::var("EMAIL_SEND_MAX_RETRIES") {
+            Ok(retries) if !retries.is_empty() => retries
+                .parse::<u32>()
+                .map_err(|err| anyhow!("Invalid EMAIL_SEND_MAX_RETRIES env var: {err:?}"))?,
+            _ => default.max_retries,
+        };
+        let retry_backoff = match std::env::var("EMAIL_SEND_RETRY_BACKOFF_MS") {
+            Ok(backoff) if !backoff.is_empty() => {
+                Duration::from_millis(backoff.parse::<u64>().map_err(|err| {
+                    anyhow!("Invalid EMAIL_SEND_RETRY_BACKOFF_MS env var: {err:?}")
+                })?)
+            }
+            _ => default.retry_backoff,
+        };
+        Ok(EmailSendPolicy {
+            rate_per_second,
+            max_retries,
+            retry_backoff,
+        })
+    }
+
+    /// Minimum time between two sends, or None when there's no limit. An
+    /// invalid rate, which `from_env` rejects, is treated as no limit.
+    pub fn send_interval(&self) -> Option<Duration> {
+        self.rate_per_second
+            .and_then(|rate| get_send_interval(rate).ok())
+    }
+
+    /// Delay before the given retry, starting at 1.
+    pub fn retry_delay(&self, retry: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+}
+
+struct EmailSendFailure {
+    error: anyhow::Error,
+    is_transient: bool,
+}
+
+impl EmailSendFailure {
+    fn new(error: anyhow::Error, is_transient: bool) -> Self {
+        EmailSendFailure {
+            error,
+            is_transient,
+        }
+    }
+}
+
+fn is_transient_ses_error<R>(err: &SdkError<SendEmailError, R>) -> bool {
+    match err {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) => true,
+        _ => matches!(
+            err.as_service_error(),
+            Some(SendEmailError::TooManyRequestsException(_))
+                | Some(SendEmailError::LimitExceededException(_))
+        ),
+    }
+}
+
 pub struct EmailSender {
     transport: EmailTransport,
     email_from: String,
+    policy: EmailSendPolicy,
+    next_send_at: Mutex<Option<Instant>>,
+    outbox: Option<EmailOutbox>,
 }
 
 impl EmailSender {
@@ -92,9 +213,109 @@ impl EmailSender {
         Ok(EmailSender {
             transport,
             email_from,
+            policy: EmailSendPolicy::from_env()?,
+            next_send_at: Mutex::new(None),
+            outbox: None,
         })
     }
 
+    /// Records every recipient of the sent emails in the outbox, and skips
+    /// the suppressed addresses of the tenant.
+    pub fn with_outbox(
+        mut self,
+        tenant_id: &str,
+        election_event_id: Option<&str>,
+        task_execution_id: Option<&str>,
+    ) -> Self {
+        self.outbox = Some(EmailOutbox {
+            tenant_id: tenant_id.to_string(),
+            election_event_id: election_event_id.map(|id| id.to_string()),
+            task_execution_id: task_execution_id.map(|id| id.to_string()),
+        });
+        self
+    }
+
+    /// Waits until the send rate allows sending another email.
+    async fn wait_for_send_slot(&self) {
+        let Some(interval) = self.policy.send_interval() else {
+            return;
+        };
+        let mut next_send_at = self.next_send_at.lock().await;
+        let now = Instant::now();
+        let send_at = match *next_send_at {
+            Some(next) if next > now => next,
+            _ => now,
+        };
+        sleep_until(send_at).await;
+        *next_send_at = Some(send_at + interval);
+    }
+
+    /// Removes the suppressed receivers, recording them in the outbox, and
+    /// returns the ids of the outbox entries of the remaining ones.
+    async fn prepare_outbox(
+        &self,
+        outbox: &EmailOutbox,
+        receivers: &mut Vec<String>,
+        subject: &str,
+    ) -> Result<Vec<String>> {
+        let suppressed = get_suppressed_emails(&outbox.tenant_id, receivers).await?;
+        let mut entry_ids = vec![];
+        let mut allowed = vec![];
+        for receiver in receivers.drain(..) {
+            let is_suppressed = suppressed.contains(&receiver.to_lowercase());
+            let status = match is_suppressed {
+                true => EmailOutboxStatus::SUPPRESSED,
+                false => EmailOutboxStatus::PENDING,
+            };
+            let entry = insert_email_outbox_entry(
+                &outbox.tenant_id,
+                outbox.election_event_id.as_deref(),
+                outbox.task_execution_id.as_deref(),
+                &receiver,
+                subject,
+                &status.to_string(),
+            )
+            .await?;
+            if is_suppressed {
+                event!(Level::INFO, "Skipping suppressed receiver {receiver}");
+            } else {
+                entry_ids.push(entry.id);
+                allowed.push(receiver);
+            }
+        }
+        *receivers = allowed;
+        Ok(entry_ids)
+    }
+
+    async fn update_outbox(
+        &self,
+        entry_ids: &[String],
+        status: EmailOutboxStatus,
+        attempts: u32,
+        last_error: Option<&str>,
+        provider_message_id: Option<&str>,
+    ) {
+        let Some(ref outbox) = self.outbox else {
+            return;
+        };
+        for entry_id in entry_ids {
+            // the email can't be unsent, so failing to record it only logs
+            if let Err(err) = update_email_outbox_entry(
+                &outbox.tenant_id,
+                entry_id,
+                &status.to_string(),
+                attempts.try_into().unwrap_or(i32::MAX),
+                last_error,
+                provider_message_id,
+                status == EmailOutboxStatus::SENT,
+            )
+            .await
+            {
+                event!(Level::ERROR, "Error updating email outbox entry: {err:?}");
+            }
+        }
+    }
+
     #[instrument(skip(self, plaintext_body, html_body, attachments), err)]
     pub async fn send(
         &self,
@@ -104,6 +325,19 @@ impl EmailSender {
         html_body: Option<String>,
         attachments: Vec<Attachment>,
     ) -> Result<()> {
+        let mut receivers = receivers;
+        let entry_ids = match self.outbox {
+            Some(ref outbox) => {
+                self.prepare_outbox(outbox, &mut receivers, &subject)
+                    .await?
+            }
+            None => vec![],
+        };
+        if receivers.is_empty() {
+            event!(Level::INFO, "No receivers left, email not sent");
+            return Ok(());
+        }
+
         // Build the email message using lettre
         let mut email_builder = Message::builder()
             .from(
@@ -163,15 +397,84 @@ impl EmailSender {
                 .map_err(|err| anyhow!("{:?}", err))?
         };
 
+        let mut attempts = 0;
+        loop {
+            self.wait_for_send_slot().await;
+            attempts += 1;
+            match self
+                .send_message(
+                    &email_message,
+                    &receivers,
+                    &subject,
+                    &plaintext_body,
+                    &html_body,
+                    &attachments,
+                )
+                .await
+            {
+                Ok(message_id) => {
+                    self.update_outbox(
+                        &entry_ids,
+                        EmailOutboxStatus::SENT,
+                        attempts,
+                        None,
+                        message_id.as_deref(),
+                    )
+                    .await;
+                    return Ok(());
+                }
+                Err(failure) if failure.is_transient && attempts <= self.policy.max_retries => {
+                    let delay = self.policy.retry_delay(attempts);
+                    event!(
+                        Level::WARN,
+                        "Transient error sending email, retrying in {delay:?}: {error:?}",
+                        error = failure.error,
+                    );
+                    self.update_outbox(
+                        &entry_ids,
+                        EmailOutboxStatus::PENDING,
+                        attempts,
+                        Some(&failure.error.to_string()),
+                        None,
+                    )
+                    .await;
+                    tokio::time::sleep(delay).await;
+                }
+                Err(failure) => {
+                    self.update_outbox(
+                        &entry_ids,
+                        EmailOutboxStatus::FAILED,
+                        attempts,
+                        Some(&failure.error.to_string()),
+                        None,
+                    )
+                    .await;
+                    return Err(failure.error.into());
+                }
+            }
+        }
+    }
+
+    /// Sends the email through the transport, returning the message id
+    /// assigned by the provider if any.
+    async fn send_message(
+        &self,
+        email_message: &Message,
+        receivers: &[String],
+        subject: &str,
+        plaintext_body: &str,
+        html_body: &Option<String>,
+        attachments: &[Attachment],
+    ) -> std::result::Result<Option<String>, EmailSendFailure> {
         match self.transport {
             EmailTransport::AwsSes(ref aws_client) => {
                 event!(
                     Level::INFO,
                     "EmailTransport::AwsSes: Sending email:\n\t - receivers={receivers:?}\n\t - subject={subject}\n\t - plaintext_body={plaintext_body:.255}\n\t - html_body={html_body:.255}",
-                    html_body=html_body.clone().unwrap_or_default(),
+                    html_body=html_body.as_deref().unwrap_or_default(),
                 );
                 if !attachments.is_empty() {
-                    for attachment in &attachments {
+                    for attachment in attachments {
                         event!(
                             Level::INFO,
                             "Attachment: name={filename}, mimetype={mimetype}",
@@ -184,7 +487,7 @@ impl EmailSender {
                 let email_bytes = email_message.formatted();
 
                 // Send the email as a raw email
-                aws_client
+                let output = aws_client
                     .send_email()
                     .from_email_address(self.email_from.as_str())
                     .content(
@@ -194,14 +497,21 @@ impl EmailSender {
                                     .data(Blob::new(email_bytes))
                                     .build()
                                     .map_err(|err| {
-                                        anyhow!("error building raw message: {err:?}")
+                                        EmailSendFailure::new(
+                                            anyhow!("error building raw message: {err:?}"),
+                                            false,
+                                        )
                                     })?,
                             )
                             .build(),
                     )
                     .send()
                     .await
-                    .map_err(|err| anyhow!("error sending email: {err:?}"))?;
+                    .map_err(|err| {
+                        let is_transient = is_transient_ses_error(&err);
+                        EmailSendFailure::new(anyhow!("error sending email: {err:?}"), is_transient)
+                    })?;
+                Ok(output.message_id().map(|message_id| message_id.to_string()))
             }
             EmailTransport::Smtp(ref smtp_transport) => {
                 event!(
@@ -209,7 +519,7 @@ impl EmailSender {
                     "EmailTransport::Smtp: Sending email:\n\t - receivers={receivers:?}\n\t - subject={subject}",
                 );
                 if !attachments.is_empty() {
-                    for attachment in &attachments {
+                    for attachment in attachments {
                         event!(
                             Level::INFO,
                             "Attachment: name={filename}, mimetype={mimetype}",
@@ -219,19 +529,24 @@ impl EmailSender {
                     }
                 }
                 // Send the email via SMTP
-                smtp_transport
-                    .send(&email_message)
-                    .map_err(|err| anyhow!("Error sending email via SMTP: {err:?}"))?;
+                smtp_transport.send(email_message).map_err(|err| {
+                    let is_transient = err.is_transient() || err.is_timeout();
+                    EmailSendFailure::new(
+                        anyhow!("Error sending email via SMTP: {err:?}"),
+                        is_transient,
+                    )
+                })?;
                 event!(Level::INFO, "Email sent successfully via SMTP");
+                Ok(None)
             }
             EmailTransport::Console => {
                 event!(
                     Level::INFO,
                     "EmailTransport::Console: Sending email:\n\t - receivers={receivers:?}\n\t - subject={subject}\n\t - plaintext_body={plaintext_body:.255}\n\t - html_body={html_body:.255}",
-                    html_body=html_body.clone().unwrap_or_default(),
+                    html_body=html_body.as_deref().unwrap_or_default(),
                 );
                 if !attachments.is_empty() {
-                    for attachment in &attachments {
+                    for attachment in attachments {
                         event!(
                             Level::INFO,
                             "Attachment: name={filename}, mimetype={mimetype}",
@@ -240,8 +555,44 @@ impl EmailSender {
                         );
                     }
                 }
+                Ok(None)
             }
         }
-        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_policy_delays() {
+        let policy = EmailSendPolicy {
+            rate_per_second: Some(4.0),
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.send_interval(), Some(Duration::from_millis(250)));
+        assert_eq!(policy.retry_delay(1), Duration::from_millis(500));
+        assert_eq!(policy.retry_delay(2), Duration::from_millis(1000));
+        assert_eq!(policy.retry_delay(3), Duration::from_millis(2000));
+        assert_eq!(EmailSendPolicy::default().send_interval(), None);
+    }
+
+    #[test]
+    fn test_send_policy_invalid_rate() {
+        assert!(get_send_interval(0.0).is_err());
+        assert!(get_send_interval(-1.0).is_err());
+        assert!(get_send_interval(f64::NAN).is_err());
+        assert!(get_send_interval(1e-300).is_err());
+        assert_eq!(get_send_interval(0.5).unwrap(), Duration::from_secs(2));
+
+        for rate in [0.0, -1.0, 1e-300] {
+            let policy = EmailSendPolicy {
+                rate_per_second: Some(rate),
+                ..Default::default()
+            };
+            assert_eq!(policy.send_interval(), None);
+        }
     }
 }
@@ -1,24 +1,137 @@
 // SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
 //
 // SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::sms_delivery::{
+    get_status_callback_url, record_sms_delivery, SmsDeliveryStatus,
+};
 use crate::types::error::Result;
 
 use anyhow::anyhow;
 use aws_sdk_sns::{types::MessageAttributeValue, Client as AwsSnsClient};
+use reqwest::{Client as HttpClient, Method};
 use sequent_core::serialization::deserialize_with_path::*;
+use sequent_core::services::reports::render_template_text;
 use sequent_core::util::aws::get_from_env_aws_config;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
 use std::collections::HashMap;
+use std::str::FromStr;
 use tracing::{event, instrument, Level};
 
 type MessageAttributes = Option<HashMap<String, MessageAttributeValue>>;
 
+const DEFAULT_TWILIO_API_URL: &str = "https://api.twilio.com";
+const DEFAULT_SMS_HTTP_BODY: &str = r#"{"to": {{{to_json receiver}}}, "message": {{{to_json message}}}, "callback_url": {{{to_json callback_url}}}}"#;
+const DEFAULT_SMS_HTTP_MESSAGE_ID_POINTER: &str = "/id";
+
+/// Twilio-compatible REST transport, configured with the `TWILIO_*` env vars.
+#[derive(Debug, Clone)]
+pub struct TwilioConfig {
+    pub api_url: String,
+    pub account_sid: String,
+    pub auth_token: String,
+    /// Phone number, or messaging service if it starts with `MG`.
+    pub from: String,
+}
+
+impl TwilioConfig {
+    #[instrument(err)]
+    pub fn from_env() -> Result<Self> {
+        let get_env =
+            |name: &str| std::env::var(name).map_err(|_err| anyhow!("{name} env var missing"));
+        Ok(TwilioConfig {
+            api_url: std::env::var("TWILIO_API_URL")
+                .unwrap_or(DEFAULT_TWILIO_API_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            account_sid: get_env("TWILIO_ACCOUNT_SID")?,
+            auth_token: get_env("TWILIO_AUTH_TOKEN")?,
+            from: get_env("TWILIO_FROM")?,
+        })
+    }
+
+    pub fn get_account_url(&self) -> String {
+        format!("{}/2010-04-01/Accounts/{}", self.api_url, self.account_sid)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct TwilioMessage {
+    sid: String,
+    status: Option<String>,
+}
+
+/// Generic HTTP transport, configured with the `SMS_HTTP_*` env vars. The
+/// url, headers and body are handlebars templates rendered with the
+/// `receiver`, `message` and `callback_url` variables.
+#[derive(Debug, Clone)]
+pub struct HttpSmsConfig {
+    pub url: String,
+    pub method: Method,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+    /// JSON pointer to the message id in the response of the gateway.
+    pub message_id_pointer: String,
+}
+
+impl HttpSmsConfig {
+    #[instrument(err)]
+    pub fn from_env() -> Result<Self> {
+        let method = std::env::var("SMS_HTTP_METHOD").unwrap_or("POST".to_string());
+        let headers: HashMap<String, String> = match std::env::var("SMS_HTTP_HEADERS") {
+            Ok(headers) => deserialize_str(&headers)
+                .map_err(|err| anyhow!("SMS_HTTP_HEADERS env var parse error: {err:?}"))?,
+            Err(_) => HashMap::new(),
+        };
+        Ok(HttpSmsConfig {
+            url: std::env::var("SMS_HTTP_URL")
+                .map_err(|_err| anyhow!("SMS_HTTP_URL env var missing"))?,
+            method: Method::from_str(&method.to_uppercase())
+                .map_err(|err| anyhow!("SMS_HTTP_METHOD env var parse error: {err:?}"))?,
+            headers,
+            body: std::env::var("SMS_HTTP_BODY").unwrap_or(DEFAULT_SMS_HTTP_BODY.to_string()),
+            message_id_pointer: std::env::var("SMS_HTTP_MESSAGE_ID_POINTER")
+                .unwrap_or(DEFAULT_SMS_HTTP_MESSAGE_ID_POINTER.to_string()),
+        })
+    }
+
+    /// Renders the url, headers and body of the request to the gateway.
+    pub fn render(
+        &self,
+        variables: &Map<String, Value>,
+    ) -> Result<(String, HashMap<String, String>, String)> {
+        let render = |template: &str| {
+            render_template_text(template, variables.clone())
+                .map_err(|err| anyhow!("Error rendering SMS_HTTP template: {err:?}"))
+        };
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), render(value)?)))
+            .collect::<anyhow::Result<HashMap<String, String>>>()?;
+        Ok((render(&self.url)?, headers, render(&self.body)?))
+    }
+}
+
 pub enum SmsTransport {
     AwsSns((AwsSnsClient, MessageAttributes)),
+    Twilio((HttpClient, TwilioConfig)),
+    Http((HttpClient, HttpSmsConfig)),
     Console,
 }
 
+/// Task execution the delivery statuses of the sent messages are recorded
+/// against.
+#[derive(Debug, Clone)]
+pub struct SmsStatusCallback {
+    pub tenant_id: String,
+    pub task_execution_id: String,
+    pub url: Option<String>,
+}
+
 pub struct SmsSender {
     pub transport: SmsTransport,
+    pub status_callback: Option<SmsStatusCallback>,
 }
 
 impl SmsSender {
@@ -61,36 +174,158 @@ impl SmsSender {
                     );
                     SmsTransport::AwsSns((client, messsage_attributes))
                 }
+                "Twilio" => SmsTransport::Twilio((HttpClient::new(), TwilioConfig::from_env()?)),
+                "Http" => SmsTransport::Http((HttpClient::new(), HttpSmsConfig::from_env()?)),
                 _ => SmsTransport::Console,
             },
+            status_callback: None,
         })
     }
 
+    /// Records the delivery status of the messages sent from now on against
+    /// the given task execution, and asks the provider to report status
+    /// updates to the `SMS_STATUS_CALLBACK_URL` if configured.
+    #[instrument(skip(self), err)]
+    pub fn with_status_callback(
+        mut self,
+        tenant_id: &str,
+        task_execution_id: &str,
+    ) -> Result<Self> {
+        self.status_callback = Some(SmsStatusCallback {
+            tenant_id: tenant_id.to_string(),
+            task_execution_id: task_execution_id.to_string(),
+            url: get_status_callback_url(tenant_id, task_execution_id)?,
+        });
+        Ok(self)
+    }
+
+    /// Sends the message and returns the id assigned by the provider, if
+    /// any.
     #[instrument(skip(self, message), err)]
-    pub async fn send(&self, receiver: String, message: String) -> Result<()> {
-        match self.transport {
+    pub async fn send(&self, receiver: String, message: String) -> Result<Option<String>> {
+        let callback_url = self
+            .status_callback
+            .as_ref()
+            .and_then(|status_callback| status_callback.url.clone());
+        let (message_id, status) = match self.transport {
             SmsTransport::AwsSns((ref aws_client, ref messsage_attributes)) => {
                 event!(
                     Level::INFO,
                     "SmsTransport::AwsSes: Sending SMS:\n\t - receiver={receiver}\n\t - message={message:.255}",
                 );
-                aws_client
+                let output = aws_client
                     .publish()
                     .set_message_attributes(messsage_attributes.clone())
-                    .set_phone_number(Some(receiver))
+                    .set_phone_number(Some(receiver.clone()))
                     .set_message(Some(message))
                     .send()
                     .await
                     .map_err(|err| anyhow!("SmsTransport::AwsSes send error: {err:?}"))?;
+                (output.message_id, None)
+            }
+            SmsTransport::Twilio((ref client, ref config)) => {
+                event!(
+                    Level::INFO,
+                    "SmsTransport::Twilio: Sending SMS:\n\t - receiver={receiver}\n\t - message={message:.255}",
+                );
+                let from_key = if config.from.starts_with("MG") {
+                    "MessagingServiceSid"
+                } else {
+                    "From"
+                };
+                let mut form = vec![
+                    ("To", receiver.clone()),
+                    (from_key, config.from.clone()),
+                    ("Body", message),
+                ];
+                if let Some(ref callback_url) = callback_url {
+                    form.push(("StatusCallback", callback_url.clone()));
+                }
+                let response = client
+                    .post(format!("{}/Messages.json", config.get_account_url()))
+                    .basic_auth(&config.account_sid, Some(&config.auth_token))
+                    .form(&form)
+                    .send()
+                    .await
+                    .map_err(|err| anyhow!("SmsTransport::Twilio send error: {err:?}"))?;
+                let status = response.status();
+                if !status.is_success() {
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(anyhow!("SmsTransport::Twilio send error: {status}: {text}").into());
+                }
+                let twilio_message: TwilioMessage = response
+                    .json()
+                    .await
+                    .map_err(|err| anyhow!("SmsTransport::Twilio response parse error: {err:?}"))?;
+                (Some(twilio_message.sid), twilio_message.status)
+            }
+            SmsTransport::Http((ref client, ref config)) => {
+                event!(
+                    Level::INFO,
+                    "SmsTransport::Http: Sending SMS:\n\t - receiver={receiver}\n\t - message={message:.255}",
+                );
+                let variables = json!({
+                    "receiver": receiver.clone(),
+                    "message": message,
+                    "callback_url": callback_url.clone().unwrap_or_default(),
+                });
+                let Value::Object(variables) = variables else {
+                    return Err(anyhow!("Invalid SMS_HTTP template variables").into());
+                };
+                let (url, headers, body) = config.render(&variables)?;
+                let mut request = client.request(config.method.clone(), url).body(body);
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|err| anyhow!("SmsTransport::Http send error: {err:?}"))?;
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                if !status.is_success() {
+                    return Err(anyhow!("SmsTransport::Http send error: {status}: {text}").into());
+                }
+                let message_id = serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .and_then(|value| value.pointer(&config.message_id_pointer).cloned())
+                    .and_then(|value| match value {
+                        Value::String(id) => Some(id),
+                        Value::Number(id) => Some(id.to_string()),
+                        _ => None,
+                    });
+                (message_id, None)
             }
             SmsTransport::Console => {
                 event!(
                     Level::INFO,
                     "SmsTransport::Console: Sending SMS:\n\t - receiver={receiver}\n\t - message={message}",
                 );
+                (None, None)
+            }
+        };
+
+        if let (Some(status_callback), Some(message_id)) = (&self.status_callback, &message_id) {
+            let delivery_status = SmsDeliveryStatus {
+                message_id: message_id.clone(),
+                status: status.unwrap_or("sent".to_string()),
+                error_code: None,
+                receiver: Some(receiver),
+            };
+            // the message was already sent, so this should not fail the
+            // sending
+            if let Err(err) = record_sms_delivery(
+                &status_callback.tenant_id,
+                &status_callback.task_execution_id,
+                &delivery_status,
+                /* overwrite */ false,
+            )
+            .await
+            {
+                event!(Level::ERROR, "Error recording sms delivery: {err:?}");
             }
         }
 
-        Ok(())
+        Ok(message_id)
     }
 }
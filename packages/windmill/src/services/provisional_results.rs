@@ -0,0 +1,1047 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+//! Provisional results feed of an election event. It aggregates, for each
+//! area of each contest, the results of the last completed tally or, until
+//! there is one, the published tally sheets. The results of an election are
+//! embargoed until its polls close. The feed is served by harvest and a
+//! snapshot is kept in the public bucket, both versioned by the hash of
+//! their results.
+
+use crate::postgres::area::get_event_areas;
+use crate::postgres::area_contest::export_area_contests;
+use crate::postgres::candidate::export_candidates;
+use crate::postgres::contest::export_contests;
+use crate::postgres::election::get_elections;
+use crate::postgres::election_event::get_election_event_by_id_if_exist;
+use crate::postgres::results_area_contest::get_event_results_area_contest;
+use crate::postgres::results_area_contest_candidate::get_results_event_area_contest_candidates;
+use crate::postgres::tally_session::get_tally_sessions_by_election_event_id;
+use crate::postgres::tally_session_execution::get_last_tally_session_execution;
+use crate::postgres::tally_sheet::get_published_tally_sheets_by_event;
+use crate::services::celery_app::get_celery_app;
+use crate::services::election_event_status::get_election_status;
+use crate::services::email_outbox::check_notifications_token;
+use crate::services::pg_lock::PgLock;
+use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::tasks::publish_provisional_results::publish_provisional_results;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use deadpool_postgres::Transaction;
+use sequent_core::ballot::{ProvisionalResultsPolicy, VotingStatus, VotingStatusChannel};
+use sequent_core::serialization::deserialize_with_path::deserialize_value;
+use sequent_core::services::date::ISO8601;
+use sequent_core::services::s3;
+use sequent_core::types::ceremonies::{TallyExecutionStatus, TallyType};
+use sequent_core::types::hasura::core::{
+    Area, AreaContest, Candidate, Contest, Election, ElectionEvent, TallySheet, VotingChannels,
+};
+use sequent_core::types::results::{ResultsAreaContest, ResultsAreaContestCandidate};
+use sequent_core::util::temp_path::generate_temp_file;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::BufWriter;
+use strand::hash::hash_sha256;
+use strum_macros::Display;
+use tracing::{event, instrument, Level};
+use uuid::Uuid;
+
+/// The snapshot is overwritten on every update, so it must be revalidated.
+pub const PROVISIONAL_RESULTS_CACHE_CONTROL: &str = "no-cache";
+
+/// Where the results of an area come from.
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize, Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvisionalResultsSource {
+    TALLY,
+    TALLY_SHEETS,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProvisionalVotes {
+    pub total_votes: u64,
+    pub total_valid_votes: u64,
+    pub total_invalid_votes: u64,
+    pub blank_votes: u64,
+}
+
+impl ProvisionalVotes {
+    fn add(&mut self, other: &ProvisionalVotes) {
+        self.total_votes += other.total_votes;
+        self.total_valid_votes += other.total_valid_votes;
+        self.total_invalid_votes += other.total_invalid_votes;
+        self.blank_votes += other.blank_votes;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvisionalCandidateResults {
+    pub candidate_id: String,
+    pub name: Option<String>,
+    pub votes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvisionalAreaResults {
+    pub area_id: String,
+    pub name: Option<String>,
+    pub source: ProvisionalResultsSource,
+    #[serde(flatten)]
+    pub votes: ProvisionalVotes,
+    pub candidates: Vec<ProvisionalCandidateResults>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvisionalContestResults {
+    pub contest_id: String,
+    pub name: Option<String>,
+    pub areas_reporting: u64,
+    pub areas_total: u64,
+    #[serde(flatten)]
+    pub votes: ProvisionalVotes,
+    pub candidates: Vec<ProvisionalCandidateResults>,
+    /// Only the areas reporting.
+    pub areas: Vec<ProvisionalAreaResults>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvisionalElectionResults {
+    pub election_id: String,
+    pub name: String,
+    /// The contests are empty while the results are embargoed.
+    pub embargoed: bool,
+    pub polls_closed_at: Option<DateTime<Utc>>,
+    pub contests: Vec<ProvisionalContestResults>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProvisionalResultsFeed {
+    pub election_event_id: String,
+    /// sha256 hash of the elections. It only changes when the results or the
+    /// embargo do, so consumers can poll it to detect updates.
+    pub version: String,
+    pub generated_at: DateTime<Utc>,
+    pub elections: Vec<ProvisionalElectionResults>,
+}
+
+/// Everything the feed is generated from. The tally results only include
+/// those of completed tally sessions, from the newest to the oldest.
+#[derive(Debug, Clone)]
+pub struct ProvisionalResultsData {
+    pub election_event: ElectionEvent,
+    pub elections: Vec<Election>,
+    pub contests: Vec<Contest>,
+    pub candidates: Vec<Candidate>,
+    pub areas: Vec<Area>,
+    pub area_contests: Vec<AreaContest>,
+    pub tally_sheets: Vec<TallySheet>,
+    pub results_area_contests: Vec<ResultsAreaContest>,
+    pub results_area_contest_candidates: Vec<ResultsAreaContestCandidate>,
+}
+
+pub fn get_provisional_results_policy(
+    election_event: &ElectionEvent,
+) -> Result<ProvisionalResultsPolicy> {
+    let presentation = election_event
+        .get_presentation()
+        .map_err(|err| anyhow!("Error parsing election event presentation: {err:?}"))?
+        .unwrap_or_default();
+    Ok(presentation.provisional_results_policy.unwrap_or_default())
+}
+
+pub fn get_provisional_results_key(tenant_id: &str, election_event_id: &str) -> String {
+    format!("tenant-{tenant_id}/event-{election_event_id}/provisional-results.json")
+}
+
+/// Voting channels with a voting period. If none is enabled, the online
+/// channel is assumed.
+fn get_election_channels(election: &Election) -> Result<Vec<VotingStatusChannel>> {
+    let voting_channels: VotingChannels = election
+        .voting_channels
+        .clone()
+        .map(|value| {
+            deserialize_value(value).context("Failed to deserialize election voting_channels")
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let channels: Vec<VotingStatusChannel> = [
+        VotingStatusChannel::ONLINE,
+        VotingStatusChannel::KIOSK,
+        VotingStatusChannel::EARLY_VOTING,
+    ]
+    .into_iter()
+    .filter(|channel| channel.channel_from(&voting_channels).unwrap_or(false))
+    .collect();
+
+    if channels.is_empty() {
+        Ok(vec![VotingStatusChannel::ONLINE])
+    } else {
+        Ok(channels)
+    }
+}
+
+fn to_count(value: Option<i64>) -> u64 {
+    value
+        .and_then(|value| u64::try_from(value).ok())
+        .unwrap_or(0)
+}
+
+fn sorted_candidates(
+    votes: HashMap<String, u64>,
+    candidates: &HashMap<&str, &Candidate>,
+) -> Vec<ProvisionalCandidateResults> {
+    let mut results: Vec<ProvisionalCandidateResults> = votes
+        .into_iter()
+        .map(|(candidate_id, votes)| ProvisionalCandidateResults {
+            name: candidates
+                .get(candidate_id.as_str())
+                .and_then(|candidate| candidate.name.clone()),
+            candidate_id,
+            votes,
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        b.votes
+            .cmp(&a.votes)
+            .then_with(|| a.candidate_id.cmp(&b.candidate_id))
+    });
+    results
+}
+
+fn get_tally_area_results(
+    data: &ProvisionalResultsData,
+    results_area_contest: &ResultsAreaContest,
+) -> (ProvisionalVotes, HashMap<String, u64>) {
+    let votes = ProvisionalVotes {
+        total_votes: to_count(results_area_contest.total_votes),
+        total_valid_votes: to_count(results_area_contest.total_valid_votes),
+        total_invalid_votes: to_count(results_area_contest.total_invalid_votes),
+        blank_votes: to_count(results_area_contest.blank_votes),
+    };
+    let candidate_votes = data
+        .results_area_contest_candidates
+        .iter()
+        .filter(|candidate| {
+            candidate.results_event_id == results_area_contest.results_event_id
+                && candidate.area_id == results_area_contest.area_id
+                && candidate.contest_id == results_area_contest.contest_id
+        })
+        .map(|candidate| {
+            (
+                candidate.candidate_id.clone(),
+                to_count(candidate.cast_votes),
+            )
+        })
+        .collect();
+    (votes, candidate_votes)
+}
+
+/// Sums the published tally sheets of all the channels of an area.
+fn get_tally_sheets_area_results(
+    tally_sheets: &[&TallySheet],
+) -> (ProvisionalVotes, HashMap<String, u64>) {
+    let mut votes = ProvisionalVotes::default();
+    let mut candidate_votes: HashMap<String, u64> = HashMap::new();
+    for content in tally_sheets
+        .iter()
+        .filter_map(|tally_sheet| tally_sheet.content.as_ref())
+    {
+        votes.add(&ProvisionalVotes {
+            total_votes: content.total_votes.unwrap_or(0),
+            total_valid_votes: content.total_valid_votes.unwrap_or(0),
+            total_invalid_votes: content
+                .invalid_votes
+                .as_ref()
+                .and_then(|invalid_votes| invalid_votes.total_invalid)
+                .unwrap_or(0),
+            blank_votes: content.total_blank_votes.unwrap_or(0),
+        });
+        for (candidate_id, candidate_results) in &content.candidate_results {
+            *candidate_votes.entry(candidate_id.clone()).or_insert(0) +=
+                candidate_results.total_votes.unwrap_or(0);
+        }
+    }
+    (votes, candidate_votes)
+}
+
+fn generate_contest_results(
+    data: &ProvisionalResultsData,
+    contest: &Contest,
+    areas: &HashMap<&str, &Area>,
+    candidates: &HashMap<&str, &Candidate>,
+) -> ProvisionalContestResults {
+    let mut area_ids: Vec<&str> = data
+        .area_contests
+        .iter()
+        .filter(|area_contest| area_contest.contest_id == contest.id)
+        .map(|area_contest| area_contest.area_id.as_str())
+        .collect();
+    area_ids.sort();
+    area_ids.dedup();
+
+    let mut votes = ProvisionalVotes::default();
+    let mut candidate_votes: HashMap<String, u64> = HashMap::new();
+    let mut area_results: Vec<ProvisionalAreaResults> = vec![];
+
+    for area_id in &area_ids {
+        // The first match is the one of the newest tally
+        let results_area_contest = data
+            .results_area_contests
+            .iter()
+            .find(|result| result.contest_id == contest.id && result.area_id.as_str() == *area_id);
+        let tally_sheets: Vec<&TallySheet> = data
+            .tally_sheets
+            .iter()
+            .filter(|tally_sheet| {
+                tally_sheet.contest_id == contest.id && tally_sheet.area_id.as_str() == *area_id
+            })
+            .collect();
+
+        let (source, (area_votes, area_candidate_votes)) = match results_area_contest {
+            Some(results_area_contest) => (
+                ProvisionalResultsSource::TALLY,
+                get_tally_area_results(data, results_area_contest),
+            ),
+            None if !tally_sheets.is_empty() => (
+                ProvisionalResultsSource::TALLY_SHEETS,
+                get_tally_sheets_area_results(&tally_sheets),
+            ),
+            None => continue,
+        };
+
+        votes.add(&area_votes);
+        for (candidate_id, candidate_votes_count) in &area_candidate_votes {
+            *candidate_votes.entry(candidate_id.clone()).or_insert(0) += candidate_votes_count;
+        }
+        area_results.push(ProvisionalAreaResults {
+            area_id: area_id.to_string(),
+            name: areas.get(area_id).and_then(|area| area.name.clone()),
+            source,
+            votes: area_votes,
+            candidates: sorted_candidates(area_candidate_votes, candidates),
+        });
+    }
+
+    ProvisionalContestResults {
+        contest_id: contest.id.clone(),
+        name: contest.name.clone(),
+        areas_reporting: area_results.len() as u64,
+        areas_total: area_ids.len() as u64,
+        votes,
+        candidates: sorted_candidates(candidate_votes, candidates),
+        areas: area_results,
+    }
+}
+
+fn generate_election_results(
+    data: &ProvisionalResultsData,
+    election: &Election,
+    areas: &HashMap<&str, &Area>,
+    candidates: &HashMap<&str, &Candidate>,
+) -> Result<ProvisionalElectionResults> {
+    let status = get_election_status(election.status.clone()).unwrap_or_default();
+    let channels = get_election_channels(election)?;
+    let embargoed = !channels
+        .iter()
+        .all(|channel| status.status_by_channel(*channel) == VotingStatus::CLOSED);
+    let polls_closed_at = if embargoed {
+        None
+    } else {
+        channels
+            .iter()
+            .filter_map(|channel| status.dates_by_channel(*channel).last_stopped_at)
+            .max()
+    };
+
+    let mut contests: Vec<&Contest> = data
+        .contests
+        .iter()
+        .filter(|contest| contest.election_id == election.id)
+        .collect();
+    contests.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(ProvisionalElectionResults {
+        election_id: election.id.clone(),
+        name: election.name.clone(),
+        embargoed,
+        polls_closed_at,
+        contests: if embargoed {
+            vec![]
+        } else {
+            contests
+                .into_iter()
+                .map(|contest| generate_contest_results(data, contest, areas, candidates))
+                .collect()
+        },
+    })
+}
+
+pub fn get_provisional_results_version(elections: &[ProvisionalElectionResults]) -> Result<String> {
+    let bytes = serde_json::to_vec(elections)?;
+    let hash =
+        hash_sha256(&bytes).map_err(|err| anyhow!("Error hashing provisional results: {err}"))?;
+    Ok(hex::encode(hash))
+}
+
+/// Generates the feed. Everything is sorted by id, except the candidates,
+/// sorted by votes, so that the version only changes with the results.
+pub fn generate_provisional_results(
+    data: &ProvisionalResultsData,
+    generated_at: DateTime<Utc>,
+) -> Result<ProvisionalResultsFeed> {
+    let areas: HashMap<&str, &Area> = data
+        .areas
+        .iter()
+        .map(|area| (area.id.as_str(), area))
+        .collect();
+    let candidates: HashMap<&str, &Candidate> = data
+        .candidates
+        .iter()
+        .map(|candidate| (candidate.id.as_str(), candidate))
+        .collect();
+
+    let mut elections: Vec<&Election> = data.elections.iter().collect();
+    elections.sort_by(|a, b| a.id.cmp(&b.id));
+    let elections = elections
+        .into_iter()
+        .map(|election| generate_election_results(data, election, &areas, &candidates))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ProvisionalResultsFeed {
+        election_event_id: data.election_event.id.clone(),
+        version: get_provisional_results_version(&elections)?,
+        generated_at,
+        elections,
+    })
+}
+
+/// Results events of the completed electoral results tally sessions, from
+/// the newest to the oldest.
+#[instrument(skip(hasura_transaction), err)]
+async fn get_completed_results_event_ids(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<Vec<String>> {
+    let tally_sessions = get_tally_sessions_by_election_event_id(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        /* only_active */ false,
+    )
+    .await?;
+
+    let mut results_event_ids = vec![];
+    for tally_session in tally_sessions.into_iter().filter(|tally_session| {
+        tally_session.is_execution_completed
+            && tally_session.execution_status == Some(TallyExecutionStatus::SUCCESS.to_string())
+            && tally_session.tally_type.as_deref().unwrap_or_default()
+                != TallyType::INITIALIZATION_REPORT.to_string()
+    }) {
+        let results_event_id = get_last_tally_session_execution(
+            hasura_transaction,
+            tenant_id,
+            election_event_id,
+            &tally_session.id,
+        )
+        .await?
+        .and_then(|execution| execution.results_event_id);
+        if let Some(results_event_id) = results_event_id {
+            results_event_ids.push(results_event_id);
+        }
+    }
+    Ok(results_event_ids)
+}
+
+#[instrument(skip(hasura_transaction, election_event), err)]
+pub async fn get_provisional_results_data(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event: ElectionEvent,
+) -> Result<ProvisionalResultsData> {
+    let election_event_id = election_event.id.clone();
+    let elections = get_elections(hasura_transaction, tenant_id, &election_event_id, None).await?;
+    let contests = export_contests(hasura_transaction, tenant_id, &election_event_id).await?;
+    let candidates = export_candidates(hasura_transaction, tenant_id, &election_event_id).await?;
+    let areas = get_event_areas(hasura_transaction, tenant_id, &election_event_id).await?;
+    let area_contests =
+        export_area_contests(hasura_transaction, tenant_id, &election_event_id).await?;
+    let tally_sheets =
+        get_published_tally_sheets_by_event(hasura_transaction, tenant_id, &election_event_id)
+            .await?;
+
+    let results_event_ids =
+        get_completed_results_event_ids(hasura_transaction, tenant_id, &election_event_id).await?;
+    let mut results_area_contests: Vec<ResultsAreaContest> =
+        get_event_results_area_contest(hasura_transaction, tenant_id, &election_event_id)
+            .await?
+            .into_iter()
+            .filter(|result| results_event_ids.contains(&result.results_event_id))
+            .collect();
+    results_area_contests.sort_by_key(|result| {
+        results_event_ids
+            .iter()
+            .position(|results_event_id| *results_event_id == result.results_event_id)
+    });
+    let mut results_area_contest_candidates = vec![];
+    for results_event_id in &results_event_ids {
+        results_area_contest_candidates.extend(
+            get_results_event_area_contest_candidates(
+                hasura_transaction,
+                tenant_id,
+                &election_event_id,
+                results_event_id,
+            )
+            .await?,
+        );
+    }
+
+    Ok(ProvisionalResultsData {
+        election_event,
+        elections,
+        contests,
+        candidates,
+        areas,
+        area_contests,
+        tally_sheets,
+        results_area_contests,
+        results_area_contest_candidates,
+    })
+}
+
+/// Returns the election event if its provisional results are published.
+#[instrument(skip(hasura_transaction), err)]
+async fn get_published_election_event(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<Option<ElectionEvent>> {
+    let Some(election_event) =
+        get_election_event_by_id_if_exist(hasura_transaction, tenant_id, election_event_id).await?
+    else {
+        return Ok(None);
+    };
+    if get_provisional_results_policy(&election_event)? == ProvisionalResultsPolicy::DISABLED {
+        return Ok(None);
+    }
+    Ok(Some(election_event))
+}
+
+/// Returns `None` if the election event doesn't exist or its provisional
+/// results are not published.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_provisional_results_feed(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<Option<ProvisionalResultsFeed>> {
+    let Some(election_event) =
+        get_published_election_event(hasura_transaction, tenant_id, election_event_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let data = get_provisional_results_data(hasura_transaction, tenant_id, election_event).await?;
+    generate_provisional_results(&data, Utc::now()).map(Some)
+}
+
+/// Serialized feed, as served to the public.
+#[derive(Debug, Clone)]
+pub struct ProvisionalResultsSnapshot {
+    pub version: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct ProvisionalResultsVersion {
+    version: String,
+}
+
+/// Returns `None` if the election event doesn't exist or its provisional
+/// results are not published. The feed is read from the snapshot in the
+/// public bucket, so that it isn't generated on every request. If there is
+/// no snapshot yet, the feed is generated and the snapshot update enqueued.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_provisional_results_snapshot(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<Option<ProvisionalResultsSnapshot>> {
+    let Some(election_event) =
+        get_published_election_event(hasura_transaction, tenant_id, election_event_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let snapshot_data = s3::get_file_from_s3_if_exists(
+        s3::get_public_bucket()?,
+        get_provisional_results_key(tenant_id, election_event_id),
+    )
+    .await
+    .with_context(|| "Error reading provisional results snapshot")?;
+    if let Some(data) = snapshot_data {
+        let snapshot: ProvisionalResultsVersion = serde_json::from_slice(&data)
+            .with_context(|| "Error parsing provisional results snapshot")?;
+        return Ok(Some(ProvisionalResultsSnapshot {
+            version: snapshot.version,
+            data,
+        }));
+    }
+    event!(
+        Level::INFO,
+        "No provisional results snapshot of election event {election_event_id}"
+    );
+
+    let data = get_provisional_results_data(hasura_transaction, tenant_id, election_event).await?;
+    let feed = generate_provisional_results(&data, Utc::now())?;
+    if let Err(err) = enqueue_provisional_results_update(tenant_id, election_event_id).await {
+        event!(
+            Level::ERROR,
+            "Error enqueuing provisional results update: {err:?}"
+        );
+    }
+    Ok(Some(ProvisionalResultsSnapshot {
+        version: feed.version.clone(),
+        data: serde_json::to_vec_pretty(&feed)?,
+    }))
+}
+
+/// Uploads the feed snapshot to the public bucket. If the provisional
+/// results are not published, the snapshot is deleted instead, so that it
+/// doesn't outlive the policy.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn upload_provisional_results_snapshot(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<()> {
+    let Some(feed) =
+        get_provisional_results_feed(hasura_transaction, tenant_id, election_event_id).await?
+    else {
+        event!(
+            Level::INFO,
+            "Provisional results of election event {election_event_id} are disabled"
+        );
+        s3::delete_files_from_s3(
+            /* s3_bucket */ s3::get_public_bucket()?,
+            /* prefix */ get_provisional_results_key(tenant_id, election_event_id),
+            /* is_public */ false,
+        )
+        .await
+        .with_context(|| "Error deleting provisional results snapshot")?;
+        return Ok(());
+    };
+
+    let file = generate_temp_file("provisional-results-", ".json")
+        .with_context(|| "Error creating temp file")?;
+    let file2 = file
+        .reopen()
+        .with_context(|| "Couldn't reopen file for writing")?;
+    let buf_writer = BufWriter::new(file2);
+    serde_json::to_writer_pretty(buf_writer, &feed)
+        .with_context(|| "Failed writing into temp file")?;
+    let temp_path = file.into_temp_path();
+
+    s3::upload_file_to_s3(
+        /* key */ get_provisional_results_key(tenant_id, election_event_id),
+        /* is_public */ false,
+        /* s3_bucket */ s3::get_public_bucket()?,
+        /* media_type */ "application/json".to_string(),
+        /* file_path */ temp_path.to_string_lossy().to_string(),
+        /* cache_control_policy */ Some(PROVISIONAL_RESULTS_CACHE_CONTROL.to_string()),
+        /* download filed name */ None,
+    )
+    .await
+    .with_context(|| "Error uploading provisional results snapshot")?;
+
+    temp_path
+        .close()
+        .with_context(|| "Error closing temp file path")?;
+
+    event!(
+        Level::INFO,
+        "Published provisional results version {}",
+        feed.version
+    );
+    Ok(())
+}
+
+/// Seconds an update stays queued before another one can be enqueued, in
+/// case the queued task is lost.
+const QUEUED_UPDATE_LOCK_SECONDS: i64 = 300;
+
+/// Seconds a running update holds the lock of the election event.
+const RUNNING_UPDATE_LOCK_SECONDS: i64 = 120;
+
+/// Seconds an update is deferred while another one is running.
+const DEFERRED_UPDATE_SECONDS: i64 = 5;
+
+fn get_queued_update_lock_key(tenant_id: &str, election_event_id: &str) -> String {
+    format!("publish_provisional_results-queued-{tenant_id}-{election_event_id}")
+}
+
+fn get_running_update_lock_key(tenant_id: &str, election_event_id: &str) -> String {
+    format!("publish_provisional_results-{tenant_id}-{election_event_id}")
+}
+
+/// Fails if another update of the election event is queued. Acquiring it
+/// again with the same value extends it.
+async fn acquire_queued_update_lock(
+    tenant_id: &str,
+    election_event_id: &str,
+    queued_lock_value: &str,
+) -> Result<PgLock> {
+    PgLock::acquire(
+        get_queued_update_lock_key(tenant_id, election_event_id),
+        queued_lock_value.to_string(),
+        ISO8601::now() + Duration::seconds(QUEUED_UPDATE_LOCK_SECONDS),
+    )
+    .await
+}
+
+async fn release_queued_update_lock(
+    tenant_id: &str,
+    election_event_id: &str,
+    queued_lock_value: &str,
+) -> Result<()> {
+    PgLock {
+        key: get_queued_update_lock_key(tenant_id, election_event_id),
+        value: queued_lock_value.to_string(),
+        expiry_date: None,
+    }
+    .release()
+    .await
+}
+
+async fn send_provisional_results_update(
+    tenant_id: &str,
+    election_event_id: &str,
+    queued_lock_value: &str,
+    eta: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let celery_app = get_celery_app().await;
+    let mut signature = publish_provisional_results::new(
+        tenant_id.to_string(),
+        election_event_id.to_string(),
+        queued_lock_value.to_string(),
+    );
+    if let Some(eta) = eta {
+        signature = signature.with_eta(eta);
+    }
+    let task = celery_app.send_task(signature).await?;
+    event!(Level::INFO, "Sent task {}", task.task_id);
+    Ok(())
+}
+
+/// Sends the task updating the snapshot. Call it once the changes to the
+/// results or the voting status are committed. If an update is already
+/// queued nothing is sent, as that update hasn't read the results yet.
+#[instrument(err)]
+pub async fn enqueue_provisional_results_update(
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<()> {
+    let queued_lock_value = Uuid::new_v4().to_string();
+    if acquire_queued_update_lock(tenant_id, election_event_id, &queued_lock_value)
+        .await
+        .is_err()
+    {
+        event!(
+            Level::INFO,
+            "Provisional results update of election event {election_event_id} already queued"
+        );
+        return Ok(());
+    }
+
+    let res =
+        send_provisional_results_update(tenant_id, election_event_id, &queued_lock_value, None)
+            .await;
+    if res.is_err() {
+        release_queued_update_lock(tenant_id, election_event_id, &queued_lock_value).await?;
+    }
+    res
+}
+
+/// Runs a queued update of the snapshot. Updates of the same election event
+/// are serialised, so that an older feed never overwrites a newer one. If
+/// another update is running it may have read the results before the
+/// changes this one was queued for, so this one is sent again later instead
+/// of waiting for it in the worker.
+#[instrument(err)]
+pub async fn run_provisional_results_update(
+    tenant_id: &str,
+    election_event_id: &str,
+    queued_lock_value: &str,
+) -> Result<()> {
+    let Ok(running_lock) = PgLock::acquire(
+        get_running_update_lock_key(tenant_id, election_event_id),
+        Uuid::new_v4().to_string(),
+        ISO8601::now() + Duration::seconds(RUNNING_UPDATE_LOCK_SECONDS),
+    )
+    .await
+    else {
+        if acquire_queued_update_lock(tenant_id, election_event_id, queued_lock_value)
+            .await
+            .is_err()
+        {
+            event!(
+                Level::INFO,
+                "Dropping provisional results update, another one is queued"
+            );
+            return Ok(());
+        }
+        return send_provisional_results_update(
+            tenant_id,
+            election_event_id,
+            queued_lock_value,
+            Some(Utc::now() + Duration::seconds(DEFERRED_UPDATE_SECONDS)),
+        )
+        .await;
+    };
+
+    // The results are read after this point, so changes committed from now
+    // on must queue a new update.
+    let res = async {
+        release_queued_update_lock(tenant_id, election_event_id, queued_lock_value).await?;
+        provide_hasura_transaction(|hasura_transaction| {
+            let tenant_id = tenant_id.to_string();
+            let election_event_id = election_event_id.to_string();
+            Box::pin(async move {
+                upload_provisional_results_snapshot(
+                    hasura_transaction,
+                    &tenant_id,
+                    &election_event_id,
+                )
+                .await
+            })
+        })
+        .await
+    }
+    .await;
+    running_lock.release().await?;
+    res
+}
+
+/// Election event row of a Hasura event trigger payload, with the fields
+/// needed to detect changes of the provisional results policy.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ElectionEventTriggerRow {
+    pub id: String,
+    pub tenant_id: String,
+    pub presentation: Option<Value>,
+}
+
+fn get_row_provisional_results_policy(row: &ElectionEventTriggerRow) -> ProvisionalResultsPolicy {
+    row.presentation
+        .as_ref()
+        .and_then(|presentation| presentation.get("provisional_results_policy"))
+        .and_then(|policy| deserialize_value::<ProvisionalResultsPolicy>(policy.clone()).ok())
+        .unwrap_or_default()
+}
+
+pub fn has_provisional_results_policy_changed(
+    old: &ElectionEventTriggerRow,
+    new: &ElectionEventTriggerRow,
+) -> bool {
+    get_row_provisional_results_policy(old) != get_row_provisional_results_policy(new)
+}
+
+/// Updates the snapshot when the provisional results policy of an election
+/// event changes, so that disabling them deletes the snapshot from the
+/// public bucket right away. Called by the Hasura event trigger of the
+/// election event presentation.
+#[instrument(err)]
+pub async fn handle_election_event_presentation_update(
+    old: &ElectionEventTriggerRow,
+    new: &ElectionEventTriggerRow,
+) -> Result<()> {
+    if !has_provisional_results_policy_changed(old, new) {
+        return Ok(());
+    }
+    enqueue_provisional_results_update(&new.tenant_id, &new.id).await
+}
+
+fn get_hasura_events_secret() -> Result<String> {
+    std::env::var("HASURA_EVENTS_SECRET")
+        .map_err(|_err| anyhow!("HASURA_EVENTS_SECRET env var missing"))
+}
+
+/// Checks the secret header sent by the Hasura event triggers.
+#[instrument(skip(secret), err)]
+pub fn verify_hasura_events_secret(secret: &str) -> Result<()> {
+    check_notifications_token(&get_hasura_events_secret()?, secret)
+        .map_err(|_err| anyhow!("Invalid Hasura events secret"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::export::results_event_data::test_data::*;
+    use serde_json::{json, Value};
+
+    fn tally_sheet(area_id: &str, channel: &str, alice: u64, bob: u64, invalid: u64) -> Value {
+        ids(json!({
+            "id": format!("{area_id}-{channel}"),
+            "election_id": ELECTION_ID,
+            "contest_id": CONTEST_ID,
+            "area_id": area_id,
+            "channel": channel,
+            "created_by_user_id": "admin",
+            "content": {
+                "area_id": area_id,
+                "contest_id": CONTEST_ID,
+                "total_votes": alice + bob + invalid,
+                "total_valid_votes": alice + bob,
+                "invalid_votes": {"total_invalid": invalid},
+                "total_blank_votes": 0,
+                "candidate_results": {
+                    ALICE_ID: {"candidate_id": ALICE_ID, "total_votes": alice},
+                    BOB_ID: {"candidate_id": BOB_ID, "total_votes": bob},
+                },
+            },
+        }))
+    }
+
+    fn get_provisional_data(voting_status: &str) -> ProvisionalResultsData {
+        let data = get_data();
+        ProvisionalResultsData {
+            election_event: data.election_event,
+            elections: vec![from_json(ids(json!({
+                "id": ELECTION_ID,
+                "name": "Mayor election",
+                "status": {
+                    "voting_status": voting_status,
+                    "voting_period_dates": {
+                        "first_started_at": "2025-05-04T08:00:00Z",
+                        "last_stopped_at": "2025-05-05T20:00:00Z",
+                    },
+                },
+            })))],
+            contests: data.contests,
+            candidates: data.candidates,
+            areas: data.areas,
+            area_contests: [CITY_ID, DISTRICT_ID]
+                .into_iter()
+                .map(|area_id| {
+                    from_json(json!({
+                        "id": area_id,
+                        "area_id": area_id,
+                        "contest_id": CONTEST_ID,
+                    }))
+                })
+                .collect(),
+            tally_sheets: vec![
+                from_json(tally_sheet(CITY_ID, "PAPER", 5, 3, 1)),
+                from_json(tally_sheet(CITY_ID, "POSTAL", 1, 2, 0)),
+                from_json(tally_sheet(DISTRICT_ID, "PAPER", 1, 1, 0)),
+            ],
+            results_area_contests: data.results_area_contests,
+            results_area_contest_candidates: data.results_area_contest_candidates,
+        }
+    }
+
+    fn votes(candidates: &[ProvisionalCandidateResults]) -> Vec<(&str, u64)> {
+        candidates
+            .iter()
+            .map(|candidate| (candidate.candidate_id.as_str(), candidate.votes))
+            .collect()
+    }
+
+    #[test]
+    fn test_embargoed_until_polls_close() {
+        let feed =
+            generate_provisional_results(&get_provisional_data("OPEN"), get_generated_date())
+                .unwrap();
+
+        let election = &feed.elections[0];
+        assert!(election.embargoed);
+        assert_eq!(election.polls_closed_at, None);
+        assert!(election.contests.is_empty());
+    }
+
+    #[test]
+    fn test_aggregates_areas() {
+        let feed =
+            generate_provisional_results(&get_provisional_data("CLOSED"), get_generated_date())
+                .unwrap();
+
+        let election = &feed.elections[0];
+        assert!(!election.embargoed);
+        assert_eq!(
+            election.polls_closed_at.unwrap().to_rfc3339(),
+            "2025-05-05T20:00:00+00:00"
+        );
+
+        let contest = &election.contests[0];
+        assert_eq!((contest.areas_reporting, contest.areas_total), (2, 2));
+
+        // The channels of the city tally sheets are summed
+        let city = &contest.areas[0];
+        assert_eq!(city.area_id, CITY_ID);
+        assert_eq!(city.source, ProvisionalResultsSource::TALLY_SHEETS);
+        assert_eq!(city.votes.total_votes, 12);
+        assert_eq!(city.votes.total_invalid_votes, 1);
+        assert_eq!(votes(&city.candidates), vec![(ALICE_ID, 6), (BOB_ID, 5)]);
+
+        // The district tally takes precedence over its tally sheet
+        let district = &contest.areas[1];
+        assert_eq!(district.source, ProvisionalResultsSource::TALLY);
+        assert_eq!(district.votes.total_votes, 13);
+        assert_eq!(
+            votes(&district.candidates),
+            vec![(ALICE_ID, 7), (BOB_ID, 4)]
+        );
+
+        assert_eq!(contest.votes.total_votes, 25);
+        assert_eq!(contest.votes.total_valid_votes, 22);
+        assert_eq!(
+            votes(&contest.candidates),
+            vec![(ALICE_ID, 13), (BOB_ID, 9)]
+        );
+    }
+
+    #[test]
+    fn test_areas_reporting() {
+        let mut data = get_provisional_data("CLOSED");
+        data.tally_sheets.clear();
+
+        let feed = generate_provisional_results(&data, get_generated_date()).unwrap();
+
+        let contest = &feed.elections[0].contests[0];
+        assert_eq!((contest.areas_reporting, contest.areas_total), (1, 2));
+        assert_eq!(contest.areas[0].area_id, DISTRICT_ID);
+    }
+
+    #[test]
+    fn test_version_changes_with_results() {
+        let mut data = get_provisional_data("CLOSED");
+        let feed = generate_provisional_results(&data, get_generated_date()).unwrap();
+        let regenerated = generate_provisional_results(&data, Utc::now()).unwrap();
+        assert_eq!(feed.version, regenerated.version);
+
+        data.tally_sheets.pop();
+        data.tally_sheets.pop();
+        let updated = generate_provisional_results(&data, get_generated_date()).unwrap();
+        assert_ne!(feed.version, updated.version);
+    }
+
+    #[test]
+    fn test_provisional_results_policy_changed() {
+        let row = |presentation: Value| ElectionEventTriggerRow {
+            id: ELECTION_EVENT_ID.to_string(),
+            tenant_id: TENANT_ID.to_string(),
+            presentation: Some(presentation),
+        };
+        let disabled = row(json!({"provisional_results_policy": "disabled"}));
+        let enabled = row(json!({"provisional_results_policy": "after-polls-close"}));
+        let unset = row(json!({"language_conf": {"default_language_code": "es"}}));
+
+        assert!(has_provisional_results_policy_changed(&enabled, &disabled));
+        assert!(has_provisional_results_policy_changed(&unset, &enabled));
+        assert!(!has_provisional_results_policy_changed(&unset, &disabled));
+        assert!(!has_provisional_results_policy_changed(&enabled, &enabled));
+    }
+}
@@ -0,0 +1,634 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::election::get_elections;
+use crate::postgres::election_event::get_election_event_by_id;
+use crate::postgres::keys_ceremony::get_keys_ceremonies;
+use crate::postgres::scheduled_event::{
+    add_scheduled_event_alerted_receiver, find_scheduled_event_by_id, set_scheduled_event_alerted,
+    update_scheduled_event_attempt,
+};
+use crate::postgres::template::get_template_by_alias;
+use crate::services::database::get_hasura_pool;
+use crate::services::election_event_status::get_election_status;
+use crate::services::providers::email_sender::EmailSender;
+use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::tasks::send_template::send_template_email;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use deadpool_postgres::{Client as DbClient, Transaction};
+use sequent_core::ballot::{VotingStatus, VotingStatusChannel};
+use sequent_core::serialization::deserialize_with_path::deserialize_value;
+use sequent_core::services::date::{parse_time_zone, ISO8601};
+use sequent_core::types::ceremonies::KeysCeremonyExecutionStatus;
+use sequent_core::types::hasura::core::{Election, KeysCeremony};
+use sequent_core::types::scheduled_event::*;
+use sequent_core::types::templates::SendTemplateBody;
+use serde_json::{json, Map, Value};
+use tracing::{event, instrument, Level};
+
+/// Outcome of a failed execution attempt of a scheduled event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledEventAttempt {
+    pub status: ScheduledEventStatus,
+    pub attempts: i32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+fn get_policy(scheduled_event: &ScheduledEvent) -> ScheduledEventPolicy {
+    scheduled_event
+        .cron_config
+        .as_ref()
+        .map(|cron_config| cron_config.policy.clone())
+        .unwrap_or_default()
+}
+
+/// IANA time zone configured in the presentation of the election event, if
+/// any.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_election_event_time_zone(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+) -> Result<Option<String>> {
+    let election_event = get_election_event_by_id(hasura_transaction, tenant_id, election_event_id)
+        .await
+        .with_context(|| "Error obtaining election event")?;
+    let time_zone = election_event
+        .get_presentation()
+        .map_err(|err| anyhow!("Error parsing election event presentation: {err:?}"))?
+        .and_then(|presentation| presentation.time_zone);
+    if let Some(ref time_zone) = time_zone {
+        parse_time_zone(time_zone)?;
+    }
+    Ok(time_zone)
+}
+
+/// Dates entered without offset are wall-clock times in the time zone of the
+/// election event. They are stored as RFC 3339 with the offset in effect at
+/// that date, so that DST transitions are taken into account. Without time
+/// zone the date is kept as is.
+pub fn normalize_scheduled_date(date: &str, time_zone: Option<&str>) -> Result<String> {
+    let Some(time_zone) = time_zone else {
+        return Ok(date.to_string());
+    };
+    let time_zone = parse_time_zone(time_zone)?;
+    Ok(ISO8601::to_date_in_time_zone(date, &time_zone)?.to_rfc3339())
+}
+
+/// Absolute date of the scheduled date of a cron config, interpreted in its
+/// time zone when it has no offset.
+pub fn get_cron_config_datetime(cron_config: &CronConfig) -> Option<DateTime<Utc>> {
+    let scheduled_date = cron_config.scheduled_date.as_ref()?;
+    let datetime = match cron_config.time_zone.as_deref() {
+        Some(time_zone) => {
+            let time_zone = parse_time_zone(time_zone).ok()?;
+            ISO8601::to_date_in_time_zone(scheduled_date, &time_zone)
+                .ok()?
+                .with_timezone(&Utc)
+        }
+        None => ISO8601::to_date(scheduled_date).ok()?.with_timezone(&Utc),
+    };
+    Some(datetime)
+}
+
+/// The scheduled date of the event, ignoring any pending retry.
+pub fn get_scheduled_datetime(scheduled_event: &ScheduledEvent) -> Option<DateTime<Utc>> {
+    get_cron_config_datetime(scheduled_event.cron_config.as_ref()?)
+}
+
+/// Election the scheduled event applies to, or None if it applies to the
+/// whole election event.
+pub fn get_scheduled_event_election_id(scheduled_event: &ScheduledEvent) -> Option<String> {
+    let event_payload = scheduled_event.event_payload.clone()?;
+    deserialize_value::<ManageElectionDatePayload>(event_payload)
+        .ok()
+        .and_then(|payload| payload.election_id)
+}
+
+/// Upper bound of the back-off between attempts, also for policies without
+/// `max_backoff_secs`, whose back-off grows without limit.
+const MAX_BACKOFF_SECS: i64 = 24 * 60 * 60;
+
+/// After a failed attempt the event is retried with back-off until the
+/// retries of its policy are exhausted, then it is marked as FAILED.
+pub fn get_failed_attempt(
+    scheduled_event: &ScheduledEvent,
+    now: DateTime<Utc>,
+) -> ScheduledEventAttempt {
+    let retry_policy = get_policy(scheduled_event).retry_policy.unwrap_or_default();
+    let attempts = scheduled_event.attempts + 1;
+    if attempts > retry_policy.max_retries {
+        return ScheduledEventAttempt {
+            status: ScheduledEventStatus::FAILED,
+            attempts,
+            next_attempt_at: None,
+        };
+    }
+    ScheduledEventAttempt {
+        status: ScheduledEventStatus::RETRYING,
+        attempts,
+        next_attempt_at: Duration::try_seconds(
+            retry_policy
+                .get_backoff_secs(attempts)
+                .clamp(0, MAX_BACKOFF_SECS),
+        )
+        .and_then(|backoff| now.checked_add_signed(backoff)),
+    }
+}
+
+/// Whether the alert of the event has to be sent: it failed, or it is still
+/// pending after the grace period since its scheduled date.
+pub fn requires_alert(scheduled_event: &ScheduledEvent, now: DateTime<Utc>) -> bool {
+    let Some(alert) = get_policy(scheduled_event).alert else {
+        return false;
+    };
+    if scheduled_event.alerted_at.is_some()
+        || scheduled_event.stopped_at.is_some()
+        || scheduled_event.archived_at.is_some()
+    {
+        return false;
+    }
+    if scheduled_event.status == Some(ScheduledEventStatus::FAILED) {
+        return true;
+    }
+    get_scheduled_datetime(scheduled_event)
+        .and_then(|datetime| {
+            Duration::try_seconds(alert.grace_period_secs)
+                .and_then(|grace_period| datetime.checked_add_signed(grace_period))
+        })
+        .map(|deadline| deadline < now)
+        .unwrap_or(false)
+}
+
+fn is_keys_ceremony_finished(keys_ceremony: &KeysCeremony) -> bool {
+    keys_ceremony
+        .execution_status()
+        .map(|execution_status| execution_status == KeysCeremonyExecutionStatus::SUCCESS)
+        .unwrap_or(false)
+}
+
+fn is_prerequisite_met(
+    prerequisite: &ScheduledEventPrerequisite,
+    election: &Election,
+    keys_ceremonies: &[KeysCeremony],
+) -> bool {
+    match prerequisite {
+        ScheduledEventPrerequisite::INIT_REPORT_GENERATED => {
+            election.initialization_report_generated.unwrap_or(false)
+        }
+        ScheduledEventPrerequisite::KEYS_CEREMONY_FINISHED => keys_ceremonies
+            .iter()
+            .filter(|keys_ceremony| match election.keys_ceremony_id {
+                Some(ref keys_ceremony_id) => keys_ceremony.id == *keys_ceremony_id,
+                None => keys_ceremony.is_default(),
+            })
+            .any(is_keys_ceremony_finished),
+        ScheduledEventPrerequisite::VOTING_PERIOD_ENDED => {
+            get_election_status(election.status.clone())
+                .unwrap_or_default()
+                .status_by_channel(VotingStatusChannel::ONLINE)
+                == VotingStatus::CLOSED
+        }
+    }
+}
+
+/// Prerequisites not met by all the given elections.
+pub fn get_unmet_prerequisites(
+    prerequisites: &[ScheduledEventPrerequisite],
+    elections: &[Election],
+    keys_ceremonies: &[KeysCeremony],
+) -> Vec<ScheduledEventPrerequisite> {
+    prerequisites
+        .iter()
+        .filter(|prerequisite| {
+            !elections
+                .iter()
+                .all(|election| is_prerequisite_met(prerequisite, election, keys_ceremonies))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Fails if any prerequisite of the scheduled event is not met by the
+/// elections it applies to.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn check_prerequisites(
+    hasura_transaction: &Transaction<'_>,
+    scheduled_event: &ScheduledEvent,
+) -> Result<()> {
+    let prerequisites = get_policy(scheduled_event).prerequisites;
+    if prerequisites.is_empty() {
+        return Ok(());
+    }
+    let (Some(tenant_id), Some(election_event_id)) = (
+        scheduled_event.tenant_id.clone(),
+        scheduled_event.election_event_id.clone(),
+    ) else {
+        return Err(anyhow!("Missing tenant_id or election_event_id"));
+    };
+    let election_id = get_scheduled_event_election_id(scheduled_event);
+
+    let elections: Vec<Election> =
+        get_elections(hasura_transaction, &tenant_id, &election_event_id, None)
+            .await
+            .with_context(|| "Error listing elections")?
+            .into_iter()
+            .filter(|election| match election_id {
+                Some(ref election_id) => election.id == *election_id,
+                None => true,
+            })
+            .collect();
+    let keys_ceremonies = get_keys_ceremonies(hasura_transaction, &tenant_id, &election_event_id)
+        .await
+        .with_context(|| "Error listing keys ceremonies")?;
+
+    let unmet = get_unmet_prerequisites(&prerequisites, &elections, &keys_ceremonies);
+    if !unmet.is_empty() {
+        return Err(anyhow!("Prerequisites not met: {unmet:?}"));
+    }
+    Ok(())
+}
+
+#[instrument(skip(hasura_transaction), err)]
+pub async fn record_scheduled_event_failure(
+    hasura_transaction: &Transaction<'_>,
+    scheduled_event: &ScheduledEvent,
+    error: &str,
+) -> Result<ScheduledEventAttempt> {
+    let tenant_id = scheduled_event
+        .tenant_id
+        .clone()
+        .ok_or_else(|| anyhow!("Missing tenant_id"))?;
+    let attempt = get_failed_attempt(scheduled_event, ISO8601::now().with_timezone(&Utc));
+    update_scheduled_event_attempt(
+        hasura_transaction,
+        &tenant_id,
+        &scheduled_event.id,
+        &attempt.status,
+        attempt.attempts,
+        attempt.next_attempt_at,
+        error,
+    )
+    .await?;
+    event!(
+        Level::WARN,
+        "Scheduled event {} failed attempt {}, status {}: {}",
+        scheduled_event.id,
+        attempt.attempts,
+        attempt.status,
+        error,
+    );
+    Ok(attempt)
+}
+
+/// Records the failure of the task that executes a scheduled event. It runs
+/// in its own transaction, as the one of the task is rolled back.
+#[instrument(err)]
+pub async fn record_scheduled_event_task_failure(
+    tenant_id: &str,
+    election_event_id: &str,
+    scheduled_event_id: &str,
+    error: &str,
+) -> Result<()> {
+    provide_hasura_transaction(|hasura_transaction| {
+        let tenant_id = tenant_id.to_string();
+        let election_event_id = election_event_id.to_string();
+        let scheduled_event_id = scheduled_event_id.to_string();
+        let error = error.to_string();
+        Box::pin(async move {
+            let Some(scheduled_event) = find_scheduled_event_by_id(
+                hasura_transaction,
+                Some(tenant_id),
+                Some(election_event_id),
+                &scheduled_event_id,
+            )
+            .await?
+            else {
+                return Ok(());
+            };
+            record_scheduled_event_failure(hasura_transaction, &scheduled_event, &error).await?;
+            Ok(())
+        })
+    })
+    .await
+}
+
+fn get_alert_variables(scheduled_event: &ScheduledEvent) -> Map<String, Value> {
+    let mut variables: Map<String, Value> = Default::default();
+    variables.insert("tenant_id".to_string(), json!(scheduled_event.tenant_id));
+    variables.insert(
+        "election_event".to_string(),
+        json!({ "id": scheduled_event.election_event_id }),
+    );
+    variables.insert(
+        "scheduled_event".to_string(),
+        json!({
+            "id": scheduled_event.id,
+            "event_processor": scheduled_event.event_processor,
+            "election_id": get_scheduled_event_election_id(scheduled_event),
+            "scheduled_date": scheduled_event
+                .cron_config
+                .as_ref()
+                .and_then(|cron_config| cron_config.scheduled_date.clone()),
+            "status": scheduled_event.status.clone().unwrap_or_default().to_string(),
+            "attempts": scheduled_event.attempts,
+            "last_error": scheduled_event.last_error,
+        }),
+    );
+    variables
+}
+
+/// Sends the alert of the scheduled event to the receivers that didn't get it
+/// yet, with the email of the configured communication template. Each
+/// receiver is recorded as soon as its email is sent, and the event is marked
+/// as alerted once all of them got it.
+#[instrument(skip(email_sender), err)]
+pub async fn send_scheduled_event_alert(
+    tenant_id: &str,
+    election_event_id: &str,
+    scheduled_event_id: &str,
+    email_sender: &EmailSender,
+) -> Result<()> {
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .with_context(|| "Error getting hasura client")?;
+
+    let hasura_transaction = hasura_db_client.transaction().await?;
+    let Some(scheduled_event) = find_scheduled_event_by_id(
+        &hasura_transaction,
+        Some(tenant_id.to_string()),
+        Some(election_event_id.to_string()),
+        scheduled_event_id,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+    if !requires_alert(&scheduled_event, Utc::now()) {
+        return Ok(());
+    }
+    let Some(alert) = get_policy(&scheduled_event).alert else {
+        return Ok(());
+    };
+    let template = get_template_by_alias(&hasura_transaction, tenant_id, &alert.template_alias)
+        .await?
+        .ok_or_else(|| anyhow!("Template {} not found", alert.template_alias))?;
+    let template_body: SendTemplateBody = deserialize_value(template.template)
+        .map_err(|err| anyhow!("Error deserializing template: {err:?}"))?;
+    hasura_transaction.commit().await?;
+
+    let variables = get_alert_variables(&scheduled_event);
+    let mut failed_receivers: Vec<String> = vec![];
+    for receiver in alert.receivers.iter() {
+        if scheduled_event.alerted_receivers.contains(receiver) {
+            continue;
+        }
+        if let Err(err) = send_template_email(
+            &Some(receiver.clone()),
+            &template_body.email,
+            &variables,
+            email_sender,
+        )
+        .await
+        {
+            event!(
+                Level::ERROR,
+                "Error sending alert of scheduled event {} to {receiver}: {err:?}",
+                scheduled_event.id,
+            );
+            failed_receivers.push(receiver.clone());
+            continue;
+        }
+
+        let hasura_transaction = hasura_db_client.transaction().await?;
+        add_scheduled_event_alerted_receiver(
+            &hasura_transaction,
+            tenant_id,
+            &scheduled_event.id,
+            receiver,
+        )
+        .await?;
+        hasura_transaction.commit().await?;
+    }
+
+    if !failed_receivers.is_empty() {
+        return Err(anyhow!(
+            "Error sending alert to {}",
+            failed_receivers.join(", ")
+        ));
+    }
+
+    let hasura_transaction = hasura_db_client.transaction().await?;
+    set_scheduled_event_alerted(&hasura_transaction, tenant_id, &scheduled_event.id).await?;
+    hasura_transaction.commit().await?;
+    event!(
+        Level::INFO,
+        "Sent alert for scheduled event {} to {} receivers",
+        scheduled_event.id,
+        alert.receivers.len(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sequent_core::ballot::ElectionStatus;
+
+    fn scheduled_event(policy: ScheduledEventPolicy) -> ScheduledEvent {
+        ScheduledEvent {
+            id: "event".to_string(),
+            tenant_id: Some("tenant".to_string()),
+            election_event_id: Some("election-event".to_string()),
+            created_at: None,
+            stopped_at: None,
+            archived_at: None,
+            labels: None,
+            annotations: None,
+            event_processor: Some(EventProcessors::START_VOTING_PERIOD),
+            cron_config: Some(CronConfig {
+                cron: None,
+                scheduled_date: Some("2025-01-01T10:00:00+00:00".to_string()),
+                time_zone: None,
+                policy,
+            }),
+            event_payload: None,
+            task_id: None,
+            status: None,
+            attempts: 0,
+            next_attempt_at: None,
+            last_error: None,
+            alerted_at: None,
+            alerted_receivers: vec![],
+        }
+    }
+
+    #[test]
+    fn test_get_failed_attempt() {
+        let now = Utc::now();
+        let mut event = scheduled_event(ScheduledEventPolicy {
+            retry_policy: Some(RetryPolicy {
+                max_retries: 2,
+                backoff_secs: 10,
+                max_backoff_secs: Some(15),
+            }),
+            ..Default::default()
+        });
+
+        let attempt = get_failed_attempt(&event, now);
+        assert_eq!(attempt.status, ScheduledEventStatus::RETRYING);
+        assert_eq!(attempt.next_attempt_at, Some(now + Duration::seconds(10)));
+
+        event.attempts = 1;
+        let attempt = get_failed_attempt(&event, now);
+        assert_eq!(attempt.attempts, 2);
+        // 20 seconds capped by max_backoff_secs
+        assert_eq!(attempt.next_attempt_at, Some(now + Duration::seconds(15)));
+
+        event.attempts = 2;
+        let attempt = get_failed_attempt(&event, now);
+        assert_eq!(attempt.status, ScheduledEventStatus::FAILED);
+        assert_eq!(attempt.next_attempt_at, None);
+    }
+
+    #[test]
+    fn test_get_failed_attempt_backoff_overflow() {
+        let now = Utc::now();
+        let mut event = scheduled_event(ScheduledEventPolicy {
+            retry_policy: Some(RetryPolicy {
+                max_retries: i32::MAX,
+                backoff_secs: i64::MAX / 2,
+                max_backoff_secs: None,
+            }),
+            ..Default::default()
+        });
+
+        for attempts in [0, 1, 10, 63, 64, 1000, i32::MAX - 1] {
+            event.attempts = attempts;
+            let attempt = get_failed_attempt(&event, now);
+            assert_eq!(attempt.status, ScheduledEventStatus::RETRYING);
+            assert_eq!(
+                attempt.next_attempt_at,
+                Some(now + Duration::seconds(MAX_BACKOFF_SECS))
+            );
+        }
+    }
+
+    #[test]
+    fn test_scheduled_date_time_zone() {
+        // spring forward in Europe/Madrid happens on 2025-03-30
+        let before = normalize_scheduled_date("2025-03-29T10:00", Some("Europe/Madrid")).unwrap();
+        let after = normalize_scheduled_date("2025-03-31T10:00", Some("Europe/Madrid")).unwrap();
+        assert_eq!(before, "2025-03-29T10:00:00+01:00");
+        assert_eq!(after, "2025-03-31T10:00:00+02:00");
+        assert_eq!(
+            normalize_scheduled_date("2025-03-31T10:00:00+00:00", None).unwrap(),
+            "2025-03-31T10:00:00+00:00"
+        );
+        assert!(normalize_scheduled_date("2025-03-31T10:00", Some("Mars/Olympus")).is_err());
+
+        let cron_config = CronConfig {
+            scheduled_date: Some("2025-03-31T10:00:00".to_string()),
+            time_zone: Some("Europe/Madrid".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_cron_config_datetime(&cron_config).map(|datetime| datetime.to_rfc3339()),
+            Some("2025-03-31T08:00:00+00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_requires_alert() {
+        let scheduled_date = get_scheduled_datetime(&scheduled_event(Default::default())).unwrap();
+        let mut event = scheduled_event(ScheduledEventPolicy {
+            alert: Some(ScheduledEventAlert {
+                template_alias: "alert".to_string(),
+                receivers: vec!["admin@example.com".to_string()],
+                grace_period_secs: 60,
+            }),
+            ..Default::default()
+        });
+
+        assert!(!requires_alert(
+            &event,
+            scheduled_date + Duration::seconds(30)
+        ));
+        assert!(requires_alert(
+            &event,
+            scheduled_date + Duration::seconds(90)
+        ));
+
+        event.status = Some(ScheduledEventStatus::FAILED);
+        assert!(requires_alert(&event, scheduled_date));
+
+        event.alerted_at = Some(scheduled_date);
+        assert!(!requires_alert(
+            &event,
+            scheduled_date + Duration::seconds(90)
+        ));
+
+        let event = scheduled_event(Default::default());
+        assert!(!requires_alert(
+            &event,
+            scheduled_date + Duration::seconds(90)
+        ));
+    }
+
+    #[test]
+    fn test_get_unmet_prerequisites() {
+        let closed_status = ElectionStatus {
+            voting_status: VotingStatus::CLOSED,
+            ..Default::default()
+        };
+        let election = |id: &str, init_report_generated: bool, status: Option<&ElectionStatus>| {
+            serde_json::from_value::<Election>(json!({
+                "id": id,
+                "tenant_id": "tenant",
+                "election_event_id": "election-event",
+                "name": id,
+                "status": status,
+                "initialization_report_generated": init_report_generated,
+                "keys_ceremony_id": "ceremony-1",
+            }))
+            .unwrap()
+        };
+        let elections = vec![
+            election("election-1", true, Some(&closed_status)),
+            election("election-2", false, None),
+        ];
+        let keys_ceremonies = vec![serde_json::from_value::<KeysCeremony>(json!({
+            "id": "ceremony-1",
+            "tenant_id": "tenant",
+            "election_event_id": "election-event",
+            "trustee_ids": [],
+            "execution_status": KeysCeremonyExecutionStatus::SUCCESS.to_string(),
+            "threshold": 2,
+        }))
+        .unwrap()];
+        let prerequisites = vec![
+            ScheduledEventPrerequisite::INIT_REPORT_GENERATED,
+            ScheduledEventPrerequisite::KEYS_CEREMONY_FINISHED,
+            ScheduledEventPrerequisite::VOTING_PERIOD_ENDED,
+        ];
+
+        assert_eq!(
+            get_unmet_prerequisites(&prerequisites, &elections[..1], &keys_ceremonies),
+            vec![]
+        );
+        assert_eq!(
+            get_unmet_prerequisites(&prerequisites, &elections, &keys_ceremonies),
+            vec![
+                ScheduledEventPrerequisite::INIT_REPORT_GENERATED,
+                ScheduledEventPrerequisite::VOTING_PERIOD_ENDED,
+            ]
+        );
+        assert_eq!(
+            get_unmet_prerequisites(&prerequisites, &elections[..1], &[]),
+            vec![ScheduledEventPrerequisite::KEYS_CEREMONY_FINISHED]
+        );
+    }
+}
@@ -0,0 +1,268 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::sms_delivery::{count_sms_delivery_by_status, upsert_sms_delivery};
+use anyhow::{anyhow, Context, Result};
+use deadpool_postgres::Transaction;
+use reqwest::Url;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::instrument;
+
+/// Delivery status of a SMS message, as reported by the provider.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SmsDeliveryStatus {
+    pub message_id: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receiver: Option<String>,
+}
+
+/// JSON pointers to the fields of the status callbacks of the generic HTTP
+/// transport.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmsStatusPointers {
+    pub message_id: String,
+    pub status: String,
+    pub error_code: String,
+}
+
+impl Default for SmsStatusPointers {
+    fn default() -> Self {
+        SmsStatusPointers {
+            message_id: "/message_id".to_string(),
+            status: "/status".to_string(),
+            error_code: "/error_code".to_string(),
+        }
+    }
+}
+
+impl SmsStatusPointers {
+    pub fn from_env() -> Self {
+        let default = SmsStatusPointers::default();
+        SmsStatusPointers {
+            message_id: std::env::var("SMS_HTTP_CALLBACK_MESSAGE_ID_POINTER")
+                .unwrap_or(default.message_id),
+            status: std::env::var("SMS_HTTP_CALLBACK_STATUS_POINTER").unwrap_or(default.status),
+            error_code: std::env::var("SMS_HTTP_CALLBACK_ERROR_CODE_POINTER")
+                .unwrap_or(default.error_code),
+        }
+    }
+}
+
+fn get_status_callback_key(secret: &str) -> hmac::Key {
+    hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes())
+}
+
+fn get_status_callback_message(tenant_id: &str, task_execution_id: &str) -> String {
+    format!("{tenant_id}:{task_execution_id}")
+}
+
+fn get_status_callback_secret() -> Result<String> {
+    std::env::var("SMS_STATUS_CALLBACK_SECRET")
+        .map_err(|_err| anyhow!("SMS_STATUS_CALLBACK_SECRET env var missing"))
+}
+
+/// Token that authenticates the status callbacks of a task execution, as
+/// providers can't use our credentials.
+pub fn compute_status_callback_token(
+    secret: &str,
+    tenant_id: &str,
+    task_execution_id: &str,
+) -> String {
+    let tag = hmac::sign(
+        &get_status_callback_key(secret),
+        get_status_callback_message(tenant_id, task_execution_id).as_bytes(),
+    );
+    hex::encode(tag.as_ref())
+}
+
+pub fn check_status_callback_token(
+    secret: &str,
+    tenant_id: &str,
+    task_execution_id: &str,
+    token: &str,
+) -> Result<()> {
+    let tag = hex::decode(token).map_err(|_err| anyhow!("Invalid status callback token"))?;
+    hmac::verify(
+        &get_status_callback_key(secret),
+        get_status_callback_message(tenant_id, task_execution_id).as_bytes(),
+        &tag,
+    )
+    .map_err(|_err| anyhow!("Invalid status callback token"))
+}
+
+#[instrument(skip(token), err)]
+pub fn verify_status_callback_token(
+    tenant_id: &str,
+    task_execution_id: &str,
+    token: &str,
+) -> Result<()> {
+    check_status_callback_token(
+        &get_status_callback_secret()?,
+        tenant_id,
+        task_execution_id,
+        token,
+    )
+}
+
+/// Url where the provider reports the delivery status of the messages sent
+/// by a task execution, or None if `SMS_STATUS_CALLBACK_URL` is not set.
+#[instrument(err)]
+pub fn get_status_callback_url(tenant_id: &str, task_execution_id: &str) -> Result<Option<String>> {
+    let Ok(base_url) = std::env::var("SMS_STATUS_CALLBACK_URL") else {
+        return Ok(None);
+    };
+    let token =
+        compute_status_callback_token(&get_status_callback_secret()?, tenant_id, task_execution_id);
+    let url = Url::parse_with_params(
+        &base_url,
+        &[
+            ("tenant_id", tenant_id),
+            ("task_execution_id", task_execution_id),
+            ("token", token.as_str()),
+        ],
+    )
+    .with_context(|| "Invalid SMS_STATUS_CALLBACK_URL")?;
+    Ok(Some(url.to_string()))
+}
+
+/// Parses the status callback of the generic HTTP transport.
+pub fn parse_json_delivery_status(
+    value: &Value,
+    pointers: &SmsStatusPointers,
+) -> Result<SmsDeliveryStatus> {
+    let get_field = |pointer: &str| match value.pointer(pointer) {
+        Some(Value::String(field)) => Some(field.clone()),
+        Some(Value::Number(field)) => Some(field.to_string()),
+        _ => None,
+    };
+    Ok(SmsDeliveryStatus {
+        message_id: get_field(&pointers.message_id)
+            .ok_or_else(|| anyhow!("Missing message id at {}", pointers.message_id))?,
+        status: get_field(&pointers.status)
+            .ok_or_else(|| anyhow!("Missing status at {}", pointers.status))?,
+        error_code: get_field(&pointers.error_code),
+        receiver: None,
+    })
+}
+
+/// Order of the delivery statuses of a message, following the statuses
+/// reported by Twilio. Statuses unknown to it, like those of the generic HTTP
+/// transport, rank as `sent`.
+pub fn get_delivery_status_rank(status: &str) -> i32 {
+    match status {
+        "accepted" | "scheduled" | "queued" => 0,
+        "sending" => 1,
+        "delivered" | "undelivered" | "failed" | "canceled" => 3,
+        "read" => 4,
+        _ => 2,
+    }
+}
+
+/// Records the delivery status of a message sent by a task execution. With
+/// `overwrite` false the status already recorded is kept, so that the status
+/// reported by a callback that arrives before the sending is recorded is not
+/// lost. Either way a status doesn't replace a later one, see
+/// `get_delivery_status_rank`.
+#[instrument(err)]
+pub async fn record_sms_delivery(
+    tenant_id: &str,
+    task_execution_id: &str,
+    delivery_status: &SmsDeliveryStatus,
+    overwrite: bool,
+) -> Result<()> {
+    upsert_sms_delivery(
+        tenant_id,
+        task_execution_id,
+        &delivery_status.message_id,
+        delivery_status.receiver.as_deref(),
+        &delivery_status.status,
+        get_delivery_status_rank(&delivery_status.status),
+        delivery_status.error_code.as_deref(),
+        overwrite,
+    )
+    .await
+}
+
+/// Number of messages sent by a task execution by delivery status.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SmsDeliverySummary {
+    pub task_execution_id: String,
+    pub total: i64,
+    pub statuses: HashMap<String, i64>,
+}
+
+#[instrument(skip(hasura_transaction), err)]
+pub async fn get_sms_delivery_summary(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    task_execution_id: &str,
+) -> Result<SmsDeliverySummary> {
+    let statuses: HashMap<String, i64> =
+        count_sms_delivery_by_status(hasura_transaction, tenant_id, task_execution_id)
+            .await?
+            .into_iter()
+            .collect();
+    Ok(SmsDeliverySummary {
+        task_execution_id: task_execution_id.to_string(),
+        total: statuses.values().sum(),
+        statuses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_status_callback_token() {
+        let token = compute_status_callback_token("secret", "tenant", "task");
+        assert!(check_status_callback_token("secret", "tenant", "task", &token).is_ok());
+        assert!(check_status_callback_token("secret", "tenant", "other-task", &token).is_err());
+        assert!(check_status_callback_token("other-secret", "tenant", "task", &token).is_err());
+        assert!(check_status_callback_token("secret", "tenant", "task", "not-hex").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_delivery_status() {
+        let pointers = SmsStatusPointers {
+            message_id: "/data/id".to_string(),
+            ..Default::default()
+        };
+        let status = parse_json_delivery_status(
+            &json!({"data": {"id": 42}, "status": "delivered"}),
+            &pointers,
+        )
+        .unwrap();
+        assert_eq!(
+            status,
+            SmsDeliveryStatus {
+                message_id: "42".to_string(),
+                status: "delivered".to_string(),
+                error_code: None,
+                receiver: None,
+            }
+        );
+
+        assert!(parse_json_delivery_status(&json!({"status": "failed"}), &pointers).is_err());
+    }
+
+    #[test]
+    fn test_get_delivery_status_rank() {
+        assert!(get_delivery_status_rank("queued") < get_delivery_status_rank("sending"));
+        assert!(get_delivery_status_rank("sending") < get_delivery_status_rank("sent"));
+        assert!(get_delivery_status_rank("sent") < get_delivery_status_rank("delivered"));
+        assert!(get_delivery_status_rank("sent") < get_delivery_status_rank("failed"));
+        assert!(get_delivery_status_rank("delivered") < get_delivery_status_rank("read"));
+        assert_eq!(
+            get_delivery_status_rank("unknown"),
+            get_delivery_status_rank("sent")
+        );
+    }
+}
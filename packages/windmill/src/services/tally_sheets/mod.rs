@@ -2,5 +2,6 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+pub mod reconciliation;
 pub mod tally;
 pub mod validation;
@@ -0,0 +1,454 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::tally_sheet::{
+    approve_tally_sheet as approve_tally_sheet_version, get_area_contest_tally_sheets,
+    get_tally_sheet_by_id,
+};
+use anyhow::{anyhow, Result};
+use deadpool_postgres::Transaction;
+use sequent_core::types::hasura::core::TallySheet;
+use sequent_core::types::tally_sheets::{AreaContestResults, VotingChannel};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use strum_macros::{Display, EnumString};
+use tracing::instrument;
+
+/// Paper tally sheets are entered twice by different operators, and both
+/// entries must match before one of them can be approved and published.
+pub fn requires_double_entry(tally_sheet: &TallySheet) -> bool {
+    VotingChannel::from(tally_sheet.channel.clone()) == VotingChannel::PAPER
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Display, EnumString, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TallySheetReconciliationStatus {
+    MATCHED,
+    MISMATCHED,
+    MISSING_ENTRY,
+    TOO_MANY_ENTRIES,
+    SAME_OPERATOR,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TallySheetFieldDiff {
+    pub field: String,
+    pub first: Option<String>,
+    pub second: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TallySheetReconciliation {
+    pub area_id: String,
+    pub contest_id: String,
+    pub channel: VotingChannel,
+    pub status: TallySheetReconciliationStatus,
+    pub tally_sheet_ids: Vec<String>,
+    pub differences: Vec<TallySheetFieldDiff>,
+}
+
+fn push_diff<T: ToString + PartialEq>(
+    differences: &mut Vec<TallySheetFieldDiff>,
+    field: &str,
+    first: Option<T>,
+    second: Option<T>,
+) {
+    if first != second {
+        differences.push(TallySheetFieldDiff {
+            field: field.to_string(),
+            first: first.map(|value| value.to_string()),
+            second: second.map(|value| value.to_string()),
+        });
+    }
+}
+
+/// Field level differences between two entries of the same tally sheet.
+pub fn diff_tally_sheet_contents(
+    first: &AreaContestResults,
+    second: &AreaContestResults,
+) -> Vec<TallySheetFieldDiff> {
+    let mut differences = vec![];
+    push_diff(
+        &mut differences,
+        "area_id",
+        Some(&first.area_id),
+        Some(&second.area_id),
+    );
+    push_diff(
+        &mut differences,
+        "contest_id",
+        Some(&first.contest_id),
+        Some(&second.contest_id),
+    );
+    push_diff(&mut differences, "census", first.census, second.census);
+    push_diff(
+        &mut differences,
+        "total_votes",
+        first.total_votes,
+        second.total_votes,
+    );
+    push_diff(
+        &mut differences,
+        "total_valid_votes",
+        first.total_valid_votes,
+        second.total_valid_votes,
+    );
+    push_diff(
+        &mut differences,
+        "total_blank_votes",
+        first.total_blank_votes,
+        second.total_blank_votes,
+    );
+
+    let first_invalid = first.invalid_votes.clone().unwrap_or_default();
+    let second_invalid = second.invalid_votes.clone().unwrap_or_default();
+    push_diff(
+        &mut differences,
+        "invalid_votes.total_invalid",
+        first_invalid.total_invalid,
+        second_invalid.total_invalid,
+    );
+    push_diff(
+        &mut differences,
+        "invalid_votes.implicit_invalid",
+        first_invalid.implicit_invalid,
+        second_invalid.implicit_invalid,
+    );
+    push_diff(
+        &mut differences,
+        "invalid_votes.explicit_invalid",
+        first_invalid.explicit_invalid,
+        second_invalid.explicit_invalid,
+    );
+
+    let candidate_ids: BTreeSet<&String> = first
+        .candidate_results
+        .keys()
+        .chain(second.candidate_results.keys())
+        .collect();
+    for candidate_id in candidate_ids {
+        push_diff(
+            &mut differences,
+            &format!("candidate_results.{candidate_id}.total_votes"),
+            first
+                .candidate_results
+                .get(candidate_id)
+                .and_then(|result| result.total_votes),
+            second
+                .candidate_results
+                .get(candidate_id)
+                .and_then(|result| result.total_votes),
+        );
+    }
+    differences
+}
+
+/// Reconciles the entries of a tally sheet for an area, contest and channel.
+/// There must be exactly two entries, created by different operators and
+/// with the same content.
+pub fn reconcile_tally_sheet_entries(
+    area_id: &str,
+    contest_id: &str,
+    channel: VotingChannel,
+    entries: &Vec<TallySheet>,
+) -> TallySheetReconciliation {
+    let mut differences = vec![];
+    let status = match entries.as_slice() {
+        [first, second] => {
+            if first.created_by_user_id == second.created_by_user_id {
+                TallySheetReconciliationStatus::SAME_OPERATOR
+            } else {
+                differences = match (&first.content, &second.content) {
+                    (Some(first), Some(second)) => diff_tally_sheet_contents(first, second),
+                    (first, second) => vec![TallySheetFieldDiff {
+                        field: "content".to_string(),
+                        first: first.as_ref().map(|_| "present".to_string()),
+                        second: second.as_ref().map(|_| "present".to_string()),
+                    }],
+                };
+                if differences.is_empty() {
+                    TallySheetReconciliationStatus::MATCHED
+                } else {
+                    TallySheetReconciliationStatus::MISMATCHED
+                }
+            }
+        }
+        entries if entries.len() < 2 => TallySheetReconciliationStatus::MISSING_ENTRY,
+        _ => TallySheetReconciliationStatus::TOO_MANY_ENTRIES,
+    };
+
+    TallySheetReconciliation {
+        area_id: area_id.to_string(),
+        contest_id: contest_id.to_string(),
+        channel,
+        status,
+        tally_sheet_ids: entries.iter().map(|entry| entry.id.clone()).collect(),
+        differences,
+    }
+}
+
+async fn get_tally_sheet_entries(
+    hasura_transaction: &Transaction<'_>,
+    tally_sheet: &TallySheet,
+) -> Result<Vec<TallySheet>> {
+    let channel = VotingChannel::from(tally_sheet.channel.clone());
+    let entries = get_area_contest_tally_sheets(
+        hasura_transaction,
+        &tally_sheet.tenant_id,
+        &tally_sheet.election_event_id,
+        &tally_sheet.area_id,
+        &tally_sheet.contest_id,
+    )
+    .await?
+    .into_iter()
+    .filter(|entry| VotingChannel::from(entry.channel.clone()) == channel)
+    .collect();
+    Ok(entries)
+}
+
+async fn get_existing_tally_sheet(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_sheet_id: &str,
+) -> Result<TallySheet> {
+    get_tally_sheet_by_id(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_sheet_id,
+    )
+    .await?
+    .ok_or_else(|| anyhow!("Tally sheet {tally_sheet_id} not found"))
+}
+
+#[instrument(skip(hasura_transaction), err)]
+pub async fn reconcile_tally_sheet(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_sheet_id: &str,
+) -> Result<TallySheetReconciliation> {
+    let tally_sheet = get_existing_tally_sheet(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_sheet_id,
+    )
+    .await?;
+    let entries = get_tally_sheet_entries(hasura_transaction, &tally_sheet).await?;
+
+    Ok(reconcile_tally_sheet_entries(
+        &tally_sheet.area_id,
+        &tally_sheet.contest_id,
+        tally_sheet.channel.clone().into(),
+        &entries,
+    ))
+}
+
+/// Approves the current version of a tally sheet. Double entry tally sheets
+/// must match their second entry, and can't be approved by any of the
+/// operators that entered them.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn approve_tally_sheet(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_sheet_id: &str,
+    user_id: &str,
+) -> Result<TallySheetReconciliation> {
+    let tally_sheet = get_existing_tally_sheet(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_sheet_id,
+    )
+    .await?;
+    let entries = get_tally_sheet_entries(hasura_transaction, &tally_sheet).await?;
+    let reconciliation = reconcile_tally_sheet_entries(
+        &tally_sheet.area_id,
+        &tally_sheet.contest_id,
+        tally_sheet.channel.clone().into(),
+        &entries,
+    );
+
+    if requires_double_entry(&tally_sheet) {
+        if reconciliation.status != TallySheetReconciliationStatus::MATCHED {
+            return Err(anyhow!(
+                "Tally sheet {tally_sheet_id} can't be approved, reconciliation status is {}",
+                reconciliation.status
+            ));
+        }
+        if entries
+            .iter()
+            .any(|entry| entry.created_by_user_id == user_id)
+        {
+            return Err(anyhow!(
+                "Tally sheet {tally_sheet_id} can't be approved by one of its operators"
+            ));
+        }
+    }
+
+    let version = tally_sheet.version.unwrap_or(1);
+    approve_tally_sheet_version(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_sheet_id,
+        version,
+        user_id,
+    )
+    .await?
+    .ok_or_else(|| {
+        anyhow!("Tally sheet {tally_sheet_id} version {version} is published or outdated")
+    })?;
+
+    Ok(reconciliation)
+}
+
+/// Checks that a tally sheet can be published: double entry tally sheets must
+/// be approved, and only one tally sheet can be published per area, contest
+/// and channel.
+#[instrument(skip(hasura_transaction), err)]
+pub async fn validate_tally_sheet_publication(
+    hasura_transaction: &Transaction<'_>,
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_sheet_id: &str,
+) -> Result<()> {
+    let tally_sheet = get_existing_tally_sheet(
+        hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_sheet_id,
+    )
+    .await?;
+    if requires_double_entry(&tally_sheet) && tally_sheet.approved_at.is_none() {
+        return Err(anyhow!(
+            "Tally sheet {tally_sheet_id} must be approved before publication"
+        ));
+    }
+    let entries = get_tally_sheet_entries(hasura_transaction, &tally_sheet).await?;
+    if let Some(published) = entries
+        .iter()
+        .find(|entry| entry.id != tally_sheet.id && entry.published_at.is_some())
+    {
+        return Err(anyhow!(
+            "Tally sheet {} is already published for this area, contest and channel",
+            published.id
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sequent_core::types::tally_sheets::{CandidateResults, InvalidVotes};
+    use std::collections::HashMap;
+
+    fn tally_sheet(id: &str, created_by_user_id: &str, candidate_votes: u64) -> TallySheet {
+        TallySheet {
+            id: id.into(),
+            tenant_id: "tenant".into(),
+            election_event_id: "election-event".into(),
+            election_id: "election".into(),
+            contest_id: "contest".into(),
+            area_id: "area".into(),
+            created_at: None,
+            last_updated_at: None,
+            labels: None,
+            annotations: None,
+            published_at: None,
+            published_by_user_id: None,
+            content: Some(AreaContestResults {
+                area_id: "area".into(),
+                contest_id: "contest".into(),
+                total_votes: Some(10),
+                total_valid_votes: Some(9),
+                invalid_votes: Some(InvalidVotes {
+                    total_invalid: Some(1),
+                    implicit_invalid: Some(0),
+                    explicit_invalid: Some(1),
+                }),
+                total_blank_votes: Some(0),
+                census: Some(20),
+                candidate_results: HashMap::from([(
+                    "candidate".to_string(),
+                    CandidateResults {
+                        candidate_id: "candidate".into(),
+                        total_votes: Some(candidate_votes),
+                    },
+                )]),
+            }),
+            channel: None,
+            deleted_at: None,
+            created_by_user_id: created_by_user_id.into(),
+            version: Some(1),
+            approved_at: None,
+            approved_by_user_id: None,
+        }
+    }
+
+    fn reconcile(entries: Vec<TallySheet>) -> TallySheetReconciliation {
+        reconcile_tally_sheet_entries("area", "contest", VotingChannel::PAPER, &entries)
+    }
+
+    #[test]
+    fn test_matching_entries() {
+        let reconciliation = reconcile(vec![
+            tally_sheet("first", "operator-1", 9),
+            tally_sheet("second", "operator-2", 9),
+        ]);
+        assert_eq!(
+            reconciliation.status,
+            TallySheetReconciliationStatus::MATCHED
+        );
+        assert!(reconciliation.differences.is_empty());
+    }
+
+    #[test]
+    fn test_mismatched_entries() {
+        let reconciliation = reconcile(vec![
+            tally_sheet("first", "operator-1", 9),
+            tally_sheet("second", "operator-2", 8),
+        ]);
+        assert_eq!(
+            reconciliation.status,
+            TallySheetReconciliationStatus::MISMATCHED
+        );
+        assert_eq!(
+            reconciliation.differences,
+            vec![TallySheetFieldDiff {
+                field: "candidate_results.candidate.total_votes".into(),
+                first: Some("9".into()),
+                second: Some("8".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_entries_need_two_operators() {
+        assert_eq!(
+            reconcile(vec![tally_sheet("first", "operator-1", 9)]).status,
+            TallySheetReconciliationStatus::MISSING_ENTRY
+        );
+        assert_eq!(
+            reconcile(vec![
+                tally_sheet("first", "operator-1", 9),
+                tally_sheet("second", "operator-1", 9),
+            ])
+            .status,
+            TallySheetReconciliationStatus::SAME_OPERATOR
+        );
+        assert_eq!(
+            reconcile(vec![
+                tally_sheet("first", "operator-1", 9),
+                tally_sheet("second", "operator-2", 9),
+                tally_sheet("third", "operator-3", 9),
+            ])
+            .status,
+            TallySheetReconciliationStatus::TOO_MANY_ENTRIES
+        );
+    }
+}
@@ -47,13 +47,24 @@ pub fn validate_tally_sheet(tally_sheet: &TallySheet, contest: &Contest) -> Resu
         .map(|candidate_result| -> u64 { candidate_result.total_votes.clone().unwrap_or(0) })
         .sum();
 
-    /*if total_valid_votes != total_valid_votes_calc + total_blank_votes {
+    // Each valid ballot has either a blank vote or at least one candidate
+    // vote. With plurality each ballot gives at most max_votes candidates one
+    // vote each, other counting algorithms like borda or cumulative give more
+    // points per ballot.
+    let max_votes = (contest.get_counting_algorithm() == "plurality-at-large")
+        .then(|| u64::try_from(contest.max_votes).unwrap_or(1).max(1));
+    let total_non_blank_votes = total_valid_votes.saturating_sub(total_blank_votes);
+    if total_blank_votes > total_valid_votes
+        || total_valid_votes_calc < total_non_blank_votes
+        || max_votes
+            .is_some_and(|max_votes| total_valid_votes_calc > total_non_blank_votes * max_votes)
+    {
         return Err(anyhow!(
             "Invalid tally sheet {:?}, inconsistent total valid votes",
             tally_sheet
         )
         .into());
-    }*/
+    }
     let candidates_map: HashMap<String, Candidate> = contest
         .candidates
         .clone()
@@ -1109,6 +1109,63 @@ pub async fn count_keycloak_enabled_users(
     Ok(user_count)
 }
 
+/// Voter marked as voted through a channel with the `voted-channel` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VotedChannelMarker {
+    pub user_id: String,
+    pub area_id: Option<String>,
+    pub channel: String,
+}
+
+/// Lists the voters of the realm with a `voted-channel` attribute, ignoring
+/// reset markers.
+#[instrument(skip(keycloak_transaction), err)]
+pub async fn list_keycloak_voted_channel_markers(
+    keycloak_transaction: &Transaction<'_>,
+    realm: &str,
+) -> Result<Vec<VotedChannelMarker>> {
+    let statement = keycloak_transaction
+        .prepare(
+            format!(
+                r#"
+                SELECT
+                    u.id AS user_id,
+                    area_attr.value AS area_id,
+                    channel_attr.value AS channel
+                FROM
+                    user_entity AS u
+                INNER JOIN
+                    realm AS ra ON ra.id = u.realm_id
+                INNER JOIN
+                    user_attribute AS channel_attr ON u.id = channel_attr.user_id AND channel_attr.name = '{VOTED_CHANNEL}'
+                LEFT JOIN
+                    user_attribute AS area_attr ON u.id = area_attr.user_id AND area_attr.name = '{AREA_ID_ATTR_NAME}'
+                WHERE
+                    ra.name = $1 AND
+                    channel_attr.value <> '{ATTR_RESET_VALUE}' AND
+                    channel_attr.value <> ''
+                "#
+            )
+            .as_str(),
+        )
+        .await?;
+
+    let rows = keycloak_transaction
+        .query(&statement, &[&realm])
+        .await
+        .map_err(|err| anyhow!("Error listing voted channel markers: {}", err))?;
+
+    rows.into_iter()
+        .map(|row| -> Result<VotedChannelMarker> {
+            Ok(VotedChannelMarker {
+                user_id: row.try_get("user_id")?,
+                area_id: row.try_get("area_id")?,
+                channel: row.try_get("channel")?,
+            })
+        })
+        .collect()
+}
+
 /// Use only for verifying application!, does not work as it seems for other situations, then use list_users instead.
 #[instrument(skip(hasura_transaction, keycloak_transaction), err)]
 pub async fn lookup_users(
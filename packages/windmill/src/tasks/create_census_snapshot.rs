@@ -0,0 +1,41 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::census_snapshot::{create_census_snapshot, CensusSnapshotTrigger};
+use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::types::error::Result;
+use celery::error::TaskError;
+use tracing::instrument;
+
+/// Freezes the census once the voting period of the election event, or of
+/// the election if given, has started.
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(max_retries = 0)]
+pub async fn create_census_snapshot_task(
+    tenant_id: String,
+    election_event_id: String,
+    election_id: Option<String>,
+) -> Result<()> {
+    provide_hasura_transaction(|hasura_transaction| {
+        let tenant_id = tenant_id.clone();
+        let election_event_id = election_event_id.clone();
+        let election_id = election_id.clone();
+        Box::pin(async move {
+            create_census_snapshot(
+                hasura_transaction,
+                &tenant_id,
+                &election_event_id,
+                election_id.as_deref(),
+                CensusSnapshotTrigger::START_VOTING_PERIOD,
+                None,
+                None,
+            )
+            .await?;
+            Ok(())
+        })
+    })
+    .await?;
+
+    Ok(())
+}
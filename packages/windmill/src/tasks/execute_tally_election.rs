@@ -0,0 +1,240 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::ceremonies::tally_checkpoints::{
+    get_tally_checkpoint_input, record_tally_checkpoint, run_tally_checkpoint,
+};
+use crate::services::database::get_hasura_pool;
+use crate::services::documents::upload_and_return_document;
+use crate::services::pg_lock::PgLock;
+use crate::services::tasks_semaphore::acquire_semaphore;
+use crate::types::error::{Error, Result};
+use anyhow::{Context, Result as AnyhowResult};
+use celery::error::TaskError;
+use chrono::Duration;
+use deadpool_postgres::Client as DbClient;
+use sequent_core::services::date::ISO8601;
+use tokio::time::Duration as ChronoDuration;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+/// Marks the checkpoint of an election as failed, so that the tally session
+/// retries it.
+#[instrument(err)]
+async fn record_tally_election_error(
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    election_id: &str,
+    input_document_id: &str,
+    error: String,
+) -> AnyhowResult<()> {
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .with_context(|| "Error acquiring hasura connection pool")?;
+    let hasura_transaction = hasura_db_client
+        .transaction()
+        .await
+        .with_context(|| "Error acquiring hasura transaction")?;
+
+    record_tally_checkpoint(
+        &hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+        election_id,
+        input_document_id,
+        None,
+        Some(error),
+    )
+    .await?;
+
+    hasura_transaction
+        .commit()
+        .await
+        .with_context(|| "error comitting transaction")?;
+    Ok(())
+}
+
+/// Uploads the velvet output of an election and marks its checkpoint as
+/// successful.
+#[instrument(err)]
+async fn store_tally_election_output(
+    tenant_id: &str,
+    election_event_id: &str,
+    tally_session_id: &str,
+    election_id: &str,
+    input_document_id: &str,
+    output_path: &str,
+    output_size: u64,
+) -> AnyhowResult<()> {
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .with_context(|| "Error acquiring hasura connection pool")?;
+    let hasura_transaction = hasura_db_client
+        .transaction()
+        .await
+        .with_context(|| "Error acquiring hasura transaction")?;
+
+    let output_document = upload_and_return_document(
+        &hasura_transaction,
+        output_path,
+        output_size,
+        "application/gzip",
+        tenant_id,
+        Some(election_event_id.to_string()),
+        &format!("tally-checkpoint-output-{}.tar.gz", election_id),
+        None,
+        false,
+    )
+    .await?;
+
+    record_tally_checkpoint(
+        &hasura_transaction,
+        tenant_id,
+        election_event_id,
+        tally_session_id,
+        election_id,
+        input_document_id,
+        Some(output_document.id.clone()),
+        None,
+    )
+    .await?;
+
+    hasura_transaction
+        .commit()
+        .await
+        .with_context(|| "error comitting transaction")?;
+    Ok(())
+}
+
+#[instrument(err)]
+async fn execute_tally_election_impl(
+    tenant_id: String,
+    election_event_id: String,
+    tally_session_id: String,
+    election_id: String,
+    input_document_id: String,
+) -> AnyhowResult<()> {
+    let input_archive = {
+        let mut hasura_db_client: DbClient = get_hasura_pool()
+            .await
+            .get()
+            .await
+            .with_context(|| "Error acquiring hasura connection pool")?;
+        let hasura_transaction = hasura_db_client
+            .transaction()
+            .await
+            .with_context(|| "Error acquiring hasura transaction")?;
+        let input_archive = get_tally_checkpoint_input(
+            &hasura_transaction,
+            &tenant_id,
+            &election_event_id,
+            &tally_session_id,
+            &election_id,
+            &input_document_id,
+        )
+        .await?;
+        hasura_transaction
+            .commit()
+            .await
+            .with_context(|| "error comitting transaction")?;
+        input_archive
+    };
+
+    let Some(input_archive) = input_archive else {
+        info!(
+            "Skipping: checkpoint of election {} is no longer pending",
+            election_id
+        );
+        return Ok(());
+    };
+
+    let result = match run_tally_checkpoint(input_archive.path()).await {
+        Ok((_output_temp_path, output_path, output_size)) => {
+            store_tally_election_output(
+                &tenant_id,
+                &election_event_id,
+                &tally_session_id,
+                &election_id,
+                &input_document_id,
+                &output_path,
+                output_size,
+            )
+            .await
+        }
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = result {
+        record_tally_election_error(
+            &tenant_id,
+            &election_event_id,
+            &tally_session_id,
+            &election_id,
+            &input_document_id,
+            err.to_string(),
+        )
+        .await?;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Tallies a single election of a tally session. Dispatched by
+/// `execute_tally_session`, which merges the election outputs once all of
+/// them are ready.
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(time_limit = 1200000, max_retries = 0)]
+pub async fn execute_tally_election(
+    tenant_id: String,
+    election_event_id: String,
+    tally_session_id: String,
+    election_id: String,
+    input_document_id: String,
+) -> Result<()> {
+    let _permit = acquire_semaphore().await?;
+    let Ok(lock) = PgLock::acquire(
+        format!(
+            "execute_tally_election-{}-{}-{}-{}",
+            tenant_id, election_event_id, tally_session_id, election_id
+        ),
+        Uuid::new_v4().to_string(),
+        ISO8601::now() + Duration::seconds(120),
+    )
+    .await
+    else {
+        info!(
+            "Skipping: tally in progress for session id {} and election {}",
+            tally_session_id, election_id
+        );
+        return Ok(());
+    };
+    let mut interval = tokio::time::interval(ChronoDuration::from_secs(30));
+    let mut current_task = tokio::spawn(execute_tally_election_impl(
+        tenant_id.clone(),
+        election_event_id.clone(),
+        tally_session_id.clone(),
+        election_id.clone(),
+        input_document_id.clone(),
+    ));
+    let res = loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                lock.update_expiry().await?;
+            }
+            res = &mut current_task => {
+                break res
+                    .map_err(|err| Error::String(format!("Error executing loop: {:?}", err)))
+                    .and_then(|res| res.map_err(Error::from));
+            }
+        }
+    };
+    lock.release().await?;
+    res
+}
@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::ceremonies::recount::execute_tally_recount;
+use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::services::tasks_execution::*;
+use crate::types::error::Result;
+use celery::error::TaskError;
+use sequent_core::types::ceremonies::RecountOverrides;
+use sequent_core::types::hasura::core::TasksExecution;
+use tracing::instrument;
+
+/// Recounts a completed tally session from its frozen snapshot. The
+/// comparison report with the original results is uploaded as `document_id`.
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(time_limit = 1200000, max_retries = 0)]
+pub async fn execute_tally_recount_task(
+    tenant_id: String,
+    election_event_id: String,
+    tally_session_id: String,
+    overrides: RecountOverrides,
+    document_id: String,
+    task_execution: TasksExecution,
+    user_id: String,
+    username: Option<String>,
+) -> Result<()> {
+    let result = provide_hasura_transaction(|hasura_transaction| {
+        let document_copy = document_id.clone();
+        let overrides_copy = overrides.clone();
+        let user_id = user_id.clone();
+        let username = username.clone();
+        Box::pin(async move {
+            execute_tally_recount(
+                hasura_transaction,
+                &tenant_id,
+                &election_event_id,
+                &tally_session_id,
+                &overrides_copy,
+                &document_copy,
+                &user_id,
+                username,
+            )
+            .await
+            .map(|_| ())
+        })
+    })
+    .await;
+
+    match result {
+        Ok(_) => {
+            let _res = update_complete(&task_execution, Some(document_id.clone())).await;
+            Ok(())
+        }
+        Err(err) => {
+            let err_str = format!("Error recounting tally session: {err:?}");
+            let _res = update_fail(&task_execution, &err.to_string()).await;
+            Err(err_str.into())
+        }
+    }
+}
@@ -15,10 +15,12 @@ use crate::postgres::tally_session_execution::insert_tally_session_execution;
 use crate::postgres::tally_sheet::get_published_tally_sheets_by_event;
 use crate::postgres::template::get_template_by_alias;
 use crate::services::cast_votes::{count_cast_votes_election, ElectionCastVotes};
+use crate::services::ceremonies::channel_reconciliation::generate_channel_reconciliation_report;
 use crate::services::ceremonies::insert_ballots::{
     get_elections_end_dates, insert_ballots_messages,
 };
 use crate::services::ceremonies::keys_ceremony::get_keys_ceremony_board;
+use crate::services::ceremonies::recount::freeze_tally_snapshot;
 use crate::services::ceremonies::results::populate_results_tables;
 use crate::services::ceremonies::serialize_logs::{
     append_tally_finished, append_tally_updated, generate_logs, print_messages, sort_logs,
@@ -27,6 +29,7 @@ use crate::services::ceremonies::tally_ceremony::find_last_tally_session_executi
 use crate::services::ceremonies::tally_ceremony::{
     get_tally_ceremony_status, set_tally_session_completed,
 };
+use crate::services::ceremonies::tally_checkpoints::run_checkpointed_velvet_tally;
 use crate::services::ceremonies::tally_progress::generate_tally_progress;
 use crate::services::ceremonies::tally_session_error::handle_tally_session_error;
 use crate::services::ceremonies::velvet_tally::run_velvet_tally;
@@ -42,6 +45,7 @@ use crate::services::reports::template_renderer::{
     ReportOriginatedFrom, ReportOrigins, TemplateRenderer,
 };
 use crate::services::reports::utils::get_public_asset_template;
+use crate::services::tally_sheets::reconciliation::requires_double_entry;
 use crate::services::tally_sheets::validation::validate_tally_sheet;
 use crate::services::tasks_semaphore::acquire_semaphore;
 use crate::services::temp_path::{
@@ -69,11 +73,13 @@ use sequent_core::services::area_tree::TreeNodeArea;
 use sequent_core::services::date::ISO8601;
 use sequent_core::services::keycloak::get_event_realm;
 use sequent_core::types::ceremonies::TallyExecutionStatus;
+use sequent_core::types::ceremonies::TallySessionDocuments;
 use sequent_core::types::ceremonies::TallyTrusteeStatus;
 use sequent_core::types::ceremonies::TallyType;
 use sequent_core::types::ceremonies::{CeremoniesPolicy, TallyCeremonyStatus};
 use sequent_core::types::hasura::core::Area;
 use sequent_core::types::hasura::core::BallotStyle as BallotStyleHasura;
+use sequent_core::types::hasura::core::Contest as ContestHasura;
 use sequent_core::types::hasura::core::ElectionEvent;
 use sequent_core::types::hasura::core::KeysCeremony;
 use sequent_core::types::hasura::core::TallySession;
@@ -113,16 +119,13 @@ fn get_ballot_styles(ballot_styles: &Vec<BallotStyleHasura>) -> Result<Vec<Ballo
 }
 
 #[instrument(skip_all, err)]
-async fn generate_area_contests_mc(
-    hasura_transaction: &Transaction<'_>,
-    relevant_plaintexts: &Vec<&Message>,
+fn generate_area_contests_mc(
+    plaintexts_by_batch: &PlaintextsByBatch,
     ballot_styles: &Vec<BallotStyle>,
     tally_session_contest: &Vec<TallySessionContest>,
     areas: &Vec<Area>,
-    tenant_id: &str,
-    election_event_id: &str,
+    all_contests: &Vec<ContestHasura>,
 ) -> AnyhowResult<Vec<AreaContestDataType>> {
-    let all_contests = export_contests(hasura_transaction, tenant_id, election_event_id).await?;
     let areas_map: HashMap<String, Area> = areas
         .clone()
         .into_iter()
@@ -171,24 +174,7 @@ async fn generate_area_contests_mc(
 
             let plaintexts = if 0 == i {
                 let batch_num: i64 = session_election.session_id as i64;
-                let Some(plaintexts) = relevant_plaintexts
-                    .iter()
-                    .find(|plaintexts_message| {
-                        batch_num == plaintexts_message.statement.get_batch_number() as i64
-                    })
-                    .map(|plaintexts_message| {
-                        plaintexts_message
-                            .artifact
-                            .clone()
-                            .map(|artifact| -> Option<Vec<<RistrettoCtx as Ctx>::P>> {
-                                Plaintexts::<RistrettoCtx>::strand_deserialize(&artifact)
-                                    .ok()
-                                    .map(|plaintexts| plaintexts.0 .0)
-                            })
-                            .flatten()
-                    })
-                    .flatten()
-                else {
+                let Some(plaintexts) = plaintexts_by_batch.get(&batch_num).cloned() else {
                     event!(Level::INFO, "Expected: Plaintexts not found yet for session contest = {}, batch number = {}", session_election.id, batch_num );
                     continue;
                 };
@@ -238,7 +224,7 @@ async fn generate_area_contests_mc(
 
 #[instrument(skip_all, err)]
 fn generate_area_contests(
-    relevant_plaintexts: &Vec<&Message>,
+    plaintexts_by_batch: &PlaintextsByBatch,
     ballot_styles: &Vec<BallotStyle>,
     tally_session_contest: &Vec<TallySessionContest>,
     areas: &Vec<Area>,
@@ -280,22 +266,7 @@ fn generate_area_contests(
                 };
 
             let batch_num: i64 = session_contest.session_id as i64;
-            let Some(plaintexts) = relevant_plaintexts
-                .iter()
-                .find(|plaintexts_message|
-                    batch_num == plaintexts_message.statement.get_batch_number() as i64
-                )
-                .map(|plaintexts_message| {
-                    plaintexts_message.artifact
-                        .clone()
-                        .map(|artifact| -> Option<Vec<<RistrettoCtx as Ctx>::P>> {
-                            Plaintexts::<RistrettoCtx>::strand_deserialize(&artifact)
-                                .ok()
-                                .map(|plaintexts| plaintexts.0 .0)
-                        })
-                        .flatten()
-                })
-                .flatten() else {
+            let Some(plaintexts) = plaintexts_by_batch.get(&batch_num).cloned() else {
                     event!(Level::INFO, "Expected: Plaintexts not found yet for session contest = {}, batch number = {}", session_contest.id, batch_num );
                     return None;
                 };
@@ -342,6 +313,57 @@ async fn process_plaintexts(
     tenant_id: &str,
     election_event_id: &str,
     contest_encryption_policy: ContestEncryptionPolicy,
+) -> Result<Vec<AreaContestDataType>> {
+    let all_contests = match contest_encryption_policy {
+        ContestEncryptionPolicy::MULTIPLE_CONTESTS => {
+            export_contests(hasura_transaction, tenant_id, election_event_id).await?
+        }
+        ContestEncryptionPolicy::SINGLE_CONTEST => vec![],
+    };
+    build_area_contests(
+        &get_plaintexts_by_batch(&relevant_plaintexts),
+        ballot_styles,
+        tally_session_contest,
+        areas,
+        &all_contests,
+        contest_encryption_policy,
+    )
+}
+
+/// Decrypted plaintexts, by batch number.
+pub type PlaintextsByBatch = HashMap<i64, Vec<<RistrettoCtx as Ctx>::P>>;
+
+/// Reads the plaintexts of the board messages. Only the first message of each
+/// batch is taken into account.
+pub fn get_plaintexts_by_batch(relevant_plaintexts: &Vec<&Message>) -> PlaintextsByBatch {
+    let mut plaintexts_by_batch: PlaintextsByBatch = HashMap::new();
+    for plaintexts_message in relevant_plaintexts {
+        let batch_num = plaintexts_message.statement.get_batch_number() as i64;
+        if plaintexts_by_batch.contains_key(&batch_num) {
+            continue;
+        }
+        let plaintexts = plaintexts_message
+            .artifact
+            .as_ref()
+            .and_then(|artifact| Plaintexts::<RistrettoCtx>::strand_deserialize(artifact).ok());
+        if let Some(plaintexts) = plaintexts {
+            plaintexts_by_batch.insert(batch_num, plaintexts.0 .0);
+        }
+    }
+    plaintexts_by_batch
+}
+
+/// Matches the plaintexts with the ballot styles and areas of each tally
+/// session contest, giving the input of the velvet tally. `all_contests` is
+/// only used with multiple contests encryption.
+#[instrument(skip_all, err)]
+pub fn build_area_contests(
+    plaintexts_by_batch: &PlaintextsByBatch,
+    ballot_styles: Vec<BallotStyle>,
+    tally_session_contest: Vec<TallySessionContest>,
+    areas: &Vec<Area>,
+    all_contests: &Vec<ContestHasura>,
+    contest_encryption_policy: ContestEncryptionPolicy,
 ) -> Result<Vec<AreaContestDataType>> {
     event!(
         Level::WARN,
@@ -349,20 +371,15 @@ async fn process_plaintexts(
         &tally_session_contest.len()
     );
     let almost_vec = match contest_encryption_policy {
-        ContestEncryptionPolicy::MULTIPLE_CONTESTS => {
-            generate_area_contests_mc(
-                hasura_transaction,
-                &relevant_plaintexts,
-                &ballot_styles,
-                &tally_session_contest,
-                areas,
-                tenant_id,
-                election_event_id,
-            )
-            .await?
-        }
+        ContestEncryptionPolicy::MULTIPLE_CONTESTS => generate_area_contests_mc(
+            plaintexts_by_batch,
+            &ballot_styles,
+            &tally_session_contest,
+            areas,
+            all_contests,
+        )?,
         ContestEncryptionPolicy::SINGLE_CONTEST => generate_area_contests(
-            &relevant_plaintexts,
+            plaintexts_by_batch,
             &ballot_styles,
             &tally_session_contest,
             areas,
@@ -600,6 +617,13 @@ pub fn clean_tally_sheets(
                     anyhow!("Invalid tally sheet {:?}, can't find contest", tally_sheet).into(),
                 );
             };
+            if requires_double_entry(tally_sheet) && tally_sheet.approved_at.is_none() {
+                return Err(anyhow!(
+                    "Invalid tally sheet {:?}, double entry not approved",
+                    tally_sheet
+                )
+                .into());
+            }
             validate_tally_sheet(tally_sheet, &contest)?;
 
             Ok(tally_sheet.clone())
@@ -1083,7 +1107,34 @@ pub async fn execute_tally_session_wrapped(
     let areas: Vec<Area> =
         get_event_areas(hasura_transaction, &tenant_id, &election_event_id).await?;
 
-    let status = if !plaintexts_data.is_empty() {
+    let status = if plaintexts_data.is_empty() {
+        None
+    } else if is_execution_completed {
+        // the final tally runs one subtask per election, resuming from the
+        // elections already tallied
+        let (state, checkpoints) = run_checkpointed_velvet_tally(
+            base_tempdir.path().to_path_buf(),
+            &plaintexts_data,
+            &cast_votes_count,
+            &tally_sheets,
+            report_content_template,
+            report_system_template,
+            pdf_options,
+            &areas,
+            hasura_transaction,
+            &election_event,
+            &tally_session,
+            tally_type_enum.clone(),
+            &mut new_status.elections_status,
+        )
+        .await?;
+        new_status.checkpoints = checkpoints;
+        let Some(state) = state else {
+            event!(Level::INFO, "Waiting for the election tallies to finish");
+            return Ok(());
+        };
+        Some(state)
+    } else {
         Some(
             run_velvet_tally(
                 base_tempdir.path().to_path_buf(),
@@ -1101,6 +1152,33 @@ pub async fn execute_tally_session_wrapped(
             )
             .await?,
         )
+    };
+
+    if status.is_some() {
+        generate_channel_reconciliation_report(
+            hasura_transaction,
+            keycloak_transaction,
+            &tenant_id,
+            &election_event_id,
+            &tally_session_contests,
+            &tally_sheets,
+            base_tempdir.path(),
+        )
+        .await?;
+    }
+
+    // freeze the input of the final tally so that it can be recounted
+    let snapshot_document_id = if is_execution_completed && status.is_some() {
+        Some(
+            freeze_tally_snapshot(
+                hasura_transaction,
+                &tenant_id,
+                &election_event_id,
+                &tally_session_id,
+                base_tempdir.path(),
+            )
+            .await?,
+        )
     } else {
         None
     };
@@ -1121,6 +1199,13 @@ pub async fn execute_tally_session_wrapped(
         plaintexts_data.is_empty(), // &tally_session,
     )
     .await?;
+    let tally_session_execution_documents = match snapshot_document_id {
+        Some(snapshot_document_id) => Some(TallySessionDocuments {
+            snapshot: Some(snapshot_document_id),
+            ..tally_session_execution_documents.unwrap_or_default()
+        }),
+        None => tally_session_execution_documents,
+    };
     // map_plaintext_data also calls this but at this point the credentials
     // could be expired
 
@@ -1,8 +1,10 @@
 // SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
 //
 // SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::export::eml_results_generator::EMLResultsMessage;
 use crate::services::export::export_tally_results::{
-    export_tally_results_to_xlsx, get_tally_session_execution_results_sqlite_file,
+    export_tally_results_to_cdf, export_tally_results_to_eml, export_tally_results_to_xlsx,
+    get_tally_session_execution_results_sqlite_file,
 };
 use crate::services::providers::transactions_provider::provide_hasura_transaction;
 use crate::services::tasks_execution::*;
@@ -59,3 +61,87 @@ pub async fn export_tally_results_to_xlsx_task(
         }
     }
 }
+
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(max_retries = 0)]
+pub async fn export_tally_results_to_cdf_task(
+    tenant_id: String,
+    election_event_id: String,
+    tally_session_id: String,
+    document_id: String,
+    task_execution: TasksExecution,
+) -> Result<()> {
+    let result = provide_hasura_transaction(|hasura_transaction| {
+        let tenant_id = tenant_id.clone();
+        let election_event_id = election_event_id.clone();
+        let tally_session_id = tally_session_id.clone();
+        let document_id = document_id.clone();
+        Box::pin(async move {
+            export_tally_results_to_cdf(
+                hasura_transaction,
+                &tenant_id,
+                &election_event_id,
+                &tally_session_id,
+                &document_id,
+            )
+            .await
+        })
+    })
+    .await;
+
+    match result {
+        Ok(_) => {
+            let _res = update_complete(&task_execution, Some(document_id.clone())).await;
+            Ok(())
+        }
+        Err(err) => {
+            let err_str = format!("Error exporting tally results to CDF: {err:?}");
+            let _res = update_fail(&task_execution, &err.to_string()).await;
+            Err(err_str.into())
+        }
+    }
+}
+
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(max_retries = 0)]
+pub async fn export_tally_results_to_eml_task(
+    tenant_id: String,
+    election_event_id: String,
+    tally_session_id: String,
+    message: EMLResultsMessage,
+    document_id: String,
+    task_execution: TasksExecution,
+) -> Result<()> {
+    let result = provide_hasura_transaction(|hasura_transaction| {
+        let tenant_id = tenant_id.clone();
+        let election_event_id = election_event_id.clone();
+        let tally_session_id = tally_session_id.clone();
+        let document_id = document_id.clone();
+        Box::pin(async move {
+            export_tally_results_to_eml(
+                hasura_transaction,
+                &tenant_id,
+                &election_event_id,
+                &tally_session_id,
+                message,
+                &document_id,
+            )
+            .await
+        })
+    })
+    .await;
+
+    match result {
+        Ok(_) => {
+            let _res = update_complete(&task_execution, Some(document_id.clone())).await;
+            Ok(())
+        }
+        Err(err) => {
+            let err_str = format!("Error exporting tally results to EML: {err:?}");
+            let _res = update_fail(&task_execution, &err.to_string()).await;
+            Err(err_str.into())
+        }
+    }
+}
@@ -230,7 +230,10 @@ fn get_political_party_extension(political_party: &str) -> String {
 }
 
 #[instrument(ret, err, skip(contests))]
-fn get_contest_from_postcode(contests: &Vec<Contest>, postcode: &str) -> Result<Option<String>> {
+pub(crate) fn get_contest_from_postcode(
+    contests: &Vec<Contest>,
+    postcode: &str,
+) -> Result<Option<String>> {
     // Mapping of postcodes to contest names
     let contest_map = vec![
         ("1", "PRESIDENT"),
@@ -5,9 +5,11 @@
 use crate::postgres::election::get_election_by_id;
 use crate::postgres::election_event::get_election_event_by_id;
 use crate::postgres::scheduled_event::*;
+use crate::services::census_snapshot::enqueue_census_snapshot;
 use crate::services::database::get_hasura_pool;
 use crate::services::pg_lock::PgLock;
-use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::services::provisional_results::enqueue_provisional_results_update;
+use crate::services::scheduled_events::record_scheduled_event_task_failure;
 use crate::services::voting_status::{self};
 use crate::types::error::{Error, Result};
 use anyhow::{anyhow, Context, Result as AnyhowResult};
@@ -24,6 +26,7 @@ use tracing::instrument;
 use tracing::{error, event, info, Level};
 use uuid::Uuid;
 
+/// Returns the voting status the election was set to, if any.
 #[instrument(err)]
 async fn manage_election_date_wrapper(
     hasura_transaction: &Transaction<'_>,
@@ -31,7 +34,7 @@ async fn manage_election_date_wrapper(
     election_event_id: String,
     scheduled_event_id: String,
     election_id: String,
-) -> AnyhowResult<()> {
+) -> AnyhowResult<Option<VotingStatus>> {
     let scheduled_manage_date_opt = find_scheduled_event_by_id(
         hasura_transaction,
         Some(tenant_id.clone()),
@@ -71,7 +74,7 @@ async fn manage_election_date_wrapper(
             info!("Invalid scheduled event type: {:?}", event_processor);
             stop_scheduled_event(&hasura_transaction, &tenant_id, &scheduled_manage_date.id)
                 .await?;
-            return Ok(());
+            return Ok(None);
         }
     };
 
@@ -84,7 +87,7 @@ async fn manage_election_date_wrapper(
             info!("Invalid scheduled event type: {:?}", event_processor);
             stop_scheduled_event(&hasura_transaction, &tenant_id, &scheduled_manage_date.id)
                 .await?;
-            return Ok(());
+            return Ok(None);
         }
     };
 
@@ -107,7 +110,7 @@ async fn manage_election_date_wrapper(
 
     result?;
 
-    Ok(())
+    Ok(Some(status))
 }
 
 #[instrument(err)]
@@ -130,30 +133,71 @@ pub async fn manage_election_date(
     .await
     .with_context(|| "Error acquiring pglock")?;
 
-    let res = provide_hasura_transaction(|hasura_transaction| {
-        let tenant_id = tenant_id.clone();
-        let election_event_id = election_event_id.clone();
-        let scheduled_event_id = scheduled_event_id.clone();
-        let election_id = election_id.clone();
-        Box::pin(async move {
-            // Your async code here
-            manage_election_date_wrapper(
-                hasura_transaction,
-                tenant_id,
-                election_event_id,
-                scheduled_event_id,
-                election_id,
-            )
+    let mut hasura_db_client: DbClient = get_hasura_pool()
+        .await
+        .get()
+        .await
+        .map_err(|e| anyhow!("Error getting hasura client {}", e))?;
+    let hasura_transaction = hasura_db_client.transaction().await?;
+    let res = match manage_election_date_wrapper(
+        &hasura_transaction,
+        tenant_id.clone(),
+        election_event_id.clone(),
+        scheduled_event_id.clone(),
+        election_id.clone(),
+    )
+    .await
+    {
+        Ok(status) => hasura_transaction
+            .commit()
             .await
-        })
-    })
-    .await;
+            .map(|_| status)
+            .map_err(|e| anyhow!("Commit failed manage_election_dates: {}", e)),
+        Err(err) => {
+            if let Err(rollback_err) = hasura_transaction.rollback().await {
+                error!("Rollback error after transaction error: {rollback_err:?}");
+            }
+            Err(err)
+        }
+    };
 
     info!("result: {:?}", res);
 
+    match &res {
+        Err(err) => {
+            if let Err(record_err) = record_scheduled_event_task_failure(
+                &tenant_id,
+                &election_event_id,
+                &scheduled_event_id,
+                &err.to_string(),
+            )
+            .await
+            {
+                error!("Error recording scheduled event failure: {record_err:?}");
+            }
+        }
+        Ok(status) => {
+            if let Err(err) =
+                enqueue_provisional_results_update(&tenant_id, &election_event_id).await
+            {
+                error!("Error enqueuing provisional results update: {err:?}");
+            }
+            // Freeze the census the voting period starts with
+            if *status == Some(VotingStatus::OPEN) {
+                if let Err(err) =
+                    enqueue_census_snapshot(&tenant_id, &election_event_id, Some(&election_id))
+                        .await
+                {
+                    error!("Error enqueuing census snapshot: {err:?}");
+                }
+            }
+        }
+    }
+
     lock.release()
         .await
         .with_context(|| "Error releasing pglock")?;
 
-    Ok(res?)
+    res?;
+    Ok(())
 }
@@ -3,9 +3,12 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 use crate::postgres::scheduled_event::*;
+use crate::services::census_snapshot::enqueue_census_snapshot;
 use crate::services::database::get_hasura_pool;
 use crate::services::election_event_status::update_event_voting_status;
 use crate::services::pg_lock::PgLock;
+use crate::services::provisional_results::enqueue_provisional_results_update;
+use crate::services::scheduled_events::record_scheduled_event_task_failure;
 use crate::types::error::{Error, Result};
 use anyhow::{anyhow, Result as AnyhowResult};
 use celery::error::TaskError;
@@ -20,13 +23,14 @@ use tracing::instrument;
 use tracing::{event, info, Level};
 use uuid::Uuid;
 
+/// Returns the voting status the election event was set to, if any.
 #[instrument(err)]
 pub async fn manage_election_event_date_wrapped(
     hasura_transaction: &Transaction<'_>,
     tenant_id: String,
     election_event_id: String,
     scheduled_event_id: String,
-) -> AnyhowResult<()> {
+) -> AnyhowResult<Option<VotingStatus>> {
     let scheduled_manage_date_opt = find_scheduled_event_by_id(
         hasura_transaction,
         Some(tenant_id.clone()),
@@ -51,7 +55,7 @@ pub async fn manage_election_event_date_wrapped(
             info!("Invalid scheduled event type: {:?}", event_processor);
             stop_scheduled_event(&hasura_transaction, &tenant_id, &scheduled_manage_date.id)
                 .await?;
-            return Ok(());
+            return Ok(None);
         }
     };
     update_event_voting_status(
@@ -67,7 +71,7 @@ pub async fn manage_election_event_date_wrapped(
 
     stop_scheduled_event(&hasura_transaction, &tenant_id, &scheduled_manage_date.id).await?;
 
-    Ok(())
+    Ok(Some(voting_status))
 }
 
 #[instrument(err)]
@@ -102,16 +106,46 @@ pub async fn manage_election_event_date(
     .await;
 
     match res {
-        Ok(data) => {
+        Ok(voting_status) => {
             let commit = hasura_transaction
                 .commit()
                 .await
                 .map_err(|e| anyhow!("Commit failed manage_event_election_dates: {}", e));
             lock.release().await?;
             commit?;
+
+            if let Err(err) =
+                enqueue_provisional_results_update(&tenant_id, &election_event_id).await
+            {
+                event!(
+                    Level::ERROR,
+                    "Error enqueuing provisional results update: {err:?}"
+                );
+            }
+            // Freeze the census the voting period starts with
+            if voting_status == Some(VotingStatus::OPEN) {
+                if let Err(err) =
+                    enqueue_census_snapshot(&tenant_id, &election_event_id, None).await
+                {
+                    event!(Level::ERROR, "Error enqueuing census snapshot: {err:?}");
+                }
+            }
         }
         Err(err) => {
             let rollback = hasura_transaction.rollback().await;
+            if let Err(record_err) = record_scheduled_event_task_failure(
+                &tenant_id,
+                &election_event_id,
+                &scheduled_event_id,
+                &err.to_string(),
+            )
+            .await
+            {
+                event!(
+                    Level::ERROR,
+                    "Error recording scheduled event failure: {record_err:?}"
+                );
+            }
             lock.release().await?;
             rollback?;
             return Err(anyhow!("{}", err).into());
@@ -8,6 +8,7 @@ use crate::postgres::scheduled_event::*;
 use crate::services::database::get_hasura_pool;
 use crate::services::pg_lock::PgLock;
 use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::services::scheduled_events::record_scheduled_event_task_failure;
 use crate::services::voting_status::{self};
 use crate::types::error::{Error, Result};
 use anyhow::{anyhow, Context, Result as AnyhowResult};
@@ -130,5 +131,18 @@ pub async fn manage_election_init_report(
 
     info!("result: {:?}", res);
 
+    if let Err(ref err) = res {
+        if let Err(record_err) = record_scheduled_event_task_failure(
+            &tenant_id,
+            &election_event_id,
+            &scheduled_event_id,
+            &err.to_string(),
+        )
+        .await
+        {
+            error!("Error recording scheduled event failure: {record_err:?}");
+        }
+    }
+
     Ok(res?)
 }
@@ -4,9 +4,12 @@
 
 pub mod activity_logs_report;
 pub mod create_ballot_receipt;
+pub mod create_census_snapshot;
 pub mod create_keys;
 pub mod delete_election_event;
 pub mod electoral_log;
+pub mod execute_tally_election;
+pub mod execute_tally_recount;
 pub mod execute_tally_session;
 pub mod export_application;
 pub mod export_ballot_publication;
@@ -40,12 +43,16 @@ pub mod miru_plugin_tasks;
 pub mod post_tally;
 pub mod prepare_publication_preview;
 pub mod process_board;
+pub mod publish_provisional_results;
 pub mod render_document_pdf;
 pub mod render_report;
 pub mod review_boards;
+pub mod scheduled_event_alert;
 pub mod scheduled_events;
 pub mod scheduled_reports;
 pub mod send_template;
 pub mod set_public_key;
+pub mod sync_census;
 pub mod update_election_event_ballot_styles;
 pub mod upsert_areas;
+pub mod validate_import;
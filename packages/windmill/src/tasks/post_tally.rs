@@ -11,7 +11,7 @@ use deadpool_postgres::{Client as DbClient, Transaction as DbTransaction};
 use sequent_core::services::date::ISO8601;
 use sequent_core::temp_path::get_file_size;
 use sequent_core::types::hasura::core::TallySessionExecution;
-use tracing::{info, instrument};
+use tracing::{error, info, instrument};
 use uuid::Uuid;
 
 use crate::postgres::tally_session_execution::get_last_tally_session_execution;
@@ -38,6 +38,7 @@ use tokio::task;
 use walkdir::WalkDir;
 
 use crate::services::pg_lock::PgLock;
+use crate::services::provisional_results::enqueue_provisional_results_update;
 use crate::services::tasks_semaphore::acquire_semaphore;
 use chrono::Duration;
 
@@ -335,7 +336,7 @@ pub async fn post_tally_task_impl(
 
     let updated_documents = TallySessionDocuments {
         sqlite: Some(database_document_id.to_string()),
-        xlsx: previous_tally_session_documents.xlsx.clone(),
+        ..previous_tally_session_documents
     };
 
     let updated_status = serde_json::from_value(
@@ -371,6 +372,10 @@ pub async fn post_tally_task_impl(
         .await
         .with_context(|| "error comitting transaction")?;
 
+    if let Err(err) = enqueue_provisional_results_update(&tenant_id, &election_event_id).await {
+        error!("Error enqueuing provisional results update: {err:?}");
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,23 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::provisional_results::run_provisional_results_update;
+use crate::types::error::Result;
+use celery::error::TaskError;
+use tracing::instrument;
+
+/// Updates the provisional results snapshot of the election event in the
+/// public bucket. Sent by `enqueue_provisional_results_update`, which holds
+/// the queued update lock with `queued_lock_value`.
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(max_retries = 0)]
+pub async fn publish_provisional_results(
+    tenant_id: String,
+    election_event_id: String,
+    queued_lock_value: String,
+) -> Result<()> {
+    run_provisional_results_update(&tenant_id, &election_event_id, &queued_lock_value).await?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::services::pg_lock::PgLock;
+use crate::services::providers::email_sender::EmailSender;
+use crate::services::scheduled_events::send_scheduled_event_alert;
+use crate::types::error::Result;
+use anyhow::Context;
+use celery::error::TaskError;
+use chrono::Duration;
+use sequent_core::services::date::ISO8601;
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(time_limit = 60, max_retries = 0, expires = 30)]
+pub async fn send_scheduled_event_alert_task(
+    tenant_id: String,
+    election_event_id: String,
+    scheduled_event_id: String,
+) -> Result<()> {
+    let email_sender = EmailSender::new().await?;
+
+    // The scheduled events beat task sends this task on every run until the
+    // event is alerted, only one of them has to send the emails.
+    let Ok(lock) = PgLock::acquire(
+        format!(
+            "send_scheduled_event_alert-{}-{}-{}",
+            tenant_id, election_event_id, scheduled_event_id
+        ),
+        Uuid::new_v4().to_string(),
+        ISO8601::now() + Duration::seconds(120),
+    )
+    .await
+    else {
+        info!("Alert of scheduled event {scheduled_event_id} is already being sent");
+        return Ok(());
+    };
+
+    let res = send_scheduled_event_alert(
+        &tenant_id,
+        &election_event_id,
+        &scheduled_event_id,
+        &email_sender,
+    )
+    .await;
+
+    lock.release()
+        .await
+        .with_context(|| "Error releasing pglock")?;
+
+    Ok(res?)
+}
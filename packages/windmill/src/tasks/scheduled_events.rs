@@ -5,6 +5,9 @@ use crate::postgres::{
     election::{get_election_by_id, update_election_presentation},
     scheduled_event::find_all_active_events,
 };
+use crate::services::scheduled_events::{
+    check_prerequisites, get_scheduled_datetime, record_scheduled_event_failure, requires_alert,
+};
 use crate::services::{
     celery_app::get_celery_app,
     database::{get_hasura_pool, get_keycloak_pool},
@@ -18,6 +21,7 @@ use crate::tasks::{
     manage_election_event_lockdown::manage_election_event_lockdown,
     manage_election_init_report::manage_election_init_report,
     manage_election_voting_period_end::manage_election_voting_period_end,
+    scheduled_event_alert::send_scheduled_event_alert_task,
 };
 use crate::types::error::{Error, Result};
 use anyhow::anyhow;
@@ -34,15 +38,15 @@ use std::sync::Arc;
 use tracing::instrument;
 use tracing::{event, info, Level};
 
+/// When the event has to run: its scheduled date, or the next attempt if it
+/// is being retried.
 #[instrument]
 pub fn get_datetime(event: &ScheduledEvent) -> Option<DateTime<Local>> {
-    let Some(cron_config) = event.cron_config.clone() else {
-        return None;
-    };
-    let Some(scheduled_date) = cron_config.scheduled_date else {
-        return None;
-    };
-    ISO8601::to_date(&scheduled_date).ok()
+    let datetime = get_scheduled_datetime(event)?.with_timezone(&Local);
+    match event.next_attempt_at {
+        Some(next_attempt_at) => Some(datetime.max(next_attempt_at.with_timezone(&Local))),
+        None => Some(datetime),
+    }
 }
 
 #[instrument(skip(celery_app), err)]
@@ -359,6 +363,47 @@ pub async fn handle_election_allow_tally(
     Ok(())
 }
 
+/// Checks the prerequisites of the event and sends the task that executes it.
+#[instrument(skip(hasura_transaction, celery_app), err)]
+pub async fn dispatch_scheduled_event(
+    hasura_transaction: &Transaction<'_>,
+    celery_app: Arc<Celery>,
+    scheduled_event: &ScheduledEvent,
+) -> Result<()> {
+    let Some(event_processor) = scheduled_event.event_processor.clone() else {
+        return Ok(());
+    };
+    check_prerequisites(hasura_transaction, scheduled_event).await?;
+    match event_processor {
+        EventProcessors::ALLOW_INIT_REPORT => {
+            handle_allow_init_report(celery_app, scheduled_event).await?;
+        }
+        EventProcessors::ALLOW_VOTING_PERIOD_END => {
+            handle_allow_voting_period_end(celery_app, scheduled_event).await?;
+        }
+        EventProcessors::START_VOTING_PERIOD | EventProcessors::END_VOTING_PERIOD => {
+            handle_voting_event(celery_app, scheduled_event).await?;
+        }
+        EventProcessors::START_ENROLLMENT_PERIOD | EventProcessors::END_ENROLLMENT_PERIOD => {
+            handle_election_event_enrollment(celery_app, scheduled_event).await?;
+        }
+        EventProcessors::START_LOCKDOWN_PERIOD | EventProcessors::END_LOCKDOWN_PERIOD => {
+            handle_election_lockdown(celery_app, scheduled_event).await?;
+        }
+        EventProcessors::ALLOW_TALLY => {
+            handle_election_allow_tally(celery_app, scheduled_event).await?;
+        }
+        EventProcessors::CREATE_REPORT | EventProcessors::SEND_TEMPLATE => {
+            // Nothing to do for these event processors.  Avoid a
+            // catch all to ignore unknown events, this way when
+            // new variants are added to `EventProcessors`, a
+            // compile time error will happen notifying about the
+            // missing logic for handling that new variant.
+        }
+    }
+    Ok(())
+}
+
 #[instrument(err)]
 #[wrap_map_err::wrap_map_err(TaskError)]
 #[celery::task(time_limit = 10, max_retries = 0, expires = 30)]
@@ -383,6 +428,9 @@ pub async fn scheduled_events(rate_seconds: u64) -> Result<()> {
     let to_be_run_now = scheduled_events
         .iter()
         .filter(|event| {
+            if event.status == Some(ScheduledEventStatus::FAILED) {
+                return false;
+            }
             let Some(formatted_date) = get_datetime(&event) else {
                 return false;
             };
@@ -391,50 +439,61 @@ pub async fn scheduled_events(rate_seconds: u64) -> Result<()> {
         .collect::<Vec<_>>();
     info!("Found {} events to be run now", to_be_run_now.len());
     for scheduled_event in to_be_run_now {
-        let Some(event_processor) = scheduled_event.event_processor.clone() else {
+        if let Err(err) =
+            dispatch_scheduled_event(&hasura_transaction, celery_app.clone(), scheduled_event).await
+        {
+            event!(
+                Level::ERROR,
+                "Event {} failed with error {}",
+                scheduled_event.id,
+                err,
+            );
+            record_scheduled_event_failure(&hasura_transaction, scheduled_event, &err.to_string())
+                .await
+                .map_err(|e| anyhow!("Error recording scheduled event failure {}", e))?;
+        } else {
+            event!(
+                Level::INFO,
+                "Event {} dispatched successfully",
+                scheduled_event.id,
+            );
+        }
+    }
+
+    let now_utc = now.with_timezone(&Utc);
+    let to_be_alerted = scheduled_events
+        .iter()
+        .filter(|event| requires_alert(event, now_utc))
+        .collect::<Vec<_>>();
+    for scheduled_event in to_be_alerted {
+        let (Some(tenant_id), Some(election_event_id)) = (
+            scheduled_event.tenant_id.clone(),
+            scheduled_event.election_event_id.clone(),
+        ) else {
             continue;
         };
-        match event_processor {
-            EventProcessors::ALLOW_INIT_REPORT => {
-                handle_allow_init_report(celery_app.clone(), scheduled_event).await?;
-            }
-            EventProcessors::ALLOW_VOTING_PERIOD_END => {
-                handle_allow_voting_period_end(celery_app.clone(), scheduled_event).await?;
-            }
-            EventProcessors::START_VOTING_PERIOD | EventProcessors::END_VOTING_PERIOD => {
-                if let Err(err) = handle_voting_event(celery_app.clone(), &scheduled_event).await {
-                    event!(
-                        Level::ERROR,
-                        "Event {} failed with error {}",
-                        scheduled_event.id,
-                        err,
-                    );
-                } else {
-                    event!(
-                        Level::INFO,
-                        "Event {} executed successfully",
-                        scheduled_event.id,
-                    );
-                }
-            }
-            EventProcessors::START_ENROLLMENT_PERIOD | EventProcessors::END_ENROLLMENT_PERIOD => {
-                handle_election_event_enrollment(celery_app.clone(), scheduled_event).await?;
-            }
-            EventProcessors::START_LOCKDOWN_PERIOD | EventProcessors::END_LOCKDOWN_PERIOD => {
-                handle_election_lockdown(celery_app.clone(), scheduled_event).await?;
-            }
-            EventProcessors::ALLOW_TALLY => {
-                handle_election_allow_tally(celery_app.clone(), scheduled_event).await?;
-            }
-            EventProcessors::CREATE_REPORT | EventProcessors::SEND_TEMPLATE => {
-                // Nothing to do for these event processors.  Avoid a
-                // catch all to ignore unknown events, this way when
-                // new variants are added to `EventProcessors`, a
-                // compile time error will happen notifying about the
-                // missing logic for handling that new variant.
-            }
+        // send the emails in a different async task, out of this short lived one
+        if let Err(err) = celery_app
+            .send_task(send_scheduled_event_alert_task::new(
+                tenant_id,
+                election_event_id,
+                scheduled_event.id.clone(),
+            ))
+            .await
+        {
+            event!(
+                Level::ERROR,
+                "Error sending alert task for event {}: {}",
+                scheduled_event.id,
+                err,
+            );
         }
     }
 
+    hasura_transaction
+        .commit()
+        .await
+        .map_err(|e| anyhow!("Error committing the hasura transaction {}", e))?;
+
     Ok(())
 }
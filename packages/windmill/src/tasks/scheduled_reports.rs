@@ -6,6 +6,7 @@ use crate::postgres::reports::{get_all_active_reports, update_report_last_docume
 use crate::services::celery_app::get_celery_app;
 use crate::services::database::get_hasura_pool;
 use crate::services::reports::template_renderer::GenerateReportMode;
+use crate::services::scheduled_events::get_election_event_time_zone;
 use crate::services::tasks_execution;
 use crate::tasks::generate_report::generate_report;
 use crate::types::error::Result;
@@ -15,13 +16,19 @@ use celery::error::TaskError;
 use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
 use croner::Cron;
 use deadpool_postgres::Client as DbClient;
+use sequent_core::services::date::{parse_time_zone, Tz};
+use std::collections::HashMap;
 use tracing::{error, event, info, instrument, Level};
 use uuid::Uuid;
 
 /// Parse the next scheduled time for the report using the cron expression.
 /// Returns the next run time if it is due within the current time window.
+///
+/// The cron expression is evaluated in the time zone of the election event
+/// (UTC if not set), so that recurring reports keep their wall-clock time
+/// across DST transitions.
 #[instrument]
-pub fn get_next_scheduled_time(report: &Report) -> Option<DateTime<Local>> {
+pub fn get_next_scheduled_time(report: &Report, time_zone: Option<&Tz>) -> Option<DateTime<Local>> {
     let Some(cron_config) = report.cron_config.clone() else {
         return None;
     };
@@ -57,6 +64,7 @@ pub fn get_next_scheduled_time(report: &Report) -> Option<DateTime<Local>> {
         }
     };
     // Get the next scheduled time after the last run
+    let last_run = last_run.with_timezone(time_zone.unwrap_or(&Tz::UTC));
     let next_run = match schedule.find_next_occurrence(&last_run, false) {
         Ok(next_run) => next_run,
         Err(err) => {
@@ -106,11 +114,42 @@ pub async fn scheduled_reports(rate_seconds: u64) -> Result<()> {
         .map_err(|err| anyhow!("Error getting all active reports: {err:?}"))?;
     info!("Found {len} active reports", len = active_reports.len());
 
+    // Time zone of each election event with active reports
+    let mut time_zones: HashMap<String, Option<Tz>> = HashMap::new();
+    for report in active_reports.iter() {
+        if time_zones.contains_key(&report.election_event_id) {
+            continue;
+        }
+        let time_zone = match get_election_event_time_zone(
+            &hasura_transaction,
+            &report.tenant_id,
+            &report.election_event_id,
+        )
+        .await
+        {
+            Ok(time_zone) => time_zone.and_then(|time_zone| parse_time_zone(&time_zone).ok()),
+            Err(err) => {
+                error!(
+                    "Error getting time zone of election event {id}, using UTC: {err:?}",
+                    id = report.election_event_id
+                );
+                None
+            }
+        };
+        time_zones.insert(report.election_event_id.clone(), time_zone);
+    }
+    let get_time_zone = |report: &Report| {
+        time_zones
+            .get(&report.election_event_id)
+            .and_then(|time_zone| time_zone.as_ref())
+    };
+
     // Filter out reports that need to run now based on their cron configuration
     let to_be_run_now = active_reports
         .iter()
         .filter(|report| {
-            let Some(formatted_date) = get_next_scheduled_time(&report) else {
+            let Some(formatted_date) = get_next_scheduled_time(&report, get_time_zone(report))
+            else {
                 return false;
             };
             formatted_date < nsecs_later
@@ -123,7 +162,7 @@ pub async fn scheduled_reports(rate_seconds: u64) -> Result<()> {
 
     // Schedule the task for each report that needs to run
     for report in to_be_run_now {
-        let Some(datetime) = get_next_scheduled_time(report) else {
+        let Some(datetime) = get_next_scheduled_time(report, get_time_zone(report)) else {
             continue;
         };
 
@@ -4,13 +4,18 @@
 use crate::postgres::area::get_elections_by_area;
 use crate::postgres::election_event::get_election_event_by_id_if_exist;
 use crate::services::celery_app::get_celery_app;
+use crate::services::communication_templates::{
+    get_user_language, localize_template, localize_variables,
+};
 use crate::services::election_event_board::get_election_event_board;
 use crate::services::election_event_statistics::update_election_event_statistics;
 use crate::services::election_statistics::update_election_statistics;
 use crate::services::electoral_log::ElectoralLog;
 use crate::services::providers::{email_sender::EmailSender, sms_sender::SmsSender};
+use crate::services::tasks_execution;
 use crate::services::users::{list_users, list_users_with_vote_info, ListUsersFilter};
 use crate::types::error::Result;
+use crate::types::tasks::ETasksExecution;
 
 use crate::services::database::{get_hasura_pool, get_keycloak_pool, PgConfig};
 use crate::types::error::Error;
@@ -28,7 +33,7 @@ use sequent_core::services::generate_urls::get_auth_url;
 use sequent_core::services::generate_urls::AuthAction;
 use sequent_core::services::keycloak::{get_event_realm, get_tenant_realm};
 use sequent_core::services::{keycloak, reports};
-use sequent_core::types::hasura::core::ElectionEvent;
+use sequent_core::types::hasura::core::{ElectionEvent, TasksExecution};
 use sequent_core::types::keycloak::{User, UserArea, AREA_ID_ATTR_NAME};
 use sequent_core::types::templates::{
     AudienceSelection, EmailConfig, SendTemplateBody, SmsConfig, TemplateMethod,
@@ -42,7 +47,7 @@ use strand::info;
 use tracing::{event, info, instrument, Level};
 
 #[instrument(err)]
-fn get_variables(
+pub fn get_variables(
     user: &User,
     election_event: Option<ElectionEvent>,
     tenant_id: String,
@@ -79,7 +84,17 @@ fn get_variables(
                 auth_action
             )),
         );
+        // used by the format_date helper
+        let time_zone = election_event
+            .get_presentation()
+            .map_err(|err| anyhow!("Error parsing election event presentation: {err:?}"))?
+            .and_then(|presentation| presentation.time_zone);
+        if let Some(time_zone) = time_zone {
+            variables.insert("time_zone".to_string(), json!(time_zone));
+        }
     }
+    let language = get_user_language(user, &election_event);
+    localize_variables(&mut variables, &election_event, &language);
     Ok(variables)
 }
 
@@ -324,6 +339,44 @@ pub async fn send_template(
     tenant_id: String,
     admin_id: String,
     election_event_id: Option<String>,
+) -> Result<()> {
+    // the delivery status of the sent emails and SMS messages is recorded
+    // against it
+    let task_execution = tasks_execution::post(
+        &tenant_id,
+        election_event_id.as_deref(),
+        ETasksExecution::SEND_TEMPLATE,
+        &admin_id,
+    )
+    .await?;
+
+    match send_template_to_audience(
+        body,
+        tenant_id,
+        admin_id,
+        election_event_id,
+        &task_execution,
+    )
+    .await
+    {
+        Ok(()) => {
+            tasks_execution::update_complete(&task_execution, None).await?;
+            Ok(())
+        }
+        Err(err) => {
+            tasks_execution::update_fail(&task_execution, &format!("{err:?}")).await?;
+            Err(err)
+        }
+    }
+}
+
+#[instrument(err, skip(task_execution))]
+async fn send_template_to_audience(
+    body: SendTemplateBody,
+    tenant_id: String,
+    admin_id: String,
+    election_event_id: Option<String>,
+    task_execution: &TasksExecution,
 ) -> Result<()> {
     let celery_app = get_celery_app().await;
     let realm = match election_event_id {
@@ -397,6 +450,16 @@ pub async fn send_template(
         .with_context(|| "Error listing elections by area")?,
     };
 
+    // created once so that the send rate is kept across batches
+    let email_sender = EmailSender::new().await?.with_outbox(
+        &tenant_id,
+        election_event_id.as_deref(),
+        Some(&task_execution.id),
+    );
+    let sms_sender = SmsSender::new()
+        .await?
+        .with_status_callback(&tenant_id, &task_execution.id)?;
+
     loop {
         let hasura_transaction = hasura_db_client
             .transaction()
@@ -452,8 +515,6 @@ pub async fn send_template(
             _ => {}
         };
 
-        let email_sender = EmailSender::new().await?;
-        let sms_sender = SmsSender::new().await?;
         let mut metrics = Metrics {
             election_event: MetricsUnit {
                 num_emails_sent: 0,
@@ -467,14 +528,19 @@ pub async fn send_template(
         };
 
         for user in filtered_users.iter() {
+            let localized = localize_template(
+                &body,
+                &election_event,
+                &get_user_language(user, &election_event),
+            )?;
             let success = send_template_email_or_sms(
                 &hasura_transaction,
                 &user,
                 &election_event,
                 &tenant_id,
                 Some(admin_id.clone()),
-                &body.email,
-                &body.sms,
+                &localized.email,
+                &localized.sms,
                 &email_sender,
                 &sms_sender,
                 Some(communication_method.clone()),
@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::document::get_document;
+use crate::services::documents::get_document_as_temp_file;
+use crate::services::import::census_sync::{sync_census, CensusSyncInput};
+use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::services::tasks_execution::*;
+use crate::types::error::Result;
+use anyhow::{anyhow, Context};
+use celery::error::TaskError;
+use sequent_core::types::hasura::core::TasksExecution;
+use sequent_core::util::integrity_check::{integrity_check, HashFileVerifyError};
+use std::io::Seek;
+use tracing::{info, instrument};
+
+/// Synchronises the voters of an election event with an electoral roll
+/// document. The report with the changes is uploaded as
+/// `report_document_id`, both in dry runs and when the changes are applied.
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(time_limit = 1200000, max_retries = 0)]
+pub async fn sync_census_task(
+    input: CensusSyncInput,
+    task_execution: TasksExecution,
+) -> Result<()> {
+    let result = provide_hasura_transaction(|hasura_transaction| {
+        let input = input.clone();
+        Box::pin(async move {
+            let document = get_document(
+                hasura_transaction,
+                &input.tenant_id,
+                None,
+                &input.document_id,
+            )
+            .await
+            .with_context(|| "Error obtaining the document")?
+            .ok_or(anyhow!("document not found"))?;
+            let separator = if document.name.clone().unwrap_or_default().ends_with(".tsv") {
+                b'\t'
+            } else {
+                b','
+            };
+
+            let mut roll_file = get_document_as_temp_file(&input.tenant_id, &document).await?;
+            roll_file.rewind()?;
+            match input.sha256.clone() {
+                Some(hash) if !hash.is_empty() => match integrity_check(&roll_file, hash) {
+                    Ok(_) => info!("Hash verified !"),
+                    Err(HashFileVerifyError::HashMismatch(input_hash, gen_hash)) => {
+                        return Err(anyhow!("Failed to verify the integrity: Hash of census file: {gen_hash} does not match with the input hash: {input_hash}"));
+                    }
+                    Err(err) => {
+                        return Err(anyhow!("Failed to verify the integrity: {err:?}"));
+                    }
+                },
+                _ => info!("No hash provided, skipping integrity check"),
+            }
+            roll_file.rewind()?;
+
+            sync_census(hasura_transaction, roll_file.as_file(), separator, &input)
+                .await
+                .map(|_| ())
+        })
+    })
+    .await;
+
+    match result {
+        Ok(_) => {
+            let _res =
+                update_complete(&task_execution, Some(input.report_document_id.clone())).await;
+            Ok(())
+        }
+        Err(err) => {
+            let err_str = format!("Error synchronising census: {err:?}");
+            let _res = update_fail(&task_execution, &err.to_string()).await;
+            Err(err_str.into())
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+use crate::postgres::document::get_document;
+use crate::services::documents::get_document_as_temp_file;
+use crate::services::import::import_validation::{validate_import_file, ImportValidationInput};
+use crate::services::providers::transactions_provider::provide_hasura_transaction;
+use crate::services::serialize_tasks_logs::append_general_log;
+use crate::services::tasks_execution::*;
+use crate::types::error::Result;
+use anyhow::{anyhow, Context};
+use celery::error::TaskError;
+use sequent_core::types::hasura::core::TasksExecution;
+use sequent_core::types::hasura::extra::TasksExecutionStatus;
+use sequent_core::util::integrity_check::{integrity_check, HashFileVerifyError};
+use std::io::Seek;
+use tracing::{info, instrument};
+
+/// Validates an import file without importing it. The per-row error report
+/// is uploaded as `report_document_id`. The task fails if the file has any
+/// error, keeping the report as its document.
+#[instrument(err)]
+#[wrap_map_err::wrap_map_err(TaskError)]
+#[celery::task(time_limit = 1200000, max_retries = 0)]
+pub async fn validate_import_task(
+    input: ImportValidationInput,
+    task_execution: TasksExecution,
+) -> Result<()> {
+    let result = provide_hasura_transaction(|hasura_transaction| {
+        let input = input.clone();
+        Box::pin(async move {
+            let document = get_document(
+                hasura_transaction,
+                &input.tenant_id,
+                None,
+                &input.document_id,
+            )
+            .await
+            .with_context(|| "Error obtaining the document")?
+            .ok_or(anyhow!("document not found"))?;
+            let separator = if document.name.clone().unwrap_or_default().ends_with(".tsv") {
+                b'\t'
+            } else {
+                b','
+            };
+
+            let mut import_file = get_document_as_temp_file(&input.tenant_id, &document).await?;
+            import_file.rewind()?;
+            match input.sha256.clone() {
+                Some(hash) if !hash.is_empty() => match integrity_check(&import_file, hash) {
+                    Ok(_) => info!("Hash verified !"),
+                    Err(HashFileVerifyError::HashMismatch(input_hash, gen_hash)) => {
+                        return Err(anyhow!("Failed to verify the integrity: Hash of import file: {gen_hash} does not match with the input hash: {input_hash}"));
+                    }
+                    Err(err) => {
+                        return Err(anyhow!("Failed to verify the integrity: {err:?}"));
+                    }
+                },
+                _ => info!("No hash provided, skipping integrity check"),
+            }
+            import_file.rewind()?;
+
+            validate_import_file(hasura_transaction, import_file.as_file(), separator, &input)
+                .await
+        })
+    })
+    .await;
+
+    match result {
+        Ok(report) if report.is_valid() => {
+            let _res =
+                update_complete(&task_execution, Some(input.report_document_id.clone())).await;
+            Ok(())
+        }
+        Ok(report) => {
+            let err_str = format!(
+                "{} of {} rows have errors, see the validation report",
+                report.invalid_rows, report.total_rows
+            );
+            // Unlike update_fail, keeps the report attached to the task
+            let logs = append_general_log(&task_execution.logs, &format!("Error: {err_str}"));
+            let _res = update(
+                &task_execution.tenant_id,
+                &task_execution.id,
+                TasksExecutionStatus::FAILED,
+                serde_json::to_value(logs).map_err(|err| anyhow!(err))?,
+                Some(input.report_document_id.clone()),
+            )
+            .await;
+            Err(err_str.into())
+        }
+        Err(err) => {
+            let err_str = format!("Error validating import file: {err:?}");
+            let _res = update_fail(&task_execution, &err.to_string()).await;
+            Err(err_str.into())
+        }
+    }
+}
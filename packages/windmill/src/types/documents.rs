@@ -54,6 +54,7 @@ pub enum ETallyDocuments {
     RESULTS_CONTEST,
     RESULTS_AREA_CONTEST_CANDIDATE,
     RESULTS_AREA_CONTEST,
+    TALLY_SHEET,
 }
 
 impl ETallyDocuments {
@@ -71,6 +72,7 @@ impl ETallyDocuments {
                 "export_results_area_contest_candidate"
             }
             ETallyDocuments::RESULTS_AREA_CONTEST => "export_results_area_contest",
+            ETallyDocuments::TALLY_SHEET => "export_tally_sheet",
         }
     }
 }
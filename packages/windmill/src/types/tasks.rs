@@ -28,6 +28,12 @@ pub enum ETasksExecution {
     DELETE_ELECTION_EVENT,
     PREPARE_PUBLICATION_PREVIEW,
     EXPORT_TALLY_RESULTS_XLSX,
+    EXPORT_TALLY_RESULTS_CDF,
+    EXPORT_TALLY_RESULTS_EML,
+    RECOUNT_TALLY_SESSION,
+    SEND_TEMPLATE,
+    SYNC_CENSUS,
+    VALIDATE_IMPORT,
 }
 
 impl ETasksExecution {
@@ -55,6 +61,12 @@ impl ETasksExecution {
             ETasksExecution::DELETE_ELECTION_EVENT => "Delete Election Event",
             ETasksExecution::PREPARE_PUBLICATION_PREVIEW => "Prepare Publication Preview",
             ETasksExecution::EXPORT_TALLY_RESULTS_XLSX => "Export Tally Results To XLSX",
+            ETasksExecution::EXPORT_TALLY_RESULTS_CDF => "Export Tally Results To CDF",
+            ETasksExecution::EXPORT_TALLY_RESULTS_EML => "Export Tally Results To EML",
+            ETasksExecution::RECOUNT_TALLY_SESSION => "Recount Tally Session",
+            ETasksExecution::SEND_TEMPLATE => "Send Template",
+            ETasksExecution::SYNC_CENSUS => "Sync Census",
+            ETasksExecution::VALIDATE_IMPORT => "Validate Import",
         }
     }
 }
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+# SPDX-FileCopyrightText: 2025 Sequent Tech Inc <legal@sequentech.io>
+#
+# SPDX-License-Identifier: AGPL-3.0-only
+
+# Vendors the official schemas the tally results exports are validated
+# against in the windmill tests:
+# - OASIS EML v7 schemas, for the EML 510/520 export.
+# - NIST SP 1500-100 V2 JSON schema, for the CDF export.
+
+set -euo pipefail
+
+SCRIPT_DIR=$( cd -- "$( dirname -- "${BASH_SOURCE[0]}" )" &> /dev/null && pwd )
+RESOURCES_DIR=$SCRIPT_DIR/../packages/windmill/src/resources
+
+EML_SCHEMAS_URL=${EML_SCHEMAS_URL:-https://docs.oasis-open.org/election/eml/v7.0/os/EML-Schema-Files/}
+NIST_SCHEMA_URL=${NIST_SCHEMA_URL:-https://raw.githubusercontent.com/usnistgov/ElectionResultsReporting/master/NIST_V2_election_results_reporting.json}
+
+mkdir -p "$RESOURCES_DIR/eml" "$RESOURCES_DIR/cdf"
+
+# The 510 and 520 schemas include the EML core and externals schemas, so the
+# whole folder is vendored
+wget --recursive --level=1 --no-parent --no-directories --accept xsd \
+    --directory-prefix "$RESOURCES_DIR/eml" "$EML_SCHEMAS_URL"
+for xsd in 510-count-v7-0.xsd 520-result-v7-0.xsd emlcore-v7-0.xsd; do
+    if [[ ! -f "$RESOURCES_DIR/eml/$xsd" ]]; then
+        echo "Missing $xsd in $EML_SCHEMAS_URL" >&2
+        exit 1
+    fi
+done
+
+
+wget --output-document "$RESOURCES_DIR/cdf/NIST_V2_election_results_reporting.json" \
+    "$NIST_SCHEMA_URL"